@@ -34,6 +34,11 @@ command: Array<string>,
  * The command's working directory.
  */
 cwd: string, 
+/**
+ * Environment variables the command sets explicitly on top of the
+ * inherited environment.
+ */
+env?: { [key in string]?: string }, 
 /**
  * Optional human-readable reason for the approval (e.g. retry without sandbox).
  */
@@ -903,20 +903,33 @@ pub enum CommandExecutionApprovalDecision {
 impl From<CoreReviewDecision> for CommandExecutionApprovalDecision {
     fn from(value: CoreReviewDecision) -> Self {
         match value {
-            CoreReviewDecision::Approved => Self::Accept,
+            // Clients cannot express an expiry, so an approval for a while
+            // is reported as a one-time approval.
+            CoreReviewDecision::Approved | CoreReviewDecision::ApprovedForDuration { .. } => {
+                Self::Accept
+            }
             CoreReviewDecision::ApprovedExecpolicyAmendment {
                 proposed_execpolicy_amendment,
             } => Self::AcceptWithExecpolicyAmendment {
                 execpolicy_amendment: proposed_execpolicy_amendment.into(),
             },
-            CoreReviewDecision::ApprovedForSession => Self::AcceptForSession,
+            // Exec approvals are cached per working directory, so directory and
+            // persisted approvals are session approvals for the client.
+            CoreReviewDecision::ApprovedForSession
+            | CoreReviewDecision::ApprovedForDirectory
+            | CoreReviewDecision::ApprovedAlways => Self::AcceptForSession,
             CoreReviewDecision::NetworkPolicyAmendment {
                 network_policy_amendment,
             } => Self::ApplyNetworkPolicyAmendment {
                 network_policy_amendment: network_policy_amendment.into(),
             },
             CoreReviewDecision::Abort => Self::Cancel,
-            CoreReviewDecision::Denied => Self::Decline,
+            // Clients have no dry-run, sandboxed or session-wide deny decision;
+            // one that reaches them declines.
+            CoreReviewDecision::ApprovedSandboxed
+            | CoreReviewDecision::DryRun
+            | CoreReviewDecision::Denied
+            | CoreReviewDecision::DeniedForSession => Self::Decline,
         }
     }
 }
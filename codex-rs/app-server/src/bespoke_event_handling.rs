@@ -433,11 +433,22 @@ pub(crate) async fn apply_bespoke_event_handling(
                 .note_permission_requested(&conversation_id.to_string())
                 .await;
             let approval_id_for_op = ev.effective_approval_id();
-            let available_decisions = ev
+            let mut available_decisions = ev
                 .effective_available_decisions()
                 .into_iter()
+                .filter(|decision| {
+                    !matches!(
+                        decision,
+                        ReviewDecision::ApprovedSandboxed
+                            | ReviewDecision::ApprovedForDuration { .. }
+                            | ReviewDecision::DryRun
+                            | ReviewDecision::DeniedForSession
+                    )
+                })
                 .map(CommandExecutionApprovalDecision::from)
                 .collect::<Vec<_>>();
+            // Directory and persisted approvals both surface as accept-for-session.
+            available_decisions.dedup();
             let ExecApprovalRequestEvent {
                 call_id,
                 approval_id,
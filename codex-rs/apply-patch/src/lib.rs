@@ -14,6 +14,7 @@ pub use parser::ParseError;
 use parser::ParseError::*;
 use parser::UpdateFileChunk;
 pub use parser::parse_patch;
+pub use parser::retain_patch_files;
 use similar::TextDiff;
 use thiserror::Error;
 
@@ -151,6 +151,30 @@ enum ParseMode {
     Lenient,
 }
 
+/// The patch with only the hunks for `included` files left in, each resolved
+/// against `cwd`. The envelope and every line of a kept hunk pass through
+/// unchanged.
+pub fn retain_patch_files(patch: &str, cwd: &Path, included: &[PathBuf]) -> String {
+    let mut keep = true;
+    let mut retained = String::with_capacity(patch.len());
+    for line in patch.split_inclusive('\n') {
+        let marker = line.trim();
+        let path = marker
+            .strip_prefix(ADD_FILE_MARKER)
+            .or_else(|| marker.strip_prefix(DELETE_FILE_MARKER))
+            .or_else(|| marker.strip_prefix(UPDATE_FILE_MARKER));
+        if let Some(path) = path {
+            keep = included.contains(&cwd.join(path));
+        } else if marker == END_PATCH_MARKER {
+            keep = true;
+        }
+        if keep {
+            retained.push_str(line);
+        }
+    }
+    retained
+}
+
 fn parse_patch_text(patch: &str, mode: ParseMode) -> Result<ApplyPatchArgs, ParseError> {
     let lines: Vec<&str> = patch.trim().lines().collect();
     let lines: &[&str] = match check_patch_boundaries_strict(&lines) {
@@ -761,3 +785,33 @@ fn test_update_file_chunk() {
         ))
     );
 }
+
+#[test]
+fn test_retain_patch_files() {
+    let patch = "*** Begin Patch
+*** Add File: new.txt
++hello
+*** Update File: src/lib.rs
+@@
+-old
++new
+*** Delete File: gone.txt
+*** End Patch";
+    let cwd = Path::new("/repo");
+    assert_eq!(
+        retain_patch_files(patch, cwd, &[PathBuf::from("/repo/src/lib.rs")]),
+        "*** Begin Patch
+*** Update File: src/lib.rs
+@@
+-old
++new
+*** End Patch"
+    );
+    assert_eq!(
+        retain_patch_files(patch, cwd, &[PathBuf::from("/repo/new.txt")]),
+        "*** Begin Patch
+*** Add File: new.txt
++hello
+*** End Patch"
+    );
+}
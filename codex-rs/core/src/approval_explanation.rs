@@ -0,0 +1,111 @@
+//! Plain-English explanations of commands that are waiting for approval.
+//!
+//! The user can ask for one from the approval prompt without resolving it. The
+//! model answers in a side request that leaves the conversation history alone,
+//! so asking does not change what the agent itself sees.
+
+use std::path::Path;
+use std::sync::Arc;
+
+use codex_api::ResponseEvent;
+use codex_protocol::models::BaseInstructions;
+use codex_protocol::models::ContentItem;
+use codex_protocol::models::ResponseItem;
+use codex_protocol::protocol::ApprovalExplanationEvent;
+use codex_protocol::protocol::Event;
+use codex_protocol::protocol::EventMsg;
+use codex_protocol::protocol::WarningEvent;
+use futures::StreamExt;
+use tracing::warn;
+
+use crate::Prompt;
+use crate::codex::Session;
+use crate::compact::content_items_to_text;
+use crate::parse_command::shlex_join;
+
+const INSTRUCTIONS: &str = "You explain shell commands to someone deciding whether a coding \
+agent may run them. In a few plain sentences, say what the command does, what it reads or \
+changes on disk or over the network, and anything risky about it. Do not suggest alternatives.";
+
+/// Explain the pending exec approval `id` and send the answer as an
+/// [`EventMsg::ApprovalExplanation`]. The approval itself stays pending.
+pub(crate) async fn explain_pending_command(sess: Arc<Session>, sub_id: String, id: String) {
+    let pending = match sess.active_turn.lock().await.as_ref() {
+        Some(turn) => turn.turn_state.lock().await.pending_approval_command(&id),
+        None => None,
+    };
+    let Some((command, cwd)) = pending else {
+        warn!("no pending command approval {id} to explain");
+        return;
+    };
+    let msg = match request_explanation(&sess, &command, &cwd).await {
+        Ok(explanation) => {
+            EventMsg::ApprovalExplanation(ApprovalExplanationEvent { id, explanation })
+        }
+        Err(err) => EventMsg::Warning(WarningEvent {
+            message: format!("Could not explain the command: {err}"),
+        }),
+    };
+    sess.send_event_raw(Event { id: sub_id, msg }).await;
+}
+
+async fn request_explanation(
+    sess: &Session,
+    command: &[String],
+    cwd: &Path,
+) -> anyhow::Result<String> {
+    let prompt = Prompt {
+        input: vec![ResponseItem::Message {
+            id: None,
+            role: "user".to_string(),
+            content: vec![ContentItem::InputText {
+                text: format!(
+                    "Command: {}\nWorking directory: {}",
+                    shlex_join(command),
+                    cwd.display()
+                ),
+            }],
+            end_turn: None,
+            phase: None,
+        }],
+        tools: Vec::new(),
+        parallel_tool_calls: false,
+        base_instructions: BaseInstructions {
+            text: INSTRUCTIONS.to_string(),
+        },
+        personality: None,
+        output_schema: None,
+    };
+
+    let turn_context = sess.new_default_turn().await;
+    let mut client_session = sess.services.model_client.new_session();
+    let mut stream = client_session
+        .stream(
+            &prompt,
+            &turn_context.model_info,
+            &turn_context.session_telemetry,
+            turn_context.reasoning_effort,
+            turn_context.reasoning_summary,
+            turn_context.config.service_tier,
+            turn_context
+                .turn_metadata_state
+                .current_header_value()
+                .as_deref(),
+        )
+        .await?;
+
+    let mut explanation = String::new();
+    while let Some(event) = stream.next().await.transpose()? {
+        match event {
+            ResponseEvent::OutputTextDelta(delta) => explanation.push_str(&delta),
+            ResponseEvent::OutputItemDone(ResponseItem::Message { content, .. })
+                if explanation.is_empty() =>
+            {
+                explanation.extend(content_items_to_text(&content));
+            }
+            ResponseEvent::Completed { .. } => break,
+            _ => {}
+        }
+    }
+    Ok(explanation.trim().to_string())
+}
@@ -0,0 +1,97 @@
+//! Commands the user approved with "always", persisted across sessions.
+//!
+//! The allowlist lives in `$CODEX_HOME/approved_commands.json`:
+//!
+//! ```json
+//! {
+//!   "commands": [
+//!     { "command": ["cargo", "test"], "cwd": "/home/me/project" }
+//!   ]
+//! }
+//! ```
+//!
+//! Each entry approves exactly that argv when it runs from exactly that
+//! working directory. Sessions load the file once at startup.
+
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+
+use serde::Deserialize;
+use serde::Serialize;
+use tracing::warn;
+
+pub const APPROVED_COMMANDS_FILENAME: &str = "approved_commands.json";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ApprovedCommand {
+    pub command: Vec<String>,
+    pub cwd: PathBuf,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct ApprovedCommandsFile {
+    #[serde(default)]
+    commands: Vec<ApprovedCommand>,
+}
+
+/// Read the allowlist. A missing file is empty; an unreadable one is logged
+/// and treated as empty so a bad edit never blocks startup.
+pub async fn load_approved_commands(codex_home: &Path) -> Vec<ApprovedCommand> {
+    match read_file(codex_home).await {
+        Ok(file) => file.commands,
+        Err(err) => {
+            warn!("failed to load {APPROVED_COMMANDS_FILENAME}: {err}");
+            Vec::new()
+        }
+    }
+}
+
+/// Append `approved` to the allowlist unless it is already there.
+pub async fn append_approved_command(
+    codex_home: &Path,
+    approved: ApprovedCommand,
+) -> io::Result<()> {
+    let mut file = read_file(codex_home).await?;
+    if file.commands.contains(&approved) {
+        return Ok(());
+    }
+    file.commands.push(approved);
+    let contents = serde_json::to_string_pretty(&file).map_err(io::Error::other)?;
+    tokio::fs::create_dir_all(codex_home).await?;
+    tokio::fs::write(codex_home.join(APPROVED_COMMANDS_FILENAME), contents).await
+}
+
+async fn read_file(codex_home: &Path) -> io::Result<ApprovedCommandsFile> {
+    match tokio::fs::read_to_string(codex_home.join(APPROVED_COMMANDS_FILENAME)).await {
+        Ok(contents) => serde_json::from_str(&contents).map_err(io::Error::other),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(ApprovedCommandsFile::default()),
+        Err(err) => Err(err),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn appended_commands_load_once() -> io::Result<()> {
+        let codex_home = TempDir::new()?;
+        assert_eq!(load_approved_commands(codex_home.path()).await, Vec::new());
+
+        let approved = ApprovedCommand {
+            command: vec!["cargo".to_string(), "test".to_string()],
+            cwd: PathBuf::from("/repo"),
+        };
+        append_approved_command(codex_home.path(), approved.clone()).await?;
+        append_approved_command(codex_home.path(), approved.clone()).await?;
+
+        assert_eq!(
+            load_approved_commands(codex_home.path()).await,
+            vec![approved]
+        );
+        Ok(())
+    }
+}
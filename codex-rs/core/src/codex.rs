@@ -16,6 +16,8 @@ use crate::analytics_client::AnalyticsEventsClient;
 use crate::analytics_client::AppInvocation;
 use crate::analytics_client::InvocationType;
 use crate::analytics_client::build_track_events_context;
+use crate::approval_explanation::explain_pending_command;
+use crate::approved_commands::load_approved_commands;
 use crate::apps::render_apps_section;
 use crate::commit_attribution::commit_message_trailer_instruction;
 use crate::compact;
@@ -236,6 +238,7 @@ use crate::protocol::ModelRerouteReason;
 use crate::protocol::NetworkApprovalContext;
 use crate::protocol::Op;
 use crate::protocol::PlanDeltaEvent;
+use crate::protocol::PostRunAssertion;
 use crate::protocol::RateLimitSnapshot;
 use crate::protocol::ReasoningContentDeltaEvent;
 use crate::protocol::ReasoningRawContentDeltaEvent;
@@ -1624,7 +1627,9 @@ impl Session {
             auth_manager: Arc::clone(&auth_manager),
             session_telemetry,
             models_manager: Arc::clone(&models_manager),
-            tool_approvals: Mutex::new(ApprovalStore::default()),
+            tool_approvals: Mutex::new(ApprovalStore::with_approved_commands(
+                load_approved_commands(&config.codex_home).await,
+            )),
             execve_session_approvals: RwLock::new(HashMap::new()),
             skills_manager,
             plugins_manager: Arc::clone(&plugins_manager),
@@ -2795,6 +2800,7 @@ impl Session {
         approval_id: Option<String>,
         command: Vec<String>,
         cwd: PathBuf,
+        env: Option<HashMap<String, String>>,
         reason: Option<String>,
         network_approval_context: Option<NetworkApprovalContext>,
         proposed_execpolicy_amendment: Option<ExecPolicyAmendment>,
@@ -2812,6 +2818,11 @@ impl Session {
             match active.as_mut() {
                 Some(at) => {
                     let mut ts = at.turn_state.lock().await;
+                    ts.insert_pending_approval_command(
+                        effective_approval_id.clone(),
+                        command.clone(),
+                        cwd.clone(),
+                    );
                     ts.insert_pending_approval(effective_approval_id.clone(), tx_approve)
                 }
                 None => None,
@@ -2848,6 +2859,7 @@ impl Session {
             turn_id: turn_context.sub_id.clone(),
             command,
             cwd,
+            env,
             reason,
             network_approval_context,
             proposed_execpolicy_amendment,
@@ -3133,6 +3145,64 @@ impl Session {
         ts.granted_permissions()
     }
 
+    pub(crate) async fn record_approved_command_override(
+        &self,
+        call_id: &str,
+        command: Vec<String>,
+    ) {
+        let mut active = self.active_turn.lock().await;
+        if let Some(at) = active.as_mut() {
+            let mut ts = at.turn_state.lock().await;
+            ts.record_approved_command_override(call_id.to_string(), command);
+        }
+    }
+
+    /// Argv the user approved in place of the command requested for `call_id`.
+    pub(crate) async fn approved_command_override(&self, call_id: &str) -> Option<Vec<String>> {
+        let active = self.active_turn.lock().await;
+        let active = active.as_ref()?;
+        let ts = active.turn_state.lock().await;
+        ts.approved_command_override(call_id)
+    }
+
+    pub(crate) async fn record_approved_patch_files(&self, call_id: &str, files: Vec<PathBuf>) {
+        let mut active = self.active_turn.lock().await;
+        if let Some(at) = active.as_mut() {
+            let mut ts = at.turn_state.lock().await;
+            ts.record_approved_patch_files(call_id.to_string(), files);
+        }
+    }
+
+    /// Files the user approved out of the patch requested for `call_id`.
+    pub(crate) async fn approved_patch_files(&self, call_id: &str) -> Option<Vec<PathBuf>> {
+        let active = self.active_turn.lock().await;
+        let active = active.as_ref()?;
+        let ts = active.turn_state.lock().await;
+        ts.approved_patch_files(call_id)
+    }
+
+    pub(crate) async fn record_post_run_assertions(
+        &self,
+        call_id: &str,
+        assertions: Vec<PostRunAssertion>,
+    ) {
+        let mut active = self.active_turn.lock().await;
+        if let Some(at) = active.as_mut() {
+            let mut ts = at.turn_state.lock().await;
+            ts.record_post_run_assertions(call_id.to_string(), assertions);
+        }
+    }
+
+    /// Assertions the user attached when approving `call_id`; returns them only once.
+    pub(crate) async fn take_post_run_assertions(&self, call_id: &str) -> Vec<PostRunAssertion> {
+        let mut active = self.active_turn.lock().await;
+        let Some(at) = active.as_mut() else {
+            return Vec::new();
+        };
+        let mut ts = at.turn_state.lock().await;
+        ts.take_post_run_assertions(call_id)
+    }
+
     pub(crate) async fn granted_session_permissions(&self) -> Option<PermissionProfile> {
         let state = self.state.lock().await;
         state.granted_permissions()
@@ -4112,6 +4182,22 @@ async fn submission_loop(sess: Arc<Session>, config: Arc<Config>, rx_sub: Receiv
                     handlers::exec_approval(&sess, approval_id, turn_id, decision).await;
                     false
                 }
+                Op::ApprovalResponse {
+                    id,
+                    turn_id,
+                    response,
+                } => {
+                    handlers::approval_response(&sess, id, turn_id, response).await;
+                    false
+                }
+                Op::RequestExplanation { id } => {
+                    tokio::spawn(explain_pending_command(
+                        Arc::clone(&sess),
+                        sub.id.clone(),
+                        id,
+                    ));
+                    false
+                }
                 Op::PatchApproval { id, decision } => {
                     handlers::patch_approval(&sess, id, decision).await;
                     false
@@ -4284,6 +4370,8 @@ mod handlers {
     use crate::tasks::UserShellCommandMode;
     use crate::tasks::UserShellCommandTask;
     use crate::tasks::execute_user_shell_command;
+    use codex_protocol::approvals::ApprovalOverrides;
+    use codex_protocol::approvals::ApprovalResponse;
     use codex_protocol::custom_prompts::CustomPrompt;
     use codex_protocol::protocol::CodexErrorInfo;
     use codex_protocol::protocol::ErrorEvent;
@@ -4315,6 +4403,8 @@ mod handlers {
     use codex_protocol::config_types::Settings;
     use codex_protocol::dynamic_tools::DynamicToolResponse;
     use codex_protocol::mcp::RequestId as ProtocolRequestId;
+    use codex_protocol::models::ContentItem;
+    use codex_protocol::models::ResponseInputItem;
     use codex_protocol::user_input::UserInput;
     use codex_rmcp_client::ElicitationAction;
     use codex_rmcp_client::ElicitationResponse;
@@ -4532,6 +4622,68 @@ mod handlers {
         }
     }
 
+    /// Resolve an exec or patch approval from a structured response: apply
+    /// any overrides, pass feedback on to the model, then deliver the decision.
+    pub async fn approval_response(
+        sess: &Arc<Session>,
+        id: String,
+        turn_id: Option<String>,
+        response: ApprovalResponse,
+    ) {
+        let ApprovalResponse {
+            version: _,
+            decision,
+            feedback,
+            overrides:
+                ApprovalOverrides {
+                    command,
+                    included_files,
+                },
+            assertions,
+        } = response;
+        // An edited command is approved for this run only: remembering the
+        // approval would key it on the command the agent proposed instead.
+        let decision = if command.is_some()
+            && matches!(
+                decision,
+                ReviewDecision::ApprovedExecpolicyAmendment { .. }
+                    | ReviewDecision::ApprovedForSession
+                    | ReviewDecision::ApprovedForDirectory
+                    | ReviewDecision::ApprovedForDuration { .. }
+                    | ReviewDecision::ApprovedAlways
+            ) {
+            ReviewDecision::Approved
+        } else {
+            decision
+        };
+        if !matches!(
+            decision,
+            ReviewDecision::Denied | ReviewDecision::DeniedForSession | ReviewDecision::Abort
+        ) {
+            if let Some(command) = command {
+                sess.record_approved_command_override(&id, command).await;
+            }
+            if let Some(files) = included_files {
+                sess.record_approved_patch_files(&id, files).await;
+            }
+            if !assertions.is_empty() {
+                sess.record_post_run_assertions(&id, assertions).await;
+            }
+        }
+        if let Some(feedback) = feedback.filter(|feedback| !feedback.trim().is_empty()) {
+            let note = ResponseInputItem::Message {
+                role: "user".to_string(),
+                content: vec![ContentItem::InputText {
+                    text: format!("Feedback on approval request {id}: {feedback}"),
+                }],
+            };
+            if sess.inject_response_items(vec![note]).await.is_err() {
+                tracing::warn!("dropping approval feedback for {id}: no active turn");
+            }
+        }
+        exec_approval(sess, id, turn_id, decision).await;
+    }
+
     pub async fn patch_approval(sess: &Arc<Session>, id: String, decision: ReviewDecision) {
         match decision {
             ReviewDecision::Abort => {
@@ -6497,6 +6649,7 @@ fn realtime_text_for_event(msg: &EventMsg) -> Option<String> {
         },
         EventMsg::Error(_)
         | EventMsg::Warning(_)
+        | EventMsg::ApprovalExplanation(_)
         | EventMsg::RealtimeConversationStarted(_)
         | EventMsg::RealtimeConversationRealtime(_)
         | EventMsg::RealtimeConversationClosed(_)
@@ -345,6 +345,7 @@ async fn handle_exec_approval(
         approval_id,
         command,
         cwd,
+        env,
         reason,
         network_approval_context,
         proposed_execpolicy_amendment,
@@ -360,6 +361,7 @@ async fn handle_exec_approval(
         approval_id,
         command,
         cwd,
+        env,
         reason,
         network_approval_context,
         proposed_execpolicy_amendment,
@@ -2297,6 +2297,44 @@ async fn notify_request_permissions_response_ignores_unmatched_call_id() {
     assert_eq!(session.granted_turn_permissions().await, None);
 }
 
+#[tokio::test]
+async fn approval_response_with_edited_command_is_not_remembered() {
+    let (session, _turn_context) = make_session_and_context().await;
+    *session.active_turn.lock().await = Some(ActiveTurn::default());
+    let session = Arc::new(session);
+    let (tx_approve, rx_approve) = oneshot::channel();
+    {
+        let active = session.active_turn.lock().await;
+        let active = active.as_ref().expect("active turn");
+        active
+            .turn_state
+            .lock()
+            .await
+            .insert_pending_approval("call-1".to_string(), tx_approve);
+    }
+    let edited = vec!["ls".to_string(), "-la".to_string()];
+
+    handlers::approval_response(
+        &session,
+        "call-1".to_string(),
+        None,
+        codex_protocol::approvals::ApprovalResponse {
+            overrides: codex_protocol::approvals::ApprovalOverrides {
+                command: Some(edited.clone()),
+                included_files: None,
+            },
+            ..codex_protocol::approvals::ApprovalResponse::new(ReviewDecision::ApprovedForSession)
+        },
+    )
+    .await;
+
+    assert_eq!(rx_approve.await.ok(), Some(ReviewDecision::Approved));
+    assert_eq!(
+        session.approved_command_override("call-1").await,
+        Some(edited)
+    );
+}
+
 #[tokio::test]
 async fn request_permissions_emits_event_when_reject_policy_allows_requests() {
     let (session, mut turn_context, rx) = make_session_and_context_with_rx().await;
@@ -201,6 +201,7 @@ fn config_toml_deserializes_model_availability_nux() {
                     ("gpt-foo".to_string(), 2),
                 ]),
             },
+            approvals: TuiApprovals::default(),
         }
     );
 }
@@ -859,6 +860,90 @@ fn tui_theme_defaults_to_none() {
     assert_eq!(parsed.tui.as_ref().and_then(|t| t.theme.as_deref()), None);
 }
 
+#[test]
+fn tui_approvals_deserializes_from_toml() {
+    let cfg = r#"
+[tui.approvals]
+review_test_changes = true
+test_file_patterns = ["*_test.rs", "spec/"]
+fatigue_nudge = false
+fatigue_threshold = 5
+fatigue_window_secs = 30
+production_target_patterns = ["*prod*", "live-*"]
+post_run_checks = true
+max_width = 100
+max_height = 20
+auto_deny_secs = 30
+mouse = true
+confirm_abort = true
+policy_command = ["approval-policy", "--strict"]
+blocked_commands = ["git push --force*", "rm -rf /*"]
+language = "de"
+compact = true
+bell_on_show = true
+word_diff = true
+copy_on_approve = true
+require_explicit_select = true
+context_lines = 4
+high_contrast = true
+acknowledge_warnings = true
+linger_ms = 300
+session_macros = true
+log_decisions = "full"
+command_synopsis = true
+audit_log = "approvals.jsonl"
+colorblind_safe = true
+extended_decisions = true
+
+[tui.approvals.keys]
+approve = "j"
+abort = "q"
+"#;
+    let parsed = toml::from_str::<ConfigToml>(cfg).expect("TOML deserialization should succeed");
+    assert_eq!(
+        parsed.tui.map(|t| t.approvals),
+        Some(TuiApprovals {
+            review_test_changes: Some(true),
+            test_file_patterns: Some(vec!["*_test.rs".to_string(), "spec/".to_string()]),
+            fatigue_nudge: Some(false),
+            fatigue_threshold: Some(5),
+            fatigue_window_secs: Some(30),
+            production_target_patterns: Some(vec!["*prod*".to_string(), "live-*".to_string()]),
+            post_run_checks: Some(true),
+            max_width: Some(100),
+            max_height: Some(20),
+            auto_deny_secs: Some(30),
+            mouse: Some(true),
+            confirm_abort: Some(true),
+            policy_command: Some(vec!["approval-policy".to_string(), "--strict".to_string()]),
+            blocked_commands: Some(vec![
+                "git push --force*".to_string(),
+                "rm -rf /*".to_string()
+            ]),
+            language: Some(ApprovalLanguage::De),
+            compact: Some(true),
+            bell_on_show: Some(true),
+            word_diff: Some(true),
+            copy_on_approve: Some(true),
+            require_explicit_select: Some(true),
+            context_lines: Some(4),
+            high_contrast: Some(true),
+            acknowledge_warnings: Some(true),
+            linger_ms: Some(300),
+            session_macros: Some(true),
+            log_decisions: Some(DecisionLogLevel::Full),
+            command_synopsis: Some(true),
+            audit_log: Some(PathBuf::from("approvals.jsonl")),
+            colorblind_safe: Some(true),
+            extended_decisions: Some(true),
+            keys: Some(BTreeMap::from([
+                (ApprovalShortcut::Approve, 'j'),
+                (ApprovalShortcut::Abort, 'q'),
+            ])),
+        }),
+    );
+}
+
 #[test]
 fn tui_config_missing_notifications_field_defaults_to_enabled() {
     let cfg = r#"
@@ -880,6 +965,7 @@ fn tui_config_missing_notifications_field_defaults_to_enabled() {
             status_line: None,
             theme: None,
             model_availability_nux: ModelAvailabilityNuxConfig::default(),
+            approvals: TuiApprovals::default(),
         }
     );
 }
@@ -4000,6 +4086,7 @@ fn test_precedence_fixture_with_o3_profile() -> std::io::Result<()> {
             tui_alternate_screen: AltScreenMode::Auto,
             tui_status_line: None,
             tui_theme: None,
+            tui_approvals: TuiApprovals::default(),
             otel: OtelConfig::default(),
         },
         o3_profile_config
@@ -4136,6 +4223,7 @@ fn test_precedence_fixture_with_gpt3_profile() -> std::io::Result<()> {
         tui_alternate_screen: AltScreenMode::Auto,
         tui_status_line: None,
         tui_theme: None,
+        tui_approvals: TuiApprovals::default(),
         otel: OtelConfig::default(),
     };
 
@@ -4270,6 +4358,7 @@ fn test_precedence_fixture_with_zdr_profile() -> std::io::Result<()> {
         tui_alternate_screen: AltScreenMode::Auto,
         tui_status_line: None,
         tui_theme: None,
+        tui_approvals: TuiApprovals::default(),
         otel: OtelConfig::default(),
     };
 
@@ -4390,6 +4479,7 @@ fn test_precedence_fixture_with_gpt5_profile() -> std::io::Result<()> {
         tui_alternate_screen: AltScreenMode::Auto,
         tui_status_line: None,
         tui_theme: None,
+        tui_approvals: TuiApprovals::default(),
         otel: OtelConfig::default(),
     };
 
@@ -105,6 +105,7 @@ mod permissions;
 pub mod profile;
 pub mod schema;
 pub mod service;
+mod tui_approvals;
 pub mod types;
 pub use codex_config::Constrained;
 pub use codex_config::ConstraintError;
@@ -122,6 +123,12 @@ pub use permissions::PermissionsToml;
 pub(crate) use permissions::resolve_permission_profile;
 pub use service::ConfigService;
 pub use service::ConfigServiceError;
+pub use tui_approvals::ApprovalLanguage;
+pub use tui_approvals::ApprovalShortcut;
+pub use tui_approvals::DEFAULT_PRODUCTION_TARGET_PATTERNS;
+pub use tui_approvals::DEFAULT_TEST_FILE_PATTERNS;
+pub use tui_approvals::DecisionLogLevel;
+pub use tui_approvals::TuiApprovals;
 
 pub use codex_git::GhostSnapshotConfig;
 
@@ -320,6 +327,9 @@ pub struct Config {
     /// Syntax highlighting theme override (kebab-case name).
     pub tui_theme: Option<String>,
 
+    /// Approval modal settings from `[tui.approvals]`.
+    pub tui_approvals: TuiApprovals,
+
     /// The directory that should be treated as the current working directory
     /// for the session. All relative paths inside the business-logic layer are
     /// resolved against this path.
@@ -2487,6 +2497,11 @@ impl Config {
                 .unwrap_or_default(),
             tui_status_line: cfg.tui.as_ref().and_then(|t| t.status_line.clone()),
             tui_theme: cfg.tui.as_ref().and_then(|t| t.theme.clone()),
+            tui_approvals: cfg
+                .tui
+                .as_ref()
+                .map(|t| t.approvals.clone())
+                .unwrap_or_default(),
             otel: {
                 let t: OtelConfigToml = cfg.otel.unwrap_or_default();
                 let log_user_prompt = t.log_user_prompt.unwrap_or(false);
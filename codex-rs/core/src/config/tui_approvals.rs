@@ -0,0 +1,278 @@
+//! Settings for the TUI approval modal, loaded from `[tui.approvals]`.
+
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde::Serialize;
+
+/// Patterns used to detect test files when `test_file_patterns` is unset.
+pub const DEFAULT_TEST_FILE_PATTERNS: &[&str] = &[
+    "tests/",
+    "test/",
+    "__tests__/",
+    "*_test.rs",
+    "*_tests.rs",
+    "*_test.go",
+    "test_*.py",
+    "*_test.py",
+    "*.spec.*",
+    "*.test.*",
+];
+
+/// Patterns used to flag production hosts and contexts when
+/// `production_target_patterns` is unset.
+pub const DEFAULT_PRODUCTION_TARGET_PATTERNS: &[&str] = &["*prod*"];
+
+/// Language of the approval modal's option labels.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ApprovalLanguage {
+    #[default]
+    En,
+    De,
+}
+
+/// An answer in the approval modal whose shortcut key can be changed with
+/// `keys`.
+#[derive(
+    Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, JsonSchema,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum ApprovalShortcut {
+    /// "Yes, proceed" and the other one-time approvals. Defaults to `y`.
+    Approve,
+    /// Approve for the rest of the session. Defaults to `a`.
+    ApproveForSession,
+    /// Approve in this directory for the session. Defaults to `D`.
+    ApproveForDirectory,
+    /// Approve for a while, picking how long. Defaults to `t`.
+    ApproveForDuration,
+    /// Approve across sessions. Defaults to `A`.
+    ApproveAlways,
+    /// Run the command sandboxed. Defaults to `s`.
+    ApproveSandboxed,
+    /// Dry-run the command read-only first. Defaults to `r`.
+    DryRun,
+    /// Approve commands that start with the proposed prefix. Defaults to `p`.
+    AllowPrefix,
+    /// Decline and let the agent continue. Defaults to `d`.
+    Deny,
+    /// Decline the command for the rest of the session. Defaults to `x`.
+    DenyForSession,
+    /// Decline and tell Codex what to do differently. Defaults to `n`.
+    Abort,
+}
+
+/// How much the transcript records about each approval decision.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum DecisionLogLevel {
+    Full,
+    #[default]
+    Compact,
+    None,
+}
+
+/// Settings that control how the TUI presents approval requests.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct TuiApprovals {
+    /// When `true`, patches that modify test files open the full diff and
+    /// preselect "No" so test changes get an explicit review.
+    /// Defaults to `false`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub review_test_changes: Option<bool>,
+
+    /// Patterns identifying test files. A pattern ending in `/` matches any
+    /// directory with that name, a pattern containing `/` matches the path
+    /// relative to the patch root, and any other pattern matches the file name.
+    /// `*` and `?` are wildcards.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub test_file_patterns: Option<Vec<String>>,
+
+    /// Show a one-time nudge after many approvals in quick succession.
+    /// Defaults to `true`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fatigue_nudge: Option<bool>,
+
+    /// Number of consecutive approvals within `fatigue_window_secs` that
+    /// triggers the nudge. Defaults to `10`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fatigue_threshold: Option<u32>,
+
+    /// Window, in seconds, used to count rapid approvals. Defaults to `60`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fatigue_window_secs: Option<u64>,
+
+    /// Case-insensitive patterns that mark a remote host (ssh, scp, rsync) or
+    /// Kubernetes context as production. Matching commands preselect "No" and
+    /// disable approval shortcuts. `*` and `?` are wildcards.
+    /// Defaults to `["*prod*"]`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub production_target_patterns: Option<Vec<String>>,
+
+    /// Offer "approve and check afterward" options on exec approvals. Core
+    /// verifies the chosen condition (exit code 0, or that the file the
+    /// command creates exists) and reports the result to the model.
+    /// Defaults to `false`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub post_run_checks: Option<bool>,
+
+    /// Maximum width, in columns, of the approval modal. On wider terminals
+    /// the modal is drawn as a centered column of this width. Unset or `0`
+    /// uses the full width.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_width: Option<u16>,
+
+    /// Maximum height, in rows, of the approval modal. A taller prompt is
+    /// clipped and scrolls with PgUp/PgDn so the options and footer stay on
+    /// screen. Unset or `0` lets the modal grow to fit its prompt.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_height: Option<u16>,
+
+    /// Seconds to wait for a response before the approval modal declines the
+    /// request on its own. Pressing any key cancels the countdown. Unset
+    /// disables the timeout.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auto_deny_secs: Option<u64>,
+
+    /// Capture the mouse while the approval modal is open so options can be
+    /// hovered and clicked. The terminal's own text selection is unavailable
+    /// while the modal is shown. Defaults to `false`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mouse: Option<bool>,
+
+    /// Require a second Esc before the approval modal aborts the request.
+    /// Ctrl-C still aborts immediately. Defaults to `false`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub confirm_abort: Option<bool>,
+
+    /// Program, as argv, asked about each command before the approval modal
+    /// opens. It reads `{"kind": "exec", "command": [...], "cwd": "..."}` on
+    /// stdin. Exit status 0 approves the command, a non-zero status with a
+    /// reason on stdout declines it, and anything else shows the modal.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub policy_command: Option<Vec<String>>,
+
+    /// Patterns for commands that may not be approved, matched against the
+    /// whole command as shown in the modal. `*` and `?` are wildcards.
+    /// Approving a matching command is refused with a note naming this
+    /// setting; declining still works.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub blocked_commands: Option<Vec<String>>,
+
+    /// Language of the option labels. Each label's shortcut key follows its
+    /// translation, so `y`/`n` become the first letters of the translated
+    /// yes and no answers. Defaults to `en`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<ApprovalLanguage>,
+
+    /// Draw the approval modal in two rows: a one-line summary of the request
+    /// and the highlighted option. Space shows the full prompt. Defaults to
+    /// `false`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub compact: Option<bool>,
+
+    /// Ring the terminal bell when an approval modal opens. Requests that
+    /// arrive within a few seconds of each other ring once. Defaults to
+    /// `false`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bell_on_show: Option<bool>,
+
+    /// Highlight the changed words within edited lines of a patch preview,
+    /// dimming the rest of the line. Defaults to `false`, as it compares each
+    /// edited line token by token.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub word_diff: Option<bool>,
+
+    /// Copy an approved command to the clipboard, quoted so it can be pasted
+    /// into a shell and run again. Defaults to `false`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub copy_on_approve: Option<bool>,
+
+    /// On a risky request (a destructive command, a production target, a
+    /// high-risk patch), ignore Enter until an option has been selected with
+    /// the arrow keys or a shortcut. Defaults to `false`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub require_explicit_select: Option<bool>,
+
+    /// Shortcut keys for the answers, replacing their defaults, such as
+    /// `approve = "j"`. Answers left out keep their default key. When two
+    /// answers would share a key, or a key is one the modal already uses,
+    /// every answer keeps its default.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub keys: Option<BTreeMap<ApprovalShortcut, char>>,
+
+    /// Number of recent transcript lines shown dimmed above the approval
+    /// prompt, so it carries what the agent did just before asking. Unset or
+    /// `0` shows none.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context_lines: Option<u16>,
+
+    /// Draw the approval modal for low vision: text in the terminal's own
+    /// foreground and background with no dimmed or colored spans, the
+    /// selected option in bold reversed video and a thick border on every
+    /// side. This aims at the WCAG contrast criteria (1.4.3 and 1.4.11) by
+    /// drawing with the highest contrast the terminal offers. Defaults to
+    /// `false`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub high_contrast: Option<bool>,
+
+    /// On the most dangerous requests (a destructive command, a high-risk
+    /// patch), show the options disabled until the warning is acknowledged
+    /// with space. Defaults to `false`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub acknowledge_warnings: Option<bool>,
+
+    /// Milliseconds the approval modal stays open after the last answer,
+    /// showing `→ sent: Approved, waiting…` to confirm the key press
+    /// registered. The answer is sent at once either way. Defaults to `0`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub linger_ms: Option<u64>,
+
+    /// Offer "Yes, and save it as a session macro" on command approvals. It
+    /// asks for a short name, approves the command, and auto-approves
+    /// identical commands (same argv and working directory) for the rest of
+    /// the session. Defaults to `false`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session_macros: Option<bool>,
+
+    /// How much the transcript records about each approval decision: `full`
+    /// keeps the sentence describing the decision and any feedback,
+    /// `compact` a single `✔ approved: <command>` line, and `none` nothing.
+    /// Defaults to `compact`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub log_decisions: Option<DecisionLogLevel>,
+
+    /// Show a one-line description of what a command's program does, dimmed
+    /// under the command: from a bundled table of common tools, or from the
+    /// `whatis` database for others, looked up in the background. Defaults
+    /// to `false`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub command_synopsis: Option<bool>,
+
+    /// Append a JSON line to this file for every approval decision: when it
+    /// was made, the session, what was asked, the decision and any
+    /// feedback, with secrets redacted. A relative path is taken from
+    /// `$CODEX_HOME`. Unset writes no audit log.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub audit_log: Option<PathBuf>,
+
+    /// Draw the approval modal for red-green color blindness: blue and
+    /// orange take the place of green and red, in the modal's own
+    /// highlights and in the diffs it shows, and risk badges and the
+    /// modal's left edge carry a shape as well as a color. Defaults to
+    /// `false`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub colorblind_safe: Option<bool>,
+
+    /// Offer more answers on command approvals than the agent asks for:
+    /// approve for a while or across sessions, run sandboxed, dry run
+    /// read-only first, and decline for the session. Opt-in because they
+    /// more than double the default list, and app-server clients cannot
+    /// offer them. Defaults to `false`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub extended_decisions: Option<bool>,
+}
@@ -8,6 +8,8 @@
 mod analytics_client;
 pub mod api_bridge;
 mod apply_patch;
+mod approval_explanation;
+pub mod approved_commands;
 mod apps;
 mod arc_monitor;
 pub mod auth;
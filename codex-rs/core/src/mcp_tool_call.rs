@@ -642,11 +642,22 @@ fn build_guardian_mcp_tool_review_request(
 
 fn mcp_tool_approval_decision_from_guardian(decision: ReviewDecision) -> McpToolApprovalDecision {
     match decision {
+        // MCP session approvals do not expire, so an approval for a while
+        // only covers this call.
         ReviewDecision::Approved
         | ReviewDecision::ApprovedExecpolicyAmendment { .. }
+        | ReviewDecision::ApprovedForDuration { .. }
         | ReviewDecision::NetworkPolicyAmendment { .. } => McpToolApprovalDecision::Accept,
-        ReviewDecision::ApprovedForSession => McpToolApprovalDecision::AcceptForSession,
-        ReviewDecision::Denied | ReviewDecision::Abort => McpToolApprovalDecision::Decline,
+        ReviewDecision::ApprovedForSession
+        | ReviewDecision::ApprovedForDirectory
+        | ReviewDecision::ApprovedAlways => McpToolApprovalDecision::AcceptForSession,
+        // MCP tools run outside the exec sandbox, so a sandboxed approval
+        // cannot be honored.
+        ReviewDecision::ApprovedSandboxed
+        | ReviewDecision::DryRun
+        | ReviewDecision::Denied
+        | ReviewDecision::DeniedForSession
+        | ReviewDecision::Abort => McpToolApprovalDecision::Decline,
     }
 }
 
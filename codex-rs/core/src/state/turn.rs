@@ -2,6 +2,7 @@
 
 use indexmap::IndexMap;
 use std::collections::HashMap;
+use std::path::PathBuf;
 use std::sync::Arc;
 use tokio::sync::Mutex;
 use tokio::sync::Notify;
@@ -17,6 +18,7 @@ use rmcp::model::RequestId;
 use tokio::sync::oneshot;
 
 use crate::codex::TurnContext;
+use crate::protocol::PostRunAssertion;
 use crate::protocol::ReviewDecision;
 use crate::protocol::TokenUsage;
 use crate::sandboxing::merge_permission_profiles;
@@ -76,12 +78,17 @@ impl ActiveTurn {
 #[derive(Default)]
 pub(crate) struct TurnState {
     pending_approvals: HashMap<String, oneshot::Sender<ReviewDecision>>,
+    /// Command and cwd of each pending exec approval, for explanations.
+    pending_approval_commands: HashMap<String, (Vec<String>, PathBuf)>,
     pending_request_permissions: HashMap<String, oneshot::Sender<RequestPermissionsResponse>>,
     pending_user_input: HashMap<String, oneshot::Sender<RequestUserInputResponse>>,
     pending_elicitations: HashMap<(String, RequestId), oneshot::Sender<ElicitationResponse>>,
     pending_dynamic_tools: HashMap<String, oneshot::Sender<DynamicToolResponse>>,
     pending_input: Vec<ResponseInputItem>,
     granted_permissions: Option<PermissionProfile>,
+    approved_command_overrides: HashMap<String, Vec<String>>,
+    approved_patch_files: HashMap<String, Vec<PathBuf>>,
+    post_run_assertions: HashMap<String, Vec<PostRunAssertion>>,
     pub(crate) tool_calls: u64,
     pub(crate) token_usage_at_turn_start: TokenUsage,
 }
@@ -99,11 +106,26 @@ impl TurnState {
         &mut self,
         key: &str,
     ) -> Option<oneshot::Sender<ReviewDecision>> {
+        self.pending_approval_commands.remove(key);
         self.pending_approvals.remove(key)
     }
 
+    pub(crate) fn insert_pending_approval_command(
+        &mut self,
+        key: String,
+        command: Vec<String>,
+        cwd: PathBuf,
+    ) {
+        self.pending_approval_commands.insert(key, (command, cwd));
+    }
+
+    pub(crate) fn pending_approval_command(&self, key: &str) -> Option<(Vec<String>, PathBuf)> {
+        self.pending_approval_commands.get(key).cloned()
+    }
+
     pub(crate) fn clear_pending(&mut self) {
         self.pending_approvals.clear();
+        self.pending_approval_commands.clear();
         self.pending_request_permissions.clear();
         self.pending_user_input.clear();
         self.pending_elicitations.clear();
@@ -201,6 +223,45 @@ impl TurnState {
     pub(crate) fn granted_permissions(&self) -> Option<PermissionProfile> {
         self.granted_permissions.clone()
     }
+
+    /// Record the argv the user approved in place of the command originally
+    /// requested for `call_id`.
+    pub(crate) fn record_approved_command_override(
+        &mut self,
+        call_id: String,
+        command: Vec<String>,
+    ) {
+        self.approved_command_overrides.insert(call_id, command);
+    }
+
+    /// The override is kept for the rest of the turn so sandbox retries of the
+    /// same call run the approved command as well.
+    pub(crate) fn approved_command_override(&self, call_id: &str) -> Option<Vec<String>> {
+        self.approved_command_overrides.get(call_id).cloned()
+    }
+
+    /// Record the files of the patch requested for `call_id` that the user
+    /// approved; the rest of the patch is skipped.
+    pub(crate) fn record_approved_patch_files(&mut self, call_id: String, files: Vec<PathBuf>) {
+        self.approved_patch_files.insert(call_id, files);
+    }
+
+    pub(crate) fn approved_patch_files(&self, call_id: &str) -> Option<Vec<PathBuf>> {
+        self.approved_patch_files.get(call_id).cloned()
+    }
+
+    pub(crate) fn record_post_run_assertions(
+        &mut self,
+        call_id: String,
+        assertions: Vec<PostRunAssertion>,
+    ) {
+        self.post_run_assertions.insert(call_id, assertions);
+    }
+
+    /// Assertions are checked once, when the command first produces a result.
+    pub(crate) fn take_post_run_assertions(&mut self, call_id: &str) -> Vec<PostRunAssertion> {
+        self.post_run_assertions.remove(call_id).unwrap_or_default()
+    }
 }
 
 impl ActiveTurn {
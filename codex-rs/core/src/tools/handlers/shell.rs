@@ -25,6 +25,7 @@ use crate::tools::handlers::normalize_and_validate_additional_permissions;
 use crate::tools::handlers::parse_arguments_with_base_path;
 use crate::tools::handlers::resolve_workdir_base_path;
 use crate::tools::orchestrator::ToolOrchestrator;
+use crate::tools::post_run_assertions::report_post_run_assertions;
 use crate::tools::registry::ToolHandler;
 use crate::tools::registry::ToolKind;
 use crate::tools::runtimes::shell::ShellRequest;
@@ -450,6 +451,16 @@ impl ShellHandler {
             )
             .await
             .map(|result| result.output);
+        if let Ok(output) = &out {
+            report_post_run_assertions(
+                session.as_ref(),
+                &call_id,
+                req.cwd.as_path(),
+                Some(output.exit_code),
+                &output.aggregated_output.text,
+            )
+            .await;
+        }
         let event_ctx = ToolEventCtx::new(session.as_ref(), turn.as_ref(), &call_id, None);
         let content = emitter.finish(event_ctx, out).await?;
         Ok(FunctionToolOutput::from_text(content, Some(true)))
@@ -16,6 +16,7 @@ use crate::tools::handlers::normalize_and_validate_additional_permissions;
 use crate::tools::handlers::parse_arguments;
 use crate::tools::handlers::parse_arguments_with_base_path;
 use crate::tools::handlers::resolve_workdir_base_path;
+use crate::tools::post_run_assertions::report_post_run_assertions;
 use crate::tools::registry::ToolHandler;
 use crate::tools::registry::ToolKind;
 use crate::unified_exec::ExecCommandRequest;
@@ -242,7 +243,7 @@ impl ToolHandler for UnifiedExecHandler {
                     });
                 }
 
-                manager
+                let response = manager
                     .exec_command(
                         ExecCommandRequest {
                             command,
@@ -265,7 +266,16 @@ impl ToolHandler for UnifiedExecHandler {
                     .await
                     .map_err(|err| {
                         FunctionCallError::RespondToModel(format!("exec_command failed: {err:?}"))
-                    })?
+                    })?;
+                report_post_run_assertions(
+                    session.as_ref(),
+                    &context.call_id,
+                    cwd.as_path(),
+                    response.exit_code,
+                    &String::from_utf8_lossy(&response.raw_output),
+                )
+                .await;
+                response
             }
             "write_stdin" => {
                 let args: WriteStdinArgs = parse_arguments(&arguments)?;
@@ -363,6 +363,7 @@ impl NetworkApprovalService {
                     None,
                     prompt_command,
                     turn_context.cwd.clone(),
+                    None,
                     Some(prompt_reason),
                     Some(network_approval_context.clone()),
                     None,
@@ -375,10 +376,14 @@ impl NetworkApprovalService {
 
         let mut cache_session_deny = false;
         let resolved = match approval_decision {
-            ReviewDecision::Approved | ReviewDecision::ApprovedExecpolicyAmendment { .. } => {
-                PendingApprovalDecision::AllowOnce
-            }
-            ReviewDecision::ApprovedForSession => PendingApprovalDecision::AllowForSession,
+            // Host approvals for the session do not expire, so an approval
+            // for a while only allows this request.
+            ReviewDecision::Approved
+            | ReviewDecision::ApprovedExecpolicyAmendment { .. }
+            | ReviewDecision::ApprovedForDuration { .. } => PendingApprovalDecision::AllowOnce,
+            ReviewDecision::ApprovedForSession
+            | ReviewDecision::ApprovedForDirectory
+            | ReviewDecision::ApprovedAlways => PendingApprovalDecision::AllowForSession,
             ReviewDecision::NetworkPolicyAmendment {
                 network_policy_amendment,
             } => match network_policy_amendment.action {
@@ -448,7 +453,13 @@ impl NetworkApprovalService {
                     PendingApprovalDecision::Deny
                 }
             },
-            ReviewDecision::Denied | ReviewDecision::Abort => {
+            // Network prompts do not offer a dry run, and a sandboxed run has
+            // no network.
+            ReviewDecision::ApprovedSandboxed
+            | ReviewDecision::DryRun
+            | ReviewDecision::Denied
+            | ReviewDecision::DeniedForSession
+            | ReviewDecision::Abort => {
                 if routes_approval_to_guardian(&turn_context) {
                     self.record_outcome_for_single_active_call(
                         NetworkApprovalOutcome::DeniedByPolicy(
@@ -4,11 +4,15 @@ Module: orchestrator
 Central place for approvals + sandbox selection + retry semantics. Drives a
 simple sequence for any ToolRuntime: approval → select sandbox → attempt →
 retry with an escalated sandbox strategy on denial (no re‑approval thanks to
-caching).
+caching). A dry-run decision runs the request read-only first and prompts
+again if the sandbox blocks it; a sandboxed approval runs it read-only once
+and keeps whatever it produced. A command the user edited in the prompt is
+checked against exec policy on its own and starts sandboxed.
 */
 use crate::error::CodexErr;
 use crate::error::SandboxErr;
 use crate::exec::ExecToolCallOutput;
+use crate::exec_policy::ExecApprovalRequest;
 use crate::features::Feature;
 use crate::guardian::GUARDIAN_REJECTION_MESSAGE;
 use crate::guardian::routes_approval_to_guardian;
@@ -23,14 +27,22 @@ use crate::tools::sandboxing::ApprovalCtx;
 use crate::tools::sandboxing::ExecApprovalRequirement;
 use crate::tools::sandboxing::SandboxAttempt;
 use crate::tools::sandboxing::SandboxOverride;
+use crate::tools::sandboxing::SandboxablePreference;
 use crate::tools::sandboxing::ToolCtx;
 use crate::tools::sandboxing::ToolError;
 use crate::tools::sandboxing::ToolRuntime;
 use crate::tools::sandboxing::default_exec_approval_requirement;
 use codex_otel::ToolDecisionSource;
+use codex_protocol::models::SandboxPermissions;
+use codex_protocol::permissions::FileSystemSandboxPolicy;
+use codex_protocol::permissions::NetworkSandboxPolicy;
 use codex_protocol::protocol::AskForApproval;
 use codex_protocol::protocol::NetworkPolicyRuleAction;
 use codex_protocol::protocol::ReviewDecision;
+use codex_protocol::protocol::SandboxPolicy;
+
+/// Lines of output kept in the reason after a blocked dry run.
+const DRY_RUN_REASON_LINES: usize = 20;
 
 pub(crate) struct ToolOrchestrator {
     sandbox: SandboxManager,
@@ -118,6 +130,7 @@ impl ToolOrchestrator {
 
         // 1) Approval
         let mut already_approved = false;
+        let mut edited_command = false;
 
         let requirement = tool.exec_approval_requirement(req).unwrap_or_else(|| {
             default_exec_approval_requirement(approval_policy, &turn_ctx.sandbox_policy)
@@ -130,39 +143,82 @@ impl ToolOrchestrator {
                 return Err(ToolError::Rejected(reason));
             }
             ExecApprovalRequirement::NeedsApproval { reason, .. } => {
-                let approval_ctx = ApprovalCtx {
-                    session: &tool_ctx.session,
-                    turn: &tool_ctx.turn,
-                    call_id: &tool_ctx.call_id,
-                    retry_reason: reason,
-                    network_approval_context: None,
-                };
-                let decision = tool.start_approval_async(req, approval_ctx).await;
+                let mut retry_reason = reason;
+                loop {
+                    let approval_ctx = ApprovalCtx {
+                        session: &tool_ctx.session,
+                        turn: &tool_ctx.turn,
+                        call_id: &tool_ctx.call_id,
+                        retry_reason: retry_reason.take(),
+                        network_approval_context: None,
+                    };
+                    let decision = tool.start_approval_async(req, approval_ctx).await;
 
-                otel.tool_decision(otel_tn, otel_ci, &decision, otel_user.clone());
+                    otel.tool_decision(otel_tn, otel_ci, &decision, otel_user.clone());
 
-                match decision {
-                    ReviewDecision::Denied | ReviewDecision::Abort => {
-                        let reason = if routes_approval_to_guardian(turn_ctx) {
-                            GUARDIAN_REJECTION_MESSAGE.to_string()
-                        } else {
-                            "rejected by user".to_string()
-                        };
-                        return Err(ToolError::Rejected(reason));
-                    }
-                    ReviewDecision::Approved
-                    | ReviewDecision::ApprovedExecpolicyAmendment { .. }
-                    | ReviewDecision::ApprovedForSession => {}
-                    ReviewDecision::NetworkPolicyAmendment {
-                        network_policy_amendment,
-                    } => match network_policy_amendment.action {
-                        NetworkPolicyRuleAction::Allow => {}
-                        NetworkPolicyRuleAction::Deny => {
-                            return Err(ToolError::Rejected("rejected by user".to_string()));
+                    match decision {
+                        ReviewDecision::Denied | ReviewDecision::Abort => {
+                            let reason = if routes_approval_to_guardian(turn_ctx) {
+                                GUARDIAN_REJECTION_MESSAGE.to_string()
+                            } else {
+                                "rejected by user".to_string()
+                            };
+                            return Err(ToolError::Rejected(reason));
+                        }
+                        ReviewDecision::DeniedForSession => {
+                            return Err(ToolError::Rejected(
+                                "rejected by user for the rest of this session".to_string(),
+                            ));
+                        }
+                        ReviewDecision::Approved
+                        | ReviewDecision::ApprovedExecpolicyAmendment { .. }
+                        | ReviewDecision::ApprovedForSession
+                        | ReviewDecision::ApprovedForDirectory
+                        | ReviewDecision::ApprovedForDuration { .. }
+                        | ReviewDecision::ApprovedAlways => {}
+                        ReviewDecision::ApprovedSandboxed => {
+                            let Some(result) =
+                                self.run_read_only(tool, req, tool_ctx, turn_ctx).await
+                            else {
+                                return Err(ToolError::Rejected(
+                                    "no sandbox is available on this platform to run the \
+                                     command sandboxed"
+                                        .to_string(),
+                                ));
+                            };
+                            return result.map(|output| OrchestratorRunResult {
+                                output,
+                                deferred_network_approval: None,
+                            });
+                        }
+                        ReviewDecision::DryRun => {
+                            match self.dry_run(tool, req, tool_ctx, turn_ctx).await {
+                                DryRunOutcome::Finished(result) => {
+                                    return result.map(|output| OrchestratorRunResult {
+                                        output,
+                                        deferred_network_approval: None,
+                                    });
+                                }
+                                DryRunOutcome::Blocked(reason) => {
+                                    // Same call id, so this prompts for the same approval.
+                                    retry_reason = Some(reason);
+                                    continue;
+                                }
+                            }
                         }
-                    },
+                        ReviewDecision::NetworkPolicyAmendment {
+                            network_policy_amendment,
+                        } => match network_policy_amendment.action {
+                            NetworkPolicyRuleAction::Allow => {}
+                            NetworkPolicyRuleAction::Deny => {
+                                return Err(ToolError::Rejected("rejected by user".to_string()));
+                            }
+                        },
+                    }
+                    break;
                 }
                 already_approved = true;
+                edited_command = Self::check_edited_command(tool_ctx, approval_policy).await?;
             }
         }
 
@@ -173,7 +229,14 @@ impl ToolOrchestrator {
             .requirements_toml()
             .network
             .is_some();
-        let initial_sandbox = match tool.sandbox_mode_for_first_attempt(req) {
+        // An edited command starts in the sandbox even when the proposed one
+        // was allowed to skip it.
+        let first_attempt_override = if edited_command {
+            SandboxOverride::NoOverride
+        } else {
+            tool.sandbox_mode_for_first_attempt(req)
+        };
+        let initial_sandbox = match first_attempt_override {
             SandboxOverride::BypassSandboxFirstAttempt => crate::exec::SandboxType::None,
             SandboxOverride::NoOverride => self.sandbox.select_initial(
                 &turn_ctx.file_system_sandbox_policy,
@@ -271,8 +334,10 @@ impl ToolOrchestrator {
                     };
 
                 // Ask for approval before retrying with the escalated sandbox.
+                // An edited command is asked about again: its approval only
+                // covered running it sandboxed.
                 let bypass_retry_approval = tool
-                    .should_bypass_approval(approval_policy, already_approved)
+                    .should_bypass_approval(approval_policy, already_approved && !edited_command)
                     && network_approval_context.is_none();
                 if !bypass_retry_approval {
                     let approval_ctx = ApprovalCtx {
@@ -295,9 +360,31 @@ impl ToolOrchestrator {
                             };
                             return Err(ToolError::Rejected(reason));
                         }
+                        ReviewDecision::DeniedForSession => {
+                            return Err(ToolError::Rejected(
+                                "rejected by user for the rest of this session".to_string(),
+                            ));
+                        }
                         ReviewDecision::Approved
                         | ReviewDecision::ApprovedExecpolicyAmendment { .. }
-                        | ReviewDecision::ApprovedForSession => {}
+                        | ReviewDecision::ApprovedForSession
+                        | ReviewDecision::ApprovedForDirectory
+                        | ReviewDecision::ApprovedForDuration { .. }
+                        | ReviewDecision::ApprovedAlways => {}
+                        // The command already ran sandboxed and was blocked,
+                        // so a second preview cannot show anything new.
+                        ReviewDecision::DryRun => {
+                            return Err(ToolError::Rejected(
+                                "dry run requested after the sandbox blocked the command"
+                                    .to_string(),
+                            ));
+                        }
+                        ReviewDecision::ApprovedSandboxed => {
+                            return Err(ToolError::Rejected(
+                                "sandboxed run requested after the sandbox blocked the command"
+                                    .to_string(),
+                            ));
+                        }
                         ReviewDecision::NetworkPolicyAmendment {
                             network_policy_amendment,
                         } => match network_policy_amendment.action {
@@ -307,6 +394,7 @@ impl ToolOrchestrator {
                             }
                         },
                     }
+                    Self::check_edited_command(tool_ctx, approval_policy).await?;
                 }
 
                 let escalated_attempt = SandboxAttempt {
@@ -339,6 +427,125 @@ impl ToolOrchestrator {
             Err(err) => Err(err),
         }
     }
+
+    /// Check a command the user edited in the approval prompt against exec
+    /// policy on its own; the verdict for the proposed command does not carry
+    /// over to it. Returns whether the call runs an edited command.
+    async fn check_edited_command(
+        tool_ctx: &ToolCtx,
+        approval_policy: AskForApproval,
+    ) -> Result<bool, ToolError> {
+        let Some(command) = tool_ctx
+            .session
+            .approved_command_override(&tool_ctx.call_id)
+            .await
+        else {
+            return Ok(false);
+        };
+        let requirement = tool_ctx
+            .session
+            .services
+            .exec_policy
+            .create_exec_approval_requirement_for_command(ExecApprovalRequest {
+                command: &command,
+                approval_policy,
+                sandbox_policy: tool_ctx.turn.sandbox_policy.get(),
+                sandbox_permissions: SandboxPermissions::UseDefault,
+                prefix_rule: None,
+            })
+            .await;
+        match requirement {
+            ExecApprovalRequirement::Forbidden { reason } => Err(ToolError::Rejected(reason)),
+            // The user reviewed the edited command itself, so an approval
+            // the policy asks for has already been given.
+            ExecApprovalRequirement::Skip { .. }
+            | ExecApprovalRequirement::NeedsApproval { .. } => Ok(true),
+        }
+    }
+
+    /// Run `req` once under a read-only sandbox so the user can preview it.
+    async fn dry_run<Rq, Out, T>(
+        &self,
+        tool: &mut T,
+        req: &Rq,
+        tool_ctx: &ToolCtx,
+        turn_ctx: &crate::codex::TurnContext,
+    ) -> DryRunOutcome<Out>
+    where
+        T: ToolRuntime<Rq, Out>,
+    {
+        match self.run_read_only(tool, req, tool_ctx, turn_ctx).await {
+            None => DryRunOutcome::Blocked(
+                "Dry run unavailable: no sandbox is available on this platform.".to_string(),
+            ),
+            Some(Err(ToolError::Codex(CodexErr::Sandbox(SandboxErr::Denied {
+                output, ..
+            })))) => DryRunOutcome::Blocked(dry_run_reason(&output)),
+            Some(result) => DryRunOutcome::Finished(result),
+        }
+    }
+
+    /// Run `req` once under a read-only sandbox with no network, whatever the
+    /// turn's policy is. Returns `None` when no sandbox is available.
+    async fn run_read_only<Rq, Out, T>(
+        &self,
+        tool: &mut T,
+        req: &Rq,
+        tool_ctx: &ToolCtx,
+        turn_ctx: &crate::codex::TurnContext,
+    ) -> Option<Result<Out, ToolError>>
+    where
+        T: ToolRuntime<Rq, Out>,
+    {
+        let policy = SandboxPolicy::new_read_only_policy();
+        let file_system_policy = FileSystemSandboxPolicy::from(&policy);
+        let network_policy = NetworkSandboxPolicy::from(&policy);
+        let sandbox = self.sandbox.select_initial(
+            &file_system_policy,
+            network_policy,
+            SandboxablePreference::Require,
+            turn_ctx.windows_sandbox_level,
+            false,
+        );
+        if sandbox == crate::exec::SandboxType::None {
+            return None;
+        }
+        let attempt = SandboxAttempt {
+            sandbox,
+            policy: &policy,
+            file_system_policy: &file_system_policy,
+            network_policy,
+            enforce_managed_network: false,
+            manager: &self.sandbox,
+            sandbox_cwd: &turn_ctx.cwd,
+            codex_linux_sandbox_exe: turn_ctx.codex_linux_sandbox_exe.as_ref(),
+            use_linux_sandbox_bwrap: turn_ctx.features.enabled(Feature::UseLinuxSandboxBwrap),
+            windows_sandbox_level: turn_ctx.windows_sandbox_level,
+        };
+        let (result, _) = Self::run_attempt(tool, req, tool_ctx, &attempt, false).await;
+        Some(result)
+    }
+}
+
+/// What a read-only preview of a command found.
+enum DryRunOutcome<Out> {
+    /// The command needed nothing beyond read access, so its result stands.
+    Finished(Result<Out, ToolError>),
+    /// The sandbox stopped the command; prompt again with this reason.
+    Blocked(String),
+}
+
+/// Reason for the prompt after a blocked dry run: the exit code and the tail
+/// of what the command printed before the sandbox stopped it.
+fn dry_run_reason(output: &ExecToolCallOutput) -> String {
+    let lines: Vec<&str> = output.aggregated_output.text.trim_end().lines().collect();
+    let tail = lines[lines.len().saturating_sub(DRY_RUN_REASON_LINES)..].join("\n");
+    let exit_code = output.exit_code;
+    if tail.is_empty() {
+        format!("Dry run was blocked by the read-only sandbox (exit code {exit_code}).")
+    } else {
+        format!("Dry run was blocked by the read-only sandbox (exit code {exit_code}):\n{tail}")
+    }
 }
 
 fn build_denial_reason_from_output(_output: &ExecToolCallOutput) -> String {
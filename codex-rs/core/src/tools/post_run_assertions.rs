@@ -0,0 +1,115 @@
+//! Post-run assertions the user attached when approving a command.
+//!
+//! The approval records the assertions on the turn state keyed by call id. Once the command has
+//! produced its result, the exec handlers call [`report_post_run_assertions`], which checks each
+//! assertion and injects a summary into the turn so the model learns whether its action met the
+//! stated goal.
+
+use std::path::Path;
+
+use codex_protocol::models::ContentItem;
+use codex_protocol::models::ResponseInputItem;
+use codex_protocol::protocol::PostRunAssertion;
+
+use crate::codex::Session;
+
+/// Check and report the assertions recorded for `call_id`, if any.
+///
+/// `exit_code` is `None` while the process is still running; exit-code assertions are then
+/// reported as unchecked rather than failed.
+pub(crate) async fn report_post_run_assertions(
+    session: &Session,
+    call_id: &str,
+    cwd: &Path,
+    exit_code: Option<i32>,
+    output: &str,
+) {
+    let assertions = session.take_post_run_assertions(call_id).await;
+    if assertions.is_empty() {
+        return;
+    }
+    let report = ResponseInputItem::Message {
+        role: "user".to_string(),
+        content: vec![ContentItem::InputText {
+            text: assertion_report(&assertions, cwd, exit_code, output),
+        }],
+    };
+    if session.inject_response_items(vec![report]).await.is_err() {
+        tracing::warn!("dropping post-run assertion report for {call_id}: no active turn");
+    }
+}
+
+fn assertion_report(
+    assertions: &[PostRunAssertion],
+    cwd: &Path,
+    exit_code: Option<i32>,
+    output: &str,
+) -> String {
+    let mut report =
+        "Post-run checks the user attached when approving your last command:".to_string();
+    for assertion in assertions {
+        let status = match assertion {
+            PostRunAssertion::ExitCode { code } => match exit_code {
+                Some(actual) if actual == *code => "PASS".to_string(),
+                Some(actual) => format!("FAIL (exit code was {actual})"),
+                None => "NOT CHECKED (command still running)".to_string(),
+            },
+            PostRunAssertion::PathExists { path } => pass_or_fail(cwd.join(path).exists()),
+            PostRunAssertion::OutputContains { text } => pass_or_fail(output.contains(text)),
+        };
+        report.push_str(&format!("\n- {assertion}: {status}"));
+    }
+    report
+}
+
+fn pass_or_fail(passed: bool) -> String {
+    if passed { "PASS" } else { "FAIL" }.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::path::PathBuf;
+
+    #[test]
+    fn report_lists_each_assertion_with_its_outcome() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        std::fs::write(dir.path().join("built.txt"), "").expect("write file");
+        let assertions = vec![
+            PostRunAssertion::ExitCode { code: 0 },
+            PostRunAssertion::PathExists {
+                path: PathBuf::from("built.txt"),
+            },
+            PostRunAssertion::PathExists {
+                path: PathBuf::from("missing.txt"),
+            },
+            PostRunAssertion::OutputContains {
+                text: "ok".to_string(),
+            },
+        ];
+
+        assert_eq!(
+            assertion_report(&assertions, dir.path(), Some(2), "all ok"),
+            "Post-run checks the user attached when approving your last command:\n\
+             - exit code 0: FAIL (exit code was 2)\n\
+             - `built.txt` exists: PASS\n\
+             - `missing.txt` exists: FAIL\n\
+             - output contains `ok`: PASS"
+        );
+    }
+
+    #[test]
+    fn exit_code_is_unchecked_while_running() {
+        assert_eq!(
+            assertion_report(
+                &[PostRunAssertion::ExitCode { code: 0 }],
+                Path::new("/"),
+                None,
+                ""
+            ),
+            "Post-run checks the user attached when approving your last command:\n\
+             - exit code 0: NOT CHECKED (command still running)"
+        );
+    }
+}
@@ -34,6 +34,7 @@ use crate::tools::sandboxing::ToolError;
 use crate::tools::sandboxing::ToolRuntime;
 use crate::tools::sandboxing::sandbox_override_for_first_attempt;
 use crate::tools::sandboxing::with_cached_approval;
+use crate::tools::sandboxing::with_session_command_decisions;
 use codex_network_proxy::NetworkProxy;
 use codex_protocol::models::PermissionProfile;
 use codex_protocol::protocol::ReviewDecision;
@@ -144,12 +145,24 @@ impl Approvable<ShellRequest> for ShellRuntime {
         let keys = self.approval_keys(req);
         let command = req.command.clone();
         let cwd = req.cwd.clone();
+        let env =
+            (!req.explicit_env_overrides.is_empty()).then(|| req.explicit_env_overrides.clone());
         let retry_reason = ctx.retry_reason.clone();
         let reason = retry_reason.clone().or_else(|| req.justification.clone());
         let session = ctx.session;
         let turn = ctx.turn;
         let call_id = ctx.call_id.to_string();
         Box::pin(async move {
+            if req.additional_permissions.is_none()
+                && session
+                    .services
+                    .tool_approvals
+                    .lock()
+                    .await
+                    .is_always_approved(&command, &cwd)
+            {
+                return ReviewDecision::ApprovedAlways;
+            }
             if routes_approval_to_guardian(turn) {
                 return review_approval_request(
                     session,
@@ -165,26 +178,33 @@ impl Approvable<ShellRequest> for ShellRuntime {
                 )
                 .await;
             }
-            with_cached_approval(&session.services, "shell", keys, move || async move {
-                let available_decisions = None;
-                session
-                    .request_command_approval(
-                        turn,
-                        call_id,
-                        None,
-                        command,
-                        cwd,
-                        reason,
-                        ctx.network_approval_context.clone(),
-                        req.exec_approval_requirement
-                            .proposed_execpolicy_amendment()
-                            .cloned(),
-                        req.additional_permissions.clone(),
-                        None,
-                        available_decisions,
-                    )
-                    .await
-            })
+            with_session_command_decisions(
+                &session.services,
+                &turn.config.codex_home,
+                command.clone(),
+                cwd.clone(),
+                with_cached_approval(&session.services, "shell", keys, move || async move {
+                    let available_decisions = None;
+                    session
+                        .request_command_approval(
+                            turn,
+                            call_id,
+                            None,
+                            command,
+                            cwd,
+                            env,
+                            reason,
+                            ctx.network_approval_context.clone(),
+                            req.exec_approval_requirement
+                                .proposed_execpolicy_amendment()
+                                .cloned(),
+                            req.additional_permissions.clone(),
+                            None,
+                            available_decisions,
+                        )
+                        .await
+                }),
+            )
             .await
         })
     }
@@ -218,8 +238,9 @@ impl ToolRuntime<ShellRequest, ExecToolCallOutput> for ShellRuntime {
         ctx: &ToolCtx,
     ) -> Result<ExecToolCallOutput, ToolError> {
         let session_shell = ctx.session.user_shell();
+        let approved_command = ctx.session.approved_command_override(&ctx.call_id).await;
         let command = maybe_wrap_shell_lc_with_snapshot(
-            &req.command,
+            approved_command.as_ref().unwrap_or(&req.command),
             session_shell.as_ref(),
             &req.cwd,
             &req.explicit_env_overrides,
@@ -464,6 +464,7 @@ impl CoreShellActionProvider {
                         None,
                         None,
                         None,
+                        None,
                         additional_permissions,
                         skill_metadata,
                         Some(available_decisions),
@@ -533,7 +534,10 @@ impl CoreShellActionProvider {
                         .await?
                     {
                         ReviewDecision::Approved
-                        | ReviewDecision::ApprovedExecpolicyAmendment { .. } => {
+                        | ReviewDecision::ApprovedExecpolicyAmendment { .. }
+                        | ReviewDecision::ApprovedForDirectory
+                        | ReviewDecision::ApprovedForDuration { .. }
+                        | ReviewDecision::ApprovedAlways => {
                             if needs_escalation {
                                 EscalationDecision::escalate(escalation_execution.clone())
                             } else {
@@ -581,9 +585,15 @@ impl CoreShellActionProvider {
                                 EscalationDecision::deny(Some("User denied execution".to_string()))
                             }
                         },
-                        ReviewDecision::Denied => {
+                        // Keep the subcommand inside the sandbox it already runs in.
+                        ReviewDecision::ApprovedSandboxed => EscalationDecision::run(),
+                        ReviewDecision::Denied | ReviewDecision::DeniedForSession => {
                             EscalationDecision::deny(Some("User denied execution".to_string()))
                         }
+                        // Not among the decisions offered above.
+                        ReviewDecision::DryRun => EscalationDecision::deny(Some(
+                            "Dry run is not available for subcommands".to_string(),
+                        )),
                         ReviewDecision::Abort => {
                             EscalationDecision::deny(Some("User cancelled execution".to_string()))
                         }
@@ -32,6 +32,7 @@ use crate::tools::sandboxing::ToolError;
 use crate::tools::sandboxing::ToolRuntime;
 use crate::tools::sandboxing::sandbox_override_for_first_attempt;
 use crate::tools::sandboxing::with_cached_approval;
+use crate::tools::sandboxing::with_session_command_decisions;
 use crate::tools::spec::UnifiedExecBackendConfig;
 use crate::unified_exec::NoopSpawnLifecycle;
 use crate::unified_exec::UnifiedExecError;
@@ -112,9 +113,21 @@ impl Approvable<UnifiedExecRequest> for UnifiedExecRuntime<'_> {
         let call_id = ctx.call_id.to_string();
         let command = req.command.clone();
         let cwd = req.cwd.clone();
+        let env =
+            (!req.explicit_env_overrides.is_empty()).then(|| req.explicit_env_overrides.clone());
         let retry_reason = ctx.retry_reason.clone();
         let reason = retry_reason.clone().or_else(|| req.justification.clone());
         Box::pin(async move {
+            if req.additional_permissions.is_none()
+                && session
+                    .services
+                    .tool_approvals
+                    .lock()
+                    .await
+                    .is_always_approved(&command, &cwd)
+            {
+                return ReviewDecision::ApprovedAlways;
+            }
             if routes_approval_to_guardian(turn) {
                 return review_approval_request(
                     session,
@@ -131,26 +144,33 @@ impl Approvable<UnifiedExecRequest> for UnifiedExecRuntime<'_> {
                 )
                 .await;
             }
-            with_cached_approval(&session.services, "unified_exec", keys, || async move {
-                let available_decisions = None;
-                session
-                    .request_command_approval(
-                        turn,
-                        call_id,
-                        None,
-                        command,
-                        cwd,
-                        reason,
-                        ctx.network_approval_context.clone(),
-                        req.exec_approval_requirement
-                            .proposed_execpolicy_amendment()
-                            .cloned(),
-                        req.additional_permissions.clone(),
-                        None,
-                        available_decisions,
-                    )
-                    .await
-            })
+            with_session_command_decisions(
+                &session.services,
+                &turn.config.codex_home,
+                command.clone(),
+                cwd.clone(),
+                with_cached_approval(&session.services, "unified_exec", keys, || async move {
+                    let available_decisions = None;
+                    session
+                        .request_command_approval(
+                            turn,
+                            call_id,
+                            None,
+                            command,
+                            cwd,
+                            env,
+                            reason,
+                            ctx.network_approval_context.clone(),
+                            req.exec_approval_requirement
+                                .proposed_execpolicy_amendment()
+                                .cloned(),
+                            req.additional_permissions.clone(),
+                            None,
+                            available_decisions,
+                        )
+                        .await
+                }),
+            )
             .await
         })
     }
@@ -186,7 +206,8 @@ impl<'a> ToolRuntime<UnifiedExecRequest, UnifiedExecProcess> for UnifiedExecRunt
         attempt: &SandboxAttempt<'_>,
         ctx: &ToolCtx,
     ) -> Result<UnifiedExecProcess, ToolError> {
-        let base_command = &req.command;
+        let approved_command = ctx.session.approved_command_override(&ctx.call_id).await;
+        let base_command = approved_command.as_ref().unwrap_or(&req.command);
         let session_shell = ctx.session.user_shell();
         let command = maybe_wrap_shell_lc_with_snapshot(
             base_command,
@@ -4,8 +4,11 @@
 //! `ApprovalCtx`, `Approvable`) together with the sandbox orchestration traits
 //! and helpers (`Sandboxable`, `ToolRuntime`, `SandboxAttempt`, etc.).
 
+use crate::approved_commands::ApprovedCommand;
+use crate::approved_commands::append_approved_command;
 use crate::codex::Session;
 use crate::codex::TurnContext;
+use crate::command_canonicalization::canonicalize_command_for_approval;
 use crate::error::CodexErr;
 use crate::protocol::SandboxPolicy;
 use crate::sandboxing::CommandSpec;
@@ -25,23 +28,101 @@ use futures::Future;
 use futures::future::BoxFuture;
 use serde::Serialize;
 use std::collections::HashMap;
+use std::collections::HashSet;
 use std::fmt::Debug;
 use std::hash::Hash;
 use std::path::Path;
+use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Instant;
 
 #[derive(Clone, Default, Debug)]
 pub(crate) struct ApprovalStore {
     // Store serialized keys for generic caching across requests.
     map: HashMap<String, ReviewDecision>,
+    // When entries of `map` approved for a while stop counting.
+    expiries: HashMap<String, Instant>,
+    // Commands approved in an earlier session, loaded at startup.
+    approved_commands: Vec<ApprovedCommand>,
+    // Canonical argv and cwd of commands the user denied for this session.
+    denied_commands: HashSet<(Vec<String>, PathBuf)>,
+    // Canonical argv and directory of commands approved for a directory.
+    directory_approvals: HashSet<(Vec<String>, PathBuf)>,
 }
 
 impl ApprovalStore {
+    pub fn with_approved_commands(approved_commands: Vec<ApprovedCommand>) -> Self {
+        Self {
+            map: HashMap::new(),
+            expiries: HashMap::new(),
+            approved_commands,
+            denied_commands: HashSet::new(),
+            directory_approvals: HashSet::new(),
+        }
+    }
+
+    /// Whether `command` run from `cwd` was approved in an earlier session.
+    pub fn is_always_approved(&self, command: &[String], cwd: &Path) -> bool {
+        let command = canonicalize_command_for_approval(command);
+        self.approved_commands.iter().any(|approved| {
+            approved.cwd == cwd && canonicalize_command_for_approval(&approved.command) == command
+        })
+    }
+
+    /// Approve `approved` for the rest of this session as if it had been
+    /// loaded at startup.
+    pub fn approve_always(&mut self, approved: ApprovedCommand) {
+        if !self.approved_commands.contains(&approved) {
+            self.approved_commands.push(approved);
+        }
+    }
+
+    /// Deny `command` run from `cwd` without prompting for the rest of the
+    /// session.
+    pub fn deny_for_session(&mut self, command: &[String], cwd: &Path) {
+        self.denied_commands.insert((
+            canonicalize_command_for_approval(command),
+            cwd.to_path_buf(),
+        ));
+    }
+
+    /// Whether the user chose to never run `command` from `cwd` this session.
+    pub fn is_denied_for_session(&self, command: &[String], cwd: &Path) -> bool {
+        self.denied_commands.contains(&(
+            canonicalize_command_for_approval(command),
+            cwd.to_path_buf(),
+        ))
+    }
+
+    /// Approve `command` run from `dir` or any directory beneath it for the
+    /// rest of the session.
+    pub fn approve_in_directory(&mut self, command: &[String], dir: &Path) {
+        self.directory_approvals.insert((
+            canonicalize_command_for_approval(command),
+            dir.to_path_buf(),
+        ));
+    }
+
+    /// Whether `command` was approved for `cwd` or a directory containing it.
+    pub fn is_approved_in_directory(&self, command: &[String], cwd: &Path) -> bool {
+        let command = canonicalize_command_for_approval(command);
+        self.directory_approvals
+            .iter()
+            .any(|(approved, dir)| *approved == command && cwd.starts_with(dir))
+    }
+
     pub fn get<K>(&self, key: &K) -> Option<ReviewDecision>
     where
         K: Serialize,
     {
         let s = serde_json::to_string(key).ok()?;
+        if self
+            .expiries
+            .get(&s)
+            .is_some_and(|expires_at| *expires_at <= Instant::now())
+        {
+            return None;
+        }
         self.map.get(&s).cloned()
     }
 
@@ -50,6 +131,19 @@ impl ApprovalStore {
         K: Serialize,
     {
         if let Ok(s) = serde_json::to_string(&key) {
+            self.expiries.remove(&s);
+            self.map.insert(s, value);
+        }
+    }
+
+    /// Like [`ApprovalStore::put`], but `get` stops returning the decision
+    /// once `expires_at` has passed.
+    pub fn put_until<K>(&mut self, key: K, value: ReviewDecision, expires_at: Instant)
+    where
+        K: Serialize,
+    {
+        if let Ok(s) = serde_json::to_string(&key) {
+            self.expiries.insert(s.clone(), expires_at);
             self.map.insert(s, value);
         }
     }
@@ -61,6 +155,11 @@ impl ApprovalStore {
 /// - If all keys are already approved for session, we skip prompting.
 /// - If the user approves for session, we store the decision for each key individually
 ///   so future requests touching any subset can also skip prompting.
+/// - "Always" approvals are cached the same way for the current session;
+///   [`with_session_command_decisions`] persists them so later sessions see them via
+///   [`ApprovalStore::is_always_approved`].
+/// - Approvals for a duration are cached until it has passed, so the next request
+///   after that prompts again.
 pub(crate) async fn with_cached_approval<K, F, Fut>(
     services: &SessionServices,
     // Name of the tool, used for metrics collection.
@@ -80,8 +179,16 @@ where
 
     let already_approved = {
         let store = services.tool_approvals.lock().await;
-        keys.iter()
-            .all(|key| matches!(store.get(key), Some(ReviewDecision::ApprovedForSession)))
+        keys.iter().all(|key| {
+            matches!(
+                store.get(key),
+                Some(
+                    ReviewDecision::ApprovedForSession
+                        | ReviewDecision::ApprovedForDuration { .. }
+                        | ReviewDecision::ApprovedAlways
+                )
+            )
+        })
     };
 
     if already_approved {
@@ -99,16 +206,95 @@ where
         ],
     );
 
-    if matches!(decision, ReviewDecision::ApprovedForSession) {
-        let mut store = services.tool_approvals.lock().await;
-        for key in keys {
-            store.put(key, ReviewDecision::ApprovedForSession);
+    match &decision {
+        ReviewDecision::ApprovedForSession | ReviewDecision::ApprovedAlways => {
+            let mut store = services.tool_approvals.lock().await;
+            for key in keys {
+                store.put(key, decision.clone());
+            }
+        }
+        ReviewDecision::ApprovedForDuration { duration } => {
+            let mut store = services.tool_approvals.lock().await;
+            // A duration too long to represent never runs out.
+            match Instant::now().checked_add(*duration) {
+                Some(expires_at) => {
+                    for key in keys {
+                        store.put_until(key, decision.clone(), expires_at);
+                    }
+                }
+                None => {
+                    for key in keys {
+                        store.put(key, decision.clone());
+                    }
+                }
+            }
         }
+        ReviewDecision::Approved
+        | ReviewDecision::ApprovedExecpolicyAmendment { .. }
+        | ReviewDecision::ApprovedForDirectory
+        | ReviewDecision::ApprovedSandboxed
+        | ReviewDecision::DryRun
+        | ReviewDecision::NetworkPolicyAmendment { .. }
+        | ReviewDecision::Denied
+        | ReviewDecision::DeniedForSession
+        | ReviewDecision::Abort => {}
     }
 
     decision
 }
 
+/// Runs `fetch` unless the user already chose, this session, to never run
+/// `command` from `cwd` or to approve it in `cwd` or a directory above it,
+/// and remembers either choice when `fetch` returns it. The signature is the
+/// canonical argv plus the directory, so the same command retried with
+/// different sandbox permissions gets the same answer. An "always" approval
+/// is added to the allowlist in `codex_home` for later sessions.
+pub(crate) async fn with_session_command_decisions<Fut>(
+    services: &SessionServices,
+    codex_home: &Path,
+    command: Vec<String>,
+    cwd: PathBuf,
+    fetch: Fut,
+) -> ReviewDecision
+where
+    Fut: Future<Output = ReviewDecision>,
+{
+    {
+        let store = services.tool_approvals.lock().await;
+        if store.is_denied_for_session(&command, &cwd) {
+            return ReviewDecision::DeniedForSession;
+        }
+        if store.is_approved_in_directory(&command, &cwd) {
+            return ReviewDecision::ApprovedForDirectory;
+        }
+    }
+    let decision = fetch.await;
+    if decision == ReviewDecision::DeniedForSession {
+        services
+            .tool_approvals
+            .lock()
+            .await
+            .deny_for_session(&command, &cwd);
+    } else if decision == ReviewDecision::ApprovedForDirectory {
+        services
+            .tool_approvals
+            .lock()
+            .await
+            .approve_in_directory(&command, &cwd);
+    } else if decision == ReviewDecision::ApprovedAlways {
+        let approved = ApprovedCommand { command, cwd };
+        services
+            .tool_approvals
+            .lock()
+            .await
+            .approve_always(approved.clone());
+        if let Err(err) = append_approved_command(codex_home, approved).await {
+            tracing::warn!("failed to save an always-approved command: {err}");
+        }
+    }
+    decision
+}
+
 #[derive(Clone)]
 pub(crate) struct ApprovalCtx<'a> {
     pub session: &'a Arc<Session>,
@@ -278,7 +464,6 @@ pub(crate) trait Approvable<Req> {
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub(crate) enum SandboxablePreference {
     Auto,
-    #[allow(dead_code)] // Will be used by later tools.
     Require,
     #[allow(dead_code)] // Will be used by later tools.
     Forbid,
@@ -362,6 +547,7 @@ mod tests {
     use codex_protocol::protocol::NetworkAccess;
     use codex_protocol::protocol::RejectConfig;
     use pretty_assertions::assert_eq;
+    use std::time::Duration;
 
     #[test]
     fn external_sandbox_skips_exec_approval_on_request() {
@@ -463,4 +649,48 @@ mod tests {
             SandboxOverride::BypassSandboxFirstAttempt
         );
     }
+
+    #[test]
+    fn session_denials_match_argv_and_cwd() {
+        let mut store = ApprovalStore::default();
+        let command = vec!["rm".to_string(), "-rf".to_string(), "build".to_string()];
+        store.deny_for_session(&command, Path::new("/repo"));
+
+        assert!(store.is_denied_for_session(&command, Path::new("/repo")));
+        assert!(!store.is_denied_for_session(&command, Path::new("/other")));
+        assert!(!store.is_denied_for_session(&command[..2], Path::new("/repo")));
+    }
+
+    #[test]
+    fn directory_approvals_cover_subdirectories() {
+        let mut store = ApprovalStore::default();
+        let command = vec!["cargo".to_string(), "test".to_string()];
+        store.approve_in_directory(&command, Path::new("/repo"));
+
+        assert!(store.is_approved_in_directory(&command, Path::new("/repo")));
+        assert!(store.is_approved_in_directory(&command, Path::new("/repo/tui")));
+        assert!(!store.is_approved_in_directory(&command, Path::new("/repository")));
+        assert!(!store.is_approved_in_directory(&command, Path::new("/other")));
+        assert!(!store.is_approved_in_directory(&command[..1], Path::new("/repo")));
+    }
+
+    #[test]
+    fn approvals_for_a_duration_lapse() {
+        let mut store = ApprovalStore::default();
+        let decision = ReviewDecision::ApprovedForDuration {
+            duration: Duration::from_secs(60),
+        };
+        let now = Instant::now();
+        store.put_until("running", decision.clone(), now + Duration::from_secs(60));
+        store.put_until("lapsed", decision.clone(), now);
+
+        assert_eq!(store.get(&"running"), Some(decision));
+        assert_eq!(store.get(&"lapsed"), None);
+
+        store.put("lapsed", ReviewDecision::ApprovedForSession);
+        assert_eq!(
+            store.get(&"lapsed"),
+            Some(ReviewDecision::ApprovedForSession)
+        );
+    }
 }
@@ -1985,6 +1985,69 @@ async fn approving_execpolicy_amendment_persists_policy_and_skips_future_prompts
     Ok(())
 }
 
+#[tokio::test(flavor = "current_thread")]
+#[cfg(unix)]
+async fn approving_always_saves_the_command_for_later_sessions() -> Result<()> {
+    let server = start_mock_server().await;
+    let approval_policy = AskForApproval::UnlessTrusted;
+    let sandbox_policy = SandboxPolicy::new_read_only_policy();
+    let sandbox_policy_for_config = sandbox_policy.clone();
+    let mut builder = test_codex().with_config(move |config| {
+        config.permissions.approval_policy = Constrained::allow_any(approval_policy);
+        config.permissions.sandbox_policy = Constrained::allow_any(sandbox_policy_for_config);
+    });
+    let test = builder.build(&server).await?;
+
+    let call_id = "approve-always";
+    let (event, expected_command) = ActionKind::RunCommand {
+        command: "touch approve-always.txt",
+    }
+    .prepare(&test, &server, call_id, SandboxPermissions::UseDefault)
+    .await?;
+    let expected_command =
+        expected_command.expect("approve-always scenario should produce a shell command");
+
+    let _ = mount_sse_once(
+        &server,
+        sse(vec![
+            ev_response_created("resp-approve-always-1"),
+            event,
+            ev_completed("resp-approve-always-1"),
+        ]),
+    )
+    .await;
+    let _ = mount_sse_once(
+        &server,
+        sse(vec![
+            ev_assistant_message("msg-approve-always-1", "done"),
+            ev_completed("resp-approve-always-2"),
+        ]),
+    )
+    .await;
+
+    submit_turn(&test, "approve-always", approval_policy, sandbox_policy).await?;
+
+    let approval = expect_exec_approval(&test, expected_command.as_str()).await;
+    test.codex
+        .submit(Op::ExecApproval {
+            id: approval.effective_approval_id(),
+            turn_id: None,
+            decision: ReviewDecision::ApprovedAlways,
+        })
+        .await?;
+    wait_for_completion(&test).await;
+
+    let saved: Value = serde_json::from_str(&fs::read_to_string(
+        test.home.path().join("approved_commands.json"),
+    )?)?;
+    assert_eq!(
+        saved,
+        json!({ "commands": [{ "command": approval.command, "cwd": approval.cwd }] })
+    );
+
+    Ok(())
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 #[cfg(unix)]
 async fn matched_prefix_rule_runs_unsandboxed_under_zsh_fork() -> Result<()> {
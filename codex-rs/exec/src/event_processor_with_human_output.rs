@@ -237,6 +237,8 @@ impl EventProcessor for EventProcessorWithHumanOutput {
                 );
             }
             EventMsg::ModelReroute(_) => {}
+            // Exec mode never prompts for approval, so there is nothing to explain.
+            EventMsg::ApprovalExplanation(_) => {}
             EventMsg::DeprecationNotice(DeprecationNoticeEvent { summary, details }) => {
                 ts_msg!(
                     self,
@@ -1023,6 +1025,7 @@ impl EventProcessorWithHumanOutput {
                     | EventMsg::TokenCount(_)
                     | EventMsg::TurnStarted(_)
                     | EventMsg::ExecApprovalRequest(_)
+                    | EventMsg::ApprovalExplanation(_)
                     | EventMsg::ApplyPatchApprovalRequest(_)
                     | EventMsg::TerminalInteraction(_)
                     | EventMsg::ExecCommandOutputDelta(_)
@@ -220,6 +220,7 @@ async fn run_codex_tool_session_inner(
                             turn_id: _,
                             command,
                             cwd,
+                            env: _,
                             call_id,
                             approval_id: _,
                             reason: _,
@@ -374,6 +375,7 @@ async fn run_codex_tool_session_inner(
                     | EventMsg::DynamicToolCallResponse(_)
                     | EventMsg::ContextCompacted(_)
                     | EventMsg::ModelReroute(_)
+                    | EventMsg::ApprovalExplanation(_)
                     | EventMsg::ThreadRolledBack(_)
                     | EventMsg::CollabAgentSpawnBegin(_)
                     | EventMsg::CollabAgentSpawnEnd(_)
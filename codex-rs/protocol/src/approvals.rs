@@ -116,6 +116,11 @@ pub struct ExecApprovalRequestEvent {
     pub command: Vec<String>,
     /// The command's working directory.
     pub cwd: PathBuf,
+    /// Environment variables the command sets explicitly on top of the
+    /// inherited environment.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub env: Option<HashMap<String, String>>,
     /// Optional human-readable reason for the approval (e.g. retry without sandbox).
     #[serde(skip_serializing_if = "Option::is_none")]
     pub reason: Option<String>,
@@ -255,6 +260,88 @@ pub enum ElicitationAction {
     Cancel,
 }
 
+/// Current version of [`ApprovalResponse`].
+pub const APPROVAL_RESPONSE_VERSION: u32 = 1;
+
+fn default_approval_response_version() -> u32 {
+    APPROVAL_RESPONSE_VERSION
+}
+
+/// Structured answer to an exec or patch approval request.
+///
+/// New optional fields may be added over time; `version` lets the receiver
+/// tell which fields the sender knew about.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
+pub struct ApprovalResponse {
+    #[serde(default = "default_approval_response_version")]
+    pub version: u32,
+    /// The user's decision in response to the request.
+    pub decision: ReviewDecision,
+    /// Free-text note from the user that is passed on to the model.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub feedback: Option<String>,
+    /// Changes the user made to the request before approving it.
+    #[serde(default, skip_serializing_if = "ApprovalOverrides::is_empty")]
+    pub overrides: ApprovalOverrides,
+    /// Post-conditions to check once an approved command finishes. The result
+    /// is reported back to the model.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub assertions: Vec<PostRunAssertion>,
+}
+
+impl ApprovalResponse {
+    pub fn new(decision: ReviewDecision) -> Self {
+        Self {
+            version: APPROVAL_RESPONSE_VERSION,
+            decision,
+            feedback: None,
+            overrides: ApprovalOverrides::default(),
+            assertions: Vec::new(),
+        }
+    }
+}
+
+/// Request fields the user replaced when approving.
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
+pub struct ApprovalOverrides {
+    /// Replacement argv to run in place of the requested command, e.g. the
+    /// same command with a verbose flag added.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub command: Option<Vec<String>>,
+    /// The files of an approved patch to apply, by the paths in the request's
+    /// changes; the rest of the patch is skipped. `None` applies every file.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub included_files: Option<Vec<PathBuf>>,
+}
+
+impl ApprovalOverrides {
+    pub fn is_empty(&self) -> bool {
+        self.command.is_none() && self.included_files.is_none()
+    }
+}
+
+/// Machine-checkable condition an approved command must satisfy.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum PostRunAssertion {
+    /// The command exits with this code.
+    ExitCode { code: i32 },
+    /// This path exists afterward. Relative paths resolve against the command's cwd.
+    PathExists { path: PathBuf },
+    /// The command's combined output contains this text.
+    OutputContains { text: String },
+}
+
+impl std::fmt::Display for PostRunAssertion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PostRunAssertion::ExitCode { code } => write!(f, "exit code {code}"),
+            PostRunAssertion::PathExists { path } => write!(f, "`{}` exists", path.display()),
+            PostRunAssertion::OutputContains { text } => write!(f, "output contains `{text}`"),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
 pub struct ApplyPatchApprovalRequestEvent {
     /// Responses API call id for the associated patch apply call, if available.
@@ -56,6 +56,8 @@ use tracing::error;
 use ts_rs::TS;
 
 pub use crate::approvals::ApplyPatchApprovalRequestEvent;
+pub use crate::approvals::ApprovalOverrides;
+pub use crate::approvals::ApprovalResponse;
 pub use crate::approvals::ElicitationAction;
 pub use crate::approvals::ExecApprovalRequestEvent;
 pub use crate::approvals::ExecApprovalRequestSkillMetadata;
@@ -64,6 +66,7 @@ pub use crate::approvals::NetworkApprovalContext;
 pub use crate::approvals::NetworkApprovalProtocol;
 pub use crate::approvals::NetworkPolicyAmendment;
 pub use crate::approvals::NetworkPolicyRuleAction;
+pub use crate::approvals::PostRunAssertion;
 pub use crate::permissions::FileSystemAccessMode;
 pub use crate::permissions::FileSystemPath;
 pub use crate::permissions::FileSystemSandboxEntry;
@@ -341,6 +344,25 @@ pub enum Op {
         decision: ReviewDecision,
     },
 
+    /// Resolve an exec or patch approval with a structured response that can
+    /// carry feedback and overrides alongside the decision.
+    ApprovalResponse {
+        /// The id of the approval request being resolved.
+        id: String,
+        /// Turn id associated with the approval event, when available.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        turn_id: Option<String>,
+        response: ApprovalResponse,
+    },
+
+    /// Ask the model to explain the command behind a pending exec approval in
+    /// plain English. The approval stays pending; the explanation arrives as an
+    /// [`EventMsg::ApprovalExplanation`].
+    RequestExplanation {
+        /// The id of the pending exec approval.
+        id: String,
+    },
+
     /// Resolve an MCP elicitation request.
     ResolveElicitation {
         /// Name of the MCP server that issued the request.
@@ -1176,6 +1198,10 @@ pub enum EventMsg {
 
     ExecApprovalRequest(ExecApprovalRequestEvent),
 
+    /// Plain-English explanation of a pending exec approval, requested with
+    /// [`Op::RequestExplanation`].
+    ApprovalExplanation(ApprovalExplanationEvent),
+
     RequestPermissions(RequestPermissionsEvent),
 
     RequestUserInput(RequestUserInputEvent),
@@ -1674,6 +1700,12 @@ pub struct WarningEvent {
     pub message: String,
 }
 
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
+pub struct ApprovalExplanationEvent {
+    pub id: String,
+    pub explanation: String,
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
 #[serde(rename_all = "snake_case")]
 #[ts(rename_all = "snake_case")]
@@ -3032,6 +3064,7 @@ pub struct ThreadNameUpdatedEvent {
 }
 
 /// User's decision in response to an ExecApprovalRequest.
+#[serde_as]
 #[derive(Debug, Default, Clone, Deserialize, Serialize, PartialEq, Eq, Display, JsonSchema, TS)]
 #[serde(rename_all = "snake_case")]
 pub enum ReviewDecision {
@@ -3049,6 +3082,37 @@ pub enum ReviewDecision {
     /// remainder of the session.
     ApprovedForSession,
 
+    /// User has approved this command and wants identical commands run from
+    /// this working directory, or any directory beneath it, to be approved for
+    /// the rest of the session.
+    ApprovedForDirectory,
+
+    /// User has approved this command and wants identical commands run from
+    /// the same working directory to be approved in future sessions too.
+    ApprovedAlways,
+
+    /// User has approved this request and wants the same session-scoped
+    /// approvals as [`ReviewDecision::ApprovedForSession`], but only until
+    /// `duration` has passed; after that the request is prompted again. In
+    /// `available_decisions` the duration is the one the client preselects.
+    /// Sent as whole seconds.
+    ApprovedForDuration {
+        #[serde_as(as = "serde_with::DurationSeconds<u64>")]
+        #[schemars(with = "u64")]
+        #[ts(type = "number")]
+        duration: Duration,
+    },
+
+    /// User has approved this command but wants it kept inside a read-only
+    /// sandbox with network access disabled, even if the session policy would
+    /// run it unsandboxed.
+    ApprovedSandboxed,
+
+    /// User wants to preview this command: it runs inside a read-only sandbox,
+    /// and if the sandbox blocks it the same approval id is prompted again with
+    /// the captured output as the reason.
+    DryRun,
+
     /// User chose to persist a network policy rule (allow/deny) for future
     /// requests to the same host.
     NetworkPolicyAmendment {
@@ -3060,6 +3124,11 @@ pub enum ReviewDecision {
     #[default]
     Denied,
 
+    /// User has denied this command and wants identical commands run from the
+    /// same working directory denied without prompting for the rest of the
+    /// session.
+    DeniedForSession,
+
     /// User has denied this command and the agent should not do anything until
     /// the user's next command.
     Abort,
@@ -3073,6 +3142,11 @@ impl ReviewDecision {
             ReviewDecision::Approved => "approved",
             ReviewDecision::ApprovedExecpolicyAmendment { .. } => "approved_with_amendment",
             ReviewDecision::ApprovedForSession => "approved_for_session",
+            ReviewDecision::ApprovedForDirectory => "approved_for_directory",
+            ReviewDecision::ApprovedAlways => "approved_always",
+            ReviewDecision::ApprovedForDuration { .. } => "approved_for_duration",
+            ReviewDecision::ApprovedSandboxed => "approved_sandboxed",
+            ReviewDecision::DryRun => "dry_run",
             ReviewDecision::NetworkPolicyAmendment {
                 network_policy_amendment,
             } => match network_policy_amendment.action {
@@ -3080,6 +3154,7 @@ impl ReviewDecision {
                 NetworkPolicyRuleAction::Deny => "denied_with_network_policy_deny",
             },
             ReviewDecision::Denied => "denied",
+            ReviewDecision::DeniedForSession => "denied_for_session",
             ReviewDecision::Abort => "abort",
         }
     }
@@ -4275,6 +4350,55 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn approved_for_duration_serializes_whole_seconds() -> Result<()> {
+        let decision = ReviewDecision::ApprovedForDuration {
+            duration: Duration::from_secs(900),
+        };
+        let serialized = serde_json::to_value(&decision)?;
+        assert_eq!(
+            serialized,
+            json!({ "approved_for_duration": { "duration": 900 } })
+        );
+
+        let deserialized: ReviewDecision = serde_json::from_value(serialized)?;
+        assert_eq!(deserialized, decision);
+        Ok(())
+    }
+
+    #[test]
+    fn approval_response_op_round_trips_with_defaults() -> Result<()> {
+        let op: Op = serde_json::from_value(json!({
+            "type": "approval_response",
+            "id": "call-1",
+            "response": { "decision": "denied", "feedback": "use the test profile" },
+        }))?;
+        assert_eq!(
+            op,
+            Op::ApprovalResponse {
+                id: "call-1".to_string(),
+                turn_id: None,
+                response: ApprovalResponse {
+                    feedback: Some("use the test profile".to_string()),
+                    ..ApprovalResponse::new(ReviewDecision::Denied)
+                },
+            }
+        );
+        assert_eq!(
+            serde_json::to_value(&op)?,
+            json!({
+                "type": "approval_response",
+                "id": "call-1",
+                "response": {
+                    "version": 1,
+                    "decision": "denied",
+                    "feedback": "use the test profile",
+                },
+            })
+        );
+        Ok(())
+    }
+
     #[test]
     fn serialize_mcp_startup_update_event() -> Result<()> {
         let event = Event {
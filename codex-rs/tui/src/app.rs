@@ -10,6 +10,7 @@ use crate::bottom_pane::FeedbackAudience;
 use crate::bottom_pane::McpServerElicitationFormRequest;
 use crate::bottom_pane::SelectionItem;
 use crate::bottom_pane::SelectionViewParams;
+use crate::bottom_pane::env_overrides;
 use crate::bottom_pane::popup_consts::standard_popup_hint_line;
 use crate::chatwidget::ChatWidget;
 use crate::chatwidget::ExternalEditorState;
@@ -18,6 +19,7 @@ use crate::cwd_prompt::CwdPromptAction;
 use crate::diff_render::DiffSummary;
 use crate::exec_command::strip_bash_lc_and_escape;
 use crate::external_editor;
+use crate::external_pager;
 use crate::file_search::FileSearchManager;
 use crate::history_cell;
 use crate::history_cell::HistoryCell;
@@ -93,6 +95,7 @@ use std::collections::BTreeMap;
 use std::collections::HashMap;
 use std::collections::HashSet;
 use std::collections::VecDeque;
+use std::io::Write;
 use std::path::Path;
 use std::path::PathBuf;
 use std::sync::Arc;
@@ -112,10 +115,14 @@ use tokio::task::JoinHandle;
 use toml::Value as TomlValue;
 
 mod agent_navigation;
+mod approval_sources;
+mod approval_stats;
 mod pending_interactive_replay;
 
 use self::agent_navigation::AgentNavigationDirection;
 use self::agent_navigation::AgentNavigationState;
+use self::approval_sources::ApprovalSources;
+use self::approval_stats::ApprovalStatsAccumulator;
 use self::pending_interactive_replay::PendingInteractiveReplayState;
 
 const EXTERNAL_EDITOR_HINT: &str = "Save and close external editor to continue.";
@@ -190,6 +197,20 @@ fn errors_for_cwd(cwd: &Path, response: &ListSkillsResponseEvent) -> Vec<SkillEr
         .unwrap_or_default()
 }
 
+/// Append `record` to the approval audit log at `path` as one JSON line.
+fn append_audit_record(path: &Path, record: &serde_json::Value) -> std::io::Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut line = serde_json::to_string(record).map_err(std::io::Error::other)?;
+    line.push('\n');
+    std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?
+        .write_all(line.as_bytes())
+}
+
 fn emit_skill_load_warnings(app_event_tx: &AppEventSender, errors: &[SkillErrorInfo]) {
     if errors.is_empty() {
         return;
@@ -655,6 +676,8 @@ pub(crate) struct App {
     pub(crate) file_search: FileSearchManager,
 
     pub(crate) transcript_cells: Vec<Arc<dyn HistoryCell>>,
+    approval_sources: ApprovalSources,
+    approval_stats: ApprovalStatsAccumulator,
 
     // Pager overlay state (Transcript or Static like Diff)
     pub(crate) overlay: Option<Overlay>,
@@ -1150,6 +1173,15 @@ impl App {
         }
     }
 
+    async fn thread_sandbox_policy(&self, thread_id: ThreadId) -> Option<SandboxPolicy> {
+        let channel = self.thread_event_channels.get(&thread_id)?;
+        let store = channel.store.lock().await;
+        match store.session_configured.as_ref().map(|event| &event.msg) {
+            Some(EventMsg::SessionConfigured(session)) => Some(session.sandbox_policy.clone()),
+            _ => None,
+        }
+    }
+
     async fn interactive_request_for_thread_event(
         &self,
         thread_id: ThreadId,
@@ -1163,10 +1195,14 @@ impl App {
                     thread_label,
                     id: ev.effective_approval_id(),
                     command: ev.command.clone(),
+                    cwd: ev.cwd.clone(),
                     reason: ev.reason.clone(),
                     available_decisions: ev.effective_available_decisions(),
                     network_approval_context: ev.network_approval_context.clone(),
                     additional_permissions: ev.additional_permissions.clone(),
+                    sandbox_policy: self.thread_sandbox_policy(thread_id).await,
+                    env: env_overrides(ev.env.as_ref()),
+                    duplicate_ids: Vec::new(),
                 }))
             }
             EventMsg::ApplyPatchApprovalRequest(ev) => Some(ThreadInteractiveRequest::Approval(
@@ -1180,6 +1216,7 @@ impl App {
                         .await
                         .unwrap_or_else(|| self.config.cwd.clone()),
                     changes: ev.changes.clone(),
+                    grant_root: ev.grant_root.clone(),
                 },
             )),
             EventMsg::ElicitationRequest(ev) => {
@@ -1924,6 +1961,8 @@ impl App {
             file_search,
             enhanced_keys_supported,
             transcript_cells: Vec::new(),
+            approval_sources: ApprovalSources::default(),
+            approval_stats: ApprovalStatsAccumulator::default(),
             overlay: None,
             deferred_history_lines: Vec::new(),
             has_emitted_history_lines: false,
@@ -2073,8 +2112,10 @@ impl App {
         if matches!(event, TuiEvent::Draw) {
             let size = tui.terminal.size()?;
             if size != tui.terminal.last_known_screen_size {
+                self.chat_widget.on_resize(size.width, size.height);
                 self.refresh_status_line();
             }
+            tui.set_mouse_capture(self.overlay.is_none() && self.chat_widget.wants_mouse_capture());
         }
 
         if self.overlay.is_some() {
@@ -2092,6 +2133,9 @@ impl App {
                     let pasted = pasted.replace("\r", "\n");
                     self.chat_widget.handle_paste(pasted);
                 }
+                TuiEvent::Mouse(mouse_event) => {
+                    self.chat_widget.handle_mouse_event(mouse_event);
+                }
                 TuiEvent::Draw => {
                     if self.backtrack_render_pending {
                         self.backtrack_render_pending = false;
@@ -2416,6 +2460,20 @@ impl App {
             AppEvent::OpenUrlInBrowser { url } => {
                 self.open_url_in_browser(url);
             }
+            AppEvent::CopyToClipboard(text) => {
+                if let Err(err) = crate::clipboard_text::copy_text_to_clipboard(&text) {
+                    self.chat_widget
+                        .add_error_message(format!("Failed to copy to clipboard: {err}"));
+                }
+            }
+            AppEvent::OpenInPager(text) => {
+                self.open_in_pager(tui, &text).await;
+            }
+            AppEvent::Bell => {
+                if let Err(err) = crate::notifications::ring_bell() {
+                    tracing::warn!(error = %err, "failed to ring the terminal bell");
+                }
+            }
             AppEvent::RefreshConnectors { force_refetch } => {
                 self.chat_widget.refresh_connectors(force_refetch);
             }
@@ -3322,6 +3380,68 @@ impl App {
                     ));
                 }
             },
+            AppEvent::ApprovalPolicyAbstained(request) => {
+                self.chat_widget.show_approval_request(request);
+            }
+            AppEvent::MarkApprovalSource { id } => {
+                self.approval_sources.mark(id, &self.transcript_cells);
+            }
+            AppEvent::ShowApprovalSource { id } => {
+                match self.approval_sources.position(&id, &self.transcript_cells) {
+                    Some(cell_idx) => {
+                        self.open_transcript_overlay(tui);
+                        if let Some(Overlay::Transcript(t)) = &mut self.overlay {
+                            t.set_highlight_cell(Some(cell_idx));
+                        }
+                    }
+                    None => self.chat_widget.add_info_message(
+                        "The agent message for this request is no longer available.".to_string(),
+                        None,
+                    ),
+                }
+            }
+            AppEvent::ApprovalResolved {
+                id,
+                kind,
+                decision,
+                had_feedback,
+                elapsed,
+            } => {
+                self.approval_stats.record(kind, elapsed);
+                tracing::info!(
+                    id,
+                    ?kind,
+                    ?decision,
+                    had_feedback,
+                    ?elapsed,
+                    stats = ?self.approval_stats.snapshot(),
+                    "approval resolved"
+                );
+            }
+            AppEvent::AuditRecord(record) => {
+                if let Some(path) = self.config.tui_approvals.audit_log.as_ref() {
+                    let path = self.config.codex_home.join(path);
+                    if let Err(err) = append_audit_record(&path, &record) {
+                        tracing::warn!(
+                            "failed to write an approval audit record to {}: {err}",
+                            path.display()
+                        );
+                    }
+                }
+            }
+            AppEvent::ApprovalClosed { id, resolved } => {
+                tracing::debug!(id, resolved, "approval modal closed");
+            }
+            AppEvent::Announce(text) => {
+                // Bridges that speak announcements read them from the log.
+                tracing::info!(target: "codex_tui::announce", "{text}");
+            }
+            AppEvent::ApproveAllPending(decision) => {
+                self.chat_widget.approve_all_pending(decision);
+            }
+            AppEvent::RegisterAutoApprove { name, signature } => {
+                self.chat_widget.register_auto_approve(name, signature);
+            }
             #[cfg(not(target_os = "linux"))]
             AppEvent::TranscriptionComplete { id, text } => {
                 self.chat_widget.replace_transcription(&id, &text);
@@ -3668,6 +3788,20 @@ impl App {
         tui.frame_requester().schedule_frame();
     }
 
+    async fn open_in_pager(&mut self, tui: &mut tui::Tui, text: &str) {
+        let pager_cmd = external_pager::resolve_pager_command();
+        let result = tui
+            .with_restored(tui::RestoreMode::KeepRaw, || async {
+                external_pager::run_pager(text, &pager_cmd).await
+            })
+            .await;
+        if let Err(err) = result {
+            self.chat_widget
+                .add_error_message(format!("Failed to open pager: {err}"));
+        }
+        tui.frame_requester().schedule_frame();
+    }
+
     fn request_external_editor_launch(&mut self, tui: &mut tui::Tui) {
         self.chat_widget
             .set_external_editor_state(ExternalEditorState::Requested);
@@ -4006,6 +4140,7 @@ mod tests {
                     turn_id: "turn-1".to_string(),
                     command: vec!["echo".to_string(), "hello".to_string()],
                     cwd: PathBuf::from("/tmp/project"),
+                    env: None,
                     reason: Some("needs approval".to_string()),
                     network_approval_context: None,
                     proposed_execpolicy_amendment: None,
@@ -5169,6 +5304,7 @@ mod tests {
                         turn_id: "turn-1".to_string(),
                         command: vec!["echo".to_string(), "hi".to_string()],
                         cwd: PathBuf::from("/tmp"),
+                        env: None,
                         reason: None,
                         network_approval_context: None,
                         proposed_execpolicy_amendment: None,
@@ -5257,6 +5393,7 @@ mod tests {
                         turn_id: "turn-approval".to_string(),
                         command: vec!["echo".to_string(), "hi".to_string()],
                         cwd: PathBuf::from("/tmp/agent"),
+                        env: None,
                         reason: Some("need approval".to_string()),
                         network_approval_context: None,
                         proposed_execpolicy_amendment: None,
@@ -5573,6 +5710,8 @@ mod tests {
             runtime_sandbox_policy_override: None,
             file_search,
             transcript_cells: Vec::new(),
+            approval_sources: ApprovalSources::default(),
+            approval_stats: ApprovalStatsAccumulator::default(),
             overlay: None,
             deferred_history_lines: Vec::new(),
             has_emitted_history_lines: false,
@@ -5633,6 +5772,8 @@ mod tests {
                 runtime_sandbox_policy_override: None,
                 file_search,
                 transcript_cells: Vec::new(),
+                approval_sources: ApprovalSources::default(),
+                approval_stats: ApprovalStatsAccumulator::default(),
                 overlay: None,
                 deferred_history_lines: Vec::new(),
                 has_emitted_history_lines: false,
@@ -0,0 +1,84 @@
+//! Links approval requests to the agent message that led to them.
+//!
+//! When an approval request arrives, `App` records the head cell of the most recent agent message
+//! in the current turn. The approval modal can later ask for that cell so the transcript overlay
+//! can scroll to and highlight it. Cells are remembered by pointer rather than index so that
+//! rollbacks or a cleared transcript turn into "no longer available" instead of pointing at an
+//! unrelated cell.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::history_cell::AgentMessageCell;
+use crate::history_cell::HistoryCell;
+use crate::history_cell::UserHistoryCell;
+
+#[derive(Default)]
+pub(crate) struct ApprovalSources {
+    sources: HashMap<String, Arc<dyn HistoryCell>>,
+}
+
+impl ApprovalSources {
+    /// Remember the latest agent message of the current turn as the source of approval `id`.
+    pub(crate) fn mark(&mut self, id: String, transcript: &[Arc<dyn HistoryCell>]) {
+        let source = transcript
+            .iter()
+            .rev()
+            .take_while(|cell| !cell.as_any().is::<UserHistoryCell>())
+            .find(|cell| cell.as_any().is::<AgentMessageCell>() && !cell.is_stream_continuation());
+        match source {
+            Some(cell) => {
+                self.sources.insert(id, cell.clone());
+            }
+            None => {
+                self.sources.remove(&id);
+            }
+        }
+    }
+
+    /// Transcript index of the source message for approval `id`, if it is still retained.
+    pub(crate) fn position(&self, id: &str, transcript: &[Arc<dyn HistoryCell>]) -> Option<usize> {
+        let source = self.sources.get(id)?;
+        transcript.iter().position(|cell| Arc::ptr_eq(cell, source))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use ratatui::text::Line;
+
+    fn agent(first: bool) -> Arc<dyn HistoryCell> {
+        Arc::new(AgentMessageCell::new(vec![Line::from("msg")], first))
+    }
+
+    fn user() -> Arc<dyn HistoryCell> {
+        Arc::new(UserHistoryCell {
+            message: "hi".to_string(),
+            text_elements: Vec::new(),
+            local_image_paths: Vec::new(),
+            remote_image_urls: Vec::new(),
+        })
+    }
+
+    #[test]
+    fn marks_head_of_latest_agent_message_in_turn() {
+        let transcript = vec![agent(true), user(), agent(true), agent(false)];
+        let mut sources = ApprovalSources::default();
+        sources.mark("call".to_string(), &transcript);
+        assert_eq!(sources.position("call", &transcript), Some(2));
+    }
+
+    #[test]
+    fn missing_or_dropped_messages_are_unavailable() {
+        let transcript = vec![agent(true), user()];
+        let mut sources = ApprovalSources::default();
+        sources.mark("call".to_string(), &transcript);
+        assert_eq!(sources.position("call", &transcript), None);
+
+        let transcript = vec![user(), agent(true)];
+        sources.mark("call".to_string(), &transcript);
+        assert_eq!(sources.position("call", &[user()]), None);
+    }
+}
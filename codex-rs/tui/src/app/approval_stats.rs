@@ -0,0 +1,111 @@
+//! How long approval prompts wait for a decision.
+//!
+//! Every [`AppEvent::ApprovalResolved`](crate::app_event::AppEvent::ApprovalResolved) carries how
+//! long the prompt was on screen. `App` adds each one here so the session's count, mean and p95
+//! latency per kind of approval can be read back at any point, for example to log alongside the
+//! decision. Samples are kept for the whole session; a long one answers a few hundred prompts.
+
+use std::time::Duration;
+
+use crate::app_event::ApprovalKind;
+
+#[derive(Default)]
+pub(crate) struct ApprovalStatsAccumulator {
+    exec: Vec<Duration>,
+    apply_patch: Vec<Duration>,
+}
+
+/// Latency figures for each kind of approval, as of a [`ApprovalStatsAccumulator::snapshot`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) struct ApprovalStats {
+    pub(crate) exec: LatencyStats,
+    pub(crate) apply_patch: LatencyStats,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub(crate) struct LatencyStats {
+    pub(crate) count: usize,
+    pub(crate) mean: Duration,
+    /// The latency 95% of decisions were at or under, by nearest rank.
+    pub(crate) p95: Duration,
+}
+
+impl ApprovalStatsAccumulator {
+    /// Record that a `kind` prompt took `elapsed` to decide.
+    pub(crate) fn record(&mut self, kind: ApprovalKind, elapsed: Duration) {
+        match kind {
+            ApprovalKind::Exec => self.exec.push(elapsed),
+            ApprovalKind::ApplyPatch => self.apply_patch.push(elapsed),
+        }
+    }
+
+    pub(crate) fn snapshot(&self) -> ApprovalStats {
+        ApprovalStats {
+            exec: LatencyStats::of(&self.exec),
+            apply_patch: LatencyStats::of(&self.apply_patch),
+        }
+    }
+}
+
+impl LatencyStats {
+    fn of(samples: &[Duration]) -> Self {
+        let count = samples.len();
+        let Some(divisor) = u32::try_from(count).ok().filter(|n| *n > 0) else {
+            return Self::default();
+        };
+        let mean = samples.iter().sum::<Duration>() / divisor;
+        let mut sorted = samples.to_vec();
+        sorted.sort_unstable();
+        // Nearest rank: the smallest sample with at least 95% of them at or below it.
+        let rank = (count * 95).div_ceil(100);
+        let p95 = sorted[rank.saturating_sub(1)];
+        Self { count, mean, p95 }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn secs(secs: u64) -> Duration {
+        Duration::from_secs(secs)
+    }
+
+    #[test]
+    fn empty_kinds_read_as_zero() {
+        let mut stats = ApprovalStatsAccumulator::default();
+        stats.record(ApprovalKind::Exec, secs(4));
+        assert_eq!(
+            stats.snapshot(),
+            ApprovalStats {
+                exec: LatencyStats {
+                    count: 1,
+                    mean: secs(4),
+                    p95: secs(4),
+                },
+                apply_patch: LatencyStats::default(),
+            }
+        );
+    }
+
+    #[test]
+    fn p95_is_the_nearest_rank() {
+        let mut stats = ApprovalStatsAccumulator::default();
+        // 1..=20 seconds, recorded out of order: rank 19 of 20 is 19s.
+        for n in (1..=20).rev() {
+            stats.record(ApprovalKind::ApplyPatch, secs(n));
+        }
+        let patch = stats.snapshot().apply_patch;
+        assert_eq!(patch.count, 20);
+        assert_eq!(patch.mean, Duration::from_millis(10_500));
+        assert_eq!(patch.p95, secs(19));
+
+        // With fewer than 20 samples the p95 is the slowest one.
+        let mut stats = ApprovalStatsAccumulator::default();
+        for n in [2, 9, 3] {
+            stats.record(ApprovalKind::Exec, secs(n));
+        }
+        assert_eq!(stats.snapshot().exec.p95, secs(9));
+    }
+}
@@ -66,6 +66,7 @@ impl PendingInteractiveReplayState {
             op,
             Op::ExecApproval { .. }
                 | Op::PatchApproval { .. }
+                | Op::ApprovalResponse { .. }
                 | Op::ResolveElicitation { .. }
                 | Op::RequestPermissionsResponse { .. }
                 | Op::UserInputAnswer { .. }
@@ -92,6 +93,17 @@ impl PendingInteractiveReplayState {
                     id,
                 );
             }
+            // The structured response does not say which kind of prompt it
+            // resolves; call ids are unique, so clear it from both.
+            Op::ApprovalResponse { id, .. } => {
+                self.exec_approval_call_ids.remove(id);
+                Self::remove_call_id_from_turn_map(&mut self.exec_approval_call_ids_by_turn_id, id);
+                self.patch_approval_call_ids.remove(id);
+                Self::remove_call_id_from_turn_map(
+                    &mut self.patch_approval_call_ids_by_turn_id,
+                    id,
+                );
+            }
             Op::ResolveElicitation {
                 server_name,
                 request_id,
@@ -448,6 +460,7 @@ mod tests {
                     turn_id: "turn-1".to_string(),
                     command: vec!["echo".to_string(), "hi".to_string()],
                     cwd: PathBuf::from("/tmp"),
+                    env: None,
                     reason: None,
                     network_approval_context: None,
                     proposed_execpolicy_amendment: None,
@@ -580,6 +593,37 @@ mod tests {
         );
     }
 
+    #[test]
+    fn thread_event_snapshot_drops_patch_approval_resolved_by_approval_response() {
+        let mut store = ThreadEventStore::new(8);
+        store.push_event(Event {
+            id: "ev-1".to_string(),
+            msg: EventMsg::ApplyPatchApprovalRequest(
+                codex_protocol::protocol::ApplyPatchApprovalRequestEvent {
+                    call_id: "call-1".to_string(),
+                    turn_id: "turn-1".to_string(),
+                    changes: HashMap::new(),
+                    reason: None,
+                    grant_root: None,
+                },
+            ),
+        });
+
+        store.note_outbound_op(&Op::ApprovalResponse {
+            id: "call-1".to_string(),
+            turn_id: None,
+            response: codex_protocol::protocol::ApprovalResponse::new(
+                codex_protocol::protocol::ReviewDecision::Denied,
+            ),
+        });
+
+        let snapshot = store.snapshot();
+        assert!(
+            snapshot.events.is_empty(),
+            "resolved patch approval prompt should not replay on thread switch"
+        );
+    }
+
     #[test]
     fn thread_event_snapshot_drops_pending_approvals_when_turn_aborts() {
         let mut store = ThreadEventStore::new(8);
@@ -592,6 +636,7 @@ mod tests {
                     turn_id: "turn-1".to_string(),
                     command: vec!["echo".to_string(), "hi".to_string()],
                     cwd: PathBuf::from("/tmp"),
+                    env: None,
                     reason: None,
                     network_approval_context: None,
                     proposed_execpolicy_amendment: None,
@@ -682,6 +727,7 @@ mod tests {
                     turn_id: "turn-1".to_string(),
                     command: vec!["echo".to_string(), "hi".to_string()],
                     cwd: PathBuf::from("/tmp"),
+                    env: None,
                     reason: None,
                     network_approval_context: None,
                     proposed_execpolicy_amendment: None,
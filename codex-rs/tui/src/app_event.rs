@@ -9,6 +9,7 @@
 //! quits without reaching into the app loop or coupling to shutdown/exit sequencing.
 
 use std::path::PathBuf;
+use std::time::Duration;
 
 use codex_chatgpt::connectors::AppInfo;
 use codex_file_search::FileMatch;
@@ -19,6 +20,7 @@ use codex_protocol::protocol::RateLimitSnapshot;
 use codex_utils_approval_presets::ApprovalPreset;
 
 use crate::bottom_pane::ApprovalRequest;
+use crate::bottom_pane::CommandSignature;
 use crate::bottom_pane::StatusLineItem;
 use crate::history_cell::HistoryCell;
 
@@ -28,6 +30,7 @@ use codex_protocol::config_types::Personality;
 use codex_protocol::config_types::ServiceTier;
 use codex_protocol::openai_models::ReasoningEffort;
 use codex_protocol::protocol::AskForApproval;
+use codex_protocol::protocol::ReviewDecision;
 use codex_protocol::protocol::SandboxPolicy;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -155,6 +158,15 @@ pub(crate) enum AppEvent {
         url: String,
     },
 
+    /// Copy text to the system clipboard, reporting failures in the transcript.
+    CopyToClipboard(String),
+
+    /// Show text in `$PAGER` (or `less`) with the TUI suspended, then resume.
+    OpenInPager(String),
+
+    /// Ring the terminal bell.
+    Bell,
+
     /// Refresh app connector state and mention bindings.
     RefreshConnectors {
         force_refetch: bool,
@@ -422,6 +434,61 @@ pub(crate) enum AppEvent {
     /// Open the approval popup.
     FullScreenApprovalRequest(ApprovalRequest),
 
+    /// The approval policy program left this request to the user, so the
+    /// modal should ask.
+    ApprovalPolicyAbstained(ApprovalRequest),
+
+    /// Remember the latest agent message in the transcript as the one that
+    /// led to approval request `id`.
+    MarkApprovalSource {
+        id: String,
+    },
+
+    /// Open the transcript at the agent message that led to approval request `id`.
+    ShowApprovalSource {
+        id: String,
+    },
+
+    /// The user resolved an exec or patch approval. Recorded so a stalled
+    /// session can be traced back to the prompt that held it up, and added
+    /// to the session's approval latency stats.
+    ApprovalResolved {
+        id: String,
+        kind: ApprovalKind,
+        decision: ReviewDecision,
+        had_feedback: bool,
+        /// How long the prompt was on screen before the user decided.
+        elapsed: Duration,
+    },
+
+    /// A JSON record of an approval decision, to append to the
+    /// `tui.approvals.audit_log` file.
+    AuditRecord(serde_json::Value),
+
+    /// The approval modal finished with request `id` on screen. `resolved`
+    /// is `false` when it was dismissed or its auto-deny timer ran out rather
+    /// than answered, so status tied to the approval can be cleared either
+    /// way.
+    ApprovalClosed {
+        id: String,
+        resolved: bool,
+    },
+
+    /// Text for a screen-reader bridge to speak, such as the approval prompt
+    /// that just opened or the decision the user made.
+    Announce(String),
+
+    /// Resolve every queued approval with the same decision, e.g. after
+    /// Shift+Y in the approval prompt.
+    ApproveAllPending(ReviewDecision),
+
+    /// Approve commands matching `signature` without asking for the rest of
+    /// the session, logging them under the macro `name`.
+    RegisterAutoApprove {
+        name: String,
+        signature: CommandSignature,
+    },
+
     /// Open the feedback note entry overlay after the user selects a category.
     OpenFeedbackNote {
         category: FeedbackCategory,
@@ -470,6 +537,13 @@ pub(crate) enum ExitMode {
     Immediate,
 }
 
+/// Which kind of approval an [`AppEvent::ApprovalResolved`] refers to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ApprovalKind {
+    Exec,
+    ApplyPatch,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub(crate) enum FeedbackCategory {
     BadResult,
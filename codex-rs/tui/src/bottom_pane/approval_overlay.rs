@@ -1,59 +1,132 @@
+use std::cell::Cell;
+use std::cell::RefCell;
 use std::collections::HashMap;
+use std::collections::HashSet;
 use std::path::PathBuf;
+use std::rc::Rc;
+use std::sync::Arc;
+use std::time::Duration;
+use std::time::Instant;
 
 use crate::app_event::AppEvent;
 use crate::app_event_sender::AppEventSender;
 use crate::bottom_pane::BottomPaneView;
 use crate::bottom_pane::CancellationEvent;
 use crate::bottom_pane::list_selection_view::ListSelectionView;
-use crate::bottom_pane::list_selection_view::SelectionItem;
-use crate::bottom_pane::list_selection_view::SelectionViewParams;
-use crate::diff_render::DiffSummary;
 use crate::exec_command::strip_bash_lc_and_escape;
 use crate::history_cell;
 use crate::key_hint;
 use crate::key_hint::KeyBinding;
-use crate::render::highlight::highlight_bash_to_lines;
 use crate::render::renderable::ColumnRenderable;
 use crate::render::renderable::Renderable;
+use codex_core::config::TuiApprovals;
 use codex_core::features::Features;
 use codex_protocol::ThreadId;
 use codex_protocol::mcp::RequestId;
-use codex_protocol::models::MacOsAutomationPermission;
-use codex_protocol::models::MacOsContactsPermission;
-use codex_protocol::models::MacOsPreferencesPermission;
 use codex_protocol::models::PermissionProfile;
+use codex_protocol::protocol::ApprovalResponse;
 use codex_protocol::protocol::ElicitationAction;
 use codex_protocol::protocol::FileChange;
 use codex_protocol::protocol::NetworkApprovalContext;
-use codex_protocol::protocol::NetworkPolicyRuleAction;
-use codex_protocol::protocol::Op;
 use codex_protocol::protocol::ReviewDecision;
-use codex_protocol::request_permissions::PermissionGrantScope;
+use codex_protocol::protocol::SandboxPolicy;
 use crossterm::event::KeyCode;
 use crossterm::event::KeyEvent;
-use crossterm::event::KeyEventKind;
-use crossterm::event::KeyModifiers;
-use ratatui::buffer::Buffer;
+use crossterm::event::MouseEvent;
+use crossterm::event::MouseEventKind;
 use ratatui::layout::Rect;
 use ratatui::style::Stylize;
 use ratatui::text::Line;
-use ratatui::text::Span;
 use ratatui::widgets::Paragraph;
 use ratatui::widgets::Wrap;
+use serde::Deserialize;
+use serde::Serialize;
+
+use builder::ApprovalOverlayBuilder;
+use decisions::AutoDeny;
+use decisions::Deferrals;
+use decisions::FATIGUE_NUDGE;
+use decisions::Linger;
+use decisions::coalesce_requests;
+use decisions::merge_identical;
+use decisions::request_count;
+use inputs::CommandEditor;
+use inputs::DurationPicker;
+use inputs::FeedbackInput;
+use inputs::FilePicker;
+use keys::CopiedHint;
+use keys::EnterGuard;
+use keys::KeyDebounce;
+use keys::WarningGate;
+use keys::patch_diff;
+use options::ApprovalDecision;
+use options::ApprovalOption;
+use prompt::bounded_argv;
+use prompt::build_header;
+use prompt::many_deletions_warning;
+use prompt::request_age_note;
+use prompt::summarize_patch;
+use prompt::until_next_change;
+use render::CachedHeight;
+use render::PromptScroll;
+use render::QueuePosition;
+use render::Severity;
+use risk::PatchRiskLevel;
+use risk::RiskLevel;
+use risk::balance_warning;
+use risk::changed_test_files;
+use risk::classify_command_risk;
+use risk::classify_patch_risk;
+use risk::command_balance_issues;
+use risk::grants_outside_workspace;
+use risk::is_empty_command;
+use risk::remote_target;
+use risk::test_changes_warning;
+
+pub(crate) use decisions::ApprovalSession;
+pub(crate) use decisions::CommandSignature;
+pub(crate) use decisions::blocked_commands_hook;
+pub(crate) use decisions::resolve_by_macro;
+pub(crate) use decisions::resolve_by_policy;
+pub(crate) use inputs::format_approval_duration;
+pub(crate) use options::format_additional_permissions_rule;
+pub(crate) use prompt::RecentContext;
+pub(crate) use prompt::env_overrides;
+pub(crate) use prompt::redact_secrets;
+pub(crate) use render::ApprovalTheme;
+pub(crate) use risk::CommandOutcome;
+
+mod builder;
+mod decisions;
+mod inputs;
+mod keys;
+mod options;
+mod prompt;
+mod render;
+mod risk;
 
 /// Request coming from the agent that needs user approval.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
 pub(crate) enum ApprovalRequest {
     Exec {
         thread_id: ThreadId,
         thread_label: Option<String>,
         id: String,
         command: Vec<String>,
+        cwd: PathBuf,
         reason: Option<String>,
         available_decisions: Vec<ReviewDecision>,
         network_approval_context: Option<NetworkApprovalContext>,
         additional_permissions: Option<PermissionProfile>,
+        /// Sandbox the command runs under once approved, when known.
+        sandbox_policy: Option<SandboxPolicy>,
+        /// Environment variables the command sets on top of the inherited
+        /// environment.
+        env: Vec<(String, String)>,
+        /// Ids of identical requests merged into this one; the decision
+        /// answers each of them too.
+        duplicate_ids: Vec<String>,
     },
     Permissions {
         thread_id: ThreadId,
@@ -69,6 +142,8 @@ pub(crate) enum ApprovalRequest {
         reason: Option<String>,
         cwd: PathBuf,
         changes: HashMap<PathBuf, FileChange>,
+        /// Root the patch asks to write under for the rest of the session.
+        grant_root: Option<PathBuf>,
     },
     McpElicitation {
         thread_id: ThreadId,
@@ -80,6 +155,26 @@ pub(crate) enum ApprovalRequest {
 }
 
 impl ApprovalRequest {
+    /// The request as JSON, tagged with its `kind`, for tools and logs that
+    /// need what the modal shows without reading the rendered screen.
+    pub(crate) fn to_json(&self) -> serde_json::Value {
+        serde_json::to_value(self).unwrap_or_default()
+    }
+
+    /// The request [`Self::to_json`] recorded, for replaying a session.
+    pub(crate) fn from_json(json: serde_json::Value) -> serde_json::Result<Self> {
+        serde_json::from_value(json)
+    }
+
+    /// The id the request is answered under.
+    fn id(&self) -> String {
+        match self {
+            ApprovalRequest::Exec { id, .. } | ApprovalRequest::ApplyPatch { id, .. } => id.clone(),
+            ApprovalRequest::Permissions { call_id, .. } => call_id.clone(),
+            ApprovalRequest::McpElicitation { request_id, .. } => request_id.to_string(),
+        }
+    }
+
     fn thread_id(&self) -> ThreadId {
         match self {
             ApprovalRequest::Exec { thread_id, .. }
@@ -99,6 +194,11 @@ impl ApprovalRequest {
     }
 }
 
+/// Lets the embedding app refuse a decision before it is sent, with the
+/// reason to show, for example a command its policy blocks.
+pub(crate) type PreDecisionHook =
+    Box<dyn Fn(&ApprovalRequest, ReviewDecision) -> Result<(), String>>;
+
 /// Modal overlay asking the user to approve or deny one or more requests.
 pub(crate) struct ApprovalOverlay {
     current_request: Option<ApprovalRequest>,
@@ -106,321 +206,493 @@ pub(crate) struct ApprovalOverlay {
     app_event_tx: AppEventSender,
     list: ListSelectionView,
     options: Vec<ApprovalOption>,
+    /// Option that `.` selects to repeat the previous decision, if any.
+    replay_idx: Option<usize>,
+    /// Approving requires navigating to an option and pressing Enter.
+    explicit_approval: bool,
+    /// Prompt of the current request, shared with the feedback input.
+    prompt: Option<Arc<CachedHeight<ColumnRenderable<'static>>>>,
+    /// Set while the user types feedback for a denial.
+    feedback: Option<FeedbackInput>,
+    /// Set while the user edits the command before approving it.
+    command_editor: Option<CommandEditor>,
+    /// Set while the user picks how long to approve the command for.
+    duration_picker: Option<DurationPicker>,
+    /// Set while the user picks which of the patch's files to apply.
+    file_picker: Option<FilePicker>,
+    prompt_scroll: Rc<PromptScroll>,
+    /// Set by `f` to show an overlong command in full.
+    full_command_shown: Rc<Cell<bool>>,
+    /// Set by `R` to show the command as its raw argv.
+    raw_command_shown: Rc<Cell<bool>>,
+    /// Set by `o` to show a long reason in full.
+    reason_expanded: Rc<Cell<bool>>,
+    /// Transcript lines shown above the prompt, already cut to
+    /// `context_lines`.
+    recent_context: Vec<Line<'static>>,
+    /// How often each request was put off with Tab.
+    deferrals: Deferrals,
+    /// Set by space to show the full prompt when `compact` is on.
+    expanded: bool,
+    /// Declines the current request if the user does not respond in time.
+    auto_deny: Rc<AutoDeny>,
+    /// Shows "copied!" in the title for a moment after `c`.
+    copied: Rc<CopiedHint>,
+    /// `(1 of 3)` in the title while more than one request is pending.
+    queue_position: QueuePosition,
+    /// Requests the current prompt answers, counting merged duplicates.
+    identical: Rc<Cell<usize>>,
+    /// Requests shown so far, including the current one.
+    shown: usize,
+    /// Set by the first Esc when `confirm_abort` is on; a second Esc aborts.
+    abort_pending: Rc<Cell<bool>>,
+    /// Ignores Enter on a risky request until an option is chosen.
+    enter_guard: EnterGuard,
+    /// Holds the options of a high-risk request until its warning is
+    /// acknowledged with space.
+    warning_gate: WarningGate,
+    /// Set when any risk check flagged the current request, so approving
+    /// the whole queue stops there.
+    risky: bool,
+    /// Drops a press that only echoes the one before it.
+    key_debounce: KeyDebounce,
+    /// When the current request was shown, for [`AppEvent::ApprovalResolved`].
+    opened_at: Instant,
+    /// When the prompt opened, for the `requested 2m ago` note.
+    issued_at: Instant,
     current_complete: bool,
+    /// Set when the auto-deny timer, not the user, declined the current
+    /// request.
+    timed_out: bool,
+    /// `?` swapped the options list for every option's description.
+    show_all_descriptions: bool,
     done: bool,
+    /// Keeps the modal open for a moment once `done`, with `linger_ms`.
+    linger: Linger,
+    /// How the most recently answered request was resolved.
+    last_decision: RefCell<Option<ReviewDecision>>,
+    /// Requests a decision was sent for, by thread and id, so a request
+    /// forwarded again is not answered twice.
+    answered_ids: RefCell<HashSet<(ThreadId, String)>>,
+    /// How risky the current request is, shown on the frame's left side.
+    severity: Severity,
+    /// Consulted before each decision is sent.
+    pre_decision_hook: Option<PreDecisionHook>,
+    /// Why the hook refused the last decision on the current request, shown
+    /// under the options until a decision goes through.
+    vetoed: Option<String>,
+    /// Cleared while another view sits on top of the modal and takes input.
+    focused: bool,
     features: Features,
+    config: TuiApprovals,
+    session: ApprovalSession,
+    theme: ApprovalTheme,
+    /// Drawn as a boxed, titled popup rather than inline.
+    popup: bool,
 }
 
 impl ApprovalOverlay {
-    pub fn new(request: ApprovalRequest, app_event_tx: AppEventSender, features: Features) -> Self {
-        let mut view = Self {
-            current_request: None,
-            queue: Vec::new(),
-            app_event_tx: app_event_tx.clone(),
-            list: ListSelectionView::new(Default::default(), app_event_tx),
-            options: Vec::new(),
-            current_complete: false,
-            done: false,
-            features,
-        };
-        view.set_current(request);
-        view
+    pub fn new(request: ApprovalRequest, app_event_tx: AppEventSender) -> Self {
+        Self::builder(request, app_event_tx).build()
     }
 
-    pub fn enqueue_request(&mut self, req: ApprovalRequest) {
-        self.queue.push(req);
+    /// The overlay for a request recorded with [`ApprovalRequest::to_json`],
+    /// so a replayed session presents the same approval again.
+    #[cfg_attr(not(test), allow(dead_code))]
+    pub(crate) fn from_recorded(
+        json: serde_json::Value,
+        app_event_tx: AppEventSender,
+    ) -> serde_json::Result<Self> {
+        ApprovalRequest::from_json(json).map(|request| Self::new(request, app_event_tx))
     }
 
-    fn set_current(&mut self, request: ApprovalRequest) {
-        self.current_complete = false;
-        let header = build_header(&request);
-        let (options, params) = Self::build_options(&request, header, &self.features);
-        self.current_request = Some(request);
-        self.options = options;
-        self.list = ListSelectionView::new(params, self.app_event_tx.clone());
+    pub(crate) fn builder(
+        request: ApprovalRequest,
+        app_event_tx: AppEventSender,
+    ) -> ApprovalOverlayBuilder {
+        ApprovalOverlayBuilder::new(request, app_event_tx)
     }
 
-    fn build_options(
-        request: &ApprovalRequest,
-        header: Box<dyn Renderable>,
-        _features: &Features,
-    ) -> (Vec<ApprovalOption>, SelectionViewParams) {
-        let (options, title) = match request {
-            ApprovalRequest::Exec {
-                available_decisions,
-                network_approval_context,
-                additional_permissions,
-                ..
-            } => (
-                exec_options(
-                    available_decisions,
-                    network_approval_context.as_ref(),
-                    additional_permissions.as_ref(),
-                ),
-                network_approval_context.as_ref().map_or_else(
-                    || "Would you like to run the following command?".to_string(),
-                    |network_approval_context| {
-                        format!(
-                            "Do you want to approve network access to \"{}\"?",
-                            network_approval_context.host
-                        )
-                    },
-                ),
-            ),
-            ApprovalRequest::Permissions { .. } => (
-                permissions_options(),
-                "Would you like to grant these permissions?".to_string(),
-            ),
-            ApprovalRequest::ApplyPatch { .. } => (
-                patch_options(),
-                "Would you like to make the following edits?".to_string(),
-            ),
-            ApprovalRequest::McpElicitation { server_name, .. } => (
-                elicitation_options(),
-                format!("{server_name} needs your approval."),
-            ),
-        };
-
-        let header = Box::new(ColumnRenderable::with([
-            Line::from(title.bold()).into(),
-            Line::from("").into(),
-            header,
-        ]));
-
-        let items = options
-            .iter()
-            .map(|opt| SelectionItem {
-                name: opt.label.clone(),
-                display_shortcut: opt
-                    .display_shortcut
-                    .or_else(|| opt.additional_shortcuts.first().copied()),
-                dismiss_on_select: false,
-                ..Default::default()
-            })
-            .collect();
-
-        let params = SelectionViewParams {
-            footer_hint: Some(approval_footer_hint(request)),
-            items,
-            header,
-            ..Default::default()
+    pub fn enqueue_request(&mut self, req: ApprovalRequest) {
+        self.ring_bell_if_due();
+        // With nothing queued, the request on screen is the one that arrived
+        // last, so a repeat of it joins the open prompt.
+        let req = match self.current_request.as_mut() {
+            Some(current) if self.queue.is_empty() && !self.current_complete => {
+                match merge_identical(current, req) {
+                    Some(req) => req,
+                    None => {
+                        self.identical.set(request_count(current));
+                        return;
+                    }
+                }
+            }
+            Some(_) | None => req,
         };
-
-        (options, params)
+        self.queue.push(req);
+        self.queue = coalesce_requests(std::mem::take(&mut self.queue));
+        self.update_queue_position();
     }
 
-    fn apply_selection(&mut self, actual_idx: usize) {
-        if self.current_complete {
-            return;
-        }
-        let Some(option) = self.options.get(actual_idx) else {
-            return;
-        };
-        if let Some(request) = self.current_request.as_ref() {
-            match (request, &option.decision) {
-                (ApprovalRequest::Exec { id, command, .. }, ApprovalDecision::Review(decision)) => {
-                    self.handle_exec_decision(id, command, decision.clone());
-                }
-                (
-                    ApprovalRequest::Permissions {
-                        call_id,
-                        permissions,
-                        ..
-                    },
-                    ApprovalDecision::Review(decision),
-                ) => self.handle_permissions_decision(call_id, permissions, decision.clone()),
-                (ApprovalRequest::ApplyPatch { id, .. }, ApprovalDecision::Review(decision)) => {
-                    self.handle_patch_decision(id, decision.clone());
-                }
-                (
-                    ApprovalRequest::McpElicitation {
-                        server_name,
-                        request_id,
-                        ..
-                    },
-                    ApprovalDecision::McpElicitation(decision),
-                ) => {
-                    self.handle_elicitation_decision(server_name, request_id, *decision);
-                }
-                _ => {}
-            }
+    fn ring_bell_if_due(&self) {
+        if self.session.take_bell(&self.config, Instant::now()) {
+            self.app_event_tx.send(AppEvent::Bell);
         }
+    }
 
-        self.current_complete = true;
-        self.advance_queue();
+    fn update_queue_position(&self) {
+        let total = self.shown + self.queue.len();
+        self.queue_position
+            .set((total > 1).then_some((self.shown, total)));
     }
 
-    fn handle_exec_decision(&self, id: &str, command: &[String], decision: ReviewDecision) {
-        let Some(request) = self.current_request.as_ref() else {
-            return;
+    fn set_current(&mut self, request: ApprovalRequest) {
+        self.current_complete = false;
+        self.timed_out = false;
+        self.vetoed = None;
+        self.show_all_descriptions = false;
+        self.abort_pending.set(false);
+        self.shown += 1;
+        self.update_queue_position();
+        self.identical.set(request_count(&request));
+        self.full_command_shown = Rc::default();
+        self.raw_command_shown = Rc::default();
+        self.reason_expanded = Rc::default();
+        self.expanded = false;
+        let mut header = build_header(
+            &request,
+            &self.full_command_shown,
+            &self.raw_command_shown,
+            &self.reason_expanded,
+            self.config.word_diff.unwrap_or(false),
+            self.config.command_synopsis.unwrap_or(false),
+            &self.theme,
+        );
+        let risk = match &request {
+            ApprovalRequest::Exec { command, .. } => {
+                classify_command_risk(&strip_bash_lc_and_escape(&bounded_argv(command)))
+            }
+            ApprovalRequest::Permissions { .. }
+            | ApprovalRequest::ApplyPatch { .. }
+            | ApprovalRequest::McpElicitation { .. } => RiskLevel::Benign,
         };
-        if request.thread_label().is_none() {
-            let cell = history_cell::new_approval_decision_cell(command.to_vec(), decision.clone());
-            self.app_event_tx.send(AppEvent::InsertHistoryCell(cell));
+        let risk_warning = match risk {
+            RiskLevel::Destructive => Some("⚠ This command may be destructive"),
+            RiskLevel::Privileged => Some("⚠ requests elevated privileges"),
+            RiskLevel::Benign => None,
+        };
+        if let Some(warning) = risk_warning {
+            header = Box::new(ColumnRenderable::with([
+                Line::from(warning.fg(self.theme.warning).bold()).into(),
+                Line::from("").into(),
+                header,
+            ]));
         }
-        let thread_id = request.thread_id();
-        self.app_event_tx.send(AppEvent::SubmitThreadOp {
-            thread_id,
-            op: Op::ExecApproval {
-                id: id.to_string(),
-                turn_id: None,
-                decision,
-            },
-        });
-    }
-
-    fn handle_permissions_decision(
-        &self,
-        call_id: &str,
-        permissions: &PermissionProfile,
-        decision: ReviewDecision,
-    ) {
-        let Some(request) = self.current_request.as_ref() else {
-            return;
+        let empty_command = match &request {
+            ApprovalRequest::Exec { command, .. } => is_empty_command(command),
+            ApprovalRequest::Permissions { .. }
+            | ApprovalRequest::ApplyPatch { .. }
+            | ApprovalRequest::McpElicitation { .. } => false,
         };
-        let granted_permissions = match decision {
-            ReviewDecision::Approved | ReviewDecision::ApprovedForSession => permissions.clone(),
-            ReviewDecision::Denied | ReviewDecision::Abort => Default::default(),
-            ReviewDecision::ApprovedExecpolicyAmendment { .. }
-            | ReviewDecision::NetworkPolicyAmendment { .. } => Default::default(),
+        if empty_command {
+            header = Box::new(ColumnRenderable::with([
+                Line::from(
+                    "⚠ empty command — denying recommended"
+                        .fg(self.theme.warning)
+                        .bold(),
+                )
+                .into(),
+                Line::from("").into(),
+                header,
+            ]));
+        }
+        let balance_issues = match &request {
+            ApprovalRequest::Exec { command, .. } => command_balance_issues(command),
+            ApprovalRequest::Permissions { .. }
+            | ApprovalRequest::ApplyPatch { .. }
+            | ApprovalRequest::McpElicitation { .. } => Vec::new(),
         };
-        let scope = if matches!(decision, ReviewDecision::ApprovedForSession) {
-            PermissionGrantScope::Session
-        } else {
-            PermissionGrantScope::Turn
+        let network = matches!(
+            &request,
+            ApprovalRequest::Exec {
+                network_approval_context: Some(_),
+                ..
+            }
+        );
+        self.severity = Severity::of(risk, network, !balance_issues.is_empty());
+        if let Some(warning) = balance_warning(&balance_issues, &self.theme) {
+            header = Box::new(ColumnRenderable::with([
+                warning.into(),
+                Line::from("").into(),
+                header,
+            ]));
+        }
+        let (patch_risk, deleted_files) = match &request {
+            ApprovalRequest::ApplyPatch { cwd, changes, .. } => {
+                let patch = patch_diff(cwd, changes);
+                let summary = summarize_patch(&patch);
+                let risk = classify_patch_risk(&summary, &patch);
+                (Some(risk), summary.deleted_files.len())
+            }
+            ApprovalRequest::Exec { .. }
+            | ApprovalRequest::Permissions { .. }
+            | ApprovalRequest::McpElicitation { .. } => (None, 0),
         };
-        if request.thread_label().is_none() {
-            let message = if granted_permissions.is_empty() {
-                "You did not grant additional permissions"
-            } else if matches!(scope, PermissionGrantScope::Session) {
-                "You granted additional permissions for this session"
-            } else {
-                "You granted additional permissions"
-            };
-            self.app_event_tx.send(AppEvent::InsertHistoryCell(Box::new(
-                crate::history_cell::PlainHistoryCell::new(vec![message.into()]),
-            )));
+        let many_deletions = many_deletions_warning(deleted_files, &self.theme);
+        let deletes_many = many_deletions.is_some();
+        if let Some(warning) = many_deletions {
+            header = Box::new(ColumnRenderable::with([
+                warning.into(),
+                Line::from("").into(),
+                header,
+            ]));
         }
-        let thread_id = request.thread_id();
-        self.app_event_tx.send(AppEvent::SubmitThreadOp {
-            thread_id,
-            op: Op::RequestPermissionsResponse {
-                id: call_id.to_string(),
-                response: codex_protocol::request_permissions::RequestPermissionsResponse {
-                    permissions: granted_permissions,
-                    scope,
-                },
-            },
-        });
-    }
-
-    fn handle_patch_decision(&self, id: &str, decision: ReviewDecision) {
-        let Some(thread_id) = self
-            .current_request
+        if let Some(badge) = patch_risk.as_ref().and_then(|risk| risk.badge(&self.theme)) {
+            header = Box::new(ColumnRenderable::with([
+                Paragraph::new(badge).wrap(Wrap { trim: false }).into(),
+                Line::from("").into(),
+                header,
+            ]));
+        }
+        let test_files = match &request {
+            ApprovalRequest::ApplyPatch { cwd, changes, .. } => {
+                changed_test_files(changes, cwd, &self.config)
+            }
+            ApprovalRequest::Exec { .. }
+            | ApprovalRequest::Permissions { .. }
+            | ApprovalRequest::McpElicitation { .. } => Vec::new(),
+        };
+        if !test_files.is_empty() {
+            header = Box::new(ColumnRenderable::with([
+                test_changes_warning(&test_files, &self.theme).into(),
+                Line::from("").into(),
+                header,
+            ]));
+        }
+        let target = match &request {
+            ApprovalRequest::Exec { command, .. } => remote_target(&bounded_argv(command)),
+            ApprovalRequest::Permissions { .. }
+            | ApprovalRequest::ApplyPatch { .. }
+            | ApprovalRequest::McpElicitation { .. } => None,
+        };
+        let production = target
             .as_ref()
-            .map(ApprovalRequest::thread_id)
-        else {
-            return;
+            .is_some_and(|target| target.is_production(&self.config));
+        if let Some(target) = &target {
+            header = Box::new(ColumnRenderable::with([
+                Paragraph::new(target.header_lines(production, &self.theme))
+                    .wrap(Wrap { trim: false })
+                    .into(),
+                Line::from("").into(),
+                header,
+            ]));
+        }
+        if self.session.is_new_command(&request) {
+            header = Box::new(ColumnRenderable::with([
+                Line::from(vec![
+                    "● New this session".fg(self.theme.accent).bold(),
+                    " · you haven't approved this command before".dim(),
+                ])
+                .into(),
+                Line::from("").into(),
+                header,
+            ]));
+        }
+        if let Some(line) = self.deferrals.still_waiting_line(&request, &self.theme) {
+            header = Box::new(ColumnRenderable::with([
+                line.into(),
+                Line::from("").into(),
+                header,
+            ]));
+        }
+        if let Some(line) = self.deferrals.note_line(&request) {
+            header = Box::new(ColumnRenderable::with([
+                Paragraph::new(line).wrap(Wrap { trim: false }).into(),
+                Line::from("").into(),
+                header,
+            ]));
+        }
+        if let Some(outcome) = self.session.prior_outcome(&request) {
+            header = Box::new(ColumnRenderable::with([
+                header,
+                Line::from("").into(),
+                outcome.advisory_line(&self.theme).into(),
+            ]));
+        }
+        if self.session.take_fatigue_nudge(&self.config) {
+            header = Box::new(ColumnRenderable::with([
+                header,
+                Line::from("").into(),
+                Paragraph::new(Line::from(FATIGUE_NUDGE.dim().italic()))
+                    .wrap(Wrap { trim: false })
+                    .into(),
+            ]));
+        }
+        self.prompt_scroll = Rc::default();
+        self.auto_deny = Rc::new(
+            self.config
+                .auto_deny_secs
+                .map_or_else(AutoDeny::default, |secs| {
+                    AutoDeny::start(Duration::from_secs(secs))
+                }),
+        );
+        self.copied = Rc::default();
+        let (mut options, mut params, prompt) = Self::build_options(
+            &request,
+            header,
+            &self.features,
+            &self.config,
+            &self.prompt_scroll,
+            &self.auto_deny,
+            &self.copied,
+            &self.queue_position,
+            &self.identical,
+            &self.abort_pending,
+            &self.recent_context,
+            self.theme,
+        );
+        if production {
+            // Approving against production should take a deliberate selection,
+            // not a stray keypress.
+            for (option, item) in options.iter_mut().zip(params.items.iter_mut()) {
+                if option.decision.is_approval() {
+                    option.display_shortcut = None;
+                    option.additional_shortcuts.clear();
+                    item.display_shortcut = None;
+                }
+            }
+        }
+        let destructive = risk == RiskLevel::Destructive;
+        let privileged = risk == RiskLevel::Privileged;
+        let outside_workspace = match &request {
+            ApprovalRequest::ApplyPatch {
+                cwd,
+                grant_root: Some(root),
+                ..
+            } => grants_outside_workspace(root, cwd),
+            ApprovalRequest::ApplyPatch { .. }
+            | ApprovalRequest::Exec { .. }
+            | ApprovalRequest::Permissions { .. }
+            | ApprovalRequest::McpElicitation { .. } => false,
         };
-        self.app_event_tx.send(AppEvent::SubmitThreadOp {
-            thread_id,
-            op: Op::PatchApproval {
-                id: id.to_string(),
-                decision,
-            },
-        });
+        let high_risk_patch = patch_risk
+            .as_ref()
+            .is_some_and(|risk| risk.level == PatchRiskLevel::High);
+        let risky = production
+            || destructive
+            || privileged
+            || empty_command
+            || deletes_many
+            || outside_workspace
+            || high_risk_patch
+            || !test_files.is_empty();
+        self.risky = risky;
+        self.enter_guard =
+            EnterGuard::new(risky && self.config.require_explicit_select.unwrap_or(false));
+        self.warning_gate = WarningGate::new(
+            (destructive || high_risk_patch) && self.config.acknowledge_warnings.unwrap_or(false),
+        );
+        if self.warning_gate.holds_options() {
+            for item in &mut params.items {
+                item.is_disabled = true;
+            }
+            params.footer_note = self.warning_gate.prompt(&self.theme);
+        }
+        if risky {
+            // Test edits can make a failing suite pass, and destructive,
+            // privileged or empty commands, production targets, high-risk
+            // patches, patches deleting many files and write access beyond
+            // the workspace deserve a second look, so default to "No" and let
+            // a stray Enter decline.
+            params.initial_selected_idx = options.iter().position(|opt| {
+                matches!(
+                    opt.decision,
+                    ApprovalDecision::Review(ReviewDecision::Abort | ReviewDecision::Denied)
+                )
+            });
+        }
+        if !test_files.is_empty() {
+            self.app_event_tx
+                .send(AppEvent::FullScreenApprovalRequest(request.clone()));
+        }
+        self.replay_idx = self
+            .session
+            .replayable_decision(&request)
+            .and_then(|decision| {
+                options
+                    .iter()
+                    .position(|opt| opt.decision == decision)
+                    .filter(|_| !(production && decision.is_approval()))
+            });
+        if let Some(option) = self.replay_idx.and_then(|idx| options.get(idx)) {
+            params.footer_hint = Some(Line::from(vec![
+                "Press ".into(),
+                key_hint::plain(KeyCode::Char('.')).into(),
+                format!(" to repeat last decision ({})", option.label).into(),
+            ]));
+        }
+        self.explicit_approval = production;
+        self.prompt = Some(prompt);
+        self.feedback = None;
+        self.command_editor = None;
+        self.duration_picker = None;
+        self.file_picker = None;
+        self.current_request = Some(request);
+        self.opened_at = Instant::now();
+        self.options = options;
+        self.list = ListSelectionView::new(params, self.app_event_tx.clone());
     }
 
-    fn handle_elicitation_decision(
-        &self,
-        server_name: &str,
-        request_id: &RequestId,
-        decision: ElicitationAction,
-    ) {
-        let Some(thread_id) = self
-            .current_request
-            .as_ref()
-            .map(ApprovalRequest::thread_id)
-        else {
-            return;
-        };
-        self.app_event_tx.send(AppEvent::SubmitThreadOp {
-            thread_id,
-            op: Op::ResolveElicitation {
-                server_name: server_name.to_string(),
-                request_id: request_id.clone(),
-                decision,
-                content: None,
-                meta: None,
-            },
-        });
+    /// The argv of the command being asked about, so the parent can show what
+    /// changed since the previous prompt. `None` unless a command is pending.
+    #[cfg_attr(not(test), allow(dead_code))]
+    pub(crate) fn pending_command(&self) -> Option<&[String]> {
+        if self.current_complete {
+            return None;
+        }
+        match self.current_request.as_ref()? {
+            ApprovalRequest::Exec { command, .. } => Some(command.as_slice()),
+            ApprovalRequest::Permissions { .. }
+            | ApprovalRequest::ApplyPatch { .. }
+            | ApprovalRequest::McpElicitation { .. } => None,
+        }
     }
 
     fn advance_queue(&mut self) {
         if let Some(next) = self.queue.pop() {
             self.set_current(next);
+            self.announce();
         } else {
-            self.done = true;
+            // A dismissed request leaves no decision behind.
+            let resolved = !self.timed_out && self.last_decision.borrow().is_some();
+            if let Some(ms) = self.config.linger_ms.filter(|ms| resolved && *ms > 0) {
+                self.linger = Linger::start(Duration::from_millis(ms));
+            }
+            self.close(resolved);
         }
     }
 
-    fn try_handle_shortcut(&mut self, key_event: &KeyEvent) -> bool {
-        match key_event {
-            KeyEvent {
-                kind: KeyEventKind::Press,
-                code: KeyCode::Char('a'),
-                modifiers,
-                ..
-            } if modifiers.contains(KeyModifiers::CONTROL) => {
-                if let Some(request) = self.current_request.as_ref() {
-                    self.app_event_tx
-                        .send(AppEvent::FullScreenApprovalRequest(request.clone()));
-                    true
-                } else {
-                    false
-                }
-            }
-            KeyEvent {
-                kind: KeyEventKind::Press,
-                code: KeyCode::Char('o'),
-                ..
-            } => {
-                if let Some(request) = self.current_request.as_ref() {
-                    if request.thread_label().is_some() {
-                        self.app_event_tx
-                            .send(AppEvent::SelectAgentThread(request.thread_id()));
-                        true
-                    } else {
-                        false
-                    }
-                } else {
-                    false
-                }
-            }
-            e => {
-                if let Some(idx) = self
-                    .options
-                    .iter()
-                    .position(|opt| opt.shortcuts().any(|s| s.is_press(*e)))
-                {
-                    self.apply_selection(idx);
-                    true
-                } else {
-                    false
-                }
-            }
+    /// Finish the modal, telling the parent whether the last request was
+    /// decided by the user (`resolved`) or dismissed or timed out.
+    fn close(&mut self, resolved: bool) {
+        self.done = true;
+        if let Some(request) = self.current_request.as_ref() {
+            self.app_event_tx.send(AppEvent::ApprovalClosed {
+                id: request.id(),
+                resolved,
+            });
         }
     }
 }
 
 impl BottomPaneView for ApprovalOverlay {
     fn handle_key_event(&mut self, key_event: KeyEvent) {
-        if self.try_handle_shortcut(&key_event) {
+        if self.done || !self.focused {
             return;
         }
-        self.list.handle_key_event(key_event);
-        if let Some(idx) = self.list.take_last_selected_index() {
-            self.apply_selection(idx);
+        self.auto_deny.cancel();
+        if !self.handle_input_key(key_event) {
+            self.handle_option_key(key_event);
         }
     }
 
@@ -428,12 +700,19 @@ impl BottomPaneView for ApprovalOverlay {
         if self.done {
             return CancellationEvent::Handled;
         }
+        // Ctrl-C answers the open request with an abort, which is a decision
+        // like any other; the modal just closes without lingering.
+        let resolved = !self.current_complete && self.current_request.is_some();
         if !self.current_complete
             && let Some(request) = self.current_request.as_ref()
         {
             match request {
                 ApprovalRequest::Exec { id, command, .. } => {
-                    self.handle_exec_decision(id, command, ReviewDecision::Abort);
+                    self.handle_exec_decision(
+                        id,
+                        command,
+                        ApprovalResponse::new(ReviewDecision::Abort),
+                    );
                 }
                 ApprovalRequest::Permissions {
                     call_id,
@@ -443,7 +722,7 @@ impl BottomPaneView for ApprovalOverlay {
                     self.handle_permissions_decision(call_id, permissions, ReviewDecision::Abort);
                 }
                 ApprovalRequest::ApplyPatch { id, .. } => {
-                    self.handle_patch_decision(id, ReviewDecision::Abort);
+                    self.handle_patch_decision(id, ApprovalResponse::new(ReviewDecision::Abort));
                 }
                 ApprovalRequest::McpElicitation {
                     server_name,
@@ -459,452 +738,264 @@ impl BottomPaneView for ApprovalOverlay {
             }
         }
         self.queue.clear();
-        self.done = true;
+        self.close(resolved);
         CancellationEvent::Handled
     }
 
     fn is_complete(&self) -> bool {
-        self.done
+        self.done && self.linger.remaining().is_none()
     }
 
-    fn try_consume_approval_request(
-        &mut self,
-        request: ApprovalRequest,
-    ) -> Option<ApprovalRequest> {
-        self.enqueue_request(request);
-        None
+    fn set_focused(&mut self, focused: bool) {
+        self.focused = focused;
     }
-}
 
-impl Renderable for ApprovalOverlay {
-    fn desired_height(&self, width: u16) -> u16 {
-        self.list.desired_height(width)
+    fn on_resize(&mut self, width: u16, _height: u16) {
+        let Some(prompt) = &self.prompt else {
+            return;
+        };
+        prompt.invalidate();
+        let width = self.content_area(Rect::new(0, 0, width, 0)).width;
+        self.prompt_scroll.clamp(prompt.desired_height(width));
     }
 
-    fn render(&self, area: Rect, buf: &mut Buffer) {
-        self.list.render(area, buf);
+    fn active_hotkeys(&self) -> Vec<(KeyBinding, String)> {
+        if self.done {
+            return Vec::new();
+        }
+        let mut hotkeys: Vec<(KeyBinding, String)> = self
+            .options
+            .iter()
+            .filter_map(|opt| {
+                let shortcut = opt
+                    .display_shortcut
+                    .or_else(|| opt.additional_shortcuts.first().copied())?;
+                Some((shortcut, opt.label.clone()))
+            })
+            .filter(|(shortcut, _)| *shortcut != key_hint::plain(KeyCode::Esc))
+            .collect();
+        hotkeys.push((key_hint::plain(KeyCode::Enter), "confirm".to_string()));
+        hotkeys.push((key_hint::plain(KeyCode::Esc), "cancel".to_string()));
+        hotkeys
     }
 
-    fn cursor_pos(&self, area: Rect) -> Option<(u16, u16)> {
-        self.list.cursor_pos(area)
+    fn outcome(&self) -> Option<ReviewDecision> {
+        if self.done {
+            self.last_decision.borrow().clone()
+        } else {
+            None
+        }
     }
-}
-
-fn approval_footer_hint(request: &ApprovalRequest) -> Line<'static> {
-    let mut spans = vec![
-        "Press ".into(),
-        key_hint::plain(KeyCode::Enter).into(),
-        " to confirm or ".into(),
-        key_hint::plain(KeyCode::Esc).into(),
-        " to cancel".into(),
-    ];
-    if request.thread_label().is_some() {
-        spans.extend([
-            " or ".into(),
-            key_hint::plain(KeyCode::Char('o')).into(),
-            " to open thread".into(),
-        ]);
-    }
-    Line::from(spans)
-}
 
-fn build_header(request: &ApprovalRequest) -> Box<dyn Renderable> {
-    match request {
-        ApprovalRequest::Exec {
-            thread_label,
-            reason,
-            command,
-            network_approval_context,
-            additional_permissions,
-            ..
-        } => {
-            let mut header: Vec<Line<'static>> = Vec::new();
-            if let Some(thread_label) = thread_label {
-                header.push(Line::from(vec![
-                    "Thread: ".into(),
-                    thread_label.clone().bold(),
-                ]));
-                header.push(Line::from(""));
-            }
-            if let Some(reason) = reason {
-                header.push(Line::from(vec!["Reason: ".into(), reason.clone().italic()]));
-                header.push(Line::from(""));
-            }
-            if let Some(additional_permissions) = additional_permissions
-                && let Some(rule_line) = format_additional_permissions_rule(additional_permissions)
-            {
-                header.push(Line::from(vec![
-                    "Permission rule: ".into(),
-                    rule_line.cyan(),
-                ]));
-                header.push(Line::from(""));
-            }
-            let full_cmd = strip_bash_lc_and_escape(command);
-            let mut full_cmd_lines = highlight_bash_to_lines(&full_cmd);
-            if let Some(first) = full_cmd_lines.first_mut() {
-                first.spans.insert(0, Span::from("$ "));
-            }
-            if network_approval_context.is_none() {
-                header.extend(full_cmd_lines);
-            }
-            Box::new(Paragraph::new(header).wrap(Wrap { trim: false }))
-        }
-        ApprovalRequest::Permissions {
-            thread_label,
-            reason,
-            permissions,
-            ..
-        } => {
-            let mut header: Vec<Line<'static>> = Vec::new();
-            if let Some(thread_label) = thread_label {
-                header.push(Line::from(vec![
-                    "Thread: ".into(),
-                    thread_label.clone().bold(),
-                ]));
-                header.push(Line::from(""));
-            }
-            if let Some(reason) = reason {
-                header.push(Line::from(vec!["Reason: ".into(), reason.clone().italic()]));
-                header.push(Line::from(""));
-            }
-            if let Some(rule_line) = format_additional_permissions_rule(permissions) {
-                header.push(Line::from(vec![
-                    "Permission rule: ".into(),
-                    rule_line.cyan(),
-                ]));
-            }
-            Box::new(Paragraph::new(header).wrap(Wrap { trim: false }))
+    fn dismiss_approvals(&mut self, thread_id: ThreadId) {
+        if self.done {
+            return;
         }
-        ApprovalRequest::ApplyPatch {
-            thread_label,
-            reason,
-            cwd,
-            changes,
-            ..
-        } => {
-            let mut header: Vec<Box<dyn Renderable>> = Vec::new();
-            if let Some(thread_label) = thread_label {
-                header.push(Box::new(Line::from(vec![
-                    "Thread: ".into(),
-                    thread_label.clone().bold(),
-                ])));
-                header.push(Box::new(Line::from("")));
-            }
-            if let Some(reason) = reason
-                && !reason.is_empty()
-            {
-                header.push(Box::new(
-                    Paragraph::new(Line::from_iter([
-                        "Reason: ".into(),
-                        reason.clone().italic(),
-                    ]))
-                    .wrap(Wrap { trim: false }),
-                ));
-                header.push(Box::new(Line::from("")));
-            }
-            header.push(DiffSummary::new(changes.clone(), cwd.clone()).into());
-            Box::new(ColumnRenderable::with(header))
+        let queued = self.queue.len();
+        self.queue
+            .retain(|request| request.thread_id() != thread_id);
+        let mut dismissed = queued - self.queue.len();
+        if !self.current_complete
+            && self
+                .current_request
+                .as_ref()
+                .is_some_and(|request| request.thread_id() == thread_id)
+        {
+            // No response goes out: answering a request core dropped would
+            // hand it a decision for nothing, so `outcome` reports none.
+            dismissed += 1;
+            self.current_complete = true;
+            self.last_decision.replace(None);
+            self.feedback = None;
+            self.command_editor = None;
+            self.duration_picker = None;
+            self.file_picker = None;
+            self.advance_queue();
         }
-        ApprovalRequest::McpElicitation {
-            thread_label,
-            server_name,
-            message,
-            ..
-        } => {
-            let mut lines = Vec::new();
-            if let Some(thread_label) = thread_label {
-                lines.push(Line::from(vec![
-                    "Thread: ".into(),
-                    thread_label.clone().bold(),
-                ]));
-                lines.push(Line::from(""));
-            }
-            lines.extend([
-                Line::from(vec!["Server: ".into(), server_name.clone().bold()]),
-                Line::from(""),
-                Line::from(message.clone()),
-            ]);
-            let header = Paragraph::new(lines).wrap(Wrap { trim: false });
-            Box::new(header)
+        if dismissed > 0 {
+            let message = if dismissed == 1 {
+                "Approval request dismissed".to_string()
+            } else {
+                format!("{dismissed} approval requests dismissed")
+            };
+            let cell = history_cell::new_info_event(
+                message,
+                Some("codex stopped waiting for an answer".to_string()),
+            );
+            self.app_event_tx
+                .send(AppEvent::InsertHistoryCell(Box::new(cell)));
         }
     }
-}
-
-#[derive(Clone)]
-enum ApprovalDecision {
-    Review(ReviewDecision),
-    McpElicitation(ElicitationAction),
-}
 
-#[derive(Clone)]
-struct ApprovalOption {
-    label: String,
-    decision: ApprovalDecision,
-    display_shortcut: Option<KeyBinding>,
-    additional_shortcuts: Vec<KeyBinding>,
-}
+    fn prefer_esc_to_handle_key_event(&self) -> bool {
+        // Esc must reach `handle_key_event` to leave a text input or to ask
+        // for confirmation; Ctrl-C still goes through `on_ctrl_c` and aborts
+        // at once.
+        self.text_input().is_some() || self.config.confirm_abort.unwrap_or(false)
+    }
 
-impl ApprovalOption {
-    fn shortcuts(&self) -> impl Iterator<Item = KeyBinding> + '_ {
-        self.display_shortcut
-            .into_iter()
-            .chain(self.additional_shortcuts.iter().copied())
+    fn approve_all_pending(&mut self, decision: ReviewDecision) -> bool {
+        let mut resolved = false;
+        // Each request gets its own response so core can match it by id. A
+        // risky request, or one that does not offer the decision, stays on
+        // screen for the user to answer.
+        while !self.done
+            && !self.risky
+            && !self.warning_gate.holds_options()
+            && self
+                .options
+                .iter()
+                .any(|option| option.decision == ApprovalDecision::Review(decision.clone()))
+            && self.send_decision(decision.clone())
+        {
+            resolved = true;
+        }
+        resolved
     }
-}
 
-fn exec_options(
-    available_decisions: &[ReviewDecision],
-    network_approval_context: Option<&NetworkApprovalContext>,
-    additional_permissions: Option<&PermissionProfile>,
-) -> Vec<ApprovalOption> {
-    available_decisions
-        .iter()
-        .filter_map(|decision| match decision {
-            ReviewDecision::Approved => Some(ApprovalOption {
-                label: if network_approval_context.is_some() {
-                    "Yes, just this once".to_string()
-                } else {
-                    "Yes, proceed".to_string()
-                },
-                decision: ApprovalDecision::Review(ReviewDecision::Approved),
-                display_shortcut: None,
-                additional_shortcuts: vec![key_hint::plain(KeyCode::Char('y'))],
-            }),
-            ReviewDecision::ApprovedExecpolicyAmendment {
-                proposed_execpolicy_amendment,
-            } => {
-                let rendered_prefix =
-                    strip_bash_lc_and_escape(proposed_execpolicy_amendment.command());
-                if rendered_prefix.contains('\n') || rendered_prefix.contains('\r') {
-                    return None;
-                }
+    fn pre_draw_tick(&mut self) -> Option<Duration> {
+        let auto_deny = self.tick_auto_deny();
+        let age = (!self.done).then(|| {
+            let age = self.issued_at.elapsed();
+            let note = self
+                .veto_note()
+                .or_else(|| self.warning_gate.prompt(&self.theme))
+                .or_else(|| self.enter_guard.note(&self.theme));
+            self.list
+                .set_footer_note(note.or_else(|| request_age_note(age)));
+            until_next_change(age)
+        });
+        [
+            auto_deny,
+            self.copied.remaining(),
+            age,
+            self.linger.remaining(),
+        ]
+        .into_iter()
+        .flatten()
+        .min()
+    }
 
-                Some(ApprovalOption {
-                    label: format!(
-                        "Yes, and don't ask again for commands that start with `{rendered_prefix}`"
-                    ),
-                    decision: ApprovalDecision::Review(
-                        ReviewDecision::ApprovedExecpolicyAmendment {
-                            proposed_execpolicy_amendment: proposed_execpolicy_amendment.clone(),
-                        },
-                    ),
-                    display_shortcut: None,
-                    additional_shortcuts: vec![key_hint::plain(KeyCode::Char('p'))],
-                })
-            }
-            ReviewDecision::ApprovedForSession => Some(ApprovalOption {
-                label: if network_approval_context.is_some() {
-                    "Yes, and allow this host for this conversation".to_string()
-                } else if additional_permissions.is_some() {
-                    "Yes, and allow these permissions for this session".to_string()
-                } else {
-                    "Yes, and don't ask again for this command in this session".to_string()
-                },
-                decision: ApprovalDecision::Review(ReviewDecision::ApprovedForSession),
-                display_shortcut: None,
-                additional_shortcuts: vec![key_hint::plain(KeyCode::Char('a'))],
-            }),
-            ReviewDecision::NetworkPolicyAmendment {
-                network_policy_amendment,
-            } => {
-                let (label, shortcut) = match network_policy_amendment.action {
-                    NetworkPolicyRuleAction::Allow => (
-                        "Yes, and allow this host in the future".to_string(),
-                        KeyCode::Char('p'),
-                    ),
-                    NetworkPolicyRuleAction::Deny => (
-                        "No, and block this host in the future".to_string(),
-                        KeyCode::Char('d'),
-                    ),
-                };
-                Some(ApprovalOption {
-                    label,
-                    decision: ApprovalDecision::Review(ReviewDecision::NetworkPolicyAmendment {
-                        network_policy_amendment: network_policy_amendment.clone(),
-                    }),
-                    display_shortcut: None,
-                    additional_shortcuts: vec![key_hint::plain(shortcut)],
-                })
-            }
-            ReviewDecision::Denied => Some(ApprovalOption {
-                label: "No, continue without running it".to_string(),
-                decision: ApprovalDecision::Review(ReviewDecision::Denied),
-                display_shortcut: None,
-                additional_shortcuts: vec![key_hint::plain(KeyCode::Char('d'))],
-            }),
-            ReviewDecision::Abort => Some(ApprovalOption {
-                label: "No, and tell Codex what to do differently".to_string(),
-                decision: ApprovalDecision::Review(ReviewDecision::Abort),
-                display_shortcut: Some(key_hint::plain(KeyCode::Esc)),
-                additional_shortcuts: vec![key_hint::plain(KeyCode::Char('n'))],
-            }),
-        })
-        .collect()
-}
+    fn handle_paste(&mut self, pasted: String) -> bool {
+        self.auto_deny.cancel();
+        if let Some(input) = self.feedback.as_mut() {
+            input.insert_str(&pasted);
+            true
+        } else if let Some(editor) = self.command_editor.as_mut() {
+            editor.insert_str(&pasted);
+            true
+        } else {
+            false
+        }
+    }
 
-pub(crate) fn format_additional_permissions_rule(
-    additional_permissions: &PermissionProfile,
-) -> Option<String> {
-    let mut parts = Vec::new();
-    if additional_permissions
-        .network
-        .as_ref()
-        .and_then(|network| network.enabled)
-        .unwrap_or(false)
-    {
-        parts.push("network".to_string());
-    }
-    if let Some(file_system) = additional_permissions.file_system.as_ref() {
-        if let Some(read) = file_system.read.as_ref() {
-            let reads = read
-                .iter()
-                .map(|path| format!("`{}`", path.display()))
-                .collect::<Vec<_>>()
-                .join(", ");
-            parts.push(format!("read {reads}"));
+    fn handle_mouse_event(&mut self, mouse_event: MouseEvent) -> bool {
+        // The compact rows do not line up with the list the clicks target.
+        if self.text_input().is_some() || self.is_compact() {
+            return false;
         }
-        if let Some(write) = file_system.write.as_ref() {
-            let writes = write
-                .iter()
-                .map(|path| format!("`{}`", path.display()))
-                .collect::<Vec<_>>()
-                .join(", ");
-            parts.push(format!("write {writes}"));
-        }
-    }
-    if let Some(macos) = additional_permissions.macos.as_ref() {
-        if !matches!(
-            macos.macos_preferences,
-            MacOsPreferencesPermission::ReadOnly
-        ) {
-            let value = match macos.macos_preferences {
-                MacOsPreferencesPermission::ReadOnly => "readonly",
-                MacOsPreferencesPermission::ReadWrite => "readwrite",
-                MacOsPreferencesPermission::None => "none",
-            };
-            parts.push(format!("macOS preferences {value}"));
+        if self.warning_gate.holds_options() {
+            return false;
         }
-        match &macos.macos_automation {
-            MacOsAutomationPermission::All => {
-                parts.push("macOS automation all".to_string());
-            }
-            MacOsAutomationPermission::BundleIds(bundle_ids) => {
-                if !bundle_ids.is_empty() {
-                    parts.push(format!("macOS automation {}", bundle_ids.join(", ")));
-                }
+        if matches!(mouse_event.kind, MouseEventKind::Down(_)) {
+            self.auto_deny.cancel();
+        }
+        self.note_interaction();
+        // A click selects and confirms in one action, like a number key, so it
+        // only highlights an approval when an explicit selection is required.
+        if self.explicit_approval && matches!(mouse_event.kind, MouseEventKind::Down(_)) {
+            let hover = MouseEvent {
+                kind: MouseEventKind::Moved,
+                ..mouse_event
+            };
+            let moved = self.list.handle_mouse_event(hover);
+            if self
+                .list
+                .selected_index()
+                .and_then(|idx| self.options.get(idx))
+                .is_some_and(|opt| opt.decision.is_approval())
+            {
+                return moved;
             }
-            MacOsAutomationPermission::None => {}
         }
-        if macos.macos_accessibility {
-            parts.push("macOS accessibility".to_string());
-        }
-        if macos.macos_calendar {
-            parts.push("macOS calendar".to_string());
-        }
-        if macos.macos_reminders {
-            parts.push("macOS reminders".to_string());
-        }
-        if !matches!(macos.macos_contacts, MacOsContactsPermission::None) {
-            let value = match macos.macos_contacts {
-                MacOsContactsPermission::None => "none",
-                MacOsContactsPermission::ReadOnly => "readonly",
-                MacOsContactsPermission::ReadWrite => "readwrite",
-            };
-            parts.push(format!("macOS contacts {value}"));
+        let needs_redraw = self.list.handle_mouse_event(mouse_event);
+        if let Some(idx) = self.list.take_last_selected_index() {
+            self.apply_selection(idx);
         }
+        needs_redraw
     }
 
-    if parts.is_empty() {
-        None
-    } else {
-        Some(parts.join("; "))
+    fn wants_mouse_capture(&self) -> bool {
+        self.config.mouse.unwrap_or(false)
     }
-}
-
-fn patch_options() -> Vec<ApprovalOption> {
-    vec![
-        ApprovalOption {
-            label: "Yes, proceed".to_string(),
-            decision: ApprovalDecision::Review(ReviewDecision::Approved),
-            display_shortcut: None,
-            additional_shortcuts: vec![key_hint::plain(KeyCode::Char('y'))],
-        },
-        ApprovalOption {
-            label: "Yes, and don't ask again for these files".to_string(),
-            decision: ApprovalDecision::Review(ReviewDecision::ApprovedForSession),
-            display_shortcut: None,
-            additional_shortcuts: vec![key_hint::plain(KeyCode::Char('a'))],
-        },
-        ApprovalOption {
-            label: "No, and tell Codex what to do differently".to_string(),
-            decision: ApprovalDecision::Review(ReviewDecision::Abort),
-            display_shortcut: Some(key_hint::plain(KeyCode::Esc)),
-            additional_shortcuts: vec![key_hint::plain(KeyCode::Char('n'))],
-        },
-    ]
-}
 
-fn permissions_options() -> Vec<ApprovalOption> {
-    vec![
-        ApprovalOption {
-            label: "Yes, grant these permissions".to_string(),
-            decision: ApprovalDecision::Review(ReviewDecision::Approved),
-            display_shortcut: None,
-            additional_shortcuts: vec![key_hint::plain(KeyCode::Char('y'))],
-        },
-        ApprovalOption {
-            label: "Yes, grant these permissions for this session".to_string(),
-            decision: ApprovalDecision::Review(ReviewDecision::ApprovedForSession),
-            display_shortcut: None,
-            additional_shortcuts: vec![key_hint::plain(KeyCode::Char('a'))],
-        },
-        ApprovalOption {
-            label: "No, continue without permissions".to_string(),
-            decision: ApprovalDecision::Review(ReviewDecision::Denied),
-            display_shortcut: None,
-            additional_shortcuts: vec![key_hint::plain(KeyCode::Char('n'))],
-        },
-    ]
-}
+    /// The narrowest width that renders as compactly as `max` does, so every
+    /// prompt line, option row and the footer fit without extra wrapping.
+    fn desired_width(&self, max: u16) -> u16 {
+        let max = self.frame_area(Rect::new(0, 0, max, 0)).width;
+        let height = self.desired_height(max);
+        let (mut lo, mut hi) = (1, max);
+        while lo < hi {
+            let mid = lo + (hi - lo) / 2;
+            if self.desired_height(mid) <= height {
+                hi = mid;
+            } else {
+                lo = mid + 1;
+            }
+        }
+        hi
+    }
 
-fn elicitation_options() -> Vec<ApprovalOption> {
-    vec![
-        ApprovalOption {
-            label: "Yes, provide the requested info".to_string(),
-            decision: ApprovalDecision::McpElicitation(ElicitationAction::Accept),
-            display_shortcut: None,
-            additional_shortcuts: vec![key_hint::plain(KeyCode::Char('y'))],
-        },
-        ApprovalOption {
-            label: "No, but continue without it".to_string(),
-            decision: ApprovalDecision::McpElicitation(ElicitationAction::Decline),
-            display_shortcut: None,
-            additional_shortcuts: vec![key_hint::plain(KeyCode::Char('n'))],
-        },
-        ApprovalOption {
-            label: "Cancel this request".to_string(),
-            decision: ApprovalDecision::McpElicitation(ElicitationAction::Cancel),
-            display_shortcut: Some(key_hint::plain(KeyCode::Esc)),
-            additional_shortcuts: vec![key_hint::plain(KeyCode::Char('c'))],
-        },
-    ]
+    fn try_consume_approval_request(
+        &mut self,
+        request: ApprovalRequest,
+    ) -> Option<ApprovalRequest> {
+        // A lingering modal has nothing left to answer, so the request gets a
+        // modal of its own.
+        if self.done {
+            return Some(request);
+        }
+        self.enqueue_request(request);
+        None
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use super::keys::copy_text;
+    use super::keys::shell_quoted;
     use super::*;
     use crate::app_event::AppEvent;
+    use crate::app_event::ApprovalKind;
+    use codex_core::config::ApprovalLanguage;
+    use codex_core::config::ApprovalShortcut;
+    use codex_core::config::DecisionLogLevel;
     use codex_protocol::models::FileSystemPermissions;
     use codex_protocol::models::MacOsAutomationPermission;
+    use codex_protocol::models::MacOsContactsPermission;
     use codex_protocol::models::MacOsPreferencesPermission;
     use codex_protocol::models::MacOsSeatbeltProfileExtensions;
     use codex_protocol::models::NetworkPermissions;
+    use codex_protocol::protocol::ApprovalOverrides;
     use codex_protocol::protocol::ExecPolicyAmendment;
     use codex_protocol::protocol::NetworkApprovalProtocol;
     use codex_protocol::protocol::NetworkPolicyAmendment;
+    use codex_protocol::protocol::NetworkPolicyRuleAction;
+    use codex_protocol::protocol::Op;
+    use codex_protocol::protocol::PostRunAssertion;
+    use codex_protocol::request_permissions::PermissionGrantScope;
     use codex_utils_absolute_path::AbsolutePathBuf;
+    use crossterm::event::KeyEventKind;
+    use crossterm::event::KeyModifiers;
+    use crossterm::event::MouseButton;
     use insta::assert_snapshot;
     use pretty_assertions::assert_eq;
+    use ratatui::buffer::Buffer;
+    use ratatui::style::Color;
+    use ratatui::style::Modifier;
+    use ratatui::widgets::BorderType;
+    use ratatui::widgets::Borders;
+    use std::collections::BTreeMap;
     use tokio::sync::mpsc::unbounded_channel;
 
     fn absolute_path(path: &str) -> AbsolutePathBuf {
@@ -944,10 +1035,14 @@ mod tests {
             thread_label: None,
             id: "test".to_string(),
             command: vec!["echo".to_string(), "hi".to_string()],
+            cwd: PathBuf::from("/tmp"),
             reason: Some("reason".to_string()),
             available_decisions: vec![ReviewDecision::Approved, ReviewDecision::Abort],
             network_approval_context: None,
             additional_permissions: None,
+            sandbox_policy: None,
+            env: Vec::new(),
+            duplicate_ids: Vec::new(),
         }
     }
 
@@ -974,7 +1069,7 @@ mod tests {
     fn ctrl_c_aborts_and_clears_queue() {
         let (tx, _rx) = unbounded_channel::<AppEvent>();
         let tx = AppEventSender::new(tx);
-        let mut view = ApprovalOverlay::new(make_exec_request(), tx, Features::with_defaults());
+        let mut view = ApprovalOverlay::new(make_exec_request(), tx);
         view.enqueue_request(make_exec_request());
         assert_eq!(CancellationEvent::Handled, view.on_ctrl_c());
         assert!(view.queue.is_empty());
@@ -985,7 +1080,7 @@ mod tests {
     fn shortcut_triggers_selection() {
         let (tx, mut rx) = unbounded_channel::<AppEvent>();
         let tx = AppEventSender::new(tx);
-        let mut view = ApprovalOverlay::new(make_exec_request(), tx, Features::with_defaults());
+        let mut view = ApprovalOverlay::new(make_exec_request(), tx);
         assert!(!view.is_complete());
         view.handle_key_event(KeyEvent::new(KeyCode::Char('y'), KeyModifiers::NONE));
         // We expect at least one thread-scoped approval op message in the queue.
@@ -999,6 +1094,415 @@ mod tests {
         assert!(saw_op, "expected approval decision to emit an op");
     }
 
+    #[test]
+    fn linger_shows_the_sent_decision_before_completing() {
+        let (tx, mut rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx);
+        let mut view = ApprovalOverlay::builder(make_exec_request(), tx)
+            .config(TuiApprovals {
+                linger_ms: Some(60_000),
+                ..Default::default()
+            })
+            .build();
+
+        view.handle_key_event(KeyEvent::new(KeyCode::Char('y'), KeyModifiers::NONE));
+        let mut saw_op = false;
+        while let Ok(ev) = rx.try_recv() {
+            saw_op |= matches!(ev, AppEvent::SubmitThreadOp { .. });
+        }
+        assert!(saw_op, "the op goes out before the modal closes");
+        assert!(!view.is_complete());
+        assert_eq!(
+            render_overlay_lines(&view, 80).trim(),
+            "→ sent: Approved, waiting…"
+        );
+        assert!(
+            view.try_consume_approval_request(make_exec_request())
+                .is_some(),
+            "a new request gets its own modal"
+        );
+
+        view.linger = Linger::default();
+        assert!(view.is_complete());
+    }
+
+    #[test]
+    fn configured_keys_answer_and_show_in_the_hints() {
+        let (tx, _rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx);
+        let mut view = ApprovalOverlay::builder(make_exec_request(), tx)
+            .config(TuiApprovals {
+                keys: Some(BTreeMap::from([(ApprovalShortcut::Approve, 'j')])),
+                ..Default::default()
+            })
+            .build();
+        assert!(render_overlay_lines(&view, 80).contains("Yes, proceed (j)"));
+
+        view.handle_key_event(KeyEvent::new(KeyCode::Char('y'), KeyModifiers::NONE));
+        assert!(!view.is_complete());
+        view.handle_key_event(KeyEvent::new(KeyCode::Char('j'), KeyModifiers::NONE));
+        assert_eq!(view.outcome(), Some(ReviewDecision::Approved));
+    }
+
+    #[test]
+    fn recent_context_is_shown_above_the_title() {
+        let (tx, _rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx);
+        let view = ApprovalOverlay::builder(make_exec_request(), tx)
+            .recent_context(vec![Line::from("• The build failed; cleaning first.")])
+            .build();
+        let rendered = render_overlay_lines(&view, 80);
+        let context = rendered.find("• The build failed; cleaning first.");
+        let rule = rendered.find("───");
+        let title = rendered.find("Would you like to run the following command?");
+        assert!(
+            context.is_some() && context < rule && rule < title,
+            "{rendered}"
+        );
+    }
+
+    #[test]
+    fn shift_y_approves_every_queued_request_with_its_own_id() {
+        let (tx, mut rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx);
+        let mut view = ApprovalOverlay::new(make_exec_request(), tx);
+        let mut patch = make_patch_request(None, None);
+        if let ApprovalRequest::ApplyPatch { id, .. } = &mut patch {
+            *id = "patch".to_string();
+        }
+        view.enqueue_request(patch);
+        let mut second_exec = make_exec_request();
+        if let ApprovalRequest::Exec { id, .. } = &mut second_exec {
+            *id = "second".to_string();
+        }
+        view.enqueue_request(second_exec);
+
+        view.handle_key_event(KeyEvent::new(KeyCode::Char('Y'), KeyModifiers::SHIFT));
+        let mut batch = None;
+        while let Ok(ev) = rx.try_recv() {
+            if let AppEvent::ApproveAllPending(decision) = ev {
+                batch = Some(decision);
+            }
+        }
+        assert_eq!(batch, Some(ReviewDecision::Approved));
+        assert!(!view.is_complete());
+
+        assert!(view.approve_all_pending(ReviewDecision::Approved));
+        let mut approved = Vec::new();
+        while let Ok(ev) = rx.try_recv() {
+            if let AppEvent::SubmitThreadOp {
+                op: Op::ApprovalResponse { id, response, .. },
+                ..
+            } = ev
+            {
+                approved.push((id, response.decision));
+            }
+        }
+        approved.sort_by(|a, b| a.0.cmp(&b.0));
+        assert_eq!(
+            approved,
+            vec![
+                ("patch".to_string(), ReviewDecision::Approved),
+                ("second".to_string(), ReviewDecision::Approved),
+                ("test".to_string(), ReviewDecision::Approved),
+            ]
+        );
+        assert!(view.is_complete());
+    }
+
+    #[test]
+    fn shift_y_stops_at_a_destructive_request() {
+        let (tx, mut rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx);
+        let mut view = ApprovalOverlay::new(make_exec_request(), tx);
+        let mut destructive = make_exec_request();
+        if let ApprovalRequest::Exec { id, command, .. } = &mut destructive {
+            *id = "rm".to_string();
+            *command = vec!["rm".to_string(), "-rf".to_string(), "build".to_string()];
+        }
+        view.enqueue_request(destructive);
+        let mut last = make_exec_request();
+        if let ApprovalRequest::Exec { id, .. } = &mut last {
+            *id = "last".to_string();
+        }
+        view.enqueue_request(last);
+
+        assert!(view.approve_all_pending(ReviewDecision::Approved));
+        let mut approved = Vec::new();
+        while let Ok(ev) = rx.try_recv() {
+            if let AppEvent::SubmitThreadOp {
+                op: Op::ApprovalResponse { id, response, .. },
+                ..
+            } = ev
+            {
+                approved.push((id, response.decision));
+            }
+        }
+        assert_eq!(
+            approved,
+            vec![
+                ("test".to_string(), ReviewDecision::Approved),
+                ("last".to_string(), ReviewDecision::Approved),
+            ]
+        );
+        assert!(!view.is_complete());
+        assert_eq!(
+            view.current_request.as_ref().map(ApprovalRequest::id),
+            Some("rm".to_string())
+        );
+    }
+
+    #[test]
+    fn identical_commands_are_answered_by_one_decision() {
+        let (tx, mut rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx);
+        let first = make_exec_request();
+        let mut view = ApprovalOverlay::new(first.clone(), tx);
+        for repeat_id in ["repeat-1", "repeat-2"] {
+            let mut repeat = first.clone();
+            if let ApprovalRequest::Exec { id, .. } = &mut repeat {
+                *id = repeat_id.to_string();
+            }
+            view.enqueue_request(repeat);
+        }
+        let rendered = render_overlay_lines(&view, 100);
+        assert!(
+            rendered.contains("(applies to 3 identical requests)"),
+            "{rendered}"
+        );
+        assert!(!rendered.contains(" of 3)"), "{rendered}");
+
+        view.handle_key_event(KeyEvent::new(KeyCode::Char('y'), KeyModifiers::NONE));
+        let mut approved = Vec::new();
+        while let Ok(ev) = rx.try_recv() {
+            if let AppEvent::SubmitThreadOp {
+                op: Op::ApprovalResponse { id, response, .. },
+                ..
+            } = ev
+            {
+                approved.push((id, response.decision));
+            }
+        }
+        approved.sort_by(|a, b| a.0.cmp(&b.0));
+        assert_eq!(
+            approved,
+            vec![
+                ("repeat-1".to_string(), ReviewDecision::Approved),
+                ("repeat-2".to_string(), ReviewDecision::Approved),
+                ("test".to_string(), ReviewDecision::Approved),
+            ]
+        );
+        assert!(view.is_complete());
+    }
+
+    #[test]
+    fn title_counts_the_pending_requests() {
+        let (tx, _rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx);
+        let mut view = ApprovalOverlay::new(make_exec_request(), tx);
+        let title = |view: &ApprovalOverlay| {
+            render_overlay_lines(view, 80)
+                .lines()
+                .find(|line| line.contains("Would you like"))
+                .map(str::trim)
+                .map(str::to_string)
+        };
+        assert_eq!(
+            title(&view).as_deref(),
+            Some("Would you like to run the following command?")
+        );
+
+        view.enqueue_request(make_exec_request());
+        view.enqueue_request(make_patch_request(None, None));
+        let height = view.desired_height(80);
+        assert_eq!(
+            title(&view).as_deref(),
+            Some("Would you like to run the following command? (1 of 3)")
+        );
+        assert_eq!(view.desired_height(80), height);
+
+        view.handle_key_event(KeyEvent::new(KeyCode::Char('y'), KeyModifiers::NONE));
+        assert_eq!(
+            title(&view).as_deref(),
+            Some("Would you like to make the following edits? (2 of 3)")
+        );
+    }
+
+    #[test]
+    fn confirm_abort_needs_a_second_esc() {
+        let (tx, mut rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx);
+        let mut view = ApprovalOverlay::builder(make_exec_request(), tx)
+            .config(TuiApprovals {
+                confirm_abort: Some(true),
+                ..Default::default()
+            })
+            .build();
+        assert!(view.prefer_esc_to_handle_key_event());
+        let esc = KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE);
+        let decisions = |rx: &mut tokio::sync::mpsc::UnboundedReceiver<AppEvent>| {
+            let mut decisions = Vec::new();
+            while let Ok(ev) = rx.try_recv() {
+                if let AppEvent::SubmitThreadOp {
+                    op: Op::ApprovalResponse { response, .. },
+                    ..
+                } = ev
+                {
+                    decisions.push(response.decision);
+                }
+            }
+            decisions
+        };
+
+        view.handle_key_event(esc);
+        assert!(
+            render_overlay_lines(&view, 80)
+                .contains("Press Esc again to abort, any other key to cancel")
+        );
+        view.handle_key_event(KeyEvent::new(KeyCode::Down, KeyModifiers::NONE));
+        assert!(render_overlay_lines(&view, 80).contains("Would you like to run"));
+        assert_eq!(decisions(&mut rx), Vec::new());
+        assert!(!view.is_complete());
+
+        view.handle_key_event(esc);
+        view.handle_key_event(esc);
+        assert_eq!(decisions(&mut rx), vec![ReviewDecision::Abort]);
+        assert!(view.is_complete());
+    }
+
+    #[test]
+    fn confirm_abort_lets_ctrl_c_abort_at_once() {
+        let (tx, mut rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx);
+        let mut view = ApprovalOverlay::builder(make_exec_request(), tx)
+            .config(TuiApprovals {
+                confirm_abort: Some(true),
+                ..Default::default()
+            })
+            .build();
+
+        assert_eq!(view.on_ctrl_c(), CancellationEvent::Handled);
+        assert!(view.is_complete());
+        let aborted = std::iter::from_fn(|| rx.try_recv().ok()).any(|ev| {
+            matches!(
+                ev,
+                AppEvent::SubmitThreadOp {
+                    op: Op::ApprovalResponse { response, .. },
+                    ..
+                } if response.decision == ReviewDecision::Abort
+            )
+        });
+        assert!(aborted);
+    }
+
+    #[test]
+    fn i_edits_the_command_before_approving() {
+        let (tx, mut rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx);
+        let mut view = ApprovalOverlay::new(make_exec_request(), tx);
+        view.handle_key_event(KeyEvent::new(KeyCode::Char('i'), KeyModifiers::NONE));
+        assert!(view.prefer_esc_to_handle_key_event());
+        assert!(render_overlay_lines(&view, 80).contains("$ echo hi"));
+
+        for c in " 'to you'".chars() {
+            view.handle_key_event(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE));
+        }
+        view.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+        let mut responses = Vec::new();
+        while let Ok(ev) = rx.try_recv() {
+            if let AppEvent::SubmitThreadOp {
+                op: Op::ApprovalResponse { response, .. },
+                ..
+            } = ev
+            {
+                responses.push(response);
+            }
+        }
+        assert_eq!(
+            responses,
+            vec![ApprovalResponse {
+                overrides: ApprovalOverrides {
+                    command: Some(vec![
+                        "echo".to_string(),
+                        "hi".to_string(),
+                        "to you".to_string(),
+                    ]),
+                    ..ApprovalOverrides::default()
+                },
+                ..ApprovalResponse::new(ReviewDecision::Approved)
+            }]
+        );
+        assert!(view.is_complete());
+    }
+
+    #[test]
+    fn request_without_options_aborts_on_any_key() {
+        let (tx, mut rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx);
+        let mut request = make_exec_request();
+        if let ApprovalRequest::Exec {
+            available_decisions,
+            network_approval_context,
+            ..
+        } = &mut request
+        {
+            *available_decisions = Vec::new();
+            *network_approval_context = Some(NetworkApprovalContext {
+                host: "example.com".to_string(),
+                protocol: NetworkApprovalProtocol::Https,
+            });
+        }
+        let mut view = ApprovalOverlay::new(request, tx);
+        assert!(view.options.is_empty());
+        assert!(view.desired_height(80) > 0);
+
+        view.handle_key_event(KeyEvent::new(KeyCode::Left, KeyModifiers::NONE));
+        assert!(view.is_complete());
+        let decisions: Vec<ReviewDecision> = std::iter::from_fn(|| rx.try_recv().ok())
+            .filter_map(|ev| match ev {
+                AppEvent::SubmitThreadOp {
+                    op: Op::ApprovalResponse { response, .. },
+                    ..
+                } => Some(response.decision),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(decisions, vec![ReviewDecision::Abort]);
+    }
+
+    #[test]
+    fn missing_working_directory_is_flagged() {
+        let (tx, _rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx);
+        let new_view = |request| ApprovalOverlay::new(request, tx.clone());
+        let warning = "⚠ working directory does not exist";
+
+        let existing = tempfile::tempdir().expect("tempdir");
+        let mut request = make_exec_request();
+        if let ApprovalRequest::Exec { cwd, .. } = &mut request {
+            *cwd = existing.path().to_path_buf();
+        }
+        assert!(!render_overlay_lines(&new_view(request), 120).contains(warning));
+
+        let mut request = make_exec_request();
+        if let ApprovalRequest::Exec { cwd, .. } = &mut request {
+            *cwd = existing.path().join("hallucinated");
+        }
+        assert!(render_overlay_lines(&new_view(request), 120).contains(warning));
+    }
+
+    #[test]
+    fn m_requests_originating_agent_message() {
+        let (tx, mut rx) = unbounded_channel::<AppEvent>();
+        let mut view = ApprovalOverlay::new(make_exec_request(), AppEventSender::new(tx));
+        view.handle_key_event(KeyEvent::new(KeyCode::Char('m'), KeyModifiers::NONE));
+        assert!(matches!(
+            rx.try_recv(),
+            Ok(AppEvent::ShowApprovalSource { id }) if id == "test"
+        ));
+        assert!(!view.is_complete());
+    }
+
     #[test]
     fn o_opens_source_thread_for_cross_thread_approval() {
         let (tx, mut rx) = unbounded_channel::<AppEvent>();
@@ -1010,13 +1514,16 @@ mod tests {
                 thread_label: Some("Robie [explorer]".to_string()),
                 id: "test".to_string(),
                 command: vec!["echo".to_string(), "hi".to_string()],
+                cwd: PathBuf::from("/tmp"),
                 reason: None,
                 available_decisions: vec![ReviewDecision::Approved, ReviewDecision::Abort],
                 network_approval_context: None,
                 additional_permissions: None,
+                sandbox_policy: None,
+                env: Vec::new(),
+                duplicate_ids: Vec::new(),
             },
             tx,
-            Features::with_defaults(),
         );
 
         view.handle_key_event(KeyEvent::new(KeyCode::Char('o'), KeyModifiers::NONE));
@@ -1038,13 +1545,16 @@ mod tests {
                 thread_label: Some("Robie [explorer]".to_string()),
                 id: "test".to_string(),
                 command: vec!["echo".to_string(), "hi".to_string()],
+                cwd: PathBuf::from("/tmp"),
                 reason: None,
                 available_decisions: vec![ReviewDecision::Approved, ReviewDecision::Abort],
                 network_approval_context: None,
                 additional_permissions: None,
+                sandbox_policy: None,
+                env: Vec::new(),
+                duplicate_ids: Vec::new(),
             },
             tx,
-            Features::with_defaults(),
         );
 
         assert_snapshot!(
@@ -1053,6 +1563,51 @@ mod tests {
         );
     }
 
+    fn make_patch_request(reason: Option<&str>, grant_root: Option<&str>) -> ApprovalRequest {
+        ApprovalRequest::ApplyPatch {
+            thread_id: ThreadId::new(),
+            thread_label: None,
+            id: "test".to_string(),
+            reason: reason.map(str::to_string),
+            cwd: PathBuf::from("/tmp"),
+            changes: HashMap::from([(
+                PathBuf::from("README.md"),
+                FileChange::Add {
+                    content: "hello\nworld\n".to_string(),
+                },
+            )]),
+            grant_root: grant_root.map(PathBuf::from),
+        }
+    }
+
+    #[test]
+    fn prompt_snapshots() {
+        let (tx, _rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx);
+        let render = |request| {
+            let view = ApprovalOverlay::new(request, tx.clone());
+            render_overlay_lines(&view, 80)
+        };
+
+        let mut exec_without_reason = make_exec_request();
+        if let ApprovalRequest::Exec { reason, .. } = &mut exec_without_reason {
+            *reason = None;
+        }
+        assert_snapshot!("approval_overlay_exec_prompt", render(make_exec_request()));
+        assert_snapshot!(
+            "approval_overlay_exec_prompt_no_reason",
+            render(exec_without_reason)
+        );
+        assert_snapshot!(
+            "approval_overlay_patch_prompt",
+            render(make_patch_request(None, None))
+        );
+        assert_snapshot!(
+            "approval_overlay_patch_prompt_with_reason_and_grant_root",
+            render(make_patch_request(Some("update the readme"), Some("/tmp")))
+        );
+    }
+
     #[test]
     fn exec_prefix_option_emits_execpolicy_amendment() {
         let (tx, mut rx) = unbounded_channel::<AppEvent>();
@@ -1063,6 +1618,7 @@ mod tests {
                 thread_label: None,
                 id: "test".to_string(),
                 command: vec!["echo".to_string()],
+                cwd: PathBuf::from("/tmp"),
                 reason: None,
                 available_decisions: vec![
                     ReviewDecision::Approved,
@@ -1075,15 +1631,21 @@ mod tests {
                 ],
                 network_approval_context: None,
                 additional_permissions: None,
+                sandbox_policy: None,
+                env: Vec::new(),
+                duplicate_ids: Vec::new(),
             },
             tx,
-            Features::with_defaults(),
         );
         view.handle_key_event(KeyEvent::new(KeyCode::Char('p'), KeyModifiers::NONE));
         let mut saw_op = false;
         while let Ok(ev) = rx.try_recv() {
             if let AppEvent::SubmitThreadOp {
-                op: Op::ExecApproval { decision, .. },
+                op:
+                    Op::ApprovalResponse {
+                        response: ApprovalResponse { decision, .. },
+                        ..
+                    },
                 ..
             } = ev
             {
@@ -1106,7 +1668,7 @@ mod tests {
     }
 
     #[test]
-    fn network_deny_forever_shortcut_is_not_bound() {
+    fn verbose_option_approves_with_command_override() {
         let (tx, mut rx) = unbounded_channel::<AppEvent>();
         let tx = AppEventSender::new(tx);
         let mut view = ApprovalOverlay::new(
@@ -1114,209 +1676,519 @@ mod tests {
                 thread_id: ThreadId::new(),
                 thread_label: None,
                 id: "test".to_string(),
-                command: vec!["curl".to_string(), "https://example.com".to_string()],
+                command: vec!["cargo".to_string(), "build".to_string()],
+                cwd: PathBuf::from("/tmp"),
                 reason: None,
-                available_decisions: vec![
-                    ReviewDecision::Approved,
-                    ReviewDecision::ApprovedForSession,
-                    ReviewDecision::NetworkPolicyAmendment {
-                        network_policy_amendment: NetworkPolicyAmendment {
-                            host: "example.com".to_string(),
-                            action: NetworkPolicyRuleAction::Allow,
-                        },
-                    },
-                    ReviewDecision::Abort,
-                ],
-                network_approval_context: Some(NetworkApprovalContext {
-                    host: "example.com".to_string(),
-                    protocol: NetworkApprovalProtocol::Https,
-                }),
+                available_decisions: vec![ReviewDecision::Approved, ReviewDecision::Abort],
+                network_approval_context: None,
                 additional_permissions: None,
+                sandbox_policy: None,
+                env: Vec::new(),
+                duplicate_ids: Vec::new(),
             },
             tx,
-            Features::with_defaults(),
         );
-        view.handle_key_event(KeyEvent::new(KeyCode::Char('d'), KeyModifiers::NONE));
+        let labels: Vec<String> = view.options.iter().map(|opt| opt.label.clone()).collect();
+        assert_eq!(
+            labels,
+            vec![
+                "Yes, proceed".to_string(),
+                "Yes, run with verbose output: `cargo --verbose build`".to_string(),
+                "No, and tell Codex what to do differently".to_string(),
+                "Explain this command".to_string(),
+                "Edit the command first".to_string(),
+            ]
+        );
 
-        assert!(
-            rx.try_recv().is_err(),
-            "unexpected approval event emitted for hidden network deny shortcut"
+        view.handle_key_event(KeyEvent::new(KeyCode::Char('2'), KeyModifiers::NONE));
+        let mut saw_op = false;
+        while let Ok(ev) = rx.try_recv() {
+            if let AppEvent::SubmitThreadOp {
+                op: Op::ApprovalResponse { response, .. },
+                ..
+            } = ev
+            {
+                assert_eq!(
+                    response,
+                    ApprovalResponse {
+                        overrides: ApprovalOverrides {
+                            command: Some(vec![
+                                "cargo".to_string(),
+                                "--verbose".to_string(),
+                                "build".to_string(),
+                            ]),
+                            ..ApprovalOverrides::default()
+                        },
+                        ..ApprovalResponse::new(ReviewDecision::Approved)
+                    }
+                );
+                saw_op = true;
+                break;
+            }
+        }
+        assert!(saw_op, "expected verbose approval to emit an op");
+    }
+
+    #[test]
+    fn number_keys_pick_options_by_position() {
+        let (tx, mut rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx);
+        let mut request = make_exec_request();
+        if let ApprovalRequest::Exec {
+            available_decisions,
+            ..
+        } = &mut request
+        {
+            *available_decisions = vec![
+                ReviewDecision::Approved,
+                ReviewDecision::ApprovedForSession,
+                ReviewDecision::Abort,
+            ];
+        }
+        let mut view = ApprovalOverlay::new(request, tx);
+        // Digits past the last option do nothing.
+        view.handle_key_event(KeyEvent::new(KeyCode::Char('9'), KeyModifiers::NONE));
+        assert!(!view.is_complete());
+
+        view.handle_key_event(KeyEvent::new(KeyCode::Char('2'), KeyModifiers::NONE));
+        let mut responses = Vec::new();
+        while let Ok(ev) = rx.try_recv() {
+            if let AppEvent::SubmitThreadOp {
+                op: Op::ApprovalResponse { response, .. },
+                ..
+            } = ev
+            {
+                responses.push(response);
+            }
+        }
+        assert_eq!(
+            responses,
+            vec![ApprovalResponse::new(ReviewDecision::ApprovedForSession)]
         );
     }
 
     #[test]
-    fn header_includes_command_snippet() {
+    fn h_wraps_from_the_first_option_to_the_last() {
         let (tx, _rx) = unbounded_channel::<AppEvent>();
         let tx = AppEventSender::new(tx);
-        let command = vec!["echo".into(), "hello".into(), "world".into()];
-        let exec_request = ApprovalRequest::Exec {
-            thread_id: ThreadId::new(),
-            thread_label: None,
-            id: "test".into(),
-            command,
-            reason: None,
-            available_decisions: vec![ReviewDecision::Approved, ReviewDecision::Abort],
-            network_approval_context: None,
-            additional_permissions: None,
-        };
+        let mut view = ApprovalOverlay::new(make_exec_request(), tx);
+        assert_eq!(view.list.selected_index(), Some(0));
 
-        let view = ApprovalOverlay::new(exec_request, tx, Features::with_defaults());
-        let mut buf = Buffer::empty(Rect::new(0, 0, 80, view.desired_height(80)));
-        view.render(Rect::new(0, 0, 80, view.desired_height(80)), &mut buf);
+        view.handle_key_event(KeyEvent::new(KeyCode::Char('h'), KeyModifiers::NONE));
+        assert_eq!(view.list.selected_index(), Some(view.options.len() - 1));
 
-        let rendered: Vec<String> = (0..buf.area.height)
-            .map(|row| {
-                (0..buf.area.width)
-                    .map(|col| buf[(col, row)].symbol().to_string())
-                    .collect()
-            })
-            .collect();
+        view.handle_key_event(KeyEvent::new(KeyCode::Char('l'), KeyModifiers::NONE));
+        assert_eq!(view.list.selected_index(), Some(0));
+        assert!(!view.is_complete());
+    }
+
+    #[test]
+    fn a_doubled_press_steps_once() {
+        let (tx, mut rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx);
+        let mut view = ApprovalOverlay::new(make_exec_request(), tx);
+        view.enqueue_request(make_exec_request());
+        let right = KeyEvent::new(KeyCode::Right, KeyModifiers::NONE);
+
+        view.handle_key_event(right);
+        view.handle_key_event(right);
+        assert_eq!(view.list.selected_index(), Some(1));
+
+        view.key_debounce = KeyDebounce::default();
+        let yes = KeyEvent::new(KeyCode::Char('y'), KeyModifiers::NONE);
+        view.handle_key_event(yes);
+        view.handle_key_event(yes);
         assert!(
-            rendered
-                .iter()
-                .any(|line| line.contains("echo hello world")),
-            "expected header to include command snippet, got {rendered:?}"
+            !view.is_complete(),
+            "the echo must not answer the queued request"
         );
+        let answered = std::iter::from_fn(|| rx.try_recv().ok())
+            .filter(|ev| matches!(ev, AppEvent::SubmitThreadOp { .. }))
+            .count();
+        assert_eq!(answered, 1);
     }
 
     #[test]
-    fn network_exec_options_use_expected_labels_and_hide_execpolicy_amendment() {
-        let network_context = NetworkApprovalContext {
-            host: "example.com".to_string(),
-            protocol: NetworkApprovalProtocol::Https,
-        };
-        let options = exec_options(
-            &[
+    fn held_arrows_repeat_but_held_enter_does_not() {
+        let (tx, mut rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx);
+        let mut view = ApprovalOverlay::new(make_exec_request(), tx);
+        let key = |code, kind| KeyEvent::new_with_kind(code, KeyModifiers::NONE, kind);
+
+        view.handle_key_event(key(KeyCode::Left, KeyEventKind::Repeat));
+        assert_eq!(view.list.selected_index(), Some(view.options.len() - 1));
+        view.handle_key_event(key(KeyCode::Right, KeyEventKind::Repeat));
+        assert_eq!(view.list.selected_index(), Some(0));
+        view.handle_key_event(key(KeyCode::Down, KeyEventKind::Release));
+        assert_eq!(view.list.selected_index(), Some(0));
+
+        view.handle_key_event(key(KeyCode::Enter, KeyEventKind::Repeat));
+        view.handle_key_event(key(KeyCode::Char('y'), KeyEventKind::Repeat));
+        assert!(!view.is_complete());
+
+        view.enqueue_request(make_patch_request(None, None));
+        view.handle_key_event(key(KeyCode::Enter, KeyEventKind::Press));
+        view.handle_key_event(key(KeyCode::Enter, KeyEventKind::Repeat));
+        let responses = std::iter::from_fn(|| rx.try_recv().ok())
+            .filter(|ev| {
+                matches!(
+                    ev,
+                    AppEvent::SubmitThreadOp {
+                        op: Op::ApprovalResponse { .. },
+                        ..
+                    }
+                )
+            })
+            .count();
+        assert_eq!(responses, 1);
+        assert!(!view.is_complete());
+    }
+
+    #[test]
+    fn mouse_hover_highlights_and_click_confirms() {
+        let (tx, mut rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx);
+        let mut request = make_exec_request();
+        if let ApprovalRequest::Exec {
+            available_decisions,
+            ..
+        } = &mut request
+        {
+            *available_decisions = vec![
                 ReviewDecision::Approved,
                 ReviewDecision::ApprovedForSession,
-                ReviewDecision::NetworkPolicyAmendment {
-                    network_policy_amendment: NetworkPolicyAmendment {
-                        host: "example.com".to_string(),
-                        action: NetworkPolicyRuleAction::Allow,
-                    },
-                },
                 ReviewDecision::Abort,
-            ],
-            Some(&network_context),
-            None,
-        );
+            ];
+        }
+        let mut view = ApprovalOverlay::builder(request, tx)
+            .config(TuiApprovals {
+                mouse: Some(true),
+                ..Default::default()
+            })
+            .build();
+        assert!(view.wants_mouse_capture());
+
+        let rendered = render_overlay_lines(&view, 80);
+        let row = rendered
+            .lines()
+            .position(|line| line.trim_start().starts_with("2."))
+            .expect("second option is rendered") as u16;
+        let mouse = |kind| MouseEvent {
+            kind,
+            column: 4,
+            row,
+            modifiers: KeyModifiers::NONE,
+        };
+
+        assert!(view.handle_mouse_event(mouse(MouseEventKind::Moved)));
+        assert_eq!(view.list.selected_index(), Some(1));
+        assert!(rx.try_recv().is_err());
 
-        let labels: Vec<String> = options.into_iter().map(|option| option.label).collect();
+        view.handle_mouse_event(mouse(MouseEventKind::Down(MouseButton::Left)));
+        let mut responses = Vec::new();
+        while let Ok(ev) = rx.try_recv() {
+            if let AppEvent::SubmitThreadOp {
+                op: Op::ApprovalResponse { response, .. },
+                ..
+            } = ev
+            {
+                responses.push(response);
+            }
+        }
         assert_eq!(
-            labels,
-            vec![
-                "Yes, just this once".to_string(),
-                "Yes, and allow this host for this conversation".to_string(),
-                "Yes, and allow this host in the future".to_string(),
-                "No, and tell Codex what to do differently".to_string(),
-            ]
+            responses,
+            vec![ApprovalResponse::new(ReviewDecision::ApprovedForSession)]
         );
     }
 
     #[test]
-    fn generic_exec_options_can_offer_allow_for_session() {
-        let options = exec_options(
-            &[
-                ReviewDecision::Approved,
-                ReviewDecision::ApprovedForSession,
-                ReviewDecision::Abort,
-            ],
-            None,
-            None,
-        );
+    fn c_copies_the_command_and_keeps_the_modal_open() {
+        let (tx, mut rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx);
+        let mut view = ApprovalOverlay::new(make_exec_request(), tx);
+        view.handle_key_event(KeyEvent::new(KeyCode::Char('c'), KeyModifiers::NONE));
 
-        let labels: Vec<String> = options.into_iter().map(|option| option.label).collect();
-        assert_eq!(
-            labels,
-            vec![
-                "Yes, proceed".to_string(),
-                "Yes, and don't ask again for this command in this session".to_string(),
-                "No, and tell Codex what to do differently".to_string(),
-            ]
+        let mut copied = Vec::new();
+        while let Ok(ev) = rx.try_recv() {
+            if let AppEvent::CopyToClipboard(text) = ev {
+                copied.push(text);
+            }
+        }
+        assert_eq!(copied, vec!["echo hi".to_string()]);
+        assert!(!view.is_complete());
+        assert!(
+            render_overlay_lines(&view, 80)
+                .contains("Would you like to run the following command? copied!")
         );
     }
 
     #[test]
-    fn additional_permissions_exec_options_hide_execpolicy_amendment() {
-        let additional_permissions = PermissionProfile {
-            file_system: Some(FileSystemPermissions {
-                read: Some(vec![absolute_path("/tmp/readme.txt")]),
-                write: Some(vec![absolute_path("/tmp/out.txt")]),
-            }),
-            ..Default::default()
+    fn copy_on_approve_copies_approved_commands_only() {
+        let copied_after = |copy_on_approve, key| {
+            let (tx, mut rx) = unbounded_channel::<AppEvent>();
+            let tx = AppEventSender::new(tx);
+            let mut request = make_exec_request();
+            if let ApprovalRequest::Exec { command, .. } = &mut request {
+                *command = vec!["echo".to_string(), "it's here".to_string()];
+            }
+            let mut view = ApprovalOverlay::builder(request, tx)
+                .config(TuiApprovals {
+                    copy_on_approve: Some(copy_on_approve),
+                    ..Default::default()
+                })
+                .build();
+            view.handle_key_event(KeyEvent::new(key, KeyModifiers::NONE));
+            let mut copied = Vec::new();
+            while let Ok(ev) = rx.try_recv() {
+                if let AppEvent::CopyToClipboard(text) = ev {
+                    copied.push(text);
+                }
+            }
+            copied
         };
-        let options = exec_options(
-            &[ReviewDecision::Approved, ReviewDecision::Abort],
-            None,
-            Some(&additional_permissions),
+
+        let quoted = shell_quoted(&["echo".to_string(), "it's here".to_string()]).unwrap();
+        assert_eq!(copied_after(true, KeyCode::Char('y')), vec![quoted]);
+        assert_eq!(copied_after(true, KeyCode::Esc), Vec::<String>::new());
+        assert_eq!(
+            copied_after(false, KeyCode::Char('y')),
+            Vec::<String>::new()
         );
+    }
+
+    #[test]
+    fn v_opens_the_patch_in_the_pager_and_keeps_the_modal_open() {
+        let (tx, mut rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx);
+        let mut view = ApprovalOverlay::new(make_patch_request(None, None), tx);
+        let before = render_overlay_lines(&view, 80);
+        view.handle_key_event(KeyEvent::new(KeyCode::Char('v'), KeyModifiers::NONE));
 
-        let labels: Vec<String> = options.into_iter().map(|option| option.label).collect();
+        let mut paged = Vec::new();
+        while let Ok(ev) = rx.try_recv() {
+            if let AppEvent::OpenInPager(text) = ev {
+                paged.push(text);
+            }
+        }
         assert_eq!(
-            labels,
-            vec![
-                "Yes, proceed".to_string(),
-                "No, and tell Codex what to do differently".to_string(),
-            ]
+            paged,
+            vec![copy_text(&make_patch_request(None, None)).expect("patch text")]
         );
+        assert!(!view.is_complete());
+        assert_eq!(render_overlay_lines(&view, 80), before);
     }
 
     #[test]
-    fn permissions_options_use_expected_labels() {
-        let labels: Vec<String> = permissions_options()
-            .into_iter()
-            .map(|option| option.label)
-            .collect();
+    fn announces_the_prompt_and_each_decision() {
+        let (tx, mut rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx);
+        let mut view = ApprovalOverlay::new(make_exec_request(), tx);
+        view.enqueue_request(make_patch_request(None, None));
+        view.announce();
+        view.handle_key_event(KeyEvent::new(KeyCode::Char('y'), KeyModifiers::NONE));
+        view.handle_key_event(KeyEvent::new(KeyCode::Char('n'), KeyModifiers::NONE));
+
+        let mut announced = Vec::new();
+        while let Ok(ev) = rx.try_recv() {
+            if let AppEvent::Announce(text) = ev {
+                announced.push(text);
+            }
+        }
         assert_eq!(
-            labels,
+            announced,
             vec![
-                "Yes, grant these permissions".to_string(),
-                "Yes, grant these permissions for this session".to_string(),
-                "No, continue without permissions".to_string(),
+                "Approval required: allow command codex wants to run echo hi".to_string(),
+                "Command approved".to_string(),
+                "Approval required: allow codex to edit 1 file".to_string(),
+                "Edits aborted".to_string(),
             ]
         );
     }
 
     #[test]
-    fn permissions_session_shortcut_submits_session_scope() {
+    fn post_run_check_option_attaches_assertion() {
         let (tx, mut rx) = unbounded_channel::<AppEvent>();
         let tx = AppEventSender::new(tx);
-        let mut view =
-            ApprovalOverlay::new(make_permissions_request(), tx, Features::with_defaults());
-
-        view.handle_key_event(KeyEvent::new(KeyCode::Char('a'), KeyModifiers::NONE));
+        let mut view = ApprovalOverlay::builder(
+            ApprovalRequest::Exec {
+                thread_id: ThreadId::new(),
+                thread_label: None,
+                id: "test".to_string(),
+                command: vec!["touch".to_string(), "done.txt".to_string()],
+                cwd: PathBuf::from("/tmp"),
+                reason: None,
+                available_decisions: vec![ReviewDecision::Approved, ReviewDecision::Abort],
+                network_approval_context: None,
+                additional_permissions: None,
+                sandbox_policy: None,
+                env: Vec::new(),
+                duplicate_ids: Vec::new(),
+            },
+            tx,
+        )
+        .config(TuiApprovals {
+            post_run_checks: Some(true),
+            ..Default::default()
+        })
+        .build();
+        let labels: Vec<String> = view.options.iter().map(|opt| opt.label.clone()).collect();
+        assert_eq!(
+            labels,
+            vec![
+                "Yes, proceed".to_string(),
+                "Yes, and check afterward: exit code 0".to_string(),
+                "Yes, and check afterward: `done.txt` exists".to_string(),
+                "No, and tell Codex what to do differently".to_string(),
+                "Explain this command".to_string(),
+                "Edit the command first".to_string(),
+            ]
+        );
 
+        view.handle_key_event(KeyEvent::new(KeyCode::Char('3'), KeyModifiers::NONE));
         let mut saw_op = false;
         while let Ok(ev) = rx.try_recv() {
             if let AppEvent::SubmitThreadOp {
-                op: Op::RequestPermissionsResponse { response, .. },
+                op: Op::ApprovalResponse { response, .. },
                 ..
             } = ev
             {
-                assert_eq!(response.scope, PermissionGrantScope::Session);
+                assert_eq!(
+                    response,
+                    ApprovalResponse {
+                        assertions: vec![PostRunAssertion::PathExists {
+                            path: PathBuf::from("done.txt"),
+                        }],
+                        ..ApprovalResponse::new(ReviewDecision::Approved)
+                    }
+                );
                 saw_op = true;
                 break;
             }
         }
+        assert!(saw_op, "expected checked approval to emit an op");
+    }
+
+    #[test]
+    fn network_deny_forever_shortcut_is_not_bound() {
+        let (tx, mut rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx);
+        let mut view = ApprovalOverlay::new(
+            ApprovalRequest::Exec {
+                thread_id: ThreadId::new(),
+                thread_label: None,
+                id: "test".to_string(),
+                command: vec!["curl".to_string(), "https://example.com".to_string()],
+                cwd: PathBuf::from("/tmp"),
+                reason: None,
+                available_decisions: vec![
+                    ReviewDecision::Approved,
+                    ReviewDecision::ApprovedForSession,
+                    ReviewDecision::NetworkPolicyAmendment {
+                        network_policy_amendment: NetworkPolicyAmendment {
+                            host: "example.com".to_string(),
+                            action: NetworkPolicyRuleAction::Allow,
+                        },
+                    },
+                    ReviewDecision::Abort,
+                ],
+                network_approval_context: Some(NetworkApprovalContext {
+                    host: "example.com".to_string(),
+                    protocol: NetworkApprovalProtocol::Https,
+                }),
+                additional_permissions: None,
+                sandbox_policy: None,
+                env: Vec::new(),
+                duplicate_ids: Vec::new(),
+            },
+            tx,
+        );
+        view.handle_key_event(KeyEvent::new(KeyCode::Char('d'), KeyModifiers::NONE));
+
         assert!(
-            saw_op,
-            "expected permission approval decision to emit a session-scoped response"
+            rx.try_recv().is_err(),
+            "unexpected approval event emitted for hidden network deny shortcut"
         );
     }
 
     #[test]
-    fn additional_permissions_prompt_shows_permission_rule_line() {
+    fn header_includes_command_snippet() {
         let (tx, _rx) = unbounded_channel::<AppEvent>();
         let tx = AppEventSender::new(tx);
+        let command = vec!["echo".into(), "hello".into(), "world".into()];
         let exec_request = ApprovalRequest::Exec {
             thread_id: ThreadId::new(),
             thread_label: None,
             id: "test".into(),
-            command: vec!["cat".into(), "/tmp/readme.txt".into()],
+            command,
+            cwd: PathBuf::from("/tmp"),
             reason: None,
             available_decisions: vec![ReviewDecision::Approved, ReviewDecision::Abort],
             network_approval_context: None,
-            additional_permissions: Some(PermissionProfile {
+            additional_permissions: None,
+            sandbox_policy: None,
+            env: Vec::new(),
+            duplicate_ids: Vec::new(),
+        };
+
+        let view = ApprovalOverlay::new(exec_request, tx);
+        let mut buf = Buffer::empty(Rect::new(0, 0, 80, view.desired_height(80)));
+        view.render(Rect::new(0, 0, 80, view.desired_height(80)), &mut buf);
+
+        let rendered: Vec<String> = (0..buf.area.height)
+            .map(|row| {
+                (0..buf.area.width)
+                    .map(|col| buf[(col, row)].symbol().to_string())
+                    .collect()
+            })
+            .collect();
+        assert!(
+            rendered
+                .iter()
+                .any(|line| line.contains("echo hello world")),
+            "expected header to include command snippet, got {rendered:?}"
+        );
+    }
+
+    #[test]
+    fn permissions_session_shortcut_submits_session_scope() {
+        let (tx, mut rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx);
+        let mut view = ApprovalOverlay::new(make_permissions_request(), tx);
+
+        view.handle_key_event(KeyEvent::new(KeyCode::Char('a'), KeyModifiers::NONE));
+
+        let mut saw_op = false;
+        while let Ok(ev) = rx.try_recv() {
+            if let AppEvent::SubmitThreadOp {
+                op: Op::RequestPermissionsResponse { response, .. },
+                ..
+            } = ev
+            {
+                assert_eq!(response.scope, PermissionGrantScope::Session);
+                saw_op = true;
+                break;
+            }
+        }
+        assert!(
+            saw_op,
+            "expected permission approval decision to emit a session-scoped response"
+        );
+    }
+
+    #[test]
+    fn additional_permissions_prompt_shows_permission_rule_line() {
+        let (tx, _rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx);
+        let exec_request = ApprovalRequest::Exec {
+            thread_id: ThreadId::new(),
+            thread_label: None,
+            id: "test".into(),
+            command: vec!["cat".into(), "/tmp/readme.txt".into()],
+            cwd: PathBuf::from("/tmp"),
+            reason: None,
+            available_decisions: vec![ReviewDecision::Approved, ReviewDecision::Abort],
+            network_approval_context: None,
+            additional_permissions: Some(PermissionProfile {
                 network: Some(NetworkPermissions {
                     enabled: Some(true),
                 }),
@@ -1326,9 +2198,12 @@ mod tests {
                 }),
                 ..Default::default()
             }),
+            sandbox_policy: None,
+            env: Vec::new(),
+            duplicate_ids: Vec::new(),
         };
 
-        let view = ApprovalOverlay::new(exec_request, tx, Features::with_defaults());
+        let view = ApprovalOverlay::new(exec_request, tx);
         let mut buf = Buffer::empty(Rect::new(0, 0, 120, view.desired_height(120)));
         view.render(Rect::new(0, 0, 120, view.desired_height(120)), &mut buf);
 
@@ -1361,6 +2236,7 @@ mod tests {
             thread_label: None,
             id: "test".into(),
             command: vec!["cat".into(), "/tmp/readme.txt".into()],
+            cwd: PathBuf::from("/tmp"),
             reason: Some("need filesystem access".into()),
             available_decisions: vec![ReviewDecision::Approved, ReviewDecision::Abort],
             network_approval_context: None,
@@ -1374,9 +2250,12 @@ mod tests {
                 }),
                 ..Default::default()
             }),
+            sandbox_policy: None,
+            env: Vec::new(),
+            duplicate_ids: Vec::new(),
         };
 
-        let view = ApprovalOverlay::new(exec_request, tx, Features::with_defaults());
+        let view = ApprovalOverlay::new(exec_request, tx);
         assert_snapshot!(
             "approval_overlay_additional_permissions_prompt",
             normalize_snapshot_paths(render_overlay_lines(&view, 120))
@@ -1387,7 +2266,7 @@ mod tests {
     fn permissions_prompt_snapshot() {
         let (tx, _rx) = unbounded_channel::<AppEvent>();
         let tx = AppEventSender::new(tx);
-        let view = ApprovalOverlay::new(make_permissions_request(), tx, Features::with_defaults());
+        let view = ApprovalOverlay::new(make_permissions_request(), tx);
         assert_snapshot!(
             "approval_overlay_permissions_prompt",
             normalize_snapshot_paths(render_overlay_lines(&view, 120))
@@ -1403,6 +2282,7 @@ mod tests {
             thread_label: None,
             id: "test".into(),
             command: vec!["osascript".into(), "-e".into(), "tell application".into()],
+            cwd: PathBuf::from("/tmp"),
             reason: Some("need macOS automation".into()),
             available_decisions: vec![ReviewDecision::Approved, ReviewDecision::Abort],
             network_approval_context: None,
@@ -1421,9 +2301,12 @@ mod tests {
                 }),
                 ..Default::default()
             }),
+            sandbox_policy: None,
+            env: Vec::new(),
+            duplicate_ids: Vec::new(),
         };
 
-        let view = ApprovalOverlay::new(exec_request, tx, Features::with_defaults());
+        let view = ApprovalOverlay::new(exec_request, tx);
         assert_snapshot!(
             "approval_overlay_additional_permissions_macos_prompt",
             render_overlay_lines(&view, 120)
@@ -1439,6 +2322,7 @@ mod tests {
             thread_label: None,
             id: "test".into(),
             command: vec!["curl".into(), "https://example.com".into()],
+            cwd: PathBuf::from("/tmp"),
             reason: Some("network request blocked".into()),
             available_decisions: vec![
                 ReviewDecision::Approved,
@@ -1456,9 +2340,12 @@ mod tests {
                 protocol: NetworkApprovalProtocol::Https,
             }),
             additional_permissions: None,
+            sandbox_policy: None,
+            env: Vec::new(),
+            duplicate_ids: Vec::new(),
         };
 
-        let view = ApprovalOverlay::new(exec_request, tx, Features::with_defaults());
+        let view = ApprovalOverlay::new(exec_request, tx);
         let mut buf = Buffer::empty(Rect::new(0, 0, 100, view.desired_height(100)));
         view.render(Rect::new(0, 0, 100, view.desired_height(100)), &mut buf);
         assert_snapshot!("network_exec_prompt", format!("{buf:?}"));
@@ -1494,7 +2381,8 @@ mod tests {
             "-lc".into(),
             "git add tui/src/render/mod.rs tui/src/render/renderable.rs".into(),
         ];
-        let cell = history_cell::new_approval_decision_cell(command, ReviewDecision::Approved);
+        let cell =
+            history_cell::new_approval_decision_cell(command, ReviewDecision::Approved, None);
         let lines = cell.display_lines(28);
         let rendered: Vec<String> = lines
             .iter()
@@ -1515,28 +2403,2678 @@ mod tests {
     }
 
     #[test]
-    fn enter_sets_last_selected_index_without_dismissing() {
-        let (tx_raw, mut rx) = unbounded_channel::<AppEvent>();
-        let tx = AppEventSender::new(tx_raw);
-        let mut view = ApprovalOverlay::new(make_exec_request(), tx, Features::with_defaults());
-        view.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+    fn dot_replays_last_decision_for_identical_request() {
+        let (tx, mut rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx);
+        let session = ApprovalSession::default();
+        let mut first = ApprovalOverlay::builder(make_exec_request(), tx.clone())
+            .session(session.clone())
+            .build();
+        first.handle_key_event(KeyEvent::new(KeyCode::Char('y'), KeyModifiers::NONE));
+        while rx.try_recv().is_ok() {}
 
+        let mut second = ApprovalOverlay::builder(make_exec_request(), tx)
+            .session(session)
+            .build();
         assert!(
-            view.is_complete(),
-            "exec approval should complete without queued requests"
+            render_overlay_lines(&second, 80)
+                .contains("Press . to repeat last decision (Yes, proceed)"),
+            "expected replay hint in footer"
         );
+        second.handle_key_event(KeyEvent::new(KeyCode::Char('.'), KeyModifiers::NONE));
+        let mut decisions = Vec::new();
+        while let Ok(ev) = rx.try_recv() {
+            if let AppEvent::SubmitThreadOp {
+                op:
+                    Op::ApprovalResponse {
+                        response: ApprovalResponse { decision, .. },
+                        ..
+                    },
+                ..
+            } = ev
+            {
+                decisions.push(decision);
+            }
+        }
+        assert_eq!(decisions, vec![ReviewDecision::Approved]);
+        assert!(second.is_complete());
+    }
 
-        let mut decision = None;
+    #[test]
+    fn dot_is_ignored_without_matching_previous_request() {
+        let (tx, mut rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx);
+        let mut view = ApprovalOverlay::new(make_exec_request(), tx);
+        view.handle_key_event(KeyEvent::new(KeyCode::Char('.'), KeyModifiers::NONE));
+        assert!(rx.try_recv().is_err());
+        assert!(!view.is_complete());
+    }
+
+    #[test]
+    fn unapproved_commands_are_marked_new_this_session() {
+        let (tx, _rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx);
+        let session = ApprovalSession::default();
+        let new_view = |request| {
+            ApprovalOverlay::builder(request, tx.clone())
+                .session(session.clone())
+                .build()
+        };
+        let mut first = new_view(make_exec_request());
+        assert!(!render_overlay_lines(&first, 80).contains("New this session"));
+        first.handle_key_event(KeyEvent::new(KeyCode::Char('y'), KeyModifiers::NONE));
+
+        let mut ls = make_exec_request();
+        if let ApprovalRequest::Exec { command, .. } = &mut ls {
+            *command = vec!["ls".to_string()];
+        }
+        assert!(render_overlay_lines(&new_view(ls), 80).contains("● New this session"));
+        let repeat = new_view(make_exec_request());
+        assert!(!render_overlay_lines(&repeat, 80).contains("New this session"));
+    }
+
+    #[test]
+    fn max_width_centers_a_capped_column() {
+        let (tx, _rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx);
+        let new_view = |config| {
+            ApprovalOverlay::builder(make_exec_request(), tx.clone())
+                .config(config)
+                .build()
+        };
+        let full = new_view(TuiApprovals::default());
+        let capped = new_view(TuiApprovals {
+            max_width: Some(40),
+            ..Default::default()
+        });
+
+        assert_eq!(capped.desired_height(120), full.desired_height(40));
+        assert_eq!(
+            capped.content_area(Rect::new(0, 0, 120, 10)),
+            Rect::new(40, 0, 40, 10)
+        );
+        assert_eq!(
+            capped.content_area(Rect::new(0, 0, 30, 10)),
+            Rect::new(0, 0, 30, 10)
+        );
+        let rendered = render_overlay_lines(&capped, 120);
+        let title_row = rendered
+            .lines()
+            .find(|line| line.contains("Would you like to run"))
+            .expect("title row");
+        assert!(title_row.starts_with(&" ".repeat(40)));
+    }
+
+    #[test]
+    fn a_request_forwarded_again_is_not_answered_twice() {
+        let (tx, mut rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx);
+        let first = make_exec_request();
+        let mut again = first.clone();
+        if let ApprovalRequest::Exec { command, .. } = &mut again {
+            *command = vec!["echo".to_string(), "again".to_string()];
+        }
+        let mut view = ApprovalOverlay::new(first, tx);
+        view.enqueue_request(again);
+
+        assert!(view.send_decision(ReviewDecision::Approved));
+        assert!(!view.send_decision(ReviewDecision::Approved));
+        let answered = std::iter::from_fn(|| rx.try_recv().ok())
+            .filter(|ev| {
+                matches!(
+                    ev,
+                    AppEvent::SubmitThreadOp {
+                        op: Op::ApprovalResponse { .. },
+                        ..
+                    }
+                )
+            })
+            .count();
+        assert_eq!(answered, 1);
+    }
+
+    #[test]
+    fn render_to_string_matches_what_the_terminal_shows() {
+        let (tx, _rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx);
+        let view = ApprovalOverlay::new(make_exec_request(), tx);
+        let height = view.desired_height(80);
+
+        let text = view.render_to_string(80, height);
+        assert_eq!(text, render_overlay_lines(&view, 80));
+        assert_eq!(view.render_to_string(80, 3).lines().count(), 3);
+
+        let ansi = view.render_to_ansi_string(80, height);
+        assert!(ansi.contains("\u{1b}["));
+        assert!(ansi.contains("Yes, proceed"));
+        assert_eq!(ansi.lines().count(), text.lines().count());
+    }
+
+    #[test]
+    fn audit_log_sends_a_record_per_decision() {
+        let records = |audit_log: Option<PathBuf>| {
+            let (tx, mut rx) = unbounded_channel::<AppEvent>();
+            let tx = AppEventSender::new(tx);
+            let mut view = ApprovalOverlay::builder(make_exec_request(), tx)
+                .config(TuiApprovals {
+                    audit_log,
+                    ..Default::default()
+                })
+                .build();
+            assert!(view.send_decision(ReviewDecision::Approved));
+            std::iter::from_fn(|| rx.try_recv().ok())
+                .filter_map(|ev| match ev {
+                    AppEvent::AuditRecord(record) => Some(record),
+                    _ => None,
+                })
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(records(None), Vec::<serde_json::Value>::new());
+
+        let records = records(Some(PathBuf::from("approvals.jsonl")));
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0]["kind"], "exec");
+        assert_eq!(records[0]["request_id"], "test");
+        assert_eq!(records[0]["command"], "echo hi");
+        assert_eq!(records[0]["decision"], "approved");
+    }
+
+    #[test]
+    fn space_confirms_the_selected_option() {
+        let (tx, mut rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx);
+        let mut view = ApprovalOverlay::new(make_exec_request(), tx);
+        view.handle_key_event(KeyEvent::new(KeyCode::Char(' '), KeyModifiers::NONE));
+
+        let mut decisions = Vec::new();
         while let Ok(ev) = rx.try_recv() {
             if let AppEvent::SubmitThreadOp {
-                op: Op::ExecApproval { decision: d, .. },
+                op: Op::ApprovalResponse { response, .. },
                 ..
             } = ev
             {
-                decision = Some(d);
-                break;
+                decisions.push(response.decision);
             }
         }
-        assert_eq!(decision, Some(ReviewDecision::Approved));
+        assert_eq!(decisions, vec![ReviewDecision::Approved]);
+        assert!(view.is_complete());
+    }
+
+    #[test]
+    fn max_height_keeps_the_options_in_view() {
+        let (tx, _rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx);
+        let script = (1..=40)
+            .map(|n| format!("echo {n}"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let mut request = make_exec_request();
+        if let ApprovalRequest::Exec { command, .. } = &mut request {
+            *command = vec!["bash".to_string(), "-lc".to_string(), script];
+        }
+        let view = ApprovalOverlay::builder(request, tx)
+            .config(TuiApprovals {
+                max_height: Some(6),
+                ..Default::default()
+            })
+            .build();
+        assert_eq!(view.desired_height(80), 6);
+
+        let area = Rect::new(0, 0, 80, 6);
+        let mut buf = Buffer::empty(area);
+        view.render(area, &mut buf);
+        let rendered = (0..area.height)
+            .map(|row| {
+                (0..area.width)
+                    .map(|col| buf[(col, row)].symbol().to_string())
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        assert!(rendered.contains("1. Yes, proceed"), "{rendered}");
+        assert!(rendered.contains("enter confirm"), "{rendered}");
+        assert!(!rendered.contains("echo 40"), "{rendered}");
+    }
+
+    #[test]
+    fn narrow_terminal_stacks_options_one_per_row() {
+        let (tx, _rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx);
+        let view = ApprovalOverlay::new(make_exec_request(), tx);
+
+        // Options are list rows rather than a button row, so a 20-column
+        // buffer wraps them instead of overflowing.
+        let rendered = render_overlay_lines(&view, 20);
+        let lines: Vec<&str> = rendered.lines().collect();
+        let yes = lines
+            .iter()
+            .position(|line| line.starts_with("› 1. Yes"))
+            .expect("selected first option");
+        let no = lines
+            .iter()
+            .position(|line| line.trim_start().starts_with("2. No"))
+            .expect("second option");
+        assert!(yes < no, "{rendered}");
+        assert!(lines.iter().all(|line| line.chars().count() <= 20));
+    }
+
+    #[test]
+    fn desired_width_fits_the_option_rows() {
+        let (tx, _rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx);
+        let view = ApprovalOverlay::new(make_exec_request(), tx);
+
+        let rendered = render_overlay_lines(&view, 200);
+        let widest_option = rendered
+            .lines()
+            .filter(|line| line.contains(". Yes") || line.contains(". No"))
+            .map(|line| line.chars().count())
+            .max()
+            .expect("option rows");
+        let width = view.desired_width(200);
+        assert!(width >= widest_option as u16, "{width} < {widest_option}");
+        assert!(width < 200);
+        assert_eq!(view.desired_width(20), 20);
+    }
+
+    #[test]
+    fn e_collects_feedback_before_declining() {
+        let (tx, mut rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx);
+        let mut view = ApprovalOverlay::new(make_exec_request(), tx);
+        view.handle_key_event(KeyEvent::new(KeyCode::Char('e'), KeyModifiers::NONE));
+        assert!(render_overlay_lines(&view, 80).contains("Tell Codex why you're declining"));
+        assert!(view.prefer_esc_to_handle_key_event());
+
+        // Esc returns to the options without deciding.
+        view.handle_key_event(KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE));
+        assert!(!view.is_complete());
+        assert!(render_overlay_lines(&view, 80).contains("Yes, proceed"));
+
+        view.handle_key_event(KeyEvent::new(KeyCode::Char('e'), KeyModifiers::NONE));
+        for c in "use a dry run".chars() {
+            view.handle_key_event(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE));
+        }
+        assert!(rx.try_recv().is_err(), "typing must not decide the request");
+        view.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+
+        let mut responses = Vec::new();
+        while let Ok(ev) = rx.try_recv() {
+            if let AppEvent::SubmitThreadOp {
+                op: Op::ApprovalResponse { response, .. },
+                ..
+            } = ev
+            {
+                responses.push(response);
+            }
+        }
+        assert_eq!(
+            responses,
+            vec![ApprovalResponse {
+                feedback: Some("use a dry run".to_string()),
+                ..ApprovalResponse::new(ReviewDecision::Denied)
+            }]
+        );
+        assert!(view.is_complete());
+    }
+
+    #[test]
+    fn w_approves_a_patch_with_a_note_for_the_agent() {
+        let (tx, mut rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx);
+        let mut view = ApprovalOverlay::new(make_patch_request(None, None), tx);
+        view.handle_key_event(KeyEvent::new(KeyCode::Char('w'), KeyModifiers::NONE));
+        assert!(render_overlay_lines(&view, 80).contains("Leave Codex a note"));
+        assert!(!view.is_complete());
+        for c in "looks good, add a test later".chars() {
+            view.handle_key_event(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE));
+        }
+        view.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+
+        let mut responses = Vec::new();
+        while let Ok(ev) = rx.try_recv() {
+            if let AppEvent::SubmitThreadOp {
+                op: Op::ApprovalResponse { response, .. },
+                ..
+            } = ev
+            {
+                responses.push(response);
+            }
+        }
+        assert_eq!(
+            responses,
+            vec![ApprovalResponse {
+                feedback: Some("looks good, add a test later".to_string()),
+                ..ApprovalResponse::new(ReviewDecision::Approved)
+            }]
+        );
+        assert!(view.is_complete());
+    }
+
+    #[test]
+    fn pending_command_is_the_exec_argv_and_none_for_patches() {
+        let (tx, _rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx);
+        let exec = ApprovalOverlay::new(make_exec_request(), tx.clone());
+        assert_eq!(
+            exec.pending_command(),
+            Some(["echo".to_string(), "hi".to_string()].as_slice())
+        );
+        let patch = ApprovalOverlay::new(make_patch_request(None, None), tx);
+        assert_eq!(patch.pending_command(), None);
+    }
+
+    #[test]
+    fn p_approves_only_the_checked_files_of_a_multi_file_patch() {
+        let (tx, mut rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx);
+        let single = ApprovalOverlay::new(make_patch_request(None, None), tx.clone());
+        assert!(!render_overlay_lines(&single, 80).contains("pick which files"));
+
+        let mut request = make_patch_request(None, None);
+        if let ApprovalRequest::ApplyPatch { changes, .. } = &mut request {
+            *changes = ["/tmp/a.txt", "/tmp/b.txt"]
+                .into_iter()
+                .map(|path| {
+                    let content = "hello\n".to_string();
+                    (PathBuf::from(path), FileChange::Add { content })
+                })
+                .collect();
+        }
+        let mut view = ApprovalOverlay::new(request, tx);
+        view.handle_key_event(KeyEvent::new(KeyCode::Char('p'), KeyModifiers::NONE));
+        view.handle_key_event(KeyEvent::new(KeyCode::Down, KeyModifiers::NONE));
+        view.handle_key_event(KeyEvent::new(KeyCode::Char(' '), KeyModifiers::NONE));
+        let rendered = render_overlay_lines(&view, 80);
+        assert!(rendered.contains("[x] a.txt"));
+        assert!(rendered.contains("[ ] b.txt"));
+        assert!(!view.is_complete());
+        view.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+
+        let mut responses = Vec::new();
+        while let Ok(ev) = rx.try_recv() {
+            if let AppEvent::SubmitThreadOp {
+                op: Op::ApprovalResponse { response, .. },
+                ..
+            } = ev
+            {
+                responses.push(response);
+            }
+        }
+        assert_eq!(
+            responses,
+            vec![ApprovalResponse {
+                overrides: ApprovalOverrides {
+                    included_files: Some(vec![PathBuf::from("/tmp/a.txt")]),
+                    ..ApprovalOverrides::default()
+                },
+                ..ApprovalResponse::new(ReviewDecision::Approved)
+            }]
+        );
+        assert!(view.is_complete());
+    }
+
+    #[test]
+    fn g_denies_asking_codex_to_investigate_first() {
+        let (tx, mut rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx);
+        let mut view = ApprovalOverlay::new(make_exec_request(), tx);
+        view.handle_key_event(KeyEvent::new(KeyCode::Char('g'), KeyModifiers::NONE));
+        assert!(render_overlay_lines(&view, 80).contains("1 investigate first"));
+        for c in "the failing test".chars() {
+            view.handle_key_event(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE));
+        }
+        view.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+
+        let mut responses = Vec::new();
+        while let Ok(ev) = rx.try_recv() {
+            if let AppEvent::SubmitThreadOp {
+                op: Op::ApprovalResponse { response, .. },
+                ..
+            } = ev
+            {
+                responses.push(response);
+            }
+        }
+        assert_eq!(
+            responses,
+            vec![ApprovalResponse {
+                feedback: Some("Please investigate before retrying: the failing test".to_string()),
+                ..ApprovalResponse::new(ReviewDecision::Denied)
+            }]
+        );
+    }
+
+    #[test]
+    fn pasted_newlines_stay_in_the_feedback_without_submitting() {
+        let (tx, mut rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx);
+        let mut view = ApprovalOverlay::new(make_exec_request(), tx);
+        view.handle_key_event(KeyEvent::new(KeyCode::Char('e'), KeyModifiers::NONE));
+        assert!(view.handle_paste("line1\nline2".to_string()));
+        assert!(!view.is_complete());
+        assert!(rx.try_recv().is_err());
+        view.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+
+        let mut responses = Vec::new();
+        while let Ok(ev) = rx.try_recv() {
+            if let AppEvent::SubmitThreadOp {
+                op: Op::ApprovalResponse { response, .. },
+                ..
+            } = ev
+            {
+                responses.push(response);
+            }
+        }
+        assert_eq!(
+            responses,
+            vec![ApprovalResponse {
+                feedback: Some("line1\nline2".to_string()),
+                ..ApprovalResponse::new(ReviewDecision::Denied)
+            }]
+        );
+    }
+
+    #[test]
+    fn patch_can_be_declined_with_feedback_from_the_options() {
+        let (tx, mut rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx);
+        let mut view = ApprovalOverlay::new(make_patch_request(None, None), tx);
+        view.handle_key_event(KeyEvent::new(KeyCode::Char('4'), KeyModifiers::NONE));
+        assert!(render_overlay_lines(&view, 80).contains("Tell Codex why you're declining"));
+        assert!(!view.is_complete());
+        for c in "keep the old heading".chars() {
+            view.handle_key_event(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE));
+        }
+        view.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+
+        let mut responses = Vec::new();
+        while let Ok(ev) = rx.try_recv() {
+            if let AppEvent::SubmitThreadOp {
+                op: Op::ApprovalResponse { response, .. },
+                ..
+            } = ev
+            {
+                responses.push(response);
+            }
+        }
+        assert_eq!(
+            responses,
+            vec![ApprovalResponse {
+                feedback: Some("keep the old heading".to_string()),
+                ..ApprovalResponse::new(ReviewDecision::Denied)
+            }]
+        );
+        assert!(view.is_complete());
+    }
+
+    #[test]
+    fn f_toggles_an_overlong_command_between_cut_and_full() {
+        let (tx, _rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx);
+        let mut request = make_exec_request();
+        if let ApprovalRequest::Exec { command, .. } = &mut request {
+            *command = std::iter::once("touch".to_string())
+                .chain((0..60).map(|i| format!("build/file{i}.txt")))
+                .collect();
+        }
+        let mut view = ApprovalOverlay::new(request, tx);
+        // Wide enough that the collapsed command and its hint share a row.
+        const WIDTH: u16 = 300;
+        let hint = "(press f to show full command)";
+        let collapsed = view.desired_height(WIDTH);
+        assert!(render_overlay_lines(&view, WIDTH).contains(hint));
+        assert!(!render_overlay_lines(&view, WIDTH).contains("build/file59.txt"));
+
+        view.handle_key_event(KeyEvent::new(KeyCode::Char('f'), KeyModifiers::NONE));
+        assert!(view.desired_height(WIDTH) > collapsed);
+        assert!(!render_overlay_lines(&view, WIDTH).contains(hint));
+        assert!(!view.is_complete());
+
+        view.handle_key_event(KeyEvent::new(KeyCode::Char('f'), KeyModifiers::NONE));
+        assert_eq!(view.desired_height(WIDTH), collapsed);
+        assert!(render_overlay_lines(&view, WIDTH).contains(hint));
+    }
+
+    #[test]
+    fn chained_commands_are_listed_step_by_step() {
+        let (tx, _rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx);
+        let mut request = make_exec_request();
+        if let ApprovalRequest::Exec { command, .. } = &mut request {
+            *command = vec![
+                "bash".to_string(),
+                "-lc".to_string(),
+                "cd build && make -j8; echo 'done; ok'".to_string(),
+            ];
+        }
+        let view = ApprovalOverlay::new(request, tx);
+        let rendered = render_overlay_lines(&view, 80);
+        for step in ["→ cd build", "1. make -j8", "2. echo 'done; ok'"] {
+            assert!(rendered.contains(step), "missing {step:?} in:\n{rendered}");
+        }
+        assert!(!rendered.contains("$ cd build"));
+    }
+
+    #[test]
+    fn a_leading_cd_shows_where_the_rest_runs() {
+        let (tx, _rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx);
+        let mut request = make_exec_request();
+        if let ApprovalRequest::Exec { command, cwd, .. } = &mut request {
+            *command = vec![
+                "bash".to_string(),
+                "-lc".to_string(),
+                "cd subdir && make".to_string(),
+            ];
+            *cwd = PathBuf::from("/work/repo");
+        }
+        let view = ApprovalOverlay::new(request, tx);
+        let rendered = render_overlay_lines(&view, 80);
+        assert!(
+            rendered.contains("→ cd subdir  /work/repo/subdir"),
+            "{rendered}"
+        );
+        assert!(rendered.contains("$ make"), "{rendered}");
+        assert!(!rendered.contains("&&"), "{rendered}");
+    }
+
+    #[test]
+    fn command_synopsis_describes_the_program_under_the_command() {
+        let (tx, _rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx);
+        let plain = ApprovalOverlay::new(make_exec_request(), tx.clone());
+        assert!(!render_overlay_lines(&plain, 80).contains("print its arguments"));
+
+        let view = ApprovalOverlay::builder(make_exec_request(), tx)
+            .config(TuiApprovals {
+                command_synopsis: Some(true),
+                ..Default::default()
+            })
+            .build();
+        let rendered = render_overlay_lines(&view, 80);
+        let lines: Vec<&str> = rendered.lines().collect();
+        let command_row = lines
+            .iter()
+            .position(|line| line.contains("$ echo hi"))
+            .expect("command row");
+        assert!(
+            lines[command_row + 1].contains("echo: print its arguments"),
+            "{rendered}"
+        );
+    }
+
+    #[test]
+    fn multi_megabyte_commands_render_a_bounded_prefix() {
+        let (tx, mut rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx);
+        let mut request = make_exec_request();
+        let huge = format!("echo {}", "x".repeat(4 * 1024 * 1024));
+        let total = huge.len() + "bash".len() + "-lc".len();
+        if let ApprovalRequest::Exec { command, .. } = &mut request {
+            *command = vec!["bash".to_string(), "-lc".to_string(), huge];
+        }
+        let started = Instant::now();
+        let mut view = ApprovalOverlay::new(request, tx);
+        view.full_command_shown.set(true);
+        let height = view.desired_height(80);
+        let rendered = render_overlay_lines(&view, 80);
+        assert!(started.elapsed() < Duration::from_secs(5));
+        assert!(height < 200, "{height}");
+        assert!(
+            rendered.contains(&format!("… (truncated, {total} bytes total)")),
+            "{rendered}"
+        );
+
+        view.handle_key_event(KeyEvent::new(KeyCode::Char('n'), KeyModifiers::NONE));
+        let cell = std::iter::from_fn(|| rx.try_recv().ok())
+            .find_map(|ev| match ev {
+                AppEvent::InsertHistoryCell(cell) => cell
+                    .as_any()
+                    .downcast_ref::<history_cell::ApprovalDecisionCell>()
+                    .map(|cell| cell.command.clone()),
+                _ => None,
+            })
+            .expect("history cell");
+        assert!(prompt::argv_bytes(&cell) <= prompt::MAX_DISPLAY_BYTES);
+    }
+
+    #[test]
+    fn question_mark_previews_every_options_description() {
+        let (tx, mut rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx);
+        let mut view = ApprovalOverlay::new(make_exec_request(), tx);
+        let list_height = view.desired_height(80);
+
+        view.handle_key_event(KeyEvent::new(KeyCode::Char('?'), KeyModifiers::NONE));
+        let rendered = render_overlay_lines(&view, 80);
+        assert!(rendered.contains("All options"), "{rendered}");
+        assert!(rendered.contains("Allows this request once"), "{rendered}");
+        assert!(
+            rendered.contains("Declines it and stops codex"),
+            "{rendered}"
+        );
+        assert_ne!(view.desired_height(80), list_height);
+
+        view.key_debounce = KeyDebounce::default();
+        view.handle_key_event(KeyEvent::new(KeyCode::Char('?'), KeyModifiers::NONE));
+        assert!(!render_overlay_lines(&view, 80).contains("All options"));
+        assert_eq!(view.desired_height(80), list_height);
+
+        // Keys that answer nothing stay in the preview; an option's key answers.
+        view.key_debounce = KeyDebounce::default();
+        view.handle_key_event(KeyEvent::new(KeyCode::Char('?'), KeyModifiers::NONE));
+        view.handle_key_event(KeyEvent::new(KeyCode::Down, KeyModifiers::NONE));
+        assert!(view.show_all_descriptions);
+        view.handle_key_event(KeyEvent::new(KeyCode::Char('y'), KeyModifiers::NONE));
+        assert!(!view.show_all_descriptions);
+        let decision = std::iter::from_fn(|| rx.try_recv().ok()).find_map(|ev| match ev {
+            AppEvent::SubmitThreadOp {
+                op: Op::ApprovalResponse { response, .. },
+                ..
+            } => Some(response.decision),
+            _ => None,
+        });
+        assert_eq!(decision, Some(ReviewDecision::Approved));
+    }
+
+    #[test]
+    fn history_entry_records_the_decision_and_feedback() {
+        let (tx, mut rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx);
+        let mut view = ApprovalOverlay::new(make_exec_request(), tx);
+        view.handle_key_event(KeyEvent::new(KeyCode::Char('e'), KeyModifiers::NONE));
+        for c in "use a dry run".chars() {
+            view.handle_key_event(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE));
+        }
+        view.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+
+        let mut entries = Vec::new();
+        while let Ok(ev) = rx.try_recv() {
+            if let AppEvent::InsertHistoryCell(cell) = ev
+                && let Some(cell) = cell
+                    .as_any()
+                    .downcast_ref::<history_cell::ApprovalDecisionCell>()
+            {
+                entries.push((
+                    cell.command.clone(),
+                    cell.decision.clone(),
+                    cell.feedback.clone(),
+                ));
+            }
+        }
+        assert_eq!(
+            entries,
+            vec![(
+                vec!["echo".to_string(), "hi".to_string()],
+                ReviewDecision::Denied,
+                Some("use a dry run".to_string()),
+            )]
+        );
+    }
+
+    #[test]
+    fn log_decisions_sets_how_much_history_a_decision_leaves() {
+        let history = |level| {
+            let (tx, mut rx) = unbounded_channel::<AppEvent>();
+            let mut view = ApprovalOverlay::builder(make_exec_request(), AppEventSender::new(tx))
+                .config(TuiApprovals {
+                    log_decisions: level,
+                    ..Default::default()
+                })
+                .build();
+            view.handle_key_event(KeyEvent::new(KeyCode::Char('y'), KeyModifiers::NONE));
+            std::iter::from_fn(|| rx.try_recv().ok())
+                .filter_map(|ev| match ev {
+                    AppEvent::InsertHistoryCell(cell) => Some(
+                        cell.display_lines(80)
+                            .iter()
+                            .map(|line| line.spans.iter().map(|span| span.content.as_ref()))
+                            .map(String::from_iter)
+                            .collect::<Vec<_>>(),
+                    ),
+                    _ => None,
+                })
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(history(None), vec![vec!["✔ approved: echo hi".to_string()]]);
+        assert_eq!(
+            history(Some(DecisionLogLevel::Full)),
+            vec![vec![
+                "✔ You approved codex to run echo hi this time".to_string()
+            ]]
+        );
+        assert_eq!(
+            history(Some(DecisionLogLevel::None)),
+            Vec::<Vec<String>>::new()
+        );
+    }
+
+    #[test]
+    fn closing_reports_whether_the_user_decided() {
+        let (tx, mut rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx);
+        let mut closed = || {
+            std::iter::from_fn(|| rx.try_recv().ok())
+                .filter_map(|ev| match ev {
+                    AppEvent::ApprovalClosed { id, resolved } => Some((id, resolved)),
+                    _ => None,
+                })
+                .collect::<Vec<_>>()
+        };
+        let yes = KeyEvent::new(KeyCode::Char('y'), KeyModifiers::NONE);
+
+        let mut view = ApprovalOverlay::new(make_exec_request(), tx.clone());
+        view.enqueue_request(make_patch_request(None, None));
+        // Answering the first request leaves the second open, so nothing closes.
+        view.handle_key_event(yes);
+        assert_eq!(closed(), Vec::new());
+        view.key_debounce = KeyDebounce::default();
+        view.handle_key_event(yes);
+        assert_eq!(closed(), vec![("test".to_string(), true)]);
+
+        // Ctrl-C sends an abort, so the request was decided.
+        let mut view = ApprovalOverlay::new(make_exec_request(), tx.clone());
+        view.on_ctrl_c();
+        assert_eq!(closed(), vec![("test".to_string(), true)]);
+
+        // A dismissal sends nothing, even after an earlier request was
+        // answered, and does not keep the modal lingering.
+        let request = make_exec_request();
+        let thread_id = request.thread_id();
+        let mut view = ApprovalOverlay::builder(request, tx.clone())
+            .config(TuiApprovals {
+                linger_ms: Some(500),
+                ..Default::default()
+            })
+            .build();
+        let mut patch = make_patch_request(None, None);
+        if let ApprovalRequest::ApplyPatch { thread_id: id, .. } = &mut patch {
+            *id = thread_id;
+        }
+        view.enqueue_request(patch);
+        view.handle_key_event(yes);
+        view.dismiss_approvals(thread_id);
+        assert_eq!(closed(), vec![("test".to_string(), false)]);
+        assert!(view.is_complete());
+
+        let mut view = ApprovalOverlay::builder(make_exec_request(), tx)
+            .config(TuiApprovals {
+                auto_deny_secs: Some(0),
+                ..Default::default()
+            })
+            .build();
+        view.pre_draw_tick();
+        assert_eq!(closed(), vec![("test".to_string(), false)]);
+    }
+
+    #[test]
+    fn decisions_are_reported_with_their_kind_and_feedback() {
+        let (tx, mut rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx);
+        let mut view = ApprovalOverlay::new(make_exec_request(), tx);
+        view.handle_key_event(KeyEvent::new(KeyCode::Char('e'), KeyModifiers::NONE));
+        view.handle_key_event(KeyEvent::new(KeyCode::Char('x'), KeyModifiers::NONE));
+        view.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+
+        let mut resolved = Vec::new();
+        while let Ok(ev) = rx.try_recv() {
+            if let AppEvent::ApprovalResolved {
+                id,
+                kind,
+                decision,
+                had_feedback,
+                ..
+            } = ev
+            {
+                resolved.push((id, kind, decision, had_feedback));
+            }
+        }
+        assert_eq!(
+            resolved,
+            vec![(
+                "test".to_string(),
+                ApprovalKind::Exec,
+                ReviewDecision::Denied,
+                true
+            )]
+        );
+    }
+
+    #[test]
+    fn shift_e_asks_for_an_explanation_and_keeps_the_prompt_open() {
+        let (tx, mut rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx);
+        let mut view = ApprovalOverlay::new(make_exec_request(), tx);
+
+        view.handle_key_event(KeyEvent::new(KeyCode::Char('E'), KeyModifiers::SHIFT));
+        let mut ops = Vec::new();
+        while let Ok(ev) = rx.try_recv() {
+            if let AppEvent::SubmitThreadOp { op, .. } = ev {
+                ops.push(op);
+            }
+        }
+        assert_eq!(
+            ops,
+            vec![Op::RequestExplanation {
+                id: "test".to_string()
+            }]
+        );
+        assert!(!view.is_complete());
+        assert!(
+            render_overlay_lines(&view, 80)
+                .contains("Would you like to run the following command?")
+        );
+    }
+
+    #[test]
+    fn secrets_are_masked_on_screen_and_in_history() {
+        let (tx, mut rx) = unbounded_channel::<AppEvent>();
+        let mut request = make_exec_request();
+        if let ApprovalRequest::Exec { command, .. } = &mut request {
+            *command = vec![
+                "bash".to_string(),
+                "-lc".to_string(),
+                "deploy --token abc123".to_string(),
+            ];
+        }
+        let mut view = ApprovalOverlay::new(request, AppEventSender::new(tx));
+        let rendered = render_overlay_lines(&view, 80);
+        assert!(rendered.contains("$ deploy --token ••••"), "{rendered}");
+        assert!(!rendered.contains("abc123"), "{rendered}");
+
+        view.handle_key_event(KeyEvent::new(KeyCode::Char('y'), KeyModifiers::NONE));
+        let mut history = Vec::new();
+        let mut responses = Vec::new();
+        while let Ok(ev) = rx.try_recv() {
+            match ev {
+                AppEvent::InsertHistoryCell(cell) => history.extend(
+                    cell.display_lines(80)
+                        .iter()
+                        .map(|line| {
+                            line.spans
+                                .iter()
+                                .map(|span| span.content.as_ref())
+                                .collect()
+                        })
+                        .collect::<Vec<String>>(),
+                ),
+                AppEvent::SubmitThreadOp {
+                    op: Op::ApprovalResponse { response, .. },
+                    ..
+                } => responses.push(response),
+                _ => {}
+            }
+        }
+        let history = history.join("\n");
+        assert!(history.contains("deploy --token ••••"), "{history}");
+        assert!(!history.contains("abc123"), "{history}");
+        // The command core runs is the original one.
+        assert_eq!(
+            responses,
+            vec![ApprovalResponse::new(ReviewDecision::Approved)]
+        );
+    }
+
+    #[test]
+    fn destructive_commands_show_a_warning() {
+        let (tx, _rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx);
+        let new_view = |script: &str| {
+            let mut request = make_exec_request();
+            if let ApprovalRequest::Exec { command, .. } = &mut request {
+                *command = vec!["bash".to_string(), "-lc".to_string(), script.to_string()];
+            }
+            ApprovalOverlay::new(request, tx.clone())
+        };
+
+        let rendered = render_overlay_lines(&new_view("rm -rf target"), 80);
+        let warning = rendered
+            .find("⚠ This command may be destructive")
+            .expect("warning line");
+        assert!(warning < rendered.find("$ rm -rf target").expect("command line"));
+        assert!(!render_overlay_lines(&new_view("ls target"), 80).contains("destructive"));
+    }
+
+    #[test]
+    fn destructive_commands_preselect_the_deny_option() {
+        let (tx, mut rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx);
+        let new_view = |script: &str| {
+            let mut request = make_exec_request();
+            if let ApprovalRequest::Exec { command, .. } = &mut request {
+                *command = vec!["bash".to_string(), "-lc".to_string(), script.to_string()];
+            }
+            ApprovalOverlay::new(request, tx.clone())
+        };
+
+        assert_eq!(new_view("ls target").list.selected_index(), Some(0));
+        let mut view = new_view("rm -rf /");
+        let no_idx = view
+            .options
+            .iter()
+            .position(|opt| opt.decision == ApprovalDecision::Review(ReviewDecision::Abort));
+        assert!(no_idx.is_some());
+        assert_eq!(view.list.selected_index(), no_idx);
+
+        view.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+        let mut decisions = Vec::new();
+        while let Ok(ev) = rx.try_recv() {
+            if let AppEvent::SubmitThreadOp {
+                op: Op::ApprovalResponse { response, .. },
+                ..
+            } = ev
+            {
+                decisions.push(response.decision);
+            }
+        }
+        assert_eq!(decisions, vec![ReviewDecision::Abort]);
+    }
+
+    #[test]
+    fn privileged_commands_show_their_own_warning_and_preselect_deny() {
+        let (tx, _rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx);
+        let new_view = |script: &str| {
+            let mut request = make_exec_request();
+            if let ApprovalRequest::Exec { command, .. } = &mut request {
+                *command = vec!["bash".to_string(), "-lc".to_string(), script.to_string()];
+            }
+            ApprovalOverlay::new(request, tx.clone())
+        };
+
+        let view = new_view("sudo apt-get install jq");
+        let rendered = render_overlay_lines(&view, 80);
+        assert!(
+            rendered.contains("⚠ requests elevated privileges"),
+            "{rendered}"
+        );
+        assert!(!rendered.contains("destructive"), "{rendered}");
+        let no_idx = view
+            .options
+            .iter()
+            .position(|opt| opt.decision == ApprovalDecision::Review(ReviewDecision::Abort));
+        assert_eq!(view.list.selected_index(), no_idx);
+
+        let rendered = render_overlay_lines(&new_view("sudo rm -rf /"), 80);
+        assert!(
+            rendered.contains("⚠ This command may be destructive"),
+            "{rendered}"
+        );
+        assert!(!rendered.contains("elevated privileges"), "{rendered}");
+    }
+
+    #[test]
+    fn empty_commands_warn_and_preselect_the_deny_option() {
+        let (tx, _rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx);
+        for empty in [
+            vec![],
+            vec!["  ".to_string()],
+            vec!["bash".to_string(), "-lc".to_string(), " ".to_string()],
+        ] {
+            let mut request = make_exec_request();
+            if let ApprovalRequest::Exec { command, .. } = &mut request {
+                *command = empty.clone();
+            }
+            let view = ApprovalOverlay::new(request, tx.clone());
+            assert!(
+                render_overlay_lines(&view, 80).contains("⚠ empty command — denying recommended"),
+                "{empty:?}"
+            );
+            let no_idx = view
+                .options
+                .iter()
+                .position(|opt| opt.decision == ApprovalDecision::Review(ReviewDecision::Abort));
+            assert_eq!(view.list.selected_index(), no_idx, "{empty:?}");
+        }
+        assert!(
+            !render_overlay_lines(&ApprovalOverlay::new(make_exec_request(), tx), 80)
+                .contains("empty command")
+        );
+    }
+
+    #[test]
+    fn unbalanced_shell_scripts_show_a_hint() {
+        let (tx, _rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx);
+        let with_script = |script: &str| {
+            let mut request = make_exec_request();
+            if let ApprovalRequest::Exec { command, .. } = &mut request {
+                *command = vec!["bash".to_string(), "-lc".to_string(), script.to_string()];
+            }
+            render_overlay_lines(&ApprovalOverlay::new(request, tx.clone()), 80)
+        };
+        assert!(
+            with_script("echo \"$(ls src").contains("⚠ unbalanced quotes/brackets (unclosed \")")
+        );
+        assert!(!with_script("echo '(' \"[\"").contains("unbalanced"));
+        assert!(
+            !render_overlay_lines(&ApprovalOverlay::new(make_exec_request(), tx), 80)
+                .contains("unbalanced")
+        );
+    }
+
+    #[test]
+    fn patches_deleting_many_files_warn_and_preselect_the_deny_option() {
+        let (tx, _rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx);
+        let mut request = make_patch_request(None, None);
+        if let ApprovalRequest::ApplyPatch { changes, .. } = &mut request {
+            for name in ["a.rs", "b.rs", "c.rs"] {
+                changes.insert(
+                    PathBuf::from(format!("/tmp/{name}")),
+                    FileChange::Delete {
+                        content: "gone\n".to_string(),
+                    },
+                );
+            }
+        }
+        let view = ApprovalOverlay::new(request, tx);
+        let rendered = render_overlay_lines(&view, 80);
+        assert!(rendered.contains("deletes: a.rs, b.rs, c.rs"), "{rendered}");
+        assert!(
+            rendered.contains("⚠ deletes 3 files — denying recommended"),
+            "{rendered}"
+        );
+        let no_idx = view.options.iter().position(|opt| {
+            matches!(
+                opt.decision,
+                ApprovalDecision::Review(ReviewDecision::Abort | ReviewDecision::Denied)
+            )
+        });
+        assert_eq!(view.list.selected_index(), no_idx);
+    }
+
+    #[test]
+    fn theme_colors_the_destructive_warning() {
+        let (tx, _rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx);
+        let mut request = make_exec_request();
+        if let ApprovalRequest::Exec { command, .. } = &mut request {
+            *command = vec!["rm".to_string(), "-rf".to_string(), "target".to_string()];
+        }
+        let theme = ApprovalTheme {
+            warning: Color::Magenta,
+            ..ApprovalTheme::default()
+        };
+        let view = ApprovalOverlay::builder(request, tx).theme(theme).build();
+
+        let area = Rect::new(0, 0, 80, view.desired_height(80));
+        let mut buf = Buffer::empty(area);
+        view.render(area, &mut buf);
+        let warning = (0..area.height)
+            .flat_map(|row| (0..area.width).map(move |col| (col, row)))
+            .find(|&pos| buf[pos].symbol() == "⚠")
+            .expect("warning glyph");
+        assert_eq!(buf[warning].fg, Color::Magenta);
+    }
+
+    #[test]
+    fn left_border_takes_the_color_of_the_riskiest_warning() {
+        let (tx, _rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx);
+        let theme = ApprovalTheme {
+            borders: Borders::LEFT,
+            ..ApprovalTheme::default()
+        };
+        let left_edge = |command: &[&str]| {
+            let mut request = make_exec_request();
+            if let ApprovalRequest::Exec { command: argv, .. } = &mut request {
+                *argv = command.iter().map(|word| word.to_string()).collect();
+            }
+            let view = ApprovalOverlay::builder(request, tx.clone())
+                .theme(theme)
+                .build();
+            let area = Rect::new(0, 0, 80, view.desired_height(80));
+            let mut buf = Buffer::empty(area);
+            view.render(area, &mut buf);
+            (0..area.height)
+                .map(|row| buf[(0, row)].fg)
+                .collect::<HashSet<_>>()
+        };
+        assert_eq!(left_edge(&["echo", "hi"]), HashSet::from([theme.accent]));
+        assert_eq!(
+            left_edge(&["bash", "-lc", "echo \"hi"]),
+            HashSet::from([theme.caution])
+        );
+        assert_eq!(
+            left_edge(&["rm", "-rf", "target"]),
+            HashSet::from([theme.warning])
+        );
+    }
+
+    #[test]
+    fn colorblind_safe_swaps_red_and_green_for_orange_and_blue() {
+        let (tx, _rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx);
+        let mut request = make_exec_request();
+        if let ApprovalRequest::Exec { command, .. } = &mut request {
+            *command = vec!["rm".to_string(), "-rf".to_string(), "target".to_string()];
+        }
+        let render = |colorblind_safe| {
+            let view = ApprovalOverlay::builder(request.clone(), tx.clone())
+                .config(TuiApprovals {
+                    colorblind_safe: Some(colorblind_safe),
+                    ..Default::default()
+                })
+                .theme(ApprovalTheme {
+                    borders: Borders::LEFT,
+                    ..ApprovalTheme::default()
+                })
+                .build();
+            let area = Rect::new(0, 0, 80, view.desired_height(80));
+            let mut buf = Buffer::empty(area);
+            view.render(area, &mut buf);
+            buf
+        };
+        let colors = |buf: &Buffer| {
+            buf.content()
+                .iter()
+                .flat_map(|cell| [cell.fg, cell.bg])
+                .collect::<HashSet<_>>()
+        };
+
+        let plain = render(false);
+        assert!(colors(&plain).contains(&Color::Red));
+        assert_eq!(plain[(0, 0)].symbol(), "│");
+
+        let safe = render(true);
+        let safe_colors = colors(&safe);
+        assert!(safe_colors.contains(&Color::Yellow), "{safe_colors:?}");
+        assert!(
+            !safe_colors.contains(&Color::Red) && !safe_colors.contains(&Color::Green),
+            "{safe_colors:?}"
+        );
+        assert_eq!(safe[(0, 0)].symbol(), "▌");
+    }
+
+    #[test]
+    fn theme_border_frames_the_modal_without_clipping_it() {
+        let (tx, _rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx);
+        let plain = ApprovalOverlay::new(make_exec_request(), tx.clone());
+        let theme = ApprovalTheme {
+            borders: Borders::ALL,
+            border_type: BorderType::Rounded,
+            ..ApprovalTheme::default()
+        };
+        let boxed = ApprovalOverlay::builder(make_exec_request(), tx)
+            .theme(theme)
+            .build();
+        assert_eq!(boxed.desired_height(80), plain.desired_height(78) + 2);
+
+        let area = Rect::new(0, 0, 80, boxed.desired_height(80));
+        let mut buf = Buffer::empty(area);
+        boxed.render(area, &mut buf);
+        assert_eq!(buf[(0, 0)].symbol(), "╭");
+        assert_eq!(buf[(79, area.height - 1)].symbol(), "╯");
+        assert_eq!(buf[(0, 0)].fg, theme.accent);
+        let rendered: String = (0..area.height)
+            .flat_map(|row| (0..area.width).map(move |col| (col, row)))
+            .map(|pos| buf[pos].symbol().to_string())
+            .collect();
+        assert!(rendered.contains("Yes, proceed"));
+        assert!(rendered.contains("confirm"));
+    }
+
+    #[test]
+    fn high_contrast_draws_in_the_terminal_colors_with_a_thick_frame() {
+        let (tx, _rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx);
+        let view = ApprovalOverlay::builder(make_exec_request(), tx)
+            .config(TuiApprovals {
+                high_contrast: Some(true),
+                ..TuiApprovals::default()
+            })
+            .build();
+
+        let area = Rect::new(0, 0, 80, view.desired_height(80));
+        let mut buf = Buffer::empty(area);
+        view.render(area, &mut buf);
+        assert_eq!(buf[(0, 0)].symbol(), "┏");
+        let cells: Vec<_> = (0..area.height)
+            .flat_map(|row| (0..area.width).map(move |col| (col, row)))
+            .collect();
+        assert!(cells.iter().all(|&pos| {
+            let cell = &buf[pos];
+            cell.fg == Color::Reset
+                && cell.bg == Color::Reset
+                && !cell.modifier.contains(Modifier::DIM)
+        }));
+        let marker = cells
+            .iter()
+            .copied()
+            .find(|&pos| buf[pos].symbol() == "›")
+            .expect("selection marker");
+        assert!(
+            buf[marker]
+                .modifier
+                .contains(Modifier::BOLD | Modifier::REVERSED)
+        );
+    }
+
+    #[test]
+    fn popup_is_boxed_padded_and_titled() {
+        let (tx, _rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx);
+        let inline = ApprovalOverlay::new(make_exec_request(), tx.clone());
+        let popup = ApprovalOverlay::builder(make_exec_request(), tx)
+            .popup(true)
+            .build();
+        assert_eq!(popup.desired_height(80), inline.desired_height(76) + 2);
+
+        let area = Rect::new(0, 0, 80, popup.desired_height(80));
+        let mut buf = Buffer::empty(area);
+        popup.render(area, &mut buf);
+        assert_eq!(buf[(0, 0)].symbol(), "┌");
+        assert_eq!(buf[(79, area.height - 1)].symbol(), "┘");
+        assert_eq!(buf[(1, 1)].symbol(), " ");
+        let top: String = (0..area.width)
+            .map(|col| buf[(col, 0)].symbol().to_string())
+            .collect();
+        assert!(top.contains(" Run command "), "{top}");
+        let rendered: String = (0..area.height)
+            .flat_map(|row| (0..area.width).map(move |col| (col, row)))
+            .map(|pos| buf[pos].symbol().to_string())
+            .collect();
+        assert!(rendered.contains("Yes, proceed"));
+        assert!(rendered.contains("confirm"));
+    }
+
+    #[test]
+    fn first_enter_on_a_destructive_command_needs_a_selection() {
+        let (tx, mut rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx);
+        let mut request = make_exec_request();
+        if let ApprovalRequest::Exec { command, .. } = &mut request {
+            *command = vec!["rm".to_string(), "-rf".to_string(), "target".to_string()];
+        }
+        let mut view = ApprovalOverlay::builder(request, tx)
+            .config(TuiApprovals {
+                require_explicit_select: Some(true),
+                ..Default::default()
+            })
+            .build();
+        let enter = KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE);
+
+        view.handle_key_event(enter);
+        assert!(!view.is_complete());
+        assert!(rx.try_recv().is_err(), "the first Enter must not decide");
+        assert!(render_overlay_lines(&view, 80).contains("Select an option first"));
+
+        view.handle_key_event(KeyEvent::new(KeyCode::Up, KeyModifiers::NONE));
+        assert!(!render_overlay_lines(&view, 80).contains("Select an option first"));
+        view.handle_key_event(enter);
+        assert!(view.is_complete());
+    }
+
+    #[test]
+    fn destructive_command_ignores_option_keys_until_the_warning_is_acknowledged() {
+        let (tx, mut rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx);
+        let mut request = make_exec_request();
+        if let ApprovalRequest::Exec { command, .. } = &mut request {
+            *command = vec!["rm".to_string(), "-rf".to_string(), "target".to_string()];
+        }
+        let mut view = ApprovalOverlay::builder(request, tx)
+            .config(TuiApprovals {
+                acknowledge_warnings: Some(true),
+                ..Default::default()
+            })
+            .build();
+        assert!(render_overlay_lines(&view, 80).contains("Press space to acknowledge the warning"));
+
+        for code in [
+            KeyCode::Char('y'),
+            KeyCode::Char('1'),
+            KeyCode::Down,
+            KeyCode::Enter,
+        ] {
+            view.handle_key_event(KeyEvent::new(code, KeyModifiers::NONE));
+        }
+        assert!(!view.is_complete());
+        assert!(rx.try_recv().is_err(), "option keys must wait for space");
+
+        view.handle_key_event(KeyEvent::new(KeyCode::Char(' '), KeyModifiers::NONE));
+        assert!(!render_overlay_lines(&view, 80).contains("acknowledge the warning"));
+        view.handle_key_event(KeyEvent::new(KeyCode::Char('y'), KeyModifiers::NONE));
+        assert!(view.is_complete());
+    }
+
+    #[test]
+    fn shift_d_approves_the_command_for_its_directory() {
+        let (tx, mut rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx);
+        let mut request = make_exec_request();
+        if let ApprovalRequest::Exec {
+            available_decisions,
+            ..
+        } = &mut request
+        {
+            *available_decisions = vec![
+                ReviewDecision::Approved,
+                ReviewDecision::ApprovedForSession,
+                ReviewDecision::Abort,
+            ];
+        }
+        let mut view = ApprovalOverlay::new(request, tx);
+        assert!(
+            render_overlay_lines(&view, 100).contains(
+                "Yes, approve this command in this directory for the session (shift + d)"
+            )
+        );
+
+        // Some terminals report an uppercase letter without Shift.
+        view.handle_key_event(KeyEvent::new(KeyCode::Char('D'), KeyModifiers::NONE));
+        let mut responses = Vec::new();
+        while let Ok(ev) = rx.try_recv() {
+            if let AppEvent::SubmitThreadOp {
+                op: Op::ApprovalResponse { response, .. },
+                ..
+            } = ev
+            {
+                responses.push(response);
+            }
+        }
+        assert_eq!(
+            responses,
+            vec![ApprovalResponse::new(ReviewDecision::ApprovedForDirectory)]
+        );
+    }
+
+    #[test]
+    fn t_asks_how_long_before_approving_for_a_while() {
+        let (tx, mut rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx);
+        let mut request = make_exec_request();
+        if let ApprovalRequest::Exec {
+            available_decisions,
+            ..
+        } = &mut request
+        {
+            *available_decisions = vec![
+                ReviewDecision::Approved,
+                ReviewDecision::ApprovedForDuration {
+                    duration: Duration::from_secs(30 * 60),
+                },
+                ReviewDecision::Abort,
+            ];
+        }
+        let mut second = request.clone();
+        if let ApprovalRequest::Exec { id, command, .. } = &mut second {
+            *id = "second".to_string();
+            *command = vec!["echo".to_string(), "bye".to_string()];
+        }
+        let mut view = ApprovalOverlay::new(request, tx);
+        view.enqueue_request(second);
+        assert!(
+            render_overlay_lines(&view, 100).contains("Yes, and don't ask again for a while (t)")
+        );
+
+        view.handle_key_event(KeyEvent::new(KeyCode::Char('t'), KeyModifiers::NONE));
+        let rendered = render_overlay_lines(&view, 100);
+        assert!(rendered.contains("1. 5m"), "{rendered}");
+        assert!(rendered.contains("Approve for how long?"), "{rendered}");
+
+        // Esc goes back to the options without answering.
+        view.handle_key_event(KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE));
+        assert!(render_overlay_lines(&view, 100).contains("Yes, proceed"));
+        view.handle_key_event(KeyEvent::new(KeyCode::Char('t'), KeyModifiers::NONE));
+        view.handle_key_event(KeyEvent::new(KeyCode::Right, KeyModifiers::NONE));
+        view.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+        // The next request takes a preset's digit directly.
+        view.handle_key_event(KeyEvent::new(KeyCode::Char('t'), KeyModifiers::NONE));
+        view.handle_key_event(KeyEvent::new(KeyCode::Char('1'), KeyModifiers::NONE));
+
+        let mut responses = Vec::new();
+        while let Ok(ev) = rx.try_recv() {
+            if let AppEvent::SubmitThreadOp {
+                op: Op::ApprovalResponse { response, .. },
+                ..
+            } = ev
+            {
+                responses.push(response.decision);
+            }
+        }
+        assert_eq!(
+            responses,
+            vec![
+                ReviewDecision::ApprovedForDuration {
+                    duration: Duration::from_secs(60 * 60),
+                },
+                ReviewDecision::ApprovedForDuration {
+                    duration: Duration::from_secs(5 * 60),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn shift_a_approves_the_command_for_later_sessions() {
+        let (tx, mut rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx);
+        let mut request = make_exec_request();
+        if let ApprovalRequest::Exec {
+            available_decisions,
+            ..
+        } = &mut request
+        {
+            *available_decisions = vec![
+                ReviewDecision::Approved,
+                ReviewDecision::ApprovedAlways,
+                ReviewDecision::Abort,
+            ];
+        }
+        let mut view = ApprovalOverlay::new(request, tx);
+        assert!(
+            render_overlay_lines(&view, 100).contains("Always, even across sessions (shift + a)")
+        );
+
+        view.handle_key_event(KeyEvent::new(KeyCode::Char('A'), KeyModifiers::SHIFT));
+        let mut responses = Vec::new();
+        while let Ok(ev) = rx.try_recv() {
+            if let AppEvent::SubmitThreadOp {
+                op: Op::ApprovalResponse { response, .. },
+                ..
+            } = ev
+            {
+                responses.push(response);
+            }
+        }
+        assert_eq!(
+            responses,
+            vec![ApprovalResponse::new(ReviewDecision::ApprovedAlways)]
+        );
+    }
+
+    #[test]
+    fn s_approves_a_sandboxed_run_and_explains_it() {
+        let (tx, mut rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx);
+        let mut request = make_exec_request();
+        if let ApprovalRequest::Exec {
+            available_decisions,
+            ..
+        } = &mut request
+        {
+            *available_decisions = vec![
+                ReviewDecision::Approved,
+                ReviewDecision::ApprovedSandboxed,
+                ReviewDecision::Abort,
+            ];
+        }
+        let mut view = ApprovalOverlay::new(request, tx);
+        let rendered = render_overlay_lines(&view, 120);
+        assert!(rendered.contains("Run sandboxed (s)"), "{rendered}");
+        assert!(!rendered.contains("file writes are blocked"), "{rendered}");
+        view.handle_key_event(KeyEvent::new(KeyCode::Down, KeyModifiers::NONE));
+        let rendered = render_overlay_lines(&view, 120);
+        assert!(
+            rendered.contains("Runs read-only: network access and file writes are blocked"),
+            "{rendered}"
+        );
+
+        view.handle_key_event(KeyEvent::new(KeyCode::Char('s'), KeyModifiers::NONE));
+        let mut responses = Vec::new();
+        while let Ok(ev) = rx.try_recv() {
+            if let AppEvent::SubmitThreadOp {
+                op: Op::ApprovalResponse { id, response, .. },
+                ..
+            } = ev
+            {
+                responses.push((id, response));
+            }
+        }
+        assert_eq!(
+            responses,
+            vec![(
+                "test".to_string(),
+                ApprovalResponse::new(ReviewDecision::ApprovedSandboxed)
+            )]
+        );
+        assert!(view.is_complete());
+    }
+
+    #[test]
+    fn x_denies_the_command_for_the_rest_of_the_session() {
+        let (tx, mut rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx);
+        let mut request = make_exec_request();
+        if let ApprovalRequest::Exec {
+            available_decisions,
+            ..
+        } = &mut request
+        {
+            *available_decisions = vec![
+                ReviewDecision::Approved,
+                ReviewDecision::DeniedForSession,
+                ReviewDecision::Abort,
+            ];
+        }
+        let mut view = ApprovalOverlay::new(request, tx);
+        let rendered = render_overlay_lines(&view, 120);
+        assert!(
+            rendered.contains("Never run this command this session (x)"),
+            "{rendered}"
+        );
+
+        view.handle_key_event(KeyEvent::new(KeyCode::Char('x'), KeyModifiers::NONE));
+        let mut responses = Vec::new();
+        while let Ok(ev) = rx.try_recv() {
+            if let AppEvent::SubmitThreadOp {
+                op: Op::ApprovalResponse { id, response, .. },
+                ..
+            } = ev
+            {
+                responses.push((id, response));
+            }
+        }
+        assert_eq!(
+            responses,
+            vec![(
+                "test".to_string(),
+                ApprovalResponse::new(ReviewDecision::DeniedForSession)
+            )]
+        );
+        assert!(view.is_complete());
+    }
+
+    #[test]
+    fn outcome_reports_the_decision_once_complete() {
+        let (tx, _rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx);
+        let mut view = ApprovalOverlay::new(make_exec_request(), tx.clone());
+        assert_eq!(view.outcome(), None);
+        view.handle_key_event(KeyEvent::new(KeyCode::Char('y'), KeyModifiers::NONE));
+        assert_eq!(view.outcome(), Some(ReviewDecision::Approved));
+
+        let mut view = ApprovalOverlay::new(make_exec_request(), tx);
+        view.enqueue_request(make_patch_request(None, None));
+        view.handle_key_event(KeyEvent::new(KeyCode::Char('y'), KeyModifiers::NONE));
+        assert_eq!(view.outcome(), None);
+        assert_eq!(view.on_ctrl_c(), CancellationEvent::Handled);
+        assert_eq!(view.outcome(), Some(ReviewDecision::Abort));
+    }
+
+    #[test]
+    fn u_expands_a_long_reason() {
+        let (tx, _rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx);
+        let mut request = make_exec_request();
+        if let ApprovalRequest::Exec { reason, .. } = &mut request {
+            *reason = Some("the build needs this because ".repeat(30));
+        }
+        let mut view = ApprovalOverlay::new(request, tx);
+        let collapsed = view.desired_height(80);
+        assert!(render_overlay_lines(&view, 80).contains("(press u to read more)"));
+
+        view.handle_key_event(KeyEvent::new(KeyCode::Char('u'), KeyModifiers::NONE));
+        assert!(!view.is_complete());
+        assert!(view.desired_height(80) > collapsed);
+        assert!(!render_overlay_lines(&view, 80).contains("(press u to read more)"));
+
+        view.handle_key_event(KeyEvent::new(KeyCode::Char('u'), KeyModifiers::NONE));
+        assert_eq!(view.desired_height(80), collapsed);
+    }
+
+    #[test]
+    fn o_still_opens_the_thread_of_a_request_with_a_long_reason() {
+        let (tx, mut rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx);
+        let mut request = make_exec_request();
+        if let ApprovalRequest::Exec {
+            reason,
+            thread_label,
+            ..
+        } = &mut request
+        {
+            *reason = Some("the build needs this because ".repeat(30));
+            *thread_label = Some("Robie [explorer]".to_string());
+        }
+        let thread_id = request.thread_id();
+        let mut view = ApprovalOverlay::new(request, tx);
+        let collapsed = view.desired_height(80);
+
+        view.handle_key_event(KeyEvent::new(KeyCode::Char('o'), KeyModifiers::NONE));
+        assert!(matches!(
+            rx.try_recv(),
+            Ok(AppEvent::SelectAgentThread(id)) if id == thread_id
+        ));
+        assert_eq!(view.desired_height(80), collapsed);
+    }
+
+    #[test]
+    fn dismissing_a_thread_drops_its_requests_without_answering() {
+        let (tx, mut rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx);
+        let request = make_exec_request();
+        let thread_id = request.thread_id();
+        let mut view = ApprovalOverlay::new(request, tx);
+        let other = make_exec_request();
+        let other_thread = other.thread_id();
+        view.enqueue_request(other);
+
+        view.dismiss_approvals(thread_id);
+        assert!(!view.is_complete());
+        assert_eq!(
+            view.current_request
+                .as_ref()
+                .map(ApprovalRequest::thread_id),
+            Some(other_thread)
+        );
+
+        view.dismiss_approvals(other_thread);
+        assert!(view.is_complete());
+        assert_eq!(view.outcome(), None);
+        let mut dismissals = 0;
+        while let Ok(event) = rx.try_recv() {
+            match event {
+                AppEvent::SubmitThreadOp { .. } => panic!("a dismissed request was answered"),
+                AppEvent::InsertHistoryCell(_) => dismissals += 1,
+                _ => {}
+            }
+        }
+        assert_eq!(dismissals, 2);
+    }
+
+    #[test]
+    fn unfocused_modal_renders_dimmed_and_ignores_keys() {
+        let (tx, _rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx);
+        let mut view = ApprovalOverlay::new(make_exec_request(), tx);
+        view.set_focused(false);
+        let area = Rect::new(0, 0, 80, view.desired_height(80));
+        let mut buf = Buffer::empty(area);
+        view.render(area, &mut buf);
+        let styled: Vec<_> = buf
+            .content()
+            .iter()
+            .filter(|cell| cell.fg != Color::Reset || cell.modifier != Modifier::DIM)
+            .collect();
+        assert!(styled.is_empty(), "{styled:?}");
+
+        view.handle_key_event(KeyEvent::new(KeyCode::Char('y'), KeyModifiers::NONE));
+        assert!(!view.is_complete());
+        view.set_focused(true);
+        view.handle_key_event(KeyEvent::new(KeyCode::Char('y'), KeyModifiers::NONE));
+        assert!(view.is_complete());
+    }
+
+    #[test]
+    fn translated_labels_bring_their_own_shortcuts() {
+        let (tx, mut rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx);
+        let mut view = ApprovalOverlay::builder(make_exec_request(), tx)
+            .config(TuiApprovals {
+                language: Some(ApprovalLanguage::De),
+                ..TuiApprovals::default()
+            })
+            .build();
+        let rendered = render_overlay_lines(&view, 120);
+        assert!(rendered.contains("1. Ja, fortfahren (j)"), "{rendered}");
+        assert!(
+            rendered.contains("Nein, und Codex sagen, was es anders machen soll (esc)"),
+            "{rendered}"
+        );
+
+        view.handle_key_event(KeyEvent::new(KeyCode::Char('y'), KeyModifiers::NONE));
+        assert!(!view.is_complete());
+        view.handle_key_event(KeyEvent::new(KeyCode::Char('j'), KeyModifiers::NONE));
+        let mut decisions = Vec::new();
+        while let Ok(ev) = rx.try_recv() {
+            if let AppEvent::SubmitThreadOp {
+                op: Op::ApprovalResponse { response, .. },
+                ..
+            } = ev
+            {
+                decisions.push(response.decision);
+            }
+        }
+        assert_eq!(decisions, vec![ReviewDecision::Approved]);
+    }
+
+    #[test]
+    fn every_translated_answer_key_reaches_its_answer() {
+        let elicitation = ApprovalRequest::McpElicitation {
+            thread_id: ThreadId::new(),
+            thread_label: None,
+            server_name: "docs".to_string(),
+            request_id: RequestId::String("request-1".to_string()),
+            message: "Which page?".to_string(),
+        };
+        let config = TuiApprovals {
+            language: Some(ApprovalLanguage::De),
+            extended_decisions: Some(true),
+            ..TuiApprovals::default()
+        };
+        let build = |request: &ApprovalRequest| {
+            let (tx, _rx) = unbounded_channel::<AppEvent>();
+            ApprovalOverlay::builder(request.clone(), AppEventSender::new(tx))
+                .config(config.clone())
+                .build()
+        };
+        for request in [
+            make_exec_request(),
+            make_patch_request(None, None),
+            make_permissions_request(),
+            elicitation,
+        ] {
+            for option in build(&request).options {
+                let expected = match &option.decision {
+                    ApprovalDecision::Review(decision) => decision.clone(),
+                    ApprovalDecision::McpElicitation(ElicitationAction::Accept) => {
+                        ReviewDecision::Approved
+                    }
+                    ApprovalDecision::McpElicitation(ElicitationAction::Decline) => {
+                        ReviewDecision::Denied
+                    }
+                    ApprovalDecision::McpElicitation(ElicitationAction::Cancel) => {
+                        ReviewDecision::Abort
+                    }
+                    // These open an input or picker instead of answering.
+                    ApprovalDecision::PickDuration(_)
+                    | ApprovalDecision::ApproveVerbose(_)
+                    | ApprovalDecision::ApproveWithAssertions(_)
+                    | ApprovalDecision::Explain
+                    | ApprovalDecision::EditCommand
+                    | ApprovalDecision::DenyWithFeedback
+                    | ApprovalDecision::DenyToInvestigate
+                    | ApprovalDecision::ApproveAsMacro
+                    | ApprovalDecision::PickFiles => continue,
+                };
+                let presses = ('a'..='z')
+                    .chain('A'..='Z')
+                    .flat_map(|c| [KeyModifiers::NONE, KeyModifiers::SHIFT].map(|m| (c, m)))
+                    .map(|(c, modifiers)| KeyEvent::new(KeyCode::Char(c), modifiers))
+                    .filter(|press| option.shortcuts().any(|s| s.is_press(*press)));
+                for press in presses {
+                    let mut view = build(&request);
+                    view.handle_key_event(press);
+                    assert_eq!(
+                        view.last_decision.borrow().clone(),
+                        Some(expected.clone()),
+                        "{} ({press:?})",
+                        option.label
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn compact_mode_takes_two_rows_until_expanded() {
+        let (tx, mut rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx);
+        let mut view = ApprovalOverlay::builder(make_exec_request(), tx)
+            .config(TuiApprovals {
+                compact: Some(true),
+                ..TuiApprovals::default()
+            })
+            .build();
+        assert_eq!(view.desired_height(80), 2);
+        let rendered = render_overlay_lines(&view, 80);
+        assert!(
+            rendered.contains("codex wants to run: echo hi"),
+            "{rendered}"
+        );
+        assert!(
+            rendered.contains("› Yes, proceed · y select · space expand"),
+            "{rendered}"
+        );
+
+        view.handle_key_event(KeyEvent::new(KeyCode::Char(' '), KeyModifiers::NONE));
+        assert!(view.desired_height(80) > 2);
+        assert!(render_overlay_lines(&view, 80).contains("Would you like to run"));
+        view.handle_key_event(KeyEvent::new(KeyCode::Char(' '), KeyModifiers::NONE));
+        assert_eq!(view.desired_height(80), 2);
+
+        view.handle_key_event(KeyEvent::new(KeyCode::Char('y'), KeyModifiers::NONE));
+        let mut decisions = Vec::new();
+        while let Ok(ev) = rx.try_recv() {
+            if let AppEvent::SubmitThreadOp {
+                op: Op::ApprovalResponse { response, .. },
+                ..
+            } = ev
+            {
+                decisions.push(response.decision);
+            }
+        }
+        assert_eq!(decisions, vec![ReviewDecision::Approved]);
+    }
+
+    #[test]
+    fn grant_root_outside_the_workspace_warns_and_defaults_to_no() {
+        let (tx, mut rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx);
+        let mut view = ApprovalOverlay::new(make_patch_request(None, Some("/")), tx);
+        let rendered = render_overlay_lines(&view, 80);
+        assert!(
+            rendered.contains("⚠ grants write access OUTSIDE your workspace"),
+            "{rendered}"
+        );
+
+        view.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+        let mut decisions = Vec::new();
+        while let Ok(ev) = rx.try_recv() {
+            if let AppEvent::SubmitThreadOp {
+                op: Op::ApprovalResponse { response, .. },
+                ..
+            } = ev
+            {
+                decisions.push(response.decision);
+            }
+        }
+        assert_eq!(decisions, vec![ReviewDecision::Abort]);
+    }
+
+    #[test]
+    fn r_requests_a_dry_run_for_the_same_id() {
+        let (tx, mut rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx);
+        let mut request = make_exec_request();
+        if let ApprovalRequest::Exec {
+            available_decisions,
+            ..
+        } = &mut request
+        {
+            *available_decisions = vec![
+                ReviewDecision::Approved,
+                ReviewDecision::DryRun,
+                ReviewDecision::Abort,
+            ];
+        }
+        let mut view = ApprovalOverlay::new(request, tx);
+        assert!(render_overlay_lines(&view, 100).contains("Dry run it read-only first (r)"));
+
+        view.handle_key_event(KeyEvent::new(KeyCode::Char('r'), KeyModifiers::NONE));
+        let mut responses = Vec::new();
+        while let Ok(ev) = rx.try_recv() {
+            if let AppEvent::SubmitThreadOp {
+                op: Op::ApprovalResponse { id, response, .. },
+                ..
+            } = ev
+            {
+                responses.push((id, response));
+            }
+        }
+        assert_eq!(
+            responses,
+            vec![(
+                "test".to_string(),
+                ApprovalResponse::new(ReviewDecision::DryRun)
+            )]
+        );
+    }
+
+    #[test]
+    fn extended_decisions_are_offered_only_when_enabled() {
+        let (tx, _rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx);
+        let offers_dry_run = |view: &ApprovalOverlay| {
+            view.options
+                .iter()
+                .any(|opt| opt.decision == ApprovalDecision::Review(ReviewDecision::DryRun))
+        };
+
+        assert!(!offers_dry_run(&ApprovalOverlay::new(
+            make_exec_request(),
+            tx.clone()
+        )));
+        let view = ApprovalOverlay::builder(make_exec_request(), tx)
+            .config(TuiApprovals {
+                extended_decisions: Some(true),
+                ..Default::default()
+            })
+            .build();
+        assert!(offers_dry_run(&view));
+    }
+
+    #[test]
+    fn auto_deny_timeout_declines_on_tick() {
+        let (tx, mut rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx);
+        let new_view = |secs| {
+            ApprovalOverlay::builder(make_exec_request(), tx.clone())
+                .config(TuiApprovals {
+                    auto_deny_secs: Some(secs),
+                    ..Default::default()
+                })
+                .build()
+        };
+
+        let mut view = new_view(0);
+        assert_eq!(view.pre_draw_tick(), None);
+        let mut responses = Vec::new();
+        while let Ok(ev) = rx.try_recv() {
+            if let AppEvent::SubmitThreadOp {
+                op: Op::ApprovalResponse { response, .. },
+                ..
+            } = ev
+            {
+                responses.push(response);
+            }
+        }
+        assert_eq!(
+            responses,
+            vec![ApprovalResponse::new(ReviewDecision::Denied)]
+        );
+        assert!(view.is_complete());
+
+        // The countdown shows next to the title until the user interacts.
+        let mut view = new_view(30);
+        assert!(render_overlay_lines(&view, 80).contains("(auto-deny in 30s)"));
+        view.handle_key_event(KeyEvent::new(KeyCode::Down, KeyModifiers::NONE));
+        assert!(!render_overlay_lines(&view, 80).contains("auto-deny"));
+        // Only the request age still asks for ticks.
+        assert!(
+            view.pre_draw_tick()
+                .is_some_and(|delay| delay <= Duration::from_secs(5))
+        );
+        assert!(!view.is_complete());
+    }
+
+    #[test]
+    fn footer_shows_how_long_the_prompt_has_waited() {
+        let (tx, _rx) = unbounded_channel::<AppEvent>();
+        let mut view = ApprovalOverlay::new(make_exec_request(), AppEventSender::new(tx));
+        view.pre_draw_tick();
+        assert!(!render_overlay_lines(&view, 80).contains("requested"));
+
+        view.issued_at = Instant::now() - Duration::from_secs(125);
+        let next_tick = view.pre_draw_tick();
+        assert!(render_overlay_lines(&view, 80).contains("requested 2m ago"));
+        assert!(next_tick.is_some_and(|delay| delay <= Duration::from_secs(55)));
+    }
+
+    #[test]
+    fn page_keys_scroll_a_clipped_prompt() {
+        let (tx, _rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx);
+        let script = (1..=40)
+            .map(|n| format!("echo {n}"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let mut request = make_exec_request();
+        if let ApprovalRequest::Exec { command, .. } = &mut request {
+            *command = vec!["bash".to_string(), "-lc".to_string(), script];
+        }
+        let mut view = ApprovalOverlay::new(request, tx);
+        let render = |view: &ApprovalOverlay| {
+            let area = Rect::new(0, 0, 60, 16);
+            let mut buf = Buffer::empty(area);
+            view.render(area, &mut buf);
+            (0..area.height)
+                .map(|row| {
+                    (0..area.width)
+                        .map(|col| buf[(col, row)].symbol().to_string())
+                        .collect::<String>()
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        };
+
+        let first = render(&view);
+        assert!(first.contains("more lines · PgDn"), "{first}");
+        assert!(!first.contains("PgUp"), "{first}");
+
+        view.handle_key_event(KeyEvent::new(KeyCode::PageDown, KeyModifiers::NONE));
+        let scrolled = render(&view);
+        assert!(scrolled.contains("more lines · PgUp"), "{scrolled}");
+        assert!(!view.is_complete());
+    }
+
+    #[test]
+    fn rapid_approvals_show_fatigue_nudge_once() {
+        let (tx, _rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx);
+        let session = ApprovalSession::default();
+        let config = TuiApprovals {
+            fatigue_threshold: Some(2),
+            ..Default::default()
+        };
+        let new_view = || {
+            ApprovalOverlay::builder(make_exec_request(), tx.clone())
+                .config(config.clone())
+                .session(session.clone())
+                .build()
+        };
+        for _ in 0..2 {
+            let mut view = new_view();
+            assert!(!render_overlay_lines(&view, 120).contains("approved a lot of requests"));
+            view.handle_key_event(KeyEvent::new(KeyCode::Char('y'), KeyModifiers::NONE));
+        }
+
+        assert!(render_overlay_lines(&new_view(), 120).contains("approved a lot of requests"));
+        assert!(!render_overlay_lines(&new_view(), 120).contains("approved a lot of requests"));
+    }
+
+    #[test]
+    fn repeated_command_shows_prior_outcome() {
+        let (tx, _rx) = unbounded_channel::<AppEvent>();
+        let session = ApprovalSession::default();
+        session.record_command_outcome(
+            vec!["echo".to_string(), "hi".to_string()],
+            PathBuf::from("/tmp"),
+            CommandOutcome::new(0, std::time::Duration::from_secs(3), "hi\n"),
+        );
+        let view = ApprovalOverlay::builder(make_exec_request(), AppEventSender::new(tx))
+            .session(session)
+            .build();
+        let rendered = render_overlay_lines(&view, 80);
+        assert!(
+            rendered.contains("Last time this ran: exit 0, 3.00s, 1 line of output"),
+            "expected prior outcome advisory: {rendered}"
+        );
+    }
+
+    fn make_ssh_request(host: &str) -> ApprovalRequest {
+        ApprovalRequest::Exec {
+            thread_id: ThreadId::new(),
+            thread_label: None,
+            id: "ssh".to_string(),
+            command: vec!["ssh".to_string(), host.to_string(), "uptime".to_string()],
+            cwd: PathBuf::from("/tmp"),
+            reason: None,
+            available_decisions: vec![ReviewDecision::Approved, ReviewDecision::Abort],
+            network_approval_context: None,
+            additional_permissions: None,
+            sandbox_policy: None,
+            env: Vec::new(),
+            duplicate_ids: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn remote_command_header_shows_target_host() {
+        let (tx, _rx) = unbounded_channel::<AppEvent>();
+        let view = ApprovalOverlay::new(
+            make_ssh_request("deploy@staging-1"),
+            AppEventSender::new(tx),
+        );
+        let rendered = render_overlay_lines(&view, 80);
+        assert!(
+            rendered.contains("Target host: staging-1"),
+            "expected target host in header: {rendered}"
+        );
+        assert!(!rendered.contains("Production"));
+        assert_eq!(view.list.selected_index(), Some(0));
+    }
+
+    #[test]
+    fn production_target_requires_explicit_selection() {
+        let (tx, mut rx) = unbounded_channel::<AppEvent>();
+        let mut view = ApprovalOverlay::new(make_ssh_request("prod-db-1"), AppEventSender::new(tx));
+        assert!(
+            render_overlay_lines(&view, 80).contains("⚠ Production host: prod-db-1"),
+            "expected production warning in header"
+        );
+        let no_idx = view
+            .options
+            .iter()
+            .position(|opt| opt.decision == ApprovalDecision::Review(ReviewDecision::Abort));
+        assert_eq!(view.list.selected_index(), no_idx);
+
+        for key in [KeyCode::Char('y'), KeyCode::Char('1')] {
+            view.handle_key_event(KeyEvent::new(key, KeyModifiers::NONE));
+        }
+        assert!(rx.try_recv().is_err());
+        assert!(!view.is_complete());
+
+        for _ in 0..no_idx.unwrap_or_default() {
+            view.handle_key_event(KeyEvent::new(KeyCode::Up, KeyModifiers::NONE));
+        }
+        view.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+        let mut decisions = Vec::new();
+        while let Ok(ev) = rx.try_recv() {
+            if let AppEvent::SubmitThreadOp {
+                op:
+                    Op::ApprovalResponse {
+                        response: ApprovalResponse { decision, .. },
+                        ..
+                    },
+                ..
+            } = ev
+            {
+                decisions.push(decision);
+            }
+        }
+        assert_eq!(decisions, vec![ReviewDecision::Approved]);
+    }
+
+    #[test]
+    fn patch_approval_previews_the_diff() {
+        let (tx, _rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx);
+        let changes = HashMap::from([(
+            PathBuf::from("/repo/src/lib.rs"),
+            FileChange::Update {
+                unified_diff: "@@ -1 +1 @@\n-let old = 1;\n+let new = 2;\n".to_string(),
+                move_path: None,
+            },
+        )]);
+        let view = ApprovalOverlay::new(
+            ApprovalRequest::ApplyPatch {
+                thread_id: ThreadId::new(),
+                thread_label: None,
+                id: "patch".to_string(),
+                reason: None,
+                cwd: PathBuf::from("/repo"),
+                changes,
+                grant_root: None,
+            },
+            tx,
+        );
+
+        let rendered = render_overlay_lines(&view, 80);
+        assert!(
+            rendered.contains("src/lib.rs"),
+            "expected file name: {rendered}"
+        );
+        assert!(
+            rendered.contains("-let old = 1;"),
+            "expected removal: {rendered}"
+        );
+        assert!(
+            rendered.contains("+let new = 2;"),
+            "expected addition: {rendered}"
+        );
+    }
+
+    #[test]
+    fn patch_touching_tests_expands_and_preselects_no() {
+        let (tx, mut rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx);
+        let changes = HashMap::from([(
+            PathBuf::from("/repo/tests/parser.rs"),
+            FileChange::Add {
+                content: "fn ok() {}\n".to_string(),
+            },
+        )]);
+        let view = ApprovalOverlay::builder(
+            ApprovalRequest::ApplyPatch {
+                thread_id: ThreadId::new(),
+                thread_label: None,
+                id: "patch".to_string(),
+                reason: None,
+                cwd: PathBuf::from("/repo"),
+                changes,
+                grant_root: None,
+            },
+            tx,
+        )
+        .config(TuiApprovals {
+            review_test_changes: Some(true),
+            ..Default::default()
+        })
+        .build();
+
+        assert_eq!(view.list.selected_index(), Some(2));
+        assert!(
+            render_overlay_lines(&view, 80).contains("⚠ Modifies tests: tests/parser.rs"),
+            "expected test warning in header"
+        );
+        assert!(matches!(
+            rx.try_recv(),
+            Ok(AppEvent::FullScreenApprovalRequest(
+                ApprovalRequest::ApplyPatch { .. }
+            ))
+        ));
+    }
+
+    #[test]
+    fn high_risk_patch_shows_a_badge_and_preselects_no() {
+        let (tx, _rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx);
+        let changes = HashMap::from([(
+            PathBuf::from("/repo/.github/workflows/ci.yml"),
+            FileChange::Add {
+                content: "on: push\n".to_string(),
+            },
+        )]);
+        let view = ApprovalOverlay::new(
+            ApprovalRequest::ApplyPatch {
+                thread_id: ThreadId::new(),
+                thread_label: None,
+                id: "patch".to_string(),
+                reason: None,
+                cwd: PathBuf::from("/repo"),
+                changes,
+                grant_root: None,
+            },
+            tx,
+        );
+
+        assert_eq!(view.list.selected_index(), Some(2));
+        assert!(
+            render_overlay_lines(&view, 80)
+                .contains("Risk: high · edits CI config (.github/workflows/ci.yml)"),
+            "expected risk badge in header"
+        );
+    }
+
+    #[test]
+    fn enter_sets_last_selected_index_without_dismissing() {
+        let (tx_raw, mut rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx_raw);
+        let mut view = ApprovalOverlay::new(make_exec_request(), tx);
+        view.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+
+        assert!(
+            view.is_complete(),
+            "exec approval should complete without queued requests"
+        );
+
+        let mut decision = None;
+        while let Ok(ev) = rx.try_recv() {
+            if let AppEvent::SubmitThreadOp {
+                op:
+                    Op::ApprovalResponse {
+                        response: ApprovalResponse { decision: d, .. },
+                        ..
+                    },
+                ..
+            } = ev
+            {
+                decision = Some(d);
+                break;
+            }
+        }
+        assert_eq!(decision, Some(ReviewDecision::Approved));
+    }
+
+    #[test]
+    fn env_overrides_are_listed_under_the_command_with_secrets_masked() {
+        let (tx, _rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx);
+        let mut request = make_exec_request();
+        if let ApprovalRequest::Exec { env, .. } = &mut request {
+            *env = vec![
+                ("PATH".to_string(), "/opt/bin:/usr/bin".to_string()),
+                (
+                    "API_KEY".to_string(),
+                    "sk-proj-4fQ8zR2mX7cV9bN1kL3wT6yH".to_string(),
+                ),
+            ];
+        }
+        let view = ApprovalOverlay::new(request, tx);
+
+        let rendered = render_overlay_lines(&view, 80);
+        assert!(
+            rendered.contains("$ echo hi\n  env: PATH=/opt/bin:/usr/bin API_KEY=••••"),
+            "{rendered}"
+        );
+        assert!(!rendered.contains("sk-proj-"));
+    }
+
+    #[test]
+    fn end_jumps_to_deny_and_home_back_to_approve() {
+        let (tx, mut rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx);
+        let mut view = ApprovalOverlay::new(
+            ApprovalRequest::Exec {
+                thread_id: ThreadId::new(),
+                thread_label: None,
+                id: "test".to_string(),
+                command: vec!["curl".to_string(), "https://example.com".to_string()],
+                cwd: PathBuf::from("/tmp"),
+                reason: None,
+                available_decisions: vec![
+                    ReviewDecision::Approved,
+                    ReviewDecision::ApprovedForSession,
+                    ReviewDecision::Abort,
+                ],
+                network_approval_context: Some(NetworkApprovalContext {
+                    host: "example.com".to_string(),
+                    protocol: NetworkApprovalProtocol::Https,
+                }),
+                additional_permissions: None,
+                sandbox_policy: None,
+                env: Vec::new(),
+                duplicate_ids: Vec::new(),
+            },
+            tx,
+        );
+
+        view.handle_key_event(KeyEvent::new(KeyCode::End, KeyModifiers::NONE));
+        assert_eq!(view.list.selected_index(), Some(2));
+        view.handle_key_event(KeyEvent::new(KeyCode::Home, KeyModifiers::NONE));
+        assert_eq!(view.list.selected_index(), Some(0));
+        view.handle_key_event(KeyEvent::new(KeyCode::End, KeyModifiers::NONE));
+        view.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+
+        let mut decisions = Vec::new();
+        while let Ok(ev) = rx.try_recv() {
+            if let AppEvent::SubmitThreadOp {
+                op: Op::ApprovalResponse { response, .. },
+                ..
+            } = ev
+            {
+                decisions.push(response.decision);
+            }
+        }
+        assert_eq!(decisions, vec![ReviewDecision::Abort]);
+    }
+
+    #[test]
+    fn choose_sends_offered_decisions_and_rejects_others() {
+        let (tx, mut rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx);
+        let mut view = ApprovalOverlay::new(make_patch_request(None, None), tx);
+
+        assert!(view.choose(ReviewDecision::DeniedForSession).is_err());
+        assert!(rx.try_recv().is_err());
+
+        assert_eq!(view.choose(ReviewDecision::ApprovedForSession), Ok(()));
+        let mut decisions = Vec::new();
+        while let Ok(ev) = rx.try_recv() {
+            if let AppEvent::SubmitThreadOp {
+                op: Op::ApprovalResponse { response, .. },
+                ..
+            } = ev
+            {
+                decisions.push(response.decision);
+            }
+        }
+        assert_eq!(decisions, vec![ReviewDecision::ApprovedForSession]);
+        assert!(view.is_complete());
+    }
+
+    #[test]
+    fn to_json_reports_the_kind_and_what_is_asked() {
+        let json = make_exec_request().to_json();
+        assert_eq!(json["kind"], "exec");
+        assert_eq!(json["id"], "test");
+        assert_eq!(json["command"], serde_json::json!(["echo", "hi"]));
+        assert_eq!(json["cwd"], "/tmp");
+        assert_eq!(json["reason"], "reason");
+
+        let json = make_patch_request(Some("tidy"), None).to_json();
+        assert_eq!(json["kind"], "apply_patch");
+        assert_eq!(json["reason"], "tidy");
+        assert!(json["changes"].is_object(), "{json}");
+    }
+
+    #[test]
+    fn recorded_patch_request_rebuilds_an_equivalent_overlay() {
+        let (tx, _rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx);
+        let request = make_patch_request(Some("tidy"), Some("/tmp"));
+        let json = request.to_json();
+        let recorded = serde_json::to_string(&json).expect("serialize request");
+
+        let replayed = ApprovalOverlay::from_recorded(
+            serde_json::from_str(&recorded).expect("parse recorded request"),
+            tx.clone(),
+        )
+        .expect("rebuild overlay");
+        let original = ApprovalOverlay::new(request, tx);
+
+        assert_eq!(
+            replayed
+                .current_request
+                .as_ref()
+                .map(ApprovalRequest::to_json),
+            Some(json)
+        );
+        assert_eq!(
+            render_overlay_lines(&replayed, 80),
+            render_overlay_lines(&original, 80)
+        );
+        assert!(ApprovalRequest::from_json(serde_json::json!({ "kind": "unknown" })).is_err());
+    }
+
+    #[test]
+    fn shift_r_toggles_the_raw_argv() {
+        let (tx, _rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx);
+        let mut view = ApprovalOverlay::new(make_exec_request(), tx);
+        let pretty_height = view.desired_height(80);
+        assert!(!render_overlay_lines(&view, 80).contains("argv[0]"));
+
+        view.handle_key_event(KeyEvent::new(KeyCode::Char('R'), KeyModifiers::SHIFT));
+        let rendered = render_overlay_lines(&view, 80);
+        assert!(rendered.contains(r#"argv[0] = "echo""#), "{rendered}");
+        assert!(rendered.contains(r#"argv[1] = "hi""#), "{rendered}");
+        assert_eq!(view.desired_height(80), pretty_height + 1);
+        assert!(!view.is_complete());
+
+        view.handle_key_event(KeyEvent::new(KeyCode::Char('R'), KeyModifiers::SHIFT));
+        assert!(!render_overlay_lines(&view, 80).contains("argv[0]"));
+        assert_eq!(view.desired_height(80), pretty_height);
+    }
+
+    #[test]
+    fn resizing_narrower_remeasures_the_prompt() {
+        let (tx, _rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx);
+        let mut request = make_exec_request();
+        if let ApprovalRequest::Exec { reason, .. } = &mut request {
+            *reason = Some("needs to list the files in the build output directory".to_string());
+        }
+        let mut view = ApprovalOverlay::new(request.clone(), tx.clone());
+        let wide = view.desired_height(100);
+
+        view.on_resize(40, 30);
+        let narrow = view.desired_height(40);
+        assert!(narrow > wide, "{narrow} <= {wide}");
+        assert_eq!(narrow, ApprovalOverlay::new(request, tx).desired_height(40));
+    }
+
+    #[test]
+    fn tab_defers_the_request_behind_the_queue() {
+        let (tx, mut rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx);
+        let mut view = ApprovalOverlay::new(make_exec_request(), tx);
+        let mut patch = make_patch_request(None, None);
+        if let ApprovalRequest::ApplyPatch { id, .. } = &mut patch {
+            *id = "patch".to_string();
+        }
+        view.enqueue_request(patch);
+        let tab = || KeyEvent::new(KeyCode::Tab, KeyModifiers::NONE);
+
+        view.handle_key_event(tab());
+        assert!(matches!(
+            view.current_request,
+            Some(ApprovalRequest::ApplyPatch { .. })
+        ));
+        assert!(!view.is_complete());
+        assert!(
+            rx.try_recv().is_err(),
+            "deferring must not answer the request"
+        );
+
+        view.handle_key_event(tab());
+        assert!(!render_overlay_lines(&view, 80).contains("Deferred"));
+        view.handle_key_event(tab());
+        view.handle_key_event(tab());
+        assert!(matches!(
+            view.current_request,
+            Some(ApprovalRequest::Exec { .. })
+        ));
+        let rendered = render_overlay_lines(&view, 80);
+        assert!(rendered.contains("Deferred 2 times"), "{rendered}");
+
+        view.handle_key_event(KeyEvent::new(KeyCode::Char('y'), KeyModifiers::NONE));
+        view.handle_key_event(tab());
+        assert!(matches!(
+            view.current_request,
+            Some(ApprovalRequest::ApplyPatch { .. })
+        ));
+    }
+
+    #[test]
+    fn shift_tab_defers_with_a_note_pinned_when_the_request_returns() {
+        let (tx, mut rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx);
+        let mut view = ApprovalOverlay::new(make_exec_request(), tx);
+        let mut patch = make_patch_request(None, None);
+        if let ApprovalRequest::ApplyPatch { id, .. } = &mut patch {
+            *id = "patch".to_string();
+        }
+        view.enqueue_request(patch);
+        let back_tab = || KeyEvent::new(KeyCode::BackTab, KeyModifiers::SHIFT);
+        let enter = || KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE);
+
+        view.handle_key_event(back_tab());
+        for c in "check this later".chars() {
+            view.handle_key_event(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE));
+        }
+        view.handle_key_event(enter());
+        assert!(matches!(
+            view.current_request,
+            Some(ApprovalRequest::ApplyPatch { .. })
+        ));
+        assert!(rx.try_recv().is_err(), "the note must not reach core");
+
+        // An empty note is left out.
+        view.handle_key_event(back_tab());
+        view.handle_key_event(enter());
+        assert!(matches!(
+            view.current_request,
+            Some(ApprovalRequest::Exec { .. })
+        ));
+        assert!(render_overlay_lines(&view, 80).contains("Your note: check this later"));
+
+        view.handle_key_event(back_tab());
+        view.handle_key_event(enter());
+        assert!(!render_overlay_lines(&view, 80).contains("Your note"));
+    }
+
+    #[test]
+    fn saving_a_macro_approves_and_registers_it_unless_the_name_is_empty() {
+        let events = |name: &str| {
+            let (tx, mut rx) = unbounded_channel::<AppEvent>();
+            let tx = AppEventSender::new(tx);
+            let mut view = ApprovalOverlay::builder(make_exec_request(), tx)
+                .config(TuiApprovals {
+                    session_macros: Some(true),
+                    ..Default::default()
+                })
+                .build();
+            view.handle_key_event(KeyEvent::new(KeyCode::Char('M'), KeyModifiers::SHIFT));
+            assert!(render_overlay_lines(&view, 80).contains("Name the macro"));
+            for c in name.chars() {
+                view.handle_key_event(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE));
+            }
+            view.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+            assert!(view.is_complete());
+            let mut registered = None;
+            let mut approved = false;
+            while let Ok(ev) = rx.try_recv() {
+                match ev {
+                    AppEvent::RegisterAutoApprove { name, signature } => {
+                        registered = Some((name, signature));
+                    }
+                    AppEvent::SubmitThreadOp {
+                        op: Op::ApprovalResponse { response, .. },
+                        ..
+                    } => approved = response.decision == ReviewDecision::Approved,
+                    _ => {}
+                }
+            }
+            assert!(approved);
+            registered
+        };
+
+        let signature = CommandSignature::of(&make_exec_request());
+        assert_eq!(
+            events("hi").map(|(name, signature)| (name, Some(signature))),
+            Some(("hi".to_string(), signature))
+        );
+        assert_eq!(events(""), None);
+    }
+
+    #[test]
+    fn active_hotkeys_list_option_keys_until_done() {
+        let (tx, _rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx);
+        let mut view = ApprovalOverlay::new(make_exec_request(), tx);
+
+        let hotkeys = view.active_hotkeys();
+        assert_eq!(
+            hotkeys.first(),
+            Some(&(
+                key_hint::plain(KeyCode::Char('y')),
+                "Yes, proceed".to_string()
+            ))
+        );
+        assert_eq!(
+            hotkeys[hotkeys.len() - 2..],
+            [
+                (key_hint::plain(KeyCode::Enter), "confirm".to_string()),
+                (key_hint::plain(KeyCode::Esc), "cancel".to_string()),
+            ]
+        );
+
+        view.handle_key_event(KeyEvent::new(KeyCode::Char('y'), KeyModifiers::NONE));
+        assert_eq!(view.active_hotkeys(), Vec::new());
+    }
+
+    #[test]
+    fn bell_rings_once_for_a_burst_of_requests() {
+        let (tx, mut rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx);
+        let mut view = ApprovalOverlay::builder(make_exec_request(), tx)
+            .config(TuiApprovals {
+                bell_on_show: Some(true),
+                ..TuiApprovals::default()
+            })
+            .build();
+        view.enqueue_request(make_patch_request(None, None));
+
+        let mut bells = 0;
+        while let Ok(ev) = rx.try_recv() {
+            if let AppEvent::Bell = ev {
+                bells += 1;
+            }
+        }
+        assert_eq!(bells, 1);
+    }
+
+    #[test]
+    fn pre_decision_hook_refusal_keeps_the_request_open() {
+        let (tx, mut rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx);
+        let mut view = ApprovalOverlay::new(make_exec_request(), tx);
+        view.set_pre_decision_hook(Box::new(|_, decision| match decision {
+            ReviewDecision::Approved => Err("blocked by org policy".to_string()),
+            _ => Ok(()),
+        }));
+        let decisions = |rx: &mut tokio::sync::mpsc::UnboundedReceiver<AppEvent>| {
+            std::iter::from_fn(|| rx.try_recv().ok())
+                .filter_map(|ev| match ev {
+                    AppEvent::SubmitThreadOp {
+                        op: Op::ApprovalResponse { response, .. },
+                        ..
+                    } => Some(response.decision),
+                    _ => None,
+                })
+                .collect::<Vec<_>>()
+        };
+
+        assert!(!view.send_decision(ReviewDecision::Approved));
+        assert!(!view.is_complete());
+        assert_eq!(decisions(&mut rx), Vec::new());
+
+        let area = Rect::new(0, 0, 80, view.desired_height(80));
+        let mut buf = Buffer::empty(area);
+        view.render(area, &mut buf);
+        let row = (0..area.height)
+            .find(|&y| {
+                (0..area.width)
+                    .map(|x| buf[(x, y)].symbol())
+                    .collect::<String>()
+                    .contains("blocked by org policy")
+            })
+            .expect("refusal shown");
+        let start = (0..area.width)
+            .find(|&x| buf[(x, row)].symbol() == "b")
+            .expect("refusal text");
+        assert_eq!(buf[(start, row)].fg, ApprovalTheme::default().warning);
+
+        assert!(view.send_decision(ReviewDecision::Denied));
+        assert_eq!(decisions(&mut rx), vec![ReviewDecision::Denied]);
     }
 }
@@ -0,0 +1,171 @@
+//! Constructing an [`ApprovalOverlay`] with anything but the defaults.
+//!
+//! `ApprovalOverlay::new(request, tx)` covers the common case; the builder
+//! sets the features, `tui.approvals` config, session memory, transcript
+//! context, theme and popup framing when they differ from their defaults.
+
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::Instant;
+
+use codex_core::config::TuiApprovals;
+use codex_core::features::Features;
+use ratatui::text::Line;
+
+use super::ApprovalOverlay;
+use super::ApprovalRequest;
+use super::decisions::ApprovalSession;
+use super::decisions::Deferrals;
+use super::decisions::Linger;
+use super::keys::EnterGuard;
+use super::keys::KeyDebounce;
+use super::keys::WarningGate;
+use super::render::ApprovalTheme;
+use super::render::Severity;
+use crate::app_event_sender::AppEventSender;
+use crate::bottom_pane::list_selection_view::ListSelectionView;
+
+/// Builder for an [`ApprovalOverlay`] showing `request`.
+///
+/// ```ignore
+/// let overlay = ApprovalOverlay::builder(request, tx)
+///     .config(approvals)
+///     .session(session)
+///     .build();
+/// ```
+pub(crate) struct ApprovalOverlayBuilder {
+    request: ApprovalRequest,
+    app_event_tx: AppEventSender,
+    features: Features,
+    config: TuiApprovals,
+    session: ApprovalSession,
+    recent_context: Vec<Line<'static>>,
+    theme: ApprovalTheme,
+    popup: bool,
+}
+
+impl ApprovalOverlayBuilder {
+    pub(crate) fn new(request: ApprovalRequest, app_event_tx: AppEventSender) -> Self {
+        Self {
+            request,
+            app_event_tx,
+            features: Features::with_defaults(),
+            config: TuiApprovals::default(),
+            session: ApprovalSession::default(),
+            recent_context: Vec::new(),
+            theme: ApprovalTheme::default(),
+            popup: false,
+        }
+    }
+
+    pub(crate) fn features(mut self, features: Features) -> Self {
+        self.features = features;
+        self
+    }
+
+    /// The `tui.approvals` settings.
+    pub(crate) fn config(mut self, config: TuiApprovals) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// What earlier prompts in this session approved or saw.
+    pub(crate) fn session(mut self, session: ApprovalSession) -> Self {
+        self.session = session;
+        self
+    }
+
+    /// The transcript lines to show dimmed above the prompt.
+    pub(crate) fn recent_context(mut self, lines: Vec<Line<'static>>) -> Self {
+        self.recent_context = lines;
+        self
+    }
+
+    pub(crate) fn theme(mut self, theme: ApprovalTheme) -> Self {
+        self.theme = theme;
+        self
+    }
+
+    /// Draw the modal as a boxed popup, with padding inside the border and
+    /// the request's kind in its top edge, for hosts that center it over the
+    /// transcript. Off by default, which draws it inline in the bottom pane.
+    pub(crate) fn popup(mut self, popup: bool) -> Self {
+        self.popup = popup;
+        self
+    }
+
+    pub(crate) fn build(self) -> ApprovalOverlay {
+        let Self {
+            request,
+            app_event_tx,
+            features,
+            config,
+            session,
+            recent_context,
+            theme,
+            popup,
+        } = self;
+        let theme = if config.high_contrast.unwrap_or(false) {
+            ApprovalTheme::high_contrast()
+        } else {
+            theme
+        };
+        let theme = if config.colorblind_safe.unwrap_or(false) {
+            theme.colorblind_safe()
+        } else {
+            theme
+        };
+        let mut view = ApprovalOverlay {
+            current_request: None,
+            queue: Vec::new(),
+            app_event_tx: app_event_tx.clone(),
+            list: ListSelectionView::new(Default::default(), app_event_tx),
+            options: Vec::new(),
+            replay_idx: None,
+            explicit_approval: false,
+            prompt: None,
+            feedback: None,
+            command_editor: None,
+            duration_picker: None,
+            file_picker: None,
+            prompt_scroll: Rc::default(),
+            full_command_shown: Rc::default(),
+            raw_command_shown: Rc::default(),
+            reason_expanded: Rc::default(),
+            recent_context,
+            deferrals: Deferrals::default(),
+            expanded: false,
+            auto_deny: Rc::default(),
+            copied: Rc::default(),
+            queue_position: Rc::default(),
+            identical: Rc::default(),
+            shown: 0,
+            abort_pending: Rc::default(),
+            enter_guard: EnterGuard::default(),
+            warning_gate: WarningGate::default(),
+            risky: false,
+            key_debounce: KeyDebounce::default(),
+            opened_at: Instant::now(),
+            issued_at: Instant::now(),
+            current_complete: false,
+            timed_out: false,
+            show_all_descriptions: false,
+            done: false,
+            linger: Linger::default(),
+            last_decision: RefCell::new(None),
+            answered_ids: RefCell::default(),
+            severity: Severity::default(),
+            pre_decision_hook: None,
+            vetoed: None,
+            focused: true,
+            features,
+            config,
+            session,
+            theme,
+            popup,
+        };
+        view.ring_bell_if_due();
+        view.set_current(request);
+        view
+    }
+}
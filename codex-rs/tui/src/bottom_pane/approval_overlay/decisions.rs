@@ -0,0 +1,2234 @@
+//! Answering requests and what happens around an answer.
+//!
+//! Sends the chosen decision to core and records it for the session, and
+//! holds everything that can answer or shape an answer besides the user
+//! picking an option: auto-deny, policy programs, blocked commands, session
+//! macros, merged repeats, deferral and the audit log.
+
+use super::ApprovalOverlay;
+use super::ApprovalRequest;
+use super::PreDecisionHook;
+use super::inputs::CommandEditor;
+use super::inputs::FeedbackKind;
+use super::keys::patch_diff;
+use super::keys::shell_quoted;
+use super::options::ApprovalDecision;
+use super::prompt::bounded_argv;
+use super::prompt::redact_secrets;
+use super::prompt::summarize_patch;
+use super::render::ApprovalTheme;
+use super::risk::CommandOutcome;
+use crate::app_event::AppEvent;
+use crate::app_event::ApprovalKind;
+use crate::app_event_sender::AppEventSender;
+use crate::exec_command::strip_bash_lc_and_escape;
+use crate::history_cell;
+use chrono::DateTime;
+use chrono::SecondsFormat;
+use chrono::Utc;
+use codex_core::config::DecisionLogLevel;
+use codex_core::config::TuiApprovals;
+use codex_protocol::ThreadId;
+use codex_protocol::mcp::RequestId;
+use codex_protocol::models::PermissionProfile;
+use codex_protocol::protocol::ApprovalOverrides;
+use codex_protocol::protocol::ApprovalResponse;
+use codex_protocol::protocol::ElicitationAction;
+use codex_protocol::protocol::NetworkPolicyRuleAction;
+use codex_protocol::protocol::Op;
+use codex_protocol::protocol::ReviewDecision;
+use codex_protocol::request_permissions::PermissionGrantScope;
+use ratatui::style::Stylize;
+use ratatui::text::Line;
+use serde::Serialize;
+use serde_json::json;
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::collections::VecDeque;
+use std::io::Read;
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
+use std::process::Command;
+use std::process::Stdio;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::mpsc;
+use std::time::Duration;
+use std::time::Instant;
+use wildmatch::WildMatch;
+
+impl ApprovalOverlay {
+    /// Answer with `decision` as if its option had been picked, for tests
+    /// that drive the prompt without key events.
+    #[cfg(test)]
+    pub(crate) fn choose(&mut self, decision: ReviewDecision) -> Result<(), String> {
+        let wanted = ApprovalDecision::Review(decision.clone());
+        let Some(idx) = self.options.iter().position(|opt| opt.decision == wanted) else {
+            return Err(format!("{decision:?} is not offered for this request"));
+        };
+        self.apply_selection(idx);
+        Ok(())
+    }
+
+    pub(crate) fn apply_selection(&mut self, actual_idx: usize) {
+        if self.current_complete {
+            return;
+        }
+        let Some(option) = self.options.get(actual_idx) else {
+            return;
+        };
+        if option.decision == ApprovalDecision::Explain {
+            self.request_explanation();
+            return;
+        }
+        if option.decision == ApprovalDecision::EditCommand {
+            self.open_command_editor();
+            return;
+        }
+        if option.decision == ApprovalDecision::DenyWithFeedback {
+            self.open_feedback_input();
+            return;
+        }
+        if option.decision == ApprovalDecision::DenyToInvestigate {
+            self.open_investigate_input();
+            return;
+        }
+        if let ApprovalDecision::PickDuration(offered) = option.decision {
+            self.open_duration_picker(offered);
+            return;
+        }
+        if option.decision == ApprovalDecision::ApproveAsMacro {
+            self.open_macro_name_input();
+            return;
+        }
+        if option.decision == ApprovalDecision::PickFiles {
+            self.open_file_picker();
+            return;
+        }
+        let review = match &option.decision {
+            ApprovalDecision::Review(decision) => Some(decision.clone()),
+            ApprovalDecision::ApproveVerbose(_) | ApprovalDecision::ApproveWithAssertions(_) => {
+                Some(ReviewDecision::Approved)
+            }
+            ApprovalDecision::Explain
+            | ApprovalDecision::EditCommand
+            | ApprovalDecision::DenyWithFeedback
+            | ApprovalDecision::DenyToInvestigate
+            | ApprovalDecision::PickDuration(_)
+            | ApprovalDecision::ApproveAsMacro
+            | ApprovalDecision::PickFiles
+            | ApprovalDecision::McpElicitation(_) => None,
+        };
+        if let Some(decision) = review
+            && !self.hook_allows(&decision)
+        {
+            return;
+        }
+        let Some(option) = self.options.get(actual_idx) else {
+            return;
+        };
+        if let Some(request) = self.current_request.as_ref() {
+            self.session.record_decision(request, &option.decision);
+            match (request, &option.decision) {
+                (ApprovalRequest::Exec { id, command, .. }, ApprovalDecision::Review(decision)) => {
+                    self.handle_exec_decision(id, command, ApprovalResponse::new(decision.clone()));
+                }
+                (ApprovalRequest::Exec { id, .. }, ApprovalDecision::ApproveVerbose(command)) => {
+                    let response = ApprovalResponse {
+                        overrides: ApprovalOverrides {
+                            command: Some(command.clone()),
+                            ..ApprovalOverrides::default()
+                        },
+                        ..ApprovalResponse::new(ReviewDecision::Approved)
+                    };
+                    self.handle_exec_decision(id, command, response);
+                }
+                (
+                    ApprovalRequest::Exec { id, command, .. },
+                    ApprovalDecision::ApproveWithAssertions(assertions),
+                ) => {
+                    let response = ApprovalResponse {
+                        assertions: assertions.clone(),
+                        ..ApprovalResponse::new(ReviewDecision::Approved)
+                    };
+                    self.handle_exec_decision(id, command, response);
+                }
+                (
+                    ApprovalRequest::Permissions {
+                        call_id,
+                        permissions,
+                        ..
+                    },
+                    ApprovalDecision::Review(decision),
+                ) => self.handle_permissions_decision(call_id, permissions, decision.clone()),
+                (ApprovalRequest::ApplyPatch { id, .. }, ApprovalDecision::Review(decision)) => {
+                    self.handle_patch_decision(id, ApprovalResponse::new(decision.clone()));
+                }
+                (
+                    ApprovalRequest::McpElicitation {
+                        server_name,
+                        request_id,
+                        ..
+                    },
+                    ApprovalDecision::McpElicitation(decision),
+                ) => {
+                    self.handle_elicitation_decision(server_name, request_id, *decision);
+                }
+                _ => {}
+            }
+        }
+
+        self.current_complete = true;
+        self.advance_queue();
+    }
+
+    /// Ask the agent to explain the current command. The prompt stays open so
+    /// the user can still decide once the explanation arrives.
+    fn request_explanation(&self) {
+        let Some(request) = self.current_request.as_ref() else {
+            return;
+        };
+        if let ApprovalRequest::Exec { id, .. } = request {
+            self.app_event_tx.send(AppEvent::SubmitThreadOp {
+                thread_id: request.thread_id(),
+                op: Op::RequestExplanation { id: id.clone() },
+            });
+        }
+    }
+
+    /// Whether another request is waiting behind the current, unanswered one.
+    pub(crate) fn can_defer(&self) -> bool {
+        !self.queue.is_empty() && !self.current_complete
+    }
+
+    /// Approve the files checked in the picker and skip the rest of the
+    /// patch; with none checked, decline it instead.
+    pub(crate) fn approve_picked_files(&mut self) {
+        let Some(picker) = self.file_picker.take() else {
+            return;
+        };
+        let included_files = picker.included_files();
+        if included_files.as_ref().is_some_and(Vec::is_empty) {
+            self.deny_current(None);
+            return;
+        }
+        if !self.hook_allows(&ReviewDecision::Approved) {
+            return;
+        }
+        let Some(request @ ApprovalRequest::ApplyPatch { id, .. }) = self.current_request.as_ref()
+        else {
+            return;
+        };
+        self.session
+            .record_decision(request, &ApprovalDecision::PickFiles);
+        let response = ApprovalResponse {
+            overrides: ApprovalOverrides {
+                included_files,
+                ..ApprovalOverrides::default()
+            },
+            ..ApprovalResponse::new(ReviewDecision::Approved)
+        };
+        self.handle_patch_decision(id, response);
+        self.current_complete = true;
+        self.advance_queue();
+    }
+
+    /// Approve the current exec request until `duration` has passed.
+    pub(crate) fn approve_for_duration(&mut self, duration: Duration) {
+        self.duration_picker = None;
+        let decision = ReviewDecision::ApprovedForDuration { duration };
+        if !self.hook_allows(&decision) {
+            return;
+        }
+        let Some(request) = self.current_request.as_ref() else {
+            return;
+        };
+        let ApprovalRequest::Exec { id, command, .. } = request else {
+            return;
+        };
+        self.session
+            .record_decision(request, &ApprovalDecision::Review(decision.clone()));
+        self.handle_exec_decision(id, command, ApprovalResponse::new(decision));
+        self.current_complete = true;
+        self.advance_queue();
+    }
+
+    /// Approve the current exec request with the command from the editor.
+    /// Text that does not parse keeps the editor open with an error hint.
+    pub(crate) fn submit_edited_command(&mut self) {
+        let Some(edited) = self.command_editor.as_mut().and_then(CommandEditor::submit) else {
+            return;
+        };
+        self.command_editor = None;
+        if !self.hook_allows(&ReviewDecision::Approved) {
+            return;
+        }
+        let Some(request) = self.current_request.as_ref() else {
+            return;
+        };
+        let ApprovalRequest::Exec { id, command, .. } = request else {
+            return;
+        };
+        let approved = ApprovalDecision::Review(ReviewDecision::Approved);
+        self.session.record_decision(request, &approved);
+        let response = if edited == *command {
+            ApprovalResponse::new(ReviewDecision::Approved)
+        } else {
+            ApprovalResponse {
+                overrides: ApprovalOverrides {
+                    command: Some(edited.clone()),
+                    ..ApprovalOverrides::default()
+                },
+                ..ApprovalResponse::new(ReviewDecision::Approved)
+            }
+        };
+        self.handle_exec_decision(id, &edited, response);
+        self.current_complete = true;
+        self.advance_queue();
+    }
+
+    pub(crate) fn handle_exec_decision(
+        &self,
+        id: &str,
+        command: &[String],
+        response: ApprovalResponse,
+    ) {
+        let Some(request) = self.current_request.as_ref() else {
+            return;
+        };
+        if !self.mark_answered(request.thread_id(), id) {
+            return;
+        }
+        self.send_audit_record(&response);
+        if request.thread_label().is_none() {
+            let command = bounded_argv(command).into_owned();
+            let decision = response.decision.clone();
+            let feedback = response.feedback.clone();
+            let cell = match self.config.log_decisions.unwrap_or_default() {
+                DecisionLogLevel::Full => Some(history_cell::new_approval_decision_cell(
+                    command, decision, feedback,
+                )),
+                DecisionLogLevel::Compact => Some(
+                    history_cell::new_compact_approval_decision_cell(command, decision, feedback),
+                ),
+                DecisionLogLevel::None => None,
+            };
+            if let Some(cell) = cell {
+                self.app_event_tx.send(AppEvent::InsertHistoryCell(cell));
+            }
+        }
+        if self.config.copy_on_approve.unwrap_or(false)
+            && ApprovalDecision::Review(response.decision.clone()).is_approval()
+            && let Some(text) = shell_quoted(command)
+        {
+            self.app_event_tx.send(AppEvent::CopyToClipboard(text));
+        }
+        self.send_resolved(id, ApprovalKind::Exec, &response);
+        let thread_id = request.thread_id();
+        if let ApprovalRequest::Exec { duplicate_ids, .. } = request {
+            for duplicate_id in duplicate_ids {
+                self.app_event_tx.send(AppEvent::SubmitThreadOp {
+                    thread_id,
+                    op: Op::ApprovalResponse {
+                        id: duplicate_id.clone(),
+                        turn_id: None,
+                        response: response.clone(),
+                    },
+                });
+            }
+        }
+        self.app_event_tx.send(AppEvent::SubmitThreadOp {
+            thread_id,
+            op: Op::ApprovalResponse {
+                id: id.to_string(),
+                turn_id: None,
+                response,
+            },
+        });
+    }
+
+    pub(crate) fn handle_permissions_decision(
+        &self,
+        call_id: &str,
+        permissions: &PermissionProfile,
+        decision: ReviewDecision,
+    ) {
+        let Some(request) = self.current_request.as_ref() else {
+            return;
+        };
+        if !self.mark_answered(request.thread_id(), call_id) {
+            return;
+        }
+        self.send_audit_record(&ApprovalResponse::new(decision.clone()));
+        self.app_event_tx
+            .send(AppEvent::Announce(decision_announcement(
+                request, &decision,
+            )));
+        self.last_decision.replace(Some(decision.clone()));
+        let granted_permissions = match decision {
+            ReviewDecision::Approved | ReviewDecision::ApprovedForSession => permissions.clone(),
+            ReviewDecision::Denied | ReviewDecision::Abort => Default::default(),
+            ReviewDecision::ApprovedExecpolicyAmendment { .. }
+            | ReviewDecision::DeniedForSession
+            | ReviewDecision::ApprovedForDirectory
+            | ReviewDecision::ApprovedForDuration { .. }
+            | ReviewDecision::ApprovedAlways
+            | ReviewDecision::ApprovedSandboxed
+            | ReviewDecision::DryRun
+            | ReviewDecision::NetworkPolicyAmendment { .. } => Default::default(),
+        };
+        let scope = if matches!(decision, ReviewDecision::ApprovedForSession) {
+            PermissionGrantScope::Session
+        } else {
+            PermissionGrantScope::Turn
+        };
+        if request.thread_label().is_none()
+            && self.config.log_decisions != Some(DecisionLogLevel::None)
+        {
+            let message = if granted_permissions.is_empty() {
+                "You did not grant additional permissions"
+            } else if matches!(scope, PermissionGrantScope::Session) {
+                "You granted additional permissions for this session"
+            } else {
+                "You granted additional permissions"
+            };
+            self.app_event_tx.send(AppEvent::InsertHistoryCell(Box::new(
+                crate::history_cell::PlainHistoryCell::new(vec![message.into()]),
+            )));
+        }
+        let thread_id = request.thread_id();
+        self.app_event_tx.send(AppEvent::SubmitThreadOp {
+            thread_id,
+            op: Op::RequestPermissionsResponse {
+                id: call_id.to_string(),
+                response: codex_protocol::request_permissions::RequestPermissionsResponse {
+                    permissions: granted_permissions,
+                    scope,
+                },
+            },
+        });
+    }
+
+    pub(crate) fn handle_patch_decision(&self, id: &str, response: ApprovalResponse) {
+        let Some(thread_id) = self
+            .current_request
+            .as_ref()
+            .map(ApprovalRequest::thread_id)
+        else {
+            return;
+        };
+        if !self.mark_answered(thread_id, id) {
+            return;
+        }
+        self.send_audit_record(&response);
+        self.send_resolved(id, ApprovalKind::ApplyPatch, &response);
+        self.app_event_tx.send(AppEvent::SubmitThreadOp {
+            thread_id,
+            op: Op::ApprovalResponse {
+                id: id.to_string(),
+                turn_id: None,
+                response,
+            },
+        });
+    }
+
+    /// Record that a decision for `id` in `thread_id` is being sent. Returns
+    /// `false`, after logging, when one already was.
+    fn mark_answered(&self, thread_id: ThreadId, id: &str) -> bool {
+        if self
+            .answered_ids
+            .borrow_mut()
+            .insert((thread_id, id.to_string()))
+        {
+            return true;
+        }
+        tracing::warn!("dropping a second decision for approval request {id}");
+        false
+    }
+
+    /// With `audit_log` set, send the audit record of answering the current
+    /// request with `response`.
+    fn send_audit_record(&self, response: &ApprovalResponse) {
+        if self.config.audit_log.is_none() {
+            return;
+        }
+        let Some(request) = self.current_request.as_ref() else {
+            return;
+        };
+        if let Some(record) = audit_record(request, response, Utc::now(), audit_user()) {
+            self.app_event_tx.send(AppEvent::AuditRecord(record));
+        }
+    }
+
+    fn send_resolved(&self, id: &str, kind: ApprovalKind, response: &ApprovalResponse) {
+        self.last_decision.replace(Some(response.decision.clone()));
+        if let Some(request) = self.current_request.as_ref() {
+            self.app_event_tx
+                .send(AppEvent::Announce(decision_announcement(
+                    request,
+                    &response.decision,
+                )));
+        }
+        let decided_at = Instant::now();
+        self.app_event_tx.send(AppEvent::ApprovalResolved {
+            id: id.to_string(),
+            kind,
+            decision: response.decision.clone(),
+            had_feedback: response.feedback.is_some(),
+            elapsed: decided_at.saturating_duration_since(self.opened_at),
+        });
+    }
+
+    pub(crate) fn handle_elicitation_decision(
+        &self,
+        server_name: &str,
+        request_id: &RequestId,
+        decision: ElicitationAction,
+    ) {
+        let Some(thread_id) = self
+            .current_request
+            .as_ref()
+            .map(ApprovalRequest::thread_id)
+        else {
+            return;
+        };
+        self.app_event_tx.send(AppEvent::SubmitThreadOp {
+            thread_id,
+            op: Op::ResolveElicitation {
+                server_name: server_name.to_string(),
+                request_id: request_id.clone(),
+                decision,
+                content: None,
+                meta: None,
+            },
+        });
+        self.app_event_tx
+            .send(AppEvent::Announce(elicitation_announcement(decision)));
+        self.last_decision.replace(Some(match decision {
+            ElicitationAction::Accept => ReviewDecision::Approved,
+            ElicitationAction::Decline => ReviewDecision::Denied,
+            ElicitationAction::Cancel => ReviewDecision::Abort,
+        }));
+    }
+
+    /// Act on the typed text: decline with it as feedback, approve the patch
+    /// with it as a note, defer with it as a note, or save the macro under it.
+    pub(crate) fn submit_feedback(&mut self) {
+        if let Some(input) = self.feedback.take() {
+            match input.kind() {
+                FeedbackKind::Deny => self.deny_current(input.feedback()),
+                FeedbackKind::Approve => self.approve_patch_with_note(input.feedback()),
+                FeedbackKind::Defer => {
+                    self.defer_current(input.feedback());
+                }
+                FeedbackKind::NameMacro => self.approve_as_macro(input.feedback()),
+            }
+        }
+    }
+
+    /// Approve the current command and, when `name` is given, ask the app to
+    /// approve identical commands under that name for the rest of the session.
+    fn approve_as_macro(&mut self, name: Option<String>) {
+        if !self.hook_allows(&ReviewDecision::Approved) {
+            return;
+        }
+        let Some(request @ ApprovalRequest::Exec { id, command, .. }) =
+            self.current_request.as_ref()
+        else {
+            return;
+        };
+        self.session
+            .record_decision(request, &ApprovalDecision::ApproveAsMacro);
+        if let Some(name) = name
+            && let Some(signature) = CommandSignature::of(request)
+        {
+            self.app_event_tx
+                .send(AppEvent::RegisterAutoApprove { name, signature });
+        }
+        self.handle_exec_decision(id, command, ApprovalResponse::new(ReviewDecision::Approved));
+        self.current_complete = true;
+        self.advance_queue();
+    }
+
+    fn approve_patch_with_note(&mut self, note: Option<String>) {
+        if !self.hook_allows(&ReviewDecision::Approved) {
+            return;
+        }
+        let Some(request @ ApprovalRequest::ApplyPatch { id, .. }) = self.current_request.as_ref()
+        else {
+            return;
+        };
+        self.session
+            .record_decision(request, &ApprovalDecision::Review(ReviewDecision::Approved));
+        let response = ApprovalResponse {
+            feedback: note,
+            ..ApprovalResponse::new(ReviewDecision::Approved)
+        };
+        self.handle_patch_decision(id, response);
+        self.current_complete = true;
+        self.advance_queue();
+    }
+
+    /// Decline the current request and move on to the next one.
+    fn deny_current(&mut self, feedback: Option<String>) {
+        if !self.hook_allows(&ReviewDecision::Denied) {
+            return;
+        }
+        let Some(request) = self.current_request.as_ref() else {
+            return;
+        };
+        self.session
+            .record_decision(request, &ApprovalDecision::Review(ReviewDecision::Denied));
+        let response = ApprovalResponse {
+            feedback,
+            ..ApprovalResponse::new(ReviewDecision::Denied)
+        };
+        match request {
+            ApprovalRequest::Exec { id, command, .. } => {
+                self.handle_exec_decision(id, command, response);
+            }
+            ApprovalRequest::ApplyPatch { id, .. } => self.handle_patch_decision(id, response),
+            ApprovalRequest::Permissions {
+                call_id,
+                permissions,
+                ..
+            } => self.handle_permissions_decision(call_id, permissions, ReviewDecision::Denied),
+            ApprovalRequest::McpElicitation {
+                server_name,
+                request_id,
+                ..
+            } => {
+                self.handle_elicitation_decision(
+                    server_name,
+                    request_id,
+                    ElicitationAction::Decline,
+                );
+            }
+        }
+        self.current_complete = true;
+        self.advance_queue();
+    }
+
+    /// Decline the current request once its timer runs out. Returns when the
+    /// countdown next needs a redraw.
+    pub(crate) fn tick_auto_deny(&mut self) -> Option<Duration> {
+        let remaining = self.auto_deny.remaining()?;
+        if !remaining.is_zero() {
+            return Some(until_next_second(remaining));
+        }
+        self.auto_deny.cancel();
+        if !self.current_complete {
+            self.feedback = None;
+            self.timed_out = true;
+            self.deny_current(None);
+        }
+        // The next queued request starts its own timer.
+        self.auto_deny.remaining().map(until_next_second)
+    }
+
+    /// Resolve the current exec or patch request with `decision` and move on.
+    /// Returns `false`, leaving the request open, when a review decision does
+    /// not answer it or it needs a deliberate selection.
+    pub(crate) fn send_decision(&mut self, decision: ReviewDecision) -> bool {
+        if self.current_complete || self.explicit_approval || !self.hook_allows(&decision) {
+            return false;
+        }
+        let Some(request) = self.current_request.as_ref() else {
+            return false;
+        };
+        let key = (request.thread_id(), request.id());
+        if self.answered_ids.borrow().contains(&key) {
+            tracing::warn!("approval request {} was already answered", key.1);
+            return false;
+        }
+        let response = ApprovalResponse::new(decision.clone());
+        match request {
+            ApprovalRequest::Exec { id, command, .. } => {
+                self.session
+                    .record_decision(request, &ApprovalDecision::Review(decision));
+                self.handle_exec_decision(id, command, response);
+            }
+            ApprovalRequest::ApplyPatch { id, .. } => {
+                self.session
+                    .record_decision(request, &ApprovalDecision::Review(decision));
+                self.handle_patch_decision(id, response);
+            }
+            ApprovalRequest::Permissions { .. } | ApprovalRequest::McpElicitation { .. } => {
+                return false;
+            }
+        }
+        self.current_complete = true;
+        self.advance_queue();
+        true
+    }
+
+    /// Consult `hook` before each decision is sent. When it returns an error
+    /// the decision is dropped, the error is shown under the options, and the
+    /// request stays open for another answer. Ctrl+C still aborts without
+    /// asking, so a hook cannot trap the user in the modal.
+    pub(crate) fn set_pre_decision_hook(&mut self, hook: PreDecisionHook) {
+        self.pre_decision_hook = Some(hook);
+    }
+
+    /// Whether the pre-decision hook lets the current request be answered
+    /// with `decision`, showing its reason when it does not.
+    fn hook_allows(&mut self, decision: &ReviewDecision) -> bool {
+        let verdict = match (&self.pre_decision_hook, &self.current_request) {
+            (Some(hook), Some(request)) => hook(request, decision.clone()),
+            (None, _) | (_, None) => Ok(()),
+        };
+        self.vetoed = verdict.err();
+        if self.vetoed.is_none() {
+            return true;
+        }
+        self.list.set_footer_note(self.veto_note());
+        false
+    }
+
+    pub(crate) fn veto_note(&self) -> Option<Line<'static>> {
+        self.vetoed
+            .as_ref()
+            .map(|message| Line::from(message.clone().fg(self.theme.warning)))
+    }
+
+    /// Tell screen-reader bridges which request is now on screen. The parent
+    /// calls this when it shows the modal; later requests announce themselves.
+    pub(crate) fn announce(&self) {
+        if let Some(request) = self.current_request.as_ref() {
+            self.app_event_tx
+                .send(AppEvent::Announce(open_announcement(request)));
+        }
+    }
+
+    /// Move the current request behind every queued one without answering
+    /// it, keeping `note` for when it comes back. Returns `false` when nothing
+    /// else is waiting.
+    pub(crate) fn defer_current(&mut self, note: Option<String>) -> bool {
+        if !self.can_defer() {
+            return false;
+        }
+        let Some(request) = self.current_request.take() else {
+            return false;
+        };
+        self.deferrals.record(&request);
+        if let Some(note) = note {
+            self.deferrals.set_note(&request, &note);
+        }
+        // The queue is taken from its end, so the front is answered last.
+        self.queue.insert(0, request);
+        // It comes back later under a new position, not as an extra request.
+        self.shown = self.shown.saturating_sub(1);
+        self.advance_queue();
+        true
+    }
+}
+
+// Audit records of approval decisions.
+//
+// With `tui.approvals.audit_log` set, every exec, patch and permissions
+// decision also sends [`AppEvent::AuditRecord`], which the app appends to
+// that file as one JSON line. A record looks like:
+//
+// ```json
+// {
+//   "timestamp": "2026-10-14T09:30:00Z",
+//   "session_id": "<thread id>",
+//   "request_id": "call-1",
+//   "kind": "exec",
+//   "command": "cargo publish --token ••••",
+//   "patch_summary": null,
+//   "decision": "approved",
+//   "feedback": null,
+//   "user": "alice"
+// }
+// ```
+//
+// `kind` is `exec`, `patch` or `permissions`. `command` is set for exec
+// requests, after any edit the user made, and `patch_summary` for patches,
+// as `<files> files, +<added> -<removed>`. `decision` is the
+// [`ReviewDecision`] as sent to the agent. The command and feedback are
+// redacted the way the prompt shows them.
+//
+// [`AppEvent::AuditRecord`]: crate::app_event::AppEvent::AuditRecord
+
+/// The audit record of answering `request` with `response` at `timestamp`.
+/// Elicitations have none: they are not approval decisions.
+pub(crate) fn audit_record(
+    request: &ApprovalRequest,
+    response: &ApprovalResponse,
+    timestamp: DateTime<Utc>,
+    user: Option<String>,
+) -> Option<serde_json::Value> {
+    let (kind, command, patch_summary) = match request {
+        ApprovalRequest::Exec { command, .. } => {
+            let command = response.overrides.command.as_ref().unwrap_or(command);
+            let command = redact_secrets(&strip_bash_lc_and_escape(command));
+            ("exec", Some(command), None)
+        }
+        ApprovalRequest::ApplyPatch { cwd, changes, .. } => {
+            let summary = summarize_patch(&patch_diff(cwd, changes));
+            let added: usize = summary.files.iter().map(|file| file.added).sum();
+            let removed: usize = summary.files.iter().map(|file| file.removed).sum();
+            let files = summary.files.len();
+            (
+                "patch",
+                None,
+                Some(format!("{files} files, +{added} -{removed}")),
+            )
+        }
+        ApprovalRequest::Permissions { .. } => ("permissions", None, None),
+        ApprovalRequest::McpElicitation { .. } => return None,
+    };
+    Some(json!({
+        "timestamp": timestamp.to_rfc3339_opts(SecondsFormat::Secs, true),
+        "session_id": request.thread_id().to_string(),
+        "request_id": request.id(),
+        "kind": kind,
+        "command": command,
+        "patch_summary": patch_summary,
+        "decision": decision_json(&response.decision),
+        "feedback": response.feedback.as_deref().map(redact_secrets),
+        "user": user,
+    }))
+}
+
+/// Who is at the keyboard, as far as the environment says.
+pub(crate) fn audit_user() -> Option<String> {
+    std::env::var("USER")
+        .or_else(|_| std::env::var("USERNAME"))
+        .ok()
+        .filter(|user| !user.is_empty())
+}
+
+fn decision_json(decision: &ReviewDecision) -> serde_json::Value {
+    serde_json::to_value(decision).unwrap_or_else(|_| json!(decision.to_string()))
+}
+
+// Optional timeout that declines an unattended approval request.
+//
+// The overlay starts an [`AutoDeny`] for each request when
+// `tui.approvals.auto_deny_secs` is set, and the bottom pane ticks it before
+// every draw. The prompt title shares the timer to show the seconds left.
+// Any key press or paste cancels the timer.
+
+#[derive(Default)]
+pub(crate) struct AutoDeny {
+    deadline: Cell<Option<Instant>>,
+}
+
+impl AutoDeny {
+    pub(crate) fn start(timeout: Duration) -> Self {
+        Self {
+            deadline: Cell::new(Some(Instant::now() + timeout)),
+        }
+    }
+
+    pub(crate) fn cancel(&self) {
+        self.deadline.set(None);
+    }
+
+    /// Time left before the request is declined, or `None` when no timer runs.
+    pub(crate) fn remaining(&self) -> Option<Duration> {
+        self.deadline
+            .get()
+            .map(|deadline| deadline.saturating_duration_since(Instant::now()))
+    }
+}
+
+/// Whole seconds shown for `remaining`, rounded up so `0s` never appears.
+pub(crate) fn display_secs(remaining: Duration) -> u64 {
+    remaining.as_secs() + u64::from(remaining.subsec_nanos() > 0)
+}
+
+/// Time until the displayed seconds change.
+pub(crate) fn until_next_second(remaining: Duration) -> Duration {
+    remaining.saturating_sub(Duration::from_secs(
+        display_secs(remaining).saturating_sub(1),
+    ))
+}
+
+// Folding repeats of the same command into one prompt.
+//
+// An agent retrying a command can ask to run it several times in a row.
+// Consecutive exec requests from the same thread with the same argv and cwd
+// become a single request that remembers the other ids, so one decision
+// answers all of them.
+
+/// Merge runs of identical exec requests; anything else stays separate.
+pub(crate) fn coalesce_requests(reqs: Vec<ApprovalRequest>) -> Vec<ApprovalRequest> {
+    let mut coalesced: Vec<ApprovalRequest> = Vec::with_capacity(reqs.len());
+    for req in reqs {
+        let req = match coalesced.last_mut() {
+            Some(last) => merge_identical(last, req),
+            None => Some(req),
+        };
+        coalesced.extend(req);
+    }
+    coalesced
+}
+
+/// Fold `req` into `into` when both run the same command in the same place.
+/// Returns `req` untouched otherwise.
+pub(crate) fn merge_identical(
+    into: &mut ApprovalRequest,
+    req: ApprovalRequest,
+) -> Option<ApprovalRequest> {
+    let ApprovalRequest::Exec {
+        thread_id,
+        command,
+        cwd,
+        network_approval_context: None,
+        duplicate_ids,
+        ..
+    } = into
+    else {
+        return Some(req);
+    };
+    match req {
+        ApprovalRequest::Exec {
+            thread_id: other_thread_id,
+            id,
+            command: other_command,
+            cwd: other_cwd,
+            network_approval_context: None,
+            duplicate_ids: other_duplicate_ids,
+            ..
+        } if other_thread_id == *thread_id && other_command == *command && other_cwd == *cwd => {
+            duplicate_ids.push(id);
+            duplicate_ids.extend(other_duplicate_ids);
+            None
+        }
+        ApprovalRequest::Exec { .. }
+        | ApprovalRequest::Permissions { .. }
+        | ApprovalRequest::ApplyPatch { .. }
+        | ApprovalRequest::McpElicitation { .. } => Some(req),
+    }
+}
+
+/// How many requests a decision on `request` answers.
+pub(crate) fn request_count(request: &ApprovalRequest) -> usize {
+    match request {
+        ApprovalRequest::Exec { duplicate_ids, .. } => duplicate_ids.len() + 1,
+        ApprovalRequest::Permissions { .. }
+        | ApprovalRequest::ApplyPatch { .. }
+        | ApprovalRequest::McpElicitation { .. } => 1,
+    }
+}
+
+// Putting the current request off until the queued ones are answered.
+//
+// Tab moves the request behind everything queued without answering it, so
+// the agent keeps waiting. A request put off [`STILL_WAITING_AFTER`] times
+// or more says so when it comes back, so it is not pushed back by habit.
+//
+// Shift+Tab puts it off with a one-line note to yourself, such as "check
+// this later", pinned dimmed to the prompt when the request comes back. The
+// note is never sent to core.
+
+/// Deferrals after which the prompt notes that the request is still waiting.
+pub(crate) const STILL_WAITING_AFTER: u32 = 2;
+
+/// How often each request was put off, and the note left on it, by
+/// [`request_key`].
+#[derive(Default)]
+pub(crate) struct Deferrals {
+    counts: HashMap<String, u32>,
+    notes: HashMap<String, String>,
+}
+
+impl Deferrals {
+    pub(crate) fn record(&mut self, request: &ApprovalRequest) {
+        *self.counts.entry(request_key(request)).or_default() += 1;
+    }
+
+    /// Keep `note` for when `request` comes back, on one line. A blank note
+    /// leaves any earlier one in place.
+    pub(crate) fn set_note(&mut self, request: &ApprovalRequest, note: &str) {
+        let note = note.split_whitespace().collect::<Vec<_>>().join(" ");
+        if !note.is_empty() {
+            self.notes.insert(request_key(request), note);
+        }
+    }
+
+    /// The note left on `request` when it was put off.
+    pub(crate) fn note_line(&self, request: &ApprovalRequest) -> Option<Line<'static>> {
+        let note = self.notes.get(&request_key(request))?;
+        Some(Line::from(vec!["Your note: ".bold(), note.clone().into()]).dim())
+    }
+
+    /// The note for a request deferred often enough to deserve one.
+    pub(crate) fn still_waiting_line(
+        &self,
+        request: &ApprovalRequest,
+        theme: &ApprovalTheme,
+    ) -> Option<Line<'static>> {
+        let times = self.counts.get(&request_key(request)).copied()?;
+        (times >= STILL_WAITING_AFTER).then(|| {
+            Line::from(vec![
+                format!("Deferred {times} times").fg(theme.accent).bold(),
+                " · codex is still waiting for your decision".dim(),
+            ])
+        })
+    }
+}
+
+fn request_key(request: &ApprovalRequest) -> String {
+    match request {
+        ApprovalRequest::Exec { id, .. } | ApprovalRequest::ApplyPatch { id, .. } => id.clone(),
+        ApprovalRequest::Permissions { call_id, .. } => call_id.clone(),
+        ApprovalRequest::McpElicitation {
+            server_name,
+            request_id,
+            ..
+        } => format!("{server_name}/{request_id}"),
+    }
+}
+
+// A moment of confirmation after the last answer.
+//
+// With `tui.approvals.linger_ms`, the modal does not vanish the instant the
+// answer goes out: it shows `→ sent: Approved, waiting…` for that long, so
+// the key press visibly registered while the agent gets going. The response
+// is sent before the note appears; only the modal's removal waits.
+
+#[derive(Default)]
+pub(crate) struct Linger {
+    until: Cell<Option<Instant>>,
+}
+
+impl Linger {
+    pub(crate) fn start(duration: Duration) -> Self {
+        Self {
+            until: Cell::new(Some(Instant::now() + duration)),
+        }
+    }
+
+    /// Time until the modal may close, or `None` once it may.
+    pub(crate) fn remaining(&self) -> Option<Duration> {
+        let remaining = self.until.get()?.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            self.until.set(None);
+            return None;
+        }
+        Some(remaining)
+    }
+}
+
+/// The note shown while the modal lingers after `decision` was sent.
+pub(crate) fn sent_line(decision: &ReviewDecision, theme: &ApprovalTheme) -> Line<'static> {
+    let mut outcome = decision_outcome(decision).to_string();
+    if let Some(first) = outcome.get_mut(..1) {
+        first.make_ascii_uppercase();
+    }
+    Line::from(vec![
+        "→ ".fg(theme.accent),
+        format!("sent: {outcome}, waiting…").dim(),
+    ])
+}
+
+// Detects rapid rubber-stamping of approval requests.
+
+const DEFAULT_FATIGUE_THRESHOLD: u32 = 10;
+const DEFAULT_FATIGUE_WINDOW: Duration = Duration::from_secs(60);
+
+pub(crate) const FATIGUE_NUDGE: &str = "You've approved a lot of requests in a short time. \
+Consider slowing down, or add allowlist rules for commands you trust.";
+
+/// Tracks the current streak of approvals so the modal can nudge once.
+#[derive(Default)]
+pub(crate) struct ApprovalFatigue {
+    approvals: VecDeque<Instant>,
+    nudged: bool,
+}
+
+impl ApprovalFatigue {
+    /// Record a decision; anything other than an approval ends the streak.
+    pub(crate) fn record(&mut self, approved: bool, now: Instant) {
+        if approved {
+            self.approvals.push_back(now);
+        } else {
+            self.approvals.clear();
+        }
+    }
+
+    /// Returns `true` the first time the streak crosses the configured
+    /// threshold within the configured window.
+    pub(crate) fn take_nudge(&mut self, config: &TuiApprovals, now: Instant) -> bool {
+        if self.nudged || !config.fatigue_nudge.unwrap_or(true) {
+            return false;
+        }
+        let window = config
+            .fatigue_window_secs
+            .map_or(DEFAULT_FATIGUE_WINDOW, Duration::from_secs);
+        while self
+            .approvals
+            .front()
+            .is_some_and(|approved_at| now.duration_since(*approved_at) > window)
+        {
+            self.approvals.pop_front();
+        }
+        let threshold = config
+            .fatigue_threshold
+            .unwrap_or(DEFAULT_FATIGUE_THRESHOLD)
+            .max(1) as usize;
+        self.nudged = self.approvals.len() >= threshold;
+        self.nudged
+    }
+}
+
+// Commands approved once and saved under a name for the rest of the session.
+//
+// With `tui.approvals.session_macros`, command prompts offer "Yes, and save
+// it as a session macro". Choosing it asks for a short name; Enter approves
+// the command and sends [`AppEvent::RegisterAutoApprove`], which the app
+// hands to the [`ApprovalSession`]. From then on a request for the same argv
+// in the same directory is approved before a modal opens, and the history
+// names the macro that approved it. An empty name approves the command once
+// without saving a macro.
+
+/// What a macro matches: the exact argv, run from the same directory.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub(crate) struct CommandSignature {
+    command: Vec<String>,
+    cwd: PathBuf,
+}
+
+impl CommandSignature {
+    /// The signature of a command request. Network prompts have none: they
+    /// grant a host, not a command.
+    pub(crate) fn of(request: &ApprovalRequest) -> Option<Self> {
+        match request {
+            ApprovalRequest::Exec {
+                command,
+                cwd,
+                network_approval_context: None,
+                ..
+            } => Some(Self {
+                command: command.clone(),
+                cwd: cwd.clone(),
+            }),
+            ApprovalRequest::Exec { .. }
+            | ApprovalRequest::Permissions { .. }
+            | ApprovalRequest::ApplyPatch { .. }
+            | ApprovalRequest::McpElicitation { .. } => None,
+        }
+    }
+}
+
+/// Approve `request` on the user's behalf when a session macro matches it.
+/// Returns the request when the modal should ask instead.
+pub(crate) fn resolve_by_macro(
+    request: ApprovalRequest,
+    session: &ApprovalSession,
+    app_event_tx: &AppEventSender,
+) -> Option<ApprovalRequest> {
+    let Some(name) = session.macro_for(&request) else {
+        return Some(request);
+    };
+    let ApprovalRequest::Exec {
+        thread_id,
+        thread_label,
+        id,
+        command,
+        ..
+    } = request
+    else {
+        return Some(request);
+    };
+    if thread_label.is_none() {
+        let command = redact_secrets(&strip_bash_lc_and_escape(&command));
+        let command = command.lines().next().unwrap_or_default();
+        app_event_tx.send(AppEvent::InsertHistoryCell(Box::new(
+            history_cell::new_info_event(format!("Macro `{name}` approved {command}"), None),
+        )));
+    }
+    app_event_tx.send(AppEvent::SubmitThreadOp {
+        thread_id,
+        op: Op::ApprovalResponse {
+            id,
+            turn_id: None,
+            response: ApprovalResponse::new(ReviewDecision::Approved),
+        },
+    });
+    None
+}
+
+// Approval state that outlives a single approval modal.
+//
+// The bottom pane creates a fresh [`super::ApprovalOverlay`] for each modal,
+// so anything that must survive between modals lives here and is shared by
+// cloning the handle.
+
+/// Requests arriving within this long of the previous one belong to the same
+/// burst and do not ring the bell again.
+const BELL_BURST_GAP: Duration = Duration::from_secs(5);
+
+/// Shared, session-scoped approval state.
+#[derive(Clone, Default)]
+pub(crate) struct ApprovalSession {
+    state: Arc<Mutex<SessionState>>,
+}
+
+#[derive(Default)]
+struct SessionState {
+    last_decision: Option<(RequestSignature, ApprovalDecision)>,
+    fatigue: ApprovalFatigue,
+    /// Most recent outcome per `(command, cwd)`.
+    outcomes: HashMap<(Vec<String>, PathBuf), CommandOutcome>,
+    /// Commands the user has approved at least once.
+    approved_commands: HashSet<Vec<String>>,
+    /// When the last request arrived, for ringing the bell once per burst.
+    last_arrival: Option<Instant>,
+    /// Commands saved as session macros, with the name each was saved under.
+    macros: HashMap<CommandSignature, String>,
+}
+
+/// Identifies requests that are byte-for-byte identical for replay purposes.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct RequestSignature {
+    command: Vec<String>,
+    cwd: PathBuf,
+    reason: Option<String>,
+}
+
+impl RequestSignature {
+    fn of(request: &ApprovalRequest) -> Option<Self> {
+        match request {
+            ApprovalRequest::Exec {
+                command,
+                cwd,
+                reason,
+                ..
+            } => Some(Self {
+                command: command.clone(),
+                cwd: cwd.clone(),
+                reason: reason.clone(),
+            }),
+            ApprovalRequest::Permissions { .. }
+            | ApprovalRequest::ApplyPatch { .. }
+            | ApprovalRequest::McpElicitation { .. } => None,
+        }
+    }
+}
+
+impl ApprovalSession {
+    /// Remember the decision made for `request`. Requests that cannot be
+    /// replayed clear the previous decision so only consecutive requests match.
+    pub(crate) fn record_decision(&self, request: &ApprovalRequest, decision: &ApprovalDecision) {
+        if let Ok(mut state) = self.state.lock() {
+            state.last_decision =
+                RequestSignature::of(request).map(|signature| (signature, decision.clone()));
+            state.fatigue.record(decision.is_approval(), Instant::now());
+            if let ApprovalRequest::Exec { command, .. } = request
+                && decision.is_approval()
+            {
+                state.approved_commands.insert(command.clone());
+            }
+        }
+    }
+
+    /// Whether to show the one-time approval fatigue nudge on the next modal.
+    pub(crate) fn take_fatigue_nudge(&self, config: &TuiApprovals) -> bool {
+        self.state
+            .lock()
+            .is_ok_and(|mut state| state.fatigue.take_nudge(config, Instant::now()))
+    }
+
+    /// Note a request arriving at `now`. Returns whether it should ring the
+    /// bell: `bell_on_show` is on and it starts a new burst.
+    pub(crate) fn take_bell(&self, config: &TuiApprovals, now: Instant) -> bool {
+        let Ok(mut state) = self.state.lock() else {
+            return false;
+        };
+        let new_burst = state
+            .last_arrival
+            .is_none_or(|last| now.saturating_duration_since(last) >= BELL_BURST_GAP);
+        state.last_arrival = Some(now);
+        new_burst && config.bell_on_show.unwrap_or(false)
+    }
+
+    /// Remember how a command finished so a later identical request can show it.
+    pub(crate) fn record_command_outcome(
+        &self,
+        command: Vec<String>,
+        cwd: PathBuf,
+        outcome: CommandOutcome,
+    ) {
+        if let Ok(mut state) = self.state.lock() {
+            state.outcomes.insert((command, cwd), outcome);
+        }
+    }
+
+    /// The outcome of the last run of the same command in the same directory.
+    pub(crate) fn prior_outcome(&self, request: &ApprovalRequest) -> Option<CommandOutcome> {
+        let ApprovalRequest::Exec { command, cwd, .. } = request else {
+            return None;
+        };
+        let state = self.state.lock().ok()?;
+        state.outcomes.get(&(command.clone(), cwd.clone())).cloned()
+    }
+
+    /// Whether `request` runs a command the user has not approved yet this
+    /// session. Before the first approval every command is new, so nothing is
+    /// flagged until there is something to compare against.
+    pub(crate) fn is_new_command(&self, request: &ApprovalRequest) -> bool {
+        let ApprovalRequest::Exec { command, .. } = request else {
+            return false;
+        };
+        self.state.lock().is_ok_and(|state| {
+            !state.approved_commands.is_empty() && !state.approved_commands.contains(command)
+        })
+    }
+
+    /// The previous decision, when it was made for a request identical to
+    /// `request`.
+    pub(crate) fn replayable_decision(
+        &self,
+        request: &ApprovalRequest,
+    ) -> Option<ApprovalDecision> {
+        let signature = RequestSignature::of(request)?;
+        let state = self.state.lock().ok()?;
+        state
+            .last_decision
+            .as_ref()
+            .filter(|(last, _)| *last == signature)
+            .map(|(_, decision)| decision.clone())
+    }
+
+    /// Approve `signature` without asking for the rest of the session. Saving
+    /// the same command again renames its macro.
+    pub(crate) fn register_macro(&self, name: String, signature: CommandSignature) {
+        if let Ok(mut state) = self.state.lock() {
+            state.macros.insert(signature, name);
+        }
+    }
+
+    /// The name of the macro that approves `request`, if one was saved.
+    pub(crate) fn macro_for(&self, request: &ApprovalRequest) -> Option<String> {
+        let signature = CommandSignature::of(request)?;
+        let state = self.state.lock().ok()?;
+        state.macros.get(&signature).cloned()
+    }
+}
+
+// An external program that can answer command approvals before the modal.
+//
+// When `policy_command` is set, each exec request is written to the
+// program's stdin as one JSON object:
+//
+// ```json
+// {"kind": "exec", "command": ["cargo", "test"], "cwd": "/repo"}
+// ```
+//
+// Exit status 0 approves the command. A non-zero status with a reason on
+// stdout declines it and passes the reason to the model. Anything else (no
+// reason, a crash, a failure to start, or no answer within
+// [`POLICY_TIMEOUT`]) leaves the decision to the user. Network access
+// prompts, requests for additional permissions, commands `blocked_commands`
+// lists, patches and other requests always go to the user.
+//
+// The program runs on a thread of its own so a slow one does not stall the
+// UI; when it abstains the request comes back as
+// [`AppEvent::ApprovalPolicyAbstained`] for the modal to show.
+
+/// How long the policy program may take before the modal is shown instead.
+const POLICY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// The policy program's verdict on a request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum PolicyDecision {
+    Approve,
+    Deny {
+        reason: String,
+    },
+    /// No verdict; the user decides.
+    Abstain,
+}
+
+impl PolicyDecision {
+    fn response(self) -> Option<ApprovalResponse> {
+        match self {
+            PolicyDecision::Approve => Some(ApprovalResponse::new(ReviewDecision::Approved)),
+            PolicyDecision::Deny { reason } => Some(ApprovalResponse {
+                feedback: Some(reason),
+                ..ApprovalResponse::new(ReviewDecision::Denied)
+            }),
+            PolicyDecision::Abstain => None,
+        }
+    }
+}
+
+/// What the policy program reads on stdin.
+#[derive(Serialize)]
+struct PolicyInput<'a> {
+    kind: &'static str,
+    command: &'a [String],
+    cwd: &'a Path,
+}
+
+/// The configured policy program, when it should be asked about `request`.
+fn policy_for<'a>(request: &ApprovalRequest, config: &'a TuiApprovals) -> Option<&'a [String]> {
+    let argv = config
+        .policy_command
+        .as_deref()
+        .filter(|argv| !argv.is_empty())?;
+    match request {
+        ApprovalRequest::Exec {
+            command,
+            network_approval_context: None,
+            additional_permissions: None,
+            ..
+        } if !is_blocked(command, config) => Some(argv),
+        ApprovalRequest::Exec { .. }
+        | ApprovalRequest::Permissions { .. }
+        | ApprovalRequest::ApplyPatch { .. }
+        | ApprovalRequest::McpElicitation { .. } => None,
+    }
+}
+
+/// Ask the configured policy program about `request`. Blocks for up to
+/// [`POLICY_TIMEOUT`], so the UI calls it through [`resolve_by_policy`].
+pub(crate) fn evaluate_policy(request: &ApprovalRequest, config: &TuiApprovals) -> PolicyDecision {
+    let Some(argv) = policy_for(request, config) else {
+        return PolicyDecision::Abstain;
+    };
+    let ApprovalRequest::Exec { command, cwd, .. } = request else {
+        return PolicyDecision::Abstain;
+    };
+    let input = PolicyInput {
+        kind: "exec",
+        command,
+        cwd,
+    };
+    match serde_json::to_string(&input) {
+        Ok(input) => run_policy(argv, input),
+        Err(err) => {
+            tracing::warn!("failed to serialize approval policy input: {err}");
+            PolicyDecision::Abstain
+        }
+    }
+}
+
+/// Hand `request` to the policy program on a background thread when one is
+/// configured for it; its verdict is sent as the answer, and an abstention
+/// sends the request back as [`AppEvent::ApprovalPolicyAbstained`]. Returns
+/// the request when the modal should ask right away.
+pub(crate) fn resolve_by_policy(
+    request: ApprovalRequest,
+    config: &TuiApprovals,
+    app_event_tx: &AppEventSender,
+) -> Option<ApprovalRequest> {
+    policy_for(&request, config)?;
+    let config = config.clone();
+    let app_event_tx = app_event_tx.clone();
+    std::thread::spawn(move || {
+        let decision = evaluate_policy(&request, &config);
+        apply_decision(request, decision, &app_event_tx);
+    });
+    None
+}
+
+/// Send the policy's answer to `request`, or the request itself back to the
+/// modal when the policy abstained.
+fn apply_decision(
+    request: ApprovalRequest,
+    decision: PolicyDecision,
+    app_event_tx: &AppEventSender,
+) {
+    let Some(response) = decision.response() else {
+        app_event_tx.send(AppEvent::ApprovalPolicyAbstained(request));
+        return;
+    };
+    let ApprovalRequest::Exec {
+        thread_id,
+        thread_label,
+        id,
+        command,
+        ..
+    } = request
+    else {
+        app_event_tx.send(AppEvent::ApprovalPolicyAbstained(request));
+        return;
+    };
+    if thread_label.is_none() {
+        let command = redact_secrets(&strip_bash_lc_and_escape(&command));
+        let command = command.lines().next().unwrap_or_default();
+        let (message, hint) = match &response.feedback {
+            Some(reason) => (
+                format!("Approval policy declined {command}"),
+                Some(reason.clone()),
+            ),
+            None => (format!("Approval policy approved {command}"), None),
+        };
+        app_event_tx.send(AppEvent::InsertHistoryCell(Box::new(
+            history_cell::new_info_event(message, hint),
+        )));
+    }
+    app_event_tx.send(AppEvent::SubmitThreadOp {
+        thread_id,
+        op: Op::ApprovalResponse {
+            id,
+            turn_id: None,
+            response,
+        },
+    });
+}
+
+/// Run the policy program with `input` on its stdin. The write, the wait and
+/// the read of stdout all share [`POLICY_TIMEOUT`]; stdin and stdout are
+/// serviced on threads of their own so a program that answers before
+/// reading, or writes a lot, cannot wedge the exchange.
+fn run_policy(argv: &[String], input: String) -> PolicyDecision {
+    let Some((program, args)) = argv.split_first() else {
+        return PolicyDecision::Abstain;
+    };
+    let mut child = match Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+    {
+        Ok(child) => child,
+        Err(err) => {
+            tracing::warn!("failed to start approval policy {program}: {err}");
+            return PolicyDecision::Abstain;
+        }
+    };
+    let deadline = Instant::now() + POLICY_TIMEOUT;
+    // Dropping stdin closes it so the program sees the end of the input. A
+    // program that exits without reading it is fine.
+    if let Some(mut stdin) = child.stdin.take() {
+        std::thread::spawn(move || {
+            let _ = stdin.write_all(input.as_bytes());
+        });
+    }
+    let (stdout_tx, stdout_rx) = mpsc::channel();
+    if let Some(mut out) = child.stdout.take() {
+        std::thread::spawn(move || {
+            let mut stdout = String::new();
+            let _ = out.read_to_string(&mut stdout);
+            let _ = stdout_tx.send(stdout);
+        });
+    }
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break status,
+            Ok(None) if Instant::now() < deadline => std::thread::sleep(Duration::from_millis(10)),
+            Ok(None) | Err(_) => {
+                tracing::warn!("approval policy {program} did not answer; asking the user");
+                let _ = child.kill();
+                let _ = child.wait();
+                return PolicyDecision::Abstain;
+            }
+        }
+    };
+    // A process the program left behind can hold stdout open past its exit.
+    let remaining = deadline.saturating_duration_since(Instant::now());
+    let Ok(stdout) = stdout_rx.recv_timeout(remaining) else {
+        tracing::warn!("approval policy {program} did not close its output; asking the user");
+        return PolicyDecision::Abstain;
+    };
+    verdict(status.code(), &stdout)
+}
+
+/// Map the program's exit code (`None` when killed by a signal) and stdout
+/// to a decision.
+fn verdict(code: Option<i32>, stdout: &str) -> PolicyDecision {
+    let reason = stdout.trim();
+    match code {
+        Some(0) => PolicyDecision::Approve,
+        Some(_) if !reason.is_empty() => PolicyDecision::Deny {
+            reason: reason.to_string(),
+        },
+        Some(_) | None => PolicyDecision::Abstain,
+    }
+}
+
+// Commands `blocked_commands` forbids approving.
+//
+// The bottom pane turns the configured patterns into the modal's
+// pre-decision hook, so approving a matching command is refused with a note
+// while declining it still goes through. The policy program is never asked
+// about a blocked command, so it cannot approve one either.
+
+const BLOCKED_NOTE: &str = "This command is blocked by tui.approvals.blocked_commands.";
+
+/// Whether `command` matches one of the patterns in `blocked_commands`.
+pub(crate) fn is_blocked(command: &[String], config: &TuiApprovals) -> bool {
+    let Some(patterns) = config.blocked_commands.as_deref() else {
+        return false;
+    };
+    let shown = strip_bash_lc_and_escape(command);
+    patterns
+        .iter()
+        .any(|pattern| WildMatch::new(pattern).matches(&shown))
+}
+
+/// A hook refusing to approve the commands `config` blocks, or `None` when
+/// it blocks none.
+pub(crate) fn blocked_commands_hook(config: &TuiApprovals) -> Option<PreDecisionHook> {
+    if config.blocked_commands.as_ref().is_none_or(Vec::is_empty) {
+        return None;
+    }
+    let config = config.clone();
+    Some(Box::new(
+        move |request: &ApprovalRequest, decision: ReviewDecision| match request {
+            ApprovalRequest::Exec { command, .. }
+                if approves(&decision) && is_blocked(command, &config) =>
+            {
+                Err(BLOCKED_NOTE.to_string())
+            }
+            _ => Ok(()),
+        },
+    ))
+}
+
+/// Whether `decision` lets the command run.
+fn approves(decision: &ReviewDecision) -> bool {
+    match decision {
+        ReviewDecision::Approved
+        | ReviewDecision::ApprovedExecpolicyAmendment { .. }
+        | ReviewDecision::ApprovedForSession
+        | ReviewDecision::ApprovedForDirectory
+        | ReviewDecision::ApprovedForDuration { .. }
+        | ReviewDecision::ApprovedAlways
+        | ReviewDecision::ApprovedSandboxed => true,
+        ReviewDecision::NetworkPolicyAmendment {
+            network_policy_amendment,
+        } => network_policy_amendment.action == NetworkPolicyRuleAction::Allow,
+        ReviewDecision::DryRun
+        | ReviewDecision::Denied
+        | ReviewDecision::DeniedForSession
+        | ReviewDecision::Abort => false,
+    }
+}
+
+// Short spoken-style summaries of the prompt for screen-reader bridges.
+//
+// The text follows the prompt title and the command or target it shows, so a
+// listener hears the same thing a reader would see first.
+
+/// Longest command spoken before it is cut off with `…`.
+const MAX_COMMAND_CHARS: usize = 80;
+
+/// Announcement when `request` becomes the active prompt.
+pub(crate) fn open_announcement(request: &ApprovalRequest) -> String {
+    match request {
+        ApprovalRequest::Exec {
+            network_approval_context: Some(network_approval_context),
+            ..
+        } => format!(
+            "Approval required: allow network access to {}",
+            network_approval_context.host
+        ),
+        ApprovalRequest::Exec { command, .. } => format!(
+            "Approval required: allow command codex wants to run {}",
+            spoken_command(command)
+        ),
+        ApprovalRequest::Permissions { .. } => {
+            "Approval required: grant additional permissions".to_string()
+        }
+        ApprovalRequest::ApplyPatch { changes, .. } => {
+            let count = changes.len();
+            let files = if count == 1 { "file" } else { "files" };
+            format!("Approval required: allow codex to edit {count} {files}")
+        }
+        ApprovalRequest::McpElicitation { server_name, .. } => {
+            format!("Approval required: {server_name} needs your approval")
+        }
+    }
+}
+
+/// Announcement after the user answered `request` with `decision`.
+pub(crate) fn decision_announcement(
+    request: &ApprovalRequest,
+    decision: &ReviewDecision,
+) -> String {
+    let subject = match request {
+        ApprovalRequest::Exec { .. } => "Command",
+        ApprovalRequest::Permissions { .. } => "Permissions",
+        ApprovalRequest::ApplyPatch { .. } => "Edits",
+        ApprovalRequest::McpElicitation { .. } => "Request",
+    };
+    format!("{subject} {}", decision_outcome(decision))
+}
+
+/// What `decision` did to the request, such as `approved` or `denied`.
+pub(crate) fn decision_outcome(decision: &ReviewDecision) -> &'static str {
+    match decision {
+        ReviewDecision::Approved
+        | ReviewDecision::ApprovedExecpolicyAmendment { .. }
+        | ReviewDecision::ApprovedForSession
+        | ReviewDecision::ApprovedForDirectory
+        | ReviewDecision::ApprovedAlways => "approved",
+        ReviewDecision::ApprovedForDuration { .. } => "approved for a while",
+        ReviewDecision::ApprovedSandboxed => "approved to run sandboxed",
+        ReviewDecision::NetworkPolicyAmendment {
+            network_policy_amendment,
+        } => match network_policy_amendment.action {
+            NetworkPolicyRuleAction::Allow => "approved",
+            NetworkPolicyRuleAction::Deny => "denied",
+        },
+        ReviewDecision::DryRun => "sent for a dry run",
+        ReviewDecision::Denied => "denied",
+        ReviewDecision::DeniedForSession => "denied for the rest of this session",
+        ReviewDecision::Abort => "aborted",
+    }
+}
+
+/// Announcement after the user answered an MCP elicitation.
+pub(crate) fn elicitation_announcement(action: ElicitationAction) -> String {
+    match action {
+        ElicitationAction::Accept => "Request accepted",
+        ElicitationAction::Decline => "Request declined",
+        ElicitationAction::Cancel => "Request cancelled",
+    }
+    .to_string()
+}
+
+/// The command's first line with secrets masked, cut to [`MAX_COMMAND_CHARS`].
+fn spoken_command(command: &[String]) -> String {
+    let rendered = redact_secrets(&strip_bash_lc_and_escape(&bounded_argv(command)));
+    let first_line = rendered.lines().next().unwrap_or_default();
+    if first_line.chars().count() > MAX_COMMAND_CHARS || rendered.contains('\n') {
+        let cut: String = first_line.chars().take(MAX_COMMAND_CHARS).collect();
+        format!("{}…", cut.trim_end())
+    } else {
+        first_line.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use pretty_assertions::assert_eq;
+    use tokio::sync::mpsc::unbounded_channel;
+
+    fn exec(command: &[&str], cwd: &str) -> ApprovalRequest {
+        ApprovalRequest::Exec {
+            thread_id: ThreadId::new(),
+            thread_label: None,
+            id: "call-1".to_string(),
+            command: command.iter().map(|word| word.to_string()).collect(),
+            cwd: PathBuf::from(cwd),
+            reason: None,
+            available_decisions: vec![ReviewDecision::Approved, ReviewDecision::Abort],
+            network_approval_context: None,
+            additional_permissions: None,
+            sandbox_policy: None,
+            env: Vec::new(),
+            duplicate_ids: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn exec_records_the_approved_command_with_secrets_masked() {
+        let thread_id = ThreadId::new();
+        let request = ApprovalRequest::Exec {
+            thread_id,
+            thread_label: None,
+            id: "call-1".to_string(),
+            command: vec!["cargo".to_string(), "publish".to_string()],
+            cwd: PathBuf::from("/repo"),
+            reason: None,
+            available_decisions: Vec::new(),
+            network_approval_context: None,
+            additional_permissions: None,
+            sandbox_policy: None,
+            env: Vec::new(),
+            duplicate_ids: Vec::new(),
+        };
+        let response = ApprovalResponse {
+            feedback: Some("ok with --token abc123".to_string()),
+            overrides: ApprovalOverrides {
+                command: Some(
+                    ["cargo", "publish", "--token", "abc123"]
+                        .map(str::to_string)
+                        .to_vec(),
+                ),
+                ..ApprovalOverrides::default()
+            },
+            ..ApprovalResponse::new(ReviewDecision::Approved)
+        };
+        let timestamp = Utc.with_ymd_and_hms(2026, 10, 14, 9, 30, 0).unwrap();
+
+        assert_eq!(
+            audit_record(&request, &response, timestamp, Some("alice".to_string())),
+            Some(json!({
+                "timestamp": "2026-10-14T09:30:00Z",
+                "session_id": thread_id.to_string(),
+                "request_id": "call-1",
+                "kind": "exec",
+                "command": "cargo publish --token ••••",
+                "patch_summary": null,
+                "decision": "approved",
+                "feedback": "ok with --token ••••",
+                "user": "alice",
+            }))
+        );
+    }
+
+    #[test]
+    fn countdown_rounds_up_to_the_next_second() {
+        assert_eq!(display_secs(Duration::from_millis(8_200)), 9);
+        assert_eq!(display_secs(Duration::from_secs(8)), 8);
+        assert_eq!(
+            until_next_second(Duration::from_millis(8_200)),
+            Duration::from_millis(200)
+        );
+        assert_eq!(
+            until_next_second(Duration::from_secs(8)),
+            Duration::from_secs(1)
+        );
+    }
+
+    fn queued(thread_id: ThreadId, id: &str, command: &str) -> ApprovalRequest {
+        ApprovalRequest::Exec {
+            thread_id,
+            thread_label: None,
+            id: id.to_string(),
+            command: vec!["bash".into(), "-lc".into(), command.into()],
+            cwd: PathBuf::from("/tmp"),
+            reason: None,
+            available_decisions: Vec::new(),
+            network_approval_context: None,
+            additional_permissions: None,
+            sandbox_policy: None,
+            env: Vec::new(),
+            duplicate_ids: Vec::new(),
+        }
+    }
+
+    fn ids(reqs: &[ApprovalRequest]) -> Vec<(String, Vec<String>)> {
+        reqs.iter()
+            .map(|req| match req {
+                ApprovalRequest::Exec {
+                    id, duplicate_ids, ..
+                } => (id.clone(), duplicate_ids.clone()),
+                ApprovalRequest::Permissions { .. }
+                | ApprovalRequest::ApplyPatch { .. }
+                | ApprovalRequest::McpElicitation { .. } => panic!("expected exec"),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn consecutive_repeats_are_merged() {
+        let thread_id = ThreadId::new();
+        let coalesced = coalesce_requests(vec![
+            queued(thread_id, "a", "cargo test"),
+            queued(thread_id, "b", "cargo test"),
+            queued(thread_id, "c", "cargo test"),
+            queued(thread_id, "d", "cargo build"),
+            queued(thread_id, "e", "cargo test"),
+        ]);
+        assert_eq!(
+            ids(&coalesced),
+            vec![
+                ("a".to_string(), vec!["b".to_string(), "c".to_string()]),
+                ("d".to_string(), Vec::new()),
+                ("e".to_string(), Vec::new()),
+            ]
+        );
+        assert_eq!(request_count(&coalesced[0]), 3);
+    }
+
+    #[test]
+    fn different_cwd_or_thread_stay_separate() {
+        let thread_id = ThreadId::new();
+        let mut elsewhere = queued(thread_id, "b", "cargo test");
+        if let ApprovalRequest::Exec { cwd, .. } = &mut elsewhere {
+            *cwd = PathBuf::from("/srv");
+        }
+        let coalesced = coalesce_requests(vec![
+            queued(thread_id, "a", "cargo test"),
+            elsewhere,
+            queued(ThreadId::new(), "c", "cargo test"),
+        ]);
+        assert_eq!(coalesced.len(), 3);
+    }
+
+    fn text(line: &Line<'_>) -> String {
+        line.spans
+            .iter()
+            .map(|span| span.content.as_ref())
+            .collect()
+    }
+
+    fn make_request() -> ApprovalRequest {
+        ApprovalRequest::Exec {
+            thread_id: ThreadId::new(),
+            thread_label: None,
+            id: "call-1".to_string(),
+            command: vec!["ls".to_string()],
+            cwd: PathBuf::from("/repo"),
+            reason: None,
+            available_decisions: Vec::new(),
+            network_approval_context: None,
+            additional_permissions: None,
+            sandbox_policy: None,
+            env: Vec::new(),
+            duplicate_ids: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn note_appears_once_deferred_often_enough() {
+        let request = make_request();
+        let theme = ApprovalTheme::default();
+        let mut deferrals = Deferrals::default();
+        deferrals.record(&request);
+        assert_eq!(deferrals.still_waiting_line(&request, &theme), None);
+        deferrals.record(&request);
+        assert_eq!(
+            deferrals
+                .still_waiting_line(&request, &theme)
+                .map(|line| text(&line)),
+            Some("Deferred 2 times · codex is still waiting for your decision".to_string())
+        );
+    }
+
+    #[test]
+    fn notes_are_kept_on_one_line_and_blank_ones_are_omitted() {
+        let request = make_request();
+        let mut deferrals = Deferrals::default();
+        deferrals.set_note(&request, "   ");
+        assert_eq!(deferrals.note_line(&request), None);
+
+        deferrals.set_note(&request, " check this\nlater ");
+        assert_eq!(
+            deferrals.note_line(&request).map(|line| text(&line)),
+            Some("Your note: check this later".to_string())
+        );
+        deferrals.set_note(&request, "");
+        assert!(deferrals.note_line(&request).is_some());
+    }
+
+    #[test]
+    fn sent_line_names_the_decision() {
+        let theme = ApprovalTheme::default();
+        let text = |decision| sent_line(&decision, &theme).to_string();
+        assert_eq!(text(ReviewDecision::Approved), "→ sent: Approved, waiting…");
+        assert_eq!(text(ReviewDecision::Denied), "→ sent: Denied, waiting…");
+    }
+
+    #[test]
+    fn linger_ends_once_its_time_is_up() {
+        assert_eq!(Linger::default().remaining(), None);
+        assert!(Linger::start(Duration::from_secs(60)).remaining().is_some());
+        assert_eq!(Linger::start(Duration::ZERO).remaining(), None);
+    }
+
+    fn nudge_after(threshold: u32) -> TuiApprovals {
+        TuiApprovals {
+            fatigue_threshold: Some(threshold),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn nudges_once_after_threshold() {
+        let mut fatigue = ApprovalFatigue::default();
+        let now = Instant::now();
+        fatigue.record(true, now);
+        fatigue.record(true, now);
+        assert!(!fatigue.take_nudge(&nudge_after(3), now));
+
+        fatigue.record(true, now);
+        assert!(fatigue.take_nudge(&nudge_after(3), now));
+
+        fatigue.record(true, now);
+        assert!(!fatigue.take_nudge(&nudge_after(3), now));
+    }
+
+    #[test]
+    fn denial_or_slow_approvals_reset_the_streak() {
+        let mut fatigue = ApprovalFatigue::default();
+        let start = Instant::now();
+        fatigue.record(true, start);
+        fatigue.record(false, start);
+        fatigue.record(true, start);
+        assert!(!fatigue.take_nudge(&nudge_after(2), start));
+
+        fatigue.record(true, start + Duration::from_secs(61));
+        assert!(!fatigue.take_nudge(&nudge_after(2), start + Duration::from_secs(61)));
+    }
+
+    #[test]
+    fn can_be_disabled() {
+        let mut fatigue = ApprovalFatigue::default();
+        let now = Instant::now();
+        fatigue.record(true, now);
+        let disabled = TuiApprovals {
+            fatigue_nudge: Some(false),
+            fatigue_threshold: Some(1),
+            ..Default::default()
+        };
+        assert!(!fatigue.take_nudge(&disabled, now));
+    }
+
+    #[test]
+    fn only_the_same_argv_in_the_same_directory_is_approved() {
+        let (tx, mut rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx);
+        let session = ApprovalSession::default();
+        let check = exec(&["cargo", "check"], "/repo");
+        session.register_macro(
+            "check".to_string(),
+            CommandSignature::of(&check).expect("signature"),
+        );
+
+        assert!(resolve_by_macro(exec(&["cargo", "check"], "/other"), &session, &tx).is_some());
+        assert!(resolve_by_macro(exec(&["cargo", "test"], "/repo"), &session, &tx).is_some());
+        assert!(rx.try_recv().is_err());
+
+        assert!(resolve_by_macro(check, &session, &tx).is_none());
+        let Ok(AppEvent::InsertHistoryCell(cell)) = rx.try_recv() else {
+            panic!("expected a history cell");
+        };
+        let text: String = cell.display_lines(80)[0]
+            .spans
+            .iter()
+            .map(|span| span.content.as_ref())
+            .collect();
+        assert_eq!(text, "• Macro `check` approved cargo check");
+        assert!(matches!(
+            rx.try_recv(),
+            Ok(AppEvent::SubmitThreadOp {
+                op: Op::ApprovalResponse { response, .. },
+                ..
+            }) if response.decision == ReviewDecision::Approved
+        ));
+    }
+
+    #[test]
+    fn replays_only_identical_consecutive_requests() {
+        let session = ApprovalSession::default();
+        let approved = ApprovalDecision::Review(ReviewDecision::Approved);
+        session.record_decision(&exec(&["ls"], "/tmp"), &approved);
+
+        assert_eq!(
+            session.replayable_decision(&exec(&["ls"], "/tmp")),
+            Some(approved)
+        );
+        assert_eq!(session.replayable_decision(&exec(&["pwd"], "/tmp")), None);
+
+        session.record_decision(
+            &exec(&["pwd"], "/tmp"),
+            &ApprovalDecision::Review(ReviewDecision::Denied),
+        );
+        assert_eq!(session.replayable_decision(&exec(&["ls"], "/tmp")), None);
+    }
+
+    #[test]
+    fn bell_rings_once_per_burst() {
+        let session = ApprovalSession::default();
+        let config = TuiApprovals {
+            bell_on_show: Some(true),
+            ..TuiApprovals::default()
+        };
+        let start = Instant::now();
+        assert!(session.take_bell(&config, start));
+        assert!(!session.take_bell(&config, start + Duration::from_secs(1)));
+        assert!(!session.take_bell(&config, start + Duration::from_secs(5)));
+        assert!(session.take_bell(&config, start + Duration::from_secs(11)));
+        assert!(!session.take_bell(&TuiApprovals::default(), start + Duration::from_secs(30)));
+    }
+
+    #[test]
+    fn commands_stay_new_until_approved() {
+        let session = ApprovalSession::default();
+        assert!(!session.is_new_command(&exec(&["ls"], "/tmp")));
+
+        session.record_decision(
+            &exec(&["ls"], "/tmp"),
+            &ApprovalDecision::Review(ReviewDecision::Approved),
+        );
+        session.record_decision(
+            &exec(&["rm"], "/tmp"),
+            &ApprovalDecision::Review(ReviewDecision::Denied),
+        );
+
+        assert!(!session.is_new_command(&exec(&["ls"], "/tmp")));
+        assert!(session.is_new_command(&exec(&["rm"], "/tmp")));
+        assert!(session.is_new_command(&exec(&["pwd"], "/tmp")));
+    }
+
+    #[test]
+    fn prior_outcome_is_keyed_by_command_and_cwd() {
+        let session = ApprovalSession::default();
+        let outcome = CommandOutcome::new(0, std::time::Duration::from_secs(3), "");
+        session.record_command_outcome(
+            vec!["ls".to_string()],
+            PathBuf::from("/tmp"),
+            outcome.clone(),
+        );
+        session.record_command_outcome(
+            vec!["pwd".to_string()],
+            PathBuf::from("/elsewhere"),
+            CommandOutcome::new(1, std::time::Duration::ZERO, ""),
+        );
+
+        assert_eq!(session.prior_outcome(&exec(&["ls"], "/tmp")), Some(outcome));
+        assert_eq!(session.prior_outcome(&exec(&["pwd"], "/tmp")), None);
+    }
+
+    fn policy(script: &str) -> TuiApprovals {
+        TuiApprovals {
+            policy_command: Some(vec!["sh".to_string(), "-c".to_string(), script.to_string()]),
+            ..TuiApprovals::default()
+        }
+    }
+
+    #[test]
+    fn exit_status_and_reason_decide() {
+        assert_eq!(verdict(Some(0), ""), PolicyDecision::Approve);
+        assert_eq!(
+            verdict(Some(1), "  rm is not allowed\n"),
+            PolicyDecision::Deny {
+                reason: "rm is not allowed".to_string()
+            }
+        );
+        assert_eq!(verdict(Some(1), "\n"), PolicyDecision::Abstain);
+        assert_eq!(verdict(None, "killed"), PolicyDecision::Abstain);
+    }
+
+    #[test]
+    fn input_lists_kind_command_and_cwd() {
+        let input = PolicyInput {
+            kind: "exec",
+            command: &["cargo".to_string(), "test".to_string()],
+            cwd: Path::new("/repo"),
+        };
+        assert_eq!(
+            serde_json::to_string(&input).expect("serialize"),
+            r#"{"kind":"exec","command":["cargo","test"],"cwd":"/repo"}"#
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn program_reads_the_request_and_answers() {
+        let deny_rm = policy(r#"grep -q '"rm"' && { echo "no rm here"; exit 1; }; exit 0"#);
+        assert_eq!(
+            evaluate_policy(&exec(&["rm", "-rf", "build"], "/repo"), &deny_rm),
+            PolicyDecision::Deny {
+                reason: "no rm here".to_string()
+            }
+        );
+        assert_eq!(
+            evaluate_policy(&exec(&["ls"], "/repo"), &deny_rm),
+            PolicyDecision::Approve
+        );
+        assert_eq!(
+            evaluate_policy(&exec(&["ls"], "/repo"), &policy("exit 2")),
+            PolicyDecision::Abstain
+        );
+        assert_eq!(
+            evaluate_policy(&exec(&["ls"], "/repo"), &TuiApprovals::default()),
+            PolicyDecision::Abstain
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn a_flood_on_stdout_does_not_block_the_answer() {
+        let chatty = policy("head -c 1000000 /dev/zero | tr '\\0' x; exit 1");
+        assert!(matches!(
+            evaluate_policy(&exec(&["ls"], "/repo"), &chatty),
+            PolicyDecision::Deny { .. }
+        ));
+    }
+
+    #[test]
+    fn requests_for_more_permissions_go_to_the_user() {
+        let mut request = exec(&["ls"], "/repo");
+        if let ApprovalRequest::Exec {
+            additional_permissions,
+            ..
+        } = &mut request
+        {
+            *additional_permissions = Some(PermissionProfile::default());
+        }
+        assert_eq!(
+            evaluate_policy(&request, &policy("exit 0")),
+            PolicyDecision::Abstain
+        );
+    }
+
+    #[test]
+    fn abstaining_hands_the_request_back_to_the_modal() {
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let app_event_tx = AppEventSender::new(tx);
+        apply_decision(
+            exec(&["ls"], "/repo"),
+            PolicyDecision::Abstain,
+            &app_event_tx,
+        );
+        assert!(matches!(
+            rx.try_recv(),
+            Ok(AppEvent::ApprovalPolicyAbstained(
+                ApprovalRequest::Exec { .. }
+            ))
+        ));
+    }
+
+    fn blocking(patterns: &[&str]) -> TuiApprovals {
+        TuiApprovals {
+            blocked_commands: Some(patterns.iter().map(ToString::to_string).collect()),
+            ..TuiApprovals::default()
+        }
+    }
+
+    #[test]
+    fn matches_the_command_as_shown() {
+        let config = blocking(&["git push --force*"]);
+        let wrapped = ["bash", "-lc", "git push --force origin main"].map(String::from);
+        assert!(is_blocked(&wrapped, &config));
+        assert!(!is_blocked(&["git", "push"].map(String::from), &config));
+        assert!(!is_blocked(&wrapped, &TuiApprovals::default()));
+    }
+
+    #[test]
+    fn refuses_approvals_but_not_denials() {
+        let hook = blocked_commands_hook(&blocking(&["rm -rf *"])).expect("hook");
+        let request = exec(&["rm", "-rf", "build"], "/repo");
+        assert_eq!(
+            hook(&request, ReviewDecision::ApprovedForSession),
+            Err(BLOCKED_NOTE.to_string())
+        );
+        assert_eq!(hook(&request, ReviewDecision::Denied), Ok(()));
+        assert_eq!(
+            hook(&exec(&["ls"], "/repo"), ReviewDecision::Approved),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn no_patterns_install_no_hook() {
+        assert!(blocked_commands_hook(&blocking(&[])).is_none());
+        assert!(blocked_commands_hook(&TuiApprovals::default()).is_none());
+    }
+
+    fn argv(words: &[&str]) -> Vec<String> {
+        words.iter().map(|word| word.to_string()).collect()
+    }
+
+    #[test]
+    fn long_and_multiline_commands_are_cut() {
+        assert_eq!(spoken_command(&argv(&["git", "status"])), "git status");
+        assert_eq!(
+            spoken_command(&argv(&["bash", "-lc", "python - <<'PY'\nprint(1)\nPY"])),
+            "python - <<'PY'…"
+        );
+        let long = "x".repeat(100);
+        assert_eq!(
+            spoken_command(&argv(&["echo", &long])),
+            format!("echo {}…", "x".repeat(MAX_COMMAND_CHARS - 5))
+        );
+    }
+}
@@ -0,0 +1,999 @@
+//! The inline inputs that replace the option list while they are open.
+//!
+//! Denying with a reason, editing the command, picking a duration and
+//! picking which files of a patch to apply each open an input under the
+//! prompt; submitting it answers the request and Esc goes back to the
+//! options.
+
+use super::ApprovalOverlay;
+use super::ApprovalRequest;
+use super::render::ApprovalTheme;
+use crate::bottom_pane::textarea::TextArea;
+use crate::bottom_pane::textarea::TextAreaState;
+use crate::exec_command::strip_bash_lc_and_escape;
+use crate::key_hint;
+use crate::render::renderable::Renderable;
+use codex_shell_command::parse_command::extract_shell_command;
+use crossterm::event::KeyCode;
+use crossterm::event::KeyEvent;
+use crossterm::event::KeyEventKind;
+use crossterm::event::KeyModifiers;
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::Stylize;
+use ratatui::text::Line;
+use ratatui::text::Span;
+use ratatui::widgets::Paragraph;
+use ratatui::widgets::StatefulWidgetRef;
+use ratatui::widgets::Widget;
+use std::cell::RefCell;
+use std::collections::HashSet;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+impl ApprovalOverlay {
+    /// The feedback input, command editor, duration picker or file picker
+    /// when one replaces the options.
+    pub(crate) fn text_input(&self) -> Option<&dyn Renderable> {
+        match (&self.feedback, &self.command_editor, &self.duration_picker) {
+            (Some(input), _, _) => Some(input),
+            (None, Some(editor), _) => Some(editor),
+            (None, None, Some(picker)) => Some(picker),
+            (None, None, None) => self
+                .file_picker
+                .as_ref()
+                .map(|picker| picker as &dyn Renderable),
+        }
+    }
+
+    /// Hand `key_event` to the text input or picker that replaces the
+    /// options, if one is open. Returns whether one took it.
+    pub(crate) fn handle_input_key(&mut self, key_event: KeyEvent) -> bool {
+        if let Some(input) = self.feedback.as_mut() {
+            match key_event {
+                KeyEvent {
+                    code: KeyCode::Esc,
+                    kind: KeyEventKind::Press,
+                    ..
+                } => self.feedback = None,
+                KeyEvent {
+                    code: KeyCode::Enter,
+                    modifiers: KeyModifiers::NONE,
+                    kind: KeyEventKind::Press,
+                    ..
+                } => self.submit_feedback(),
+                other => input.input(other),
+            }
+            return true;
+        }
+        if let Some(editor) = self.command_editor.as_mut() {
+            match key_event {
+                KeyEvent {
+                    code: KeyCode::Esc,
+                    kind: KeyEventKind::Press,
+                    ..
+                } => self.command_editor = None,
+                KeyEvent {
+                    code: KeyCode::Enter,
+                    modifiers: KeyModifiers::NONE,
+                    kind: KeyEventKind::Press,
+                    ..
+                } => self.submit_edited_command(),
+                other => editor.input(other),
+            }
+            return true;
+        }
+        if self.duration_picker.is_some() {
+            // The Enter or digit that approves also advances the queue, so a
+            // doubled press must not reach the next request.
+            if key_event.kind != KeyEventKind::Press || self.is_duplicate_press(&key_event) {
+                return true;
+            }
+            let Some(picker) = self.duration_picker.as_mut() else {
+                return true;
+            };
+            match key_event.code {
+                KeyCode::Esc => self.duration_picker = None,
+                KeyCode::Enter => {
+                    let duration = picker.selected();
+                    self.approve_for_duration(duration);
+                }
+                KeyCode::Char(digit) if digit.is_ascii_digit() => {
+                    if let Some(duration) = DurationPicker::preset(digit) {
+                        self.approve_for_duration(duration);
+                    }
+                }
+                KeyCode::Left | KeyCode::Char('h') => picker.select_previous(),
+                KeyCode::Right | KeyCode::Char('l') => picker.select_next(),
+                _ => {}
+            }
+            return true;
+        }
+        if self.file_picker.is_some() {
+            if key_event.kind == KeyEventKind::Release || self.is_duplicate_press(&key_event) {
+                return true;
+            }
+            let Some(picker) = self.file_picker.as_mut() else {
+                return true;
+            };
+            match key_event.code {
+                KeyCode::Esc => self.file_picker = None,
+                KeyCode::Enter if key_event.kind == KeyEventKind::Press => {
+                    self.approve_picked_files();
+                }
+                KeyCode::Char(' ') if key_event.kind == KeyEventKind::Press => picker.toggle(),
+                KeyCode::Up | KeyCode::Char('k') => picker.select_previous(),
+                KeyCode::Down | KeyCode::Char('j') => picker.select_next(),
+                _ => {}
+            }
+            return true;
+        }
+        false
+    }
+
+    /// Replace the options with a text input for why the request is declined.
+    /// Returns whether the current request takes feedback.
+    pub(crate) fn open_feedback_input(&mut self) -> bool {
+        match self.current_request.as_ref() {
+            Some(ApprovalRequest::Exec { .. } | ApprovalRequest::ApplyPatch { .. }) => {
+                let prompt: Option<Arc<dyn Renderable>> = self
+                    .prompt
+                    .clone()
+                    .map(|prompt| prompt as Arc<dyn Renderable>);
+                self.feedback = prompt.map(|prompt| FeedbackInput::new(prompt, self.theme));
+                self.feedback.is_some()
+            }
+            Some(ApprovalRequest::Permissions { .. } | ApprovalRequest::McpElicitation { .. })
+            | None => false,
+        }
+    }
+
+    /// Open the feedback input seeded with a request to investigate before
+    /// retrying. Returns whether the current request takes feedback.
+    pub(crate) fn open_investigate_input(&mut self) -> bool {
+        if !self.open_feedback_input() {
+            return false;
+        }
+        self.feedback = self.feedback.take().map(FeedbackInput::investigating);
+        true
+    }
+
+    /// Replace the options with a text input for a note that goes to the
+    /// agent with the patch's approval. Returns whether the current request is
+    /// a patch.
+    pub(crate) fn open_approval_note_input(&mut self) -> bool {
+        let Some(ApprovalRequest::ApplyPatch { .. }) = self.current_request.as_ref() else {
+            return false;
+        };
+        let prompt: Option<Arc<dyn Renderable>> = self
+            .prompt
+            .clone()
+            .map(|prompt| prompt as Arc<dyn Renderable>);
+        self.feedback = prompt.map(|prompt| FeedbackInput::new(prompt, self.theme).approving());
+        self.feedback.is_some()
+    }
+
+    /// Replace the options with a text input for a note to yourself, then put
+    /// the request off. Returns `false` when nothing else is waiting.
+    pub(crate) fn open_defer_note_input(&mut self) -> bool {
+        if !self.can_defer() {
+            return false;
+        }
+        let prompt: Option<Arc<dyn Renderable>> = self
+            .prompt
+            .clone()
+            .map(|prompt| prompt as Arc<dyn Renderable>);
+        self.feedback = prompt.map(|prompt| FeedbackInput::new(prompt, self.theme).deferring());
+        self.feedback.is_some()
+    }
+
+    /// Replace the options with a text input for the name to save the command
+    /// under as a session macro.
+    pub(crate) fn open_macro_name_input(&mut self) {
+        let prompt: Option<Arc<dyn Renderable>> = self
+            .prompt
+            .clone()
+            .map(|prompt| prompt as Arc<dyn Renderable>);
+        self.feedback = prompt.map(|prompt| FeedbackInput::new(prompt, self.theme).naming_macro());
+    }
+
+    pub(crate) fn open_command_editor(&mut self) {
+        let Some(ApprovalRequest::Exec { command, .. }) = self.current_request.as_ref() else {
+            return;
+        };
+        let prompt: Option<Arc<dyn Renderable>> = self
+            .prompt
+            .clone()
+            .map(|prompt| prompt as Arc<dyn Renderable>);
+        self.command_editor = prompt.map(|prompt| CommandEditor::new(prompt, command, self.theme));
+    }
+
+    pub(crate) fn open_duration_picker(&mut self, offered: Duration) {
+        let prompt: Option<Arc<dyn Renderable>> = self
+            .prompt
+            .clone()
+            .map(|prompt| prompt as Arc<dyn Renderable>);
+        self.duration_picker =
+            prompt.map(|prompt| DurationPicker::new(prompt, offered, self.theme));
+    }
+
+    pub(crate) fn open_file_picker(&mut self) {
+        let Some(ApprovalRequest::ApplyPatch { cwd, changes, .. }) = self.current_request.as_ref()
+        else {
+            return;
+        };
+        let prompt: Option<Arc<dyn Renderable>> = self
+            .prompt
+            .clone()
+            .map(|prompt| prompt as Arc<dyn Renderable>);
+        self.file_picker =
+            prompt.map(|prompt| FilePicker::new(prompt, changes.keys().cloned(), cwd, self.theme));
+    }
+}
+
+// Inline text input for explaining a denial before it is sent, for leaving
+// the agent a note along with an approved patch, for leaving yourself a
+// note on a request put off with Shift+Tab, or for naming a session macro.
+//
+// While active it replaces the option list under the approval prompt. The
+// overlay owns the key routing: Enter submits, Esc returns to the options,
+// and everything else edits the text.
+//
+// A denial offers a few canned requests to start from, such as asking the
+// agent to investigate before retrying. While the input is still empty,
+// `1`–`3` seed it with one to edit; once anything is typed, digits are text.
+
+/// Most rows the input grows to before it scrolls.
+const FEEDBACK_INPUT_ROWS: u16 = 3;
+
+/// The denial templates, with the label the presets row shows. Each ends
+/// where the user fills in what the agent should look at.
+const FEEDBACK_PRESETS: [(&str, &str); 3] = [
+    ("investigate first", "Please investigate before retrying: "),
+    (
+        "read the code first",
+        "Please read the relevant code and explain the change before retrying: ",
+    ),
+    ("find a safer way", "Please find a safer way to do this: "),
+];
+
+/// What submitting the input does with the current request.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum FeedbackKind {
+    Deny,
+    Approve,
+    /// Put it off, keeping the text as a note for when it comes back. The
+    /// note stays in the TUI.
+    Defer,
+    /// Approve the command, saving it as a session macro under the text.
+    NameMacro,
+}
+
+pub(crate) struct FeedbackInput {
+    prompt: Arc<dyn Renderable>,
+    theme: ApprovalTheme,
+    kind: FeedbackKind,
+    textarea: TextArea,
+    textarea_state: RefCell<TextAreaState>,
+}
+
+impl FeedbackInput {
+    pub(crate) fn new(prompt: Arc<dyn Renderable>, theme: ApprovalTheme) -> Self {
+        Self {
+            prompt,
+            theme,
+            kind: FeedbackKind::Deny,
+            textarea: TextArea::new(),
+            textarea_state: RefCell::new(TextAreaState::default()),
+        }
+    }
+
+    /// An input whose text goes out with an approval instead.
+    pub(crate) fn approving(mut self) -> Self {
+        self.kind = FeedbackKind::Approve;
+        self
+    }
+
+    /// An input whose text is kept as a note on the deferred request.
+    pub(crate) fn deferring(mut self) -> Self {
+        self.kind = FeedbackKind::Defer;
+        self
+    }
+
+    /// An input whose text names the session macro the approved command is
+    /// saved as.
+    pub(crate) fn naming_macro(mut self) -> Self {
+        self.kind = FeedbackKind::NameMacro;
+        self
+    }
+
+    /// A denial input seeded with the request to investigate first.
+    pub(crate) fn investigating(mut self) -> Self {
+        self.seed_preset(0);
+        self
+    }
+
+    pub(crate) fn kind(&self) -> FeedbackKind {
+        self.kind
+    }
+
+    pub(crate) fn input(&mut self, key_event: KeyEvent) {
+        if let KeyEvent {
+            code: KeyCode::Char(digit),
+            modifiers: KeyModifiers::NONE,
+            kind: KeyEventKind::Press,
+            ..
+        } = key_event
+            && let Some(idx) = self.preset_for(digit)
+        {
+            self.seed_preset(idx);
+            return;
+        }
+        self.textarea.input(key_event);
+    }
+
+    /// The preset `digit` picks, while a denial's input is still empty.
+    fn preset_for(&self, digit: char) -> Option<usize> {
+        if self.kind != FeedbackKind::Deny || !self.textarea.is_empty() {
+            return None;
+        }
+        let idx = usize::try_from(digit.to_digit(10)?.checked_sub(1)?).ok()?;
+        (idx < FEEDBACK_PRESETS.len()).then_some(idx)
+    }
+
+    fn seed_preset(&mut self, idx: usize) {
+        if let Some((_, template)) = FEEDBACK_PRESETS.get(idx) {
+            self.textarea.insert_str(template);
+        }
+    }
+
+    /// The presets a denial can start from, numbered by their keys.
+    fn presets_line(&self) -> Line<'static> {
+        let mut spans = vec!["Start from: ".into()];
+        for (idx, (label, _)) in FEEDBACK_PRESETS.iter().enumerate() {
+            if idx > 0 {
+                spans.push(" · ".into());
+            }
+            spans.push(format!("{} ", idx + 1).fg(self.theme.accent));
+            spans.push((*label).into());
+        }
+        Line::from(spans).dim()
+    }
+
+    pub(crate) fn insert_str(&mut self, text: &str) {
+        self.textarea.insert_str(text);
+    }
+
+    /// The typed feedback, or `None` when only whitespace was entered.
+    pub(crate) fn feedback(&self) -> Option<String> {
+        let text = self.textarea.text().trim();
+        (!text.is_empty()).then(|| text.to_string())
+    }
+
+    fn input_height(&self, width: u16) -> u16 {
+        self.textarea
+            .desired_height(width.saturating_sub(2))
+            .clamp(1, FEEDBACK_INPUT_ROWS)
+    }
+
+    /// Area of the text input inside `area`, below the prompt and a blank row.
+    fn input_area(&self, area: Rect) -> Rect {
+        let prompt_height = self.prompt.desired_height(area.width);
+        let top = area.y.saturating_add(prompt_height).saturating_add(1);
+        Rect {
+            x: area.x.saturating_add(2),
+            y: top.min(area.bottom()),
+            width: area.width.saturating_sub(2),
+            height: self
+                .input_height(area.width)
+                .min(area.bottom().saturating_sub(top)),
+        }
+    }
+}
+
+impl Renderable for FeedbackInput {
+    fn desired_height(&self, width: u16) -> u16 {
+        // Prompt, blank row, input, blank row, presets for a denial, hint.
+        self.prompt.desired_height(width)
+            + 1
+            + self.input_height(width)
+            + 2
+            + u16::from(self.kind == FeedbackKind::Deny)
+    }
+
+    fn render(&self, area: Rect, buf: &mut Buffer) {
+        if area.is_empty() {
+            return;
+        }
+        self.prompt.render(area, buf);
+        let input = self.input_area(area);
+        for row in input.y..input.bottom() {
+            Paragraph::new(Line::from("▌ ".fg(self.theme.accent)))
+                .render(Rect::new(area.x, row, 2, 1), buf);
+        }
+        let mut state = self.textarea_state.borrow_mut();
+        StatefulWidgetRef::render_ref(&(&self.textarea), input, buf, &mut state);
+        if self.textarea.is_empty() {
+            let placeholder = match self.kind {
+                FeedbackKind::Deny => "Tell Codex why you're declining (optional)",
+                FeedbackKind::Approve => "Leave Codex a note with your approval (optional)",
+                FeedbackKind::Defer => "Leave yourself a note for when this comes back (optional)",
+                FeedbackKind::NameMacro => "Name the macro, e.g. check (empty to not save one)",
+            };
+            Paragraph::new(Line::from(placeholder.dim())).render(input, buf);
+        }
+        let mut hint_y = input.bottom().saturating_add(1);
+        if self.kind == FeedbackKind::Deny && hint_y < area.bottom() {
+            Paragraph::new(self.presets_line())
+                .render(Rect::new(area.x, hint_y, area.width, 1), buf);
+            hint_y = hint_y.saturating_add(1);
+        }
+        if hint_y < area.bottom() {
+            let hint = Line::from(vec![
+                "Press ".into(),
+                key_hint::plain(KeyCode::Enter).into(),
+                match self.kind {
+                    FeedbackKind::Deny => " to decline with this feedback or ".into(),
+                    FeedbackKind::Approve => " to approve with this note or ".into(),
+                    FeedbackKind::Defer => " to defer with this note or ".into(),
+                    FeedbackKind::NameMacro => " to approve and save the macro or ".into(),
+                },
+                key_hint::plain(KeyCode::Esc).into(),
+                " to go back".into(),
+            ]);
+            Paragraph::new(hint.dim()).render(Rect::new(area.x, hint_y, area.width, 1), buf);
+        }
+    }
+
+    fn cursor_pos(&self, area: Rect) -> Option<(u16, u16)> {
+        let input = self.input_area(area);
+        if input.is_empty() {
+            return None;
+        }
+        let state = *self.textarea_state.borrow();
+        self.textarea.cursor_pos_with_state(input, state)
+    }
+}
+
+// Inline editor for changing an exec command before approving it.
+//
+// The input starts with the command as the prompt displays it. On submit the
+// text is turned back into an argv: a shell script replaces the script of the
+// original `bash -lc` style invocation, and anything else is split with
+// shell quoting rules, the inverse of [`escape_command`].
+//
+// [`escape_command`]: crate::exec_command::escape_command
+
+/// Most rows the input grows to before it scrolls.
+const EDITOR_INPUT_ROWS: u16 = 5;
+
+pub(crate) struct CommandEditor {
+    prompt: Arc<dyn Renderable>,
+    theme: ApprovalTheme,
+    original: Vec<String>,
+    textarea: TextArea,
+    textarea_state: RefCell<TextAreaState>,
+    /// Set when the last submit could not be parsed, until the next edit.
+    invalid: bool,
+}
+
+impl CommandEditor {
+    pub(crate) fn new(
+        prompt: Arc<dyn Renderable>,
+        original: &[String],
+        theme: ApprovalTheme,
+    ) -> Self {
+        let mut textarea = TextArea::new();
+        textarea.insert_str(&strip_bash_lc_and_escape(original));
+        Self {
+            prompt,
+            theme,
+            original: original.to_vec(),
+            textarea,
+            textarea_state: RefCell::new(TextAreaState::default()),
+            invalid: false,
+        }
+    }
+
+    pub(crate) fn input(&mut self, key_event: KeyEvent) {
+        self.invalid = false;
+        self.textarea.input(key_event);
+    }
+
+    pub(crate) fn insert_str(&mut self, text: &str) {
+        self.invalid = false;
+        self.textarea.insert_str(text);
+    }
+
+    /// The edited command, or `None` (and an error hint) when the text is
+    /// empty or its quoting does not balance.
+    pub(crate) fn submit(&mut self) -> Option<Vec<String>> {
+        let command = edited_command(&self.original, self.textarea.text());
+        self.invalid = command.is_none();
+        command
+    }
+
+    fn input_height(&self, width: u16) -> u16 {
+        self.textarea
+            .desired_height(width.saturating_sub(2))
+            .clamp(1, EDITOR_INPUT_ROWS)
+    }
+
+    /// Area of the text input inside `area`, below the prompt and a blank row.
+    fn input_area(&self, area: Rect) -> Rect {
+        let prompt_height = self.prompt.desired_height(area.width);
+        let top = area.y.saturating_add(prompt_height).saturating_add(1);
+        Rect {
+            x: area.x.saturating_add(2),
+            y: top.min(area.bottom()),
+            width: area.width.saturating_sub(2),
+            height: self
+                .input_height(area.width)
+                .min(area.bottom().saturating_sub(top)),
+        }
+    }
+}
+
+/// Turn the edited text back into an argv shaped like `original`.
+fn edited_command(original: &[String], edited: &str) -> Option<Vec<String>> {
+    let edited = edited.trim();
+    if edited.is_empty() {
+        return None;
+    }
+    if extract_shell_command(original).is_some() {
+        // The shell parses the script, so it is passed through untouched.
+        let mut command = original.to_vec();
+        *command.last_mut()? = edited.to_string();
+        return Some(command);
+    }
+    shlex::split(edited).filter(|words| !words.is_empty())
+}
+
+impl Renderable for CommandEditor {
+    fn desired_height(&self, width: u16) -> u16 {
+        // Prompt, blank row, input, blank row, hint.
+        self.prompt.desired_height(width) + 1 + self.input_height(width) + 2
+    }
+
+    fn render(&self, area: Rect, buf: &mut Buffer) {
+        if area.is_empty() {
+            return;
+        }
+        self.prompt.render(area, buf);
+        let input = self.input_area(area);
+        for row in input.y..input.bottom() {
+            Paragraph::new(Line::from("$ ".fg(self.theme.accent)))
+                .render(Rect::new(area.x, row, 2, 1), buf);
+        }
+        let mut state = self.textarea_state.borrow_mut();
+        StatefulWidgetRef::render_ref(&(&self.textarea), input, buf, &mut state);
+        let hint_y = input.bottom().saturating_add(1);
+        if hint_y < area.bottom() {
+            let hint = if self.invalid {
+                Line::from("Could not parse the command; check its quoting".fg(self.theme.warning))
+            } else {
+                Line::from(vec![
+                    "Press ".into(),
+                    key_hint::plain(KeyCode::Enter).into(),
+                    " to run the edited command or ".into(),
+                    key_hint::plain(KeyCode::Esc).into(),
+                    " to go back".into(),
+                ])
+                .dim()
+            };
+            Paragraph::new(hint).render(Rect::new(area.x, hint_y, area.width, 1), buf);
+        }
+    }
+
+    fn cursor_pos(&self, area: Rect) -> Option<(u16, u16)> {
+        let input = self.input_area(area);
+        if input.is_empty() {
+            return None;
+        }
+        let state = *self.textarea_state.borrow();
+        self.textarea.cursor_pos_with_state(input, state)
+    }
+}
+
+// Picking how long an approval "for a while" lasts.
+//
+// Choosing that option replaces the option list with a row of preset
+// durations. The overlay owns the key routing: `1`–`3` approve for that
+// preset at once, ←/→ (or `h`/`l`) move between them and Enter approves for
+// the highlighted one, and Esc returns to the options without answering.
+
+/// The durations offered, with the label each button shows.
+const DURATION_PRESETS: [(Duration, &str); 3] = [
+    (Duration::from_secs(5 * 60), "5m"),
+    (Duration::from_secs(30 * 60), "30m"),
+    (Duration::from_secs(60 * 60), "1h"),
+];
+
+pub(crate) struct DurationPicker {
+    prompt: Arc<dyn Renderable>,
+    theme: ApprovalTheme,
+    selected: usize,
+}
+
+impl DurationPicker {
+    /// A picker with `offered` highlighted, or the middle preset when it is
+    /// not one of them.
+    pub(crate) fn new(
+        prompt: Arc<dyn Renderable>,
+        offered: Duration,
+        theme: ApprovalTheme,
+    ) -> Self {
+        let selected = DURATION_PRESETS
+            .iter()
+            .position(|(duration, _)| *duration == offered)
+            .unwrap_or(1);
+        Self {
+            prompt,
+            theme,
+            selected,
+        }
+    }
+
+    pub(crate) fn select_previous(&mut self) {
+        self.selected = self.selected.saturating_sub(1);
+    }
+
+    pub(crate) fn select_next(&mut self) {
+        self.selected = (self.selected + 1).min(DURATION_PRESETS.len() - 1);
+    }
+
+    pub(crate) fn selected(&self) -> Duration {
+        DURATION_PRESETS[self.selected].0
+    }
+
+    /// The preset `digit` picks, counting from `1`.
+    pub(crate) fn preset(digit: char) -> Option<Duration> {
+        let idx = digit.to_digit(10)?.checked_sub(1)?;
+        DURATION_PRESETS
+            .get(usize::try_from(idx).ok()?)
+            .map(|(duration, _)| *duration)
+    }
+
+    fn buttons(&self) -> Line<'static> {
+        let mut spans: Vec<Span<'static>> = Vec::new();
+        for (idx, (_, label)) in DURATION_PRESETS.iter().enumerate() {
+            if idx > 0 {
+                spans.push("  ".into());
+            }
+            let button = format!(" {}. {label} ", idx + 1);
+            spans.push(if idx == self.selected {
+                button.fg(self.theme.accent).bold().reversed()
+            } else {
+                button.into()
+            });
+        }
+        Line::from(spans)
+    }
+}
+
+impl Renderable for DurationPicker {
+    fn desired_height(&self, width: u16) -> u16 {
+        // Prompt, blank row, buttons, blank row, hint.
+        self.prompt.desired_height(width) + 4
+    }
+
+    fn render(&self, area: Rect, buf: &mut Buffer) {
+        if area.is_empty() {
+            return;
+        }
+        self.prompt.render(area, buf);
+        let top = area
+            .y
+            .saturating_add(self.prompt.desired_height(area.width))
+            .saturating_add(1);
+        let hint = Line::from(vec![
+            "Approve for how long? ".into(),
+            key_hint::plain(KeyCode::Enter).into(),
+            " to approve, ".into(),
+            key_hint::plain(KeyCode::Esc).into(),
+            " to go back".into(),
+        ])
+        .dim();
+        let x = area.x.saturating_add(2);
+        let width = area.width.saturating_sub(2);
+        for (row, line) in [(top, self.buttons()), (top.saturating_add(2), hint)] {
+            if row < area.bottom() {
+                Paragraph::new(line).render(Rect::new(x, row, width, 1), buf);
+            }
+        }
+    }
+}
+
+/// `duration` for the transcript, such as "30 minutes" or "1 hour".
+pub(crate) fn format_approval_duration(duration: Duration) -> String {
+    let minutes = duration.as_secs() / 60;
+    if minutes >= 60 && minutes % 60 == 0 {
+        let hours = minutes / 60;
+        format!("{hours} {}", if hours == 1 { "hour" } else { "hours" })
+    } else if minutes > 0 {
+        format!(
+            "{minutes} {}",
+            if minutes == 1 { "minute" } else { "minutes" }
+        )
+    } else {
+        let seconds = duration.as_secs();
+        format!(
+            "{seconds} {}",
+            if seconds == 1 { "second" } else { "seconds" }
+        )
+    }
+}
+
+// Approving only some of a multi-file patch.
+//
+// A patch that touches more than one file offers "Yes, but pick which files
+// to apply". Choosing it replaces the option list with a checklist of the
+// changed files, all checked. ↑/↓ move, space toggles the highlighted file,
+// Enter approves the checked files and Esc returns to the options. Core
+// applies the approved files and skips the rest of the patch; approving with
+// nothing checked declines it.
+
+pub(crate) struct FilePicker {
+    prompt: Arc<dyn Renderable>,
+    theme: ApprovalTheme,
+    cwd: PathBuf,
+    files: Vec<PathBuf>,
+    included: HashSet<PathBuf>,
+    cursor: usize,
+}
+
+impl FilePicker {
+    /// A checklist of `files`, sorted and all checked, shown relative to
+    /// `cwd`.
+    pub(crate) fn new(
+        prompt: Arc<dyn Renderable>,
+        files: impl IntoIterator<Item = PathBuf>,
+        cwd: &Path,
+        theme: ApprovalTheme,
+    ) -> Self {
+        let mut files: Vec<PathBuf> = files.into_iter().collect();
+        files.sort();
+        Self {
+            prompt,
+            theme,
+            cwd: cwd.to_path_buf(),
+            included: files.iter().cloned().collect(),
+            files,
+            cursor: 0,
+        }
+    }
+
+    pub(crate) fn select_previous(&mut self) {
+        self.cursor = self.cursor.saturating_sub(1);
+    }
+
+    pub(crate) fn select_next(&mut self) {
+        self.cursor = (self.cursor + 1).min(self.files.len().saturating_sub(1));
+    }
+
+    /// Check or uncheck the highlighted file.
+    pub(crate) fn toggle(&mut self) {
+        let Some(path) = self.files.get(self.cursor) else {
+            return;
+        };
+        if !self.included.remove(path) {
+            self.included.insert(path.clone());
+        }
+    }
+
+    /// The checked files, in list order, or `None` while every file is
+    /// checked.
+    pub(crate) fn included_files(&self) -> Option<Vec<PathBuf>> {
+        (self.included.len() < self.files.len()).then(|| {
+            self.files
+                .iter()
+                .filter(|path| self.included.contains(*path))
+                .cloned()
+                .collect()
+        })
+    }
+
+    fn row(&self, idx: usize, path: &Path) -> Line<'static> {
+        let check = if self.included.contains(path) {
+            "[x]"
+        } else {
+            "[ ]"
+        };
+        let shown = path.strip_prefix(&self.cwd).unwrap_or(path);
+        let row = format!("{check} {}", shown.display());
+        if idx == self.cursor {
+            Line::from(row.fg(self.theme.accent).bold())
+        } else {
+            Line::from(row)
+        }
+    }
+}
+
+impl Renderable for FilePicker {
+    fn desired_height(&self, width: u16) -> u16 {
+        // Prompt, blank row, one row per file, blank row, hint.
+        let files = u16::try_from(self.files.len()).unwrap_or(u16::MAX);
+        self.prompt
+            .desired_height(width)
+            .saturating_add(files)
+            .saturating_add(3)
+    }
+
+    fn render(&self, area: Rect, buf: &mut Buffer) {
+        if area.is_empty() {
+            return;
+        }
+        self.prompt.render(area, buf);
+        let top = area
+            .y
+            .saturating_add(self.prompt.desired_height(area.width))
+            .saturating_add(1);
+        let hint = Line::from(vec![
+            key_hint::plain(KeyCode::Char(' ')).into(),
+            " to toggle, ".into(),
+            key_hint::plain(KeyCode::Enter).into(),
+            " to apply the checked files, ".into(),
+            key_hint::plain(KeyCode::Esc).into(),
+            " to go back".into(),
+        ])
+        .dim();
+        let rows = self
+            .files
+            .iter()
+            .enumerate()
+            .map(|(idx, path)| self.row(idx, path))
+            .chain([Line::from(""), hint]);
+        let x = area.x.saturating_add(2);
+        let width = area.width.saturating_sub(2);
+        for (row, line) in (top..area.bottom()).zip(rows) {
+            Paragraph::new(line).render(Rect::new(x, row, width, 1), buf);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn type_text(input: &mut FeedbackInput, text: &str) {
+        for c in text.chars() {
+            input.input(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE));
+        }
+    }
+
+    #[test]
+    fn blank_feedback_is_none() {
+        let mut input =
+            FeedbackInput::new(Arc::new(Line::from("prompt")), ApprovalTheme::default());
+        assert_eq!(input.feedback(), None);
+        type_text(&mut input, "   ");
+        assert_eq!(input.feedback(), None);
+        type_text(&mut input, "use the staging db ");
+        assert_eq!(input.feedback(), Some("use the staging db".to_string()));
+    }
+
+    #[test]
+    fn digits_seed_a_denial_only_while_it_is_empty() {
+        let theme = ApprovalTheme::default();
+        let mut input = FeedbackInput::new(Arc::new(Line::from("prompt")), theme);
+        type_text(&mut input, "3");
+        type_text(&mut input, "the rm");
+        assert_eq!(
+            input.feedback(),
+            Some("Please find a safer way to do this: the rm".to_string())
+        );
+
+        let mut input = FeedbackInput::new(Arc::new(Line::from("prompt")), theme);
+        type_text(&mut input, "x1");
+        assert_eq!(input.feedback(), Some("x1".to_string()));
+
+        let mut note = FeedbackInput::new(Arc::new(Line::from("prompt")), theme).approving();
+        type_text(&mut note, "2 tests");
+        assert_eq!(note.feedback(), Some("2 tests".to_string()));
+    }
+
+    fn argv(words: &[&str]) -> Vec<String> {
+        words.iter().map(|word| word.to_string()).collect()
+    }
+
+    #[test]
+    fn plain_commands_are_split_with_quotes() {
+        assert_eq!(
+            edited_command(&argv(&["git", "commit"]), "git commit -m 'fix the \"bug\"'"),
+            Some(argv(&["git", "commit", "-m", "fix the \"bug\""]))
+        );
+        assert_eq!(edited_command(&argv(&["echo"]), "echo 'unbalanced"), None);
+        assert_eq!(edited_command(&argv(&["echo"]), "   "), None);
+    }
+
+    #[test]
+    fn shell_scripts_replace_only_the_script() {
+        assert_eq!(
+            edited_command(
+                &argv(&["bash", "-lc", "ls | wc -l"]),
+                "ls -a | grep 'it''s' | wc -l"
+            ),
+            Some(argv(&["bash", "-lc", "ls -a | grep 'it''s' | wc -l"]))
+        );
+    }
+
+    #[test]
+    fn editor_starts_with_the_displayed_command() {
+        let mut editor = CommandEditor::new(
+            Arc::new(Line::from("prompt")),
+            &argv(&["echo", "hello world"]),
+            ApprovalTheme::default(),
+        );
+        assert_eq!(editor.textarea.text(), "echo 'hello world'");
+        assert_eq!(editor.submit(), Some(argv(&["echo", "hello world"])));
+    }
+
+    #[test]
+    fn digits_pick_presets_and_arrows_stop_at_the_ends() {
+        assert_eq!(DurationPicker::preset('1'), Some(Duration::from_secs(300)));
+        assert_eq!(DurationPicker::preset('3'), Some(Duration::from_secs(3600)));
+        assert_eq!(DurationPicker::preset('0'), None);
+        assert_eq!(DurationPicker::preset('4'), None);
+
+        let prompt = Arc::new(Line::from("prompt"));
+        let mut picker =
+            DurationPicker::new(prompt, Duration::from_secs(42), ApprovalTheme::default());
+        assert_eq!(picker.selected(), Duration::from_secs(30 * 60));
+        picker.select_next();
+        picker.select_next();
+        assert_eq!(picker.selected(), Duration::from_secs(60 * 60));
+        for _ in 0..3 {
+            picker.select_previous();
+        }
+        assert_eq!(picker.selected(), Duration::from_secs(5 * 60));
+    }
+
+    #[test]
+    fn durations_read_as_words() {
+        assert_eq!(
+            format_approval_duration(Duration::from_secs(300)),
+            "5 minutes"
+        );
+        assert_eq!(
+            format_approval_duration(Duration::from_secs(3600)),
+            "1 hour"
+        );
+        assert_eq!(
+            format_approval_duration(Duration::from_secs(5400)),
+            "90 minutes"
+        );
+        assert_eq!(
+            format_approval_duration(Duration::from_secs(45)),
+            "45 seconds"
+        );
+    }
+
+    #[test]
+    fn unchecked_files_are_left_out_and_all_checked_means_all() {
+        let prompt = Arc::new(Line::from("prompt"));
+        let files = ["/repo/b.rs", "/repo/a.rs", "/repo/c.rs"].map(PathBuf::from);
+        let mut picker =
+            FilePicker::new(prompt, files, Path::new("/repo"), ApprovalTheme::default());
+        assert_eq!(picker.included_files(), None);
+
+        picker.select_next();
+        picker.toggle();
+        assert_eq!(
+            picker.included_files(),
+            Some(vec![
+                PathBuf::from("/repo/a.rs"),
+                PathBuf::from("/repo/c.rs")
+            ])
+        );
+        assert_eq!(
+            picker.row(1, Path::new("/repo/b.rs")).to_string(),
+            "[ ] b.rs"
+        );
+
+        picker.toggle();
+        assert_eq!(picker.included_files(), None);
+    }
+}
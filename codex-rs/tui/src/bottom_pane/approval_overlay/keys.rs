@@ -0,0 +1,796 @@
+//! Key handling for the option list.
+//!
+//! Keys reach the options once no inline input has claimed them. Before an
+//! option is chosen, repeated presses are dropped, Enter on a risky prompt
+//! is held back, and the most dangerous prompts wait for the warning to be
+//! acknowledged. Copying the command or patch lives here too.
+
+use super::ApprovalOverlay;
+use super::ApprovalRequest;
+use super::prompt::bounded_argv;
+use super::prompt::collapses;
+use super::prompt::redact_secrets;
+use super::prompt::request_age_note;
+use super::prompt::request_reason;
+use super::render::ApprovalTheme;
+use crate::app_event::AppEvent;
+use crate::bottom_pane::BottomPaneView;
+use crate::exec_command::strip_bash_lc_and_escape;
+use codex_protocol::protocol::FileChange;
+use codex_protocol::protocol::ReviewDecision;
+use crossterm::event::KeyCode;
+use crossterm::event::KeyEvent;
+use crossterm::event::KeyEventKind;
+use crossterm::event::KeyModifiers;
+use ratatui::style::Stylize;
+use ratatui::text::Line;
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::path::Path;
+use std::path::PathBuf;
+use std::time::Duration;
+use std::time::Instant;
+
+impl ApprovalOverlay {
+    /// Answer, navigate or act on `key_event` while the options are shown.
+    pub(crate) fn handle_option_key(&mut self, key_event: KeyEvent) {
+        let key_event = with_shift_for_uppercase(key_event);
+        // Holding a key repeats it; only navigation may repeat, so a held
+        // Enter or hotkey cannot answer the prompts queued behind this one.
+        let repeats = matches!(
+            key_event.code,
+            KeyCode::Up
+                | KeyCode::Down
+                | KeyCode::Left
+                | KeyCode::Right
+                | KeyCode::PageUp
+                | KeyCode::PageDown
+        );
+        match key_event.kind {
+            KeyEventKind::Press => {}
+            KeyEventKind::Repeat if repeats => {}
+            KeyEventKind::Repeat | KeyEventKind::Release => return,
+        }
+        if self.options.is_empty() {
+            // Nothing to choose from makes the prompt informational, so any
+            // key dismisses it the way Esc would.
+            self.on_ctrl_c();
+            return;
+        }
+        if self.abort_pending.replace(false) {
+            // Any key other than a second Esc keeps the prompt open.
+            if key_event.code == KeyCode::Esc {
+                self.on_ctrl_c();
+            }
+            return;
+        }
+        if self.show_all_descriptions {
+            let answers = self
+                .options
+                .iter()
+                .any(|opt| opt.shortcuts().any(|s| s.is_press(key_event)));
+            if !answers {
+                if matches!(key_event.code, KeyCode::Char('?') | KeyCode::Esc) {
+                    self.show_all_descriptions = false;
+                }
+                return;
+            }
+            self.show_all_descriptions = false;
+        }
+        if self.warning_gate.holds_options() {
+            // Until the warning is acknowledged only Esc and scrolling get
+            // through; space enables the options.
+            match key_event.code {
+                KeyCode::Char(' ') if key_event.modifiers == KeyModifiers::NONE => {
+                    // Recorded so a doubled press cannot go on to confirm.
+                    self.is_duplicate_press(&key_event);
+                    self.acknowledge_warning();
+                    return;
+                }
+                KeyCode::Esc | KeyCode::PageUp | KeyCode::PageDown => {}
+                _ => return,
+            }
+        }
+        if key_event.code == KeyCode::Esc && self.config.confirm_abort.unwrap_or(false) {
+            self.abort_pending.set(true);
+            return;
+        }
+        if self.is_duplicate_press(&key_event) {
+            return;
+        }
+        if self.try_handle_shortcut(&key_event) {
+            return;
+        }
+        // Option hotkeys win above, so `h`/`l` only move when no option claims
+        // them. The options stack vertically, so Left/Right step like Up/Down.
+        let key_event = match key_event {
+            KeyEvent {
+                code: KeyCode::Char('h'),
+                modifiers: KeyModifiers::NONE,
+                kind,
+                ..
+            }
+            | KeyEvent {
+                code: KeyCode::Left,
+                modifiers: KeyModifiers::NONE,
+                kind,
+                ..
+            } => KeyEvent::new_with_kind(KeyCode::Up, KeyModifiers::NONE, kind),
+            KeyEvent {
+                code: KeyCode::Char('l'),
+                modifiers: KeyModifiers::NONE,
+                kind,
+                ..
+            }
+            | KeyEvent {
+                code: KeyCode::Right,
+                modifiers: KeyModifiers::NONE,
+                kind,
+                ..
+            } => KeyEvent::new_with_kind(KeyCode::Down, KeyModifiers::NONE, kind),
+            // Space confirms like Enter. The warning gate and compact mode,
+            // which give space a meaning of their own, consumed it above.
+            KeyEvent {
+                code: KeyCode::Char(' '),
+                modifiers: KeyModifiers::NONE,
+                kind,
+                ..
+            } => KeyEvent::new_with_kind(KeyCode::Enter, KeyModifiers::NONE, kind),
+            other => other,
+        };
+        // Number keys select and confirm in one press, so they cannot approve
+        // when an explicit selection is required.
+        if self.explicit_approval
+            && let KeyCode::Char(c) = key_event.code
+            && c.to_digit(10)
+                .and_then(|digit| (digit as usize).checked_sub(1))
+                .and_then(|idx| self.options.get(idx))
+                .is_some_and(|opt| opt.decision.is_approval())
+        {
+            return;
+        }
+        if key_event.code != KeyCode::Enter {
+            self.note_interaction();
+        } else if !self.enter_guard.allows_enter() {
+            self.list
+                .set_footer_note(self.enter_guard.note(&self.theme));
+            return;
+        }
+        self.list.handle_key_event(key_event);
+        if let Some(idx) = self.list.take_last_selected_index() {
+            self.apply_selection(idx);
+        }
+    }
+
+    fn try_handle_shortcut(&mut self, key_event: &KeyEvent) -> bool {
+        match key_event {
+            KeyEvent {
+                kind: KeyEventKind::Press,
+                code: KeyCode::Char('a'),
+                modifiers,
+                ..
+            } if modifiers.contains(KeyModifiers::CONTROL) => {
+                if let Some(request) = self.current_request.as_ref() {
+                    self.app_event_tx
+                        .send(AppEvent::FullScreenApprovalRequest(request.clone()));
+                    true
+                } else {
+                    false
+                }
+            }
+            KeyEvent {
+                kind: KeyEventKind::Press,
+                code: KeyCode::Char('o'),
+                ..
+            } => {
+                if let Some(request) = self.current_request.as_ref() {
+                    if request.thread_label().is_some() {
+                        self.app_event_tx
+                            .send(AppEvent::SelectAgentThread(request.thread_id()));
+                        true
+                    } else {
+                        false
+                    }
+                } else {
+                    false
+                }
+            }
+            KeyEvent {
+                kind: KeyEventKind::Press,
+                code: KeyCode::Char('u'),
+                modifiers: KeyModifiers::NONE,
+                ..
+            } => {
+                if self
+                    .current_request
+                    .as_ref()
+                    .and_then(request_reason)
+                    .is_some()
+                {
+                    self.reason_expanded.set(!self.reason_expanded.get());
+                    if let Some(prompt) = &self.prompt {
+                        prompt.invalidate();
+                    }
+                    true
+                } else {
+                    false
+                }
+            }
+            KeyEvent {
+                kind: KeyEventKind::Press,
+                code: KeyCode::Char('?'),
+                ..
+            } => {
+                self.show_all_descriptions = true;
+                true
+            }
+            KeyEvent {
+                kind: KeyEventKind::Press,
+                code: KeyCode::Char('m'),
+                ..
+            } => {
+                // The transcript only holds the active thread, so requests from
+                // other threads have no source message to show.
+                let id = match self.current_request.as_ref() {
+                    Some(ApprovalRequest::Exec {
+                        id,
+                        thread_label: None,
+                        ..
+                    })
+                    | Some(ApprovalRequest::ApplyPatch {
+                        id,
+                        thread_label: None,
+                        ..
+                    }) => id.clone(),
+                    Some(
+                        ApprovalRequest::Exec { .. }
+                        | ApprovalRequest::ApplyPatch { .. }
+                        | ApprovalRequest::Permissions { .. }
+                        | ApprovalRequest::McpElicitation { .. },
+                    )
+                    | None => return false,
+                };
+                self.app_event_tx.send(AppEvent::ShowApprovalSource { id });
+                true
+            }
+            KeyEvent {
+                kind: KeyEventKind::Press,
+                code: KeyCode::Char('e'),
+                modifiers: KeyModifiers::NONE,
+                ..
+            } => self.open_feedback_input(),
+            KeyEvent {
+                kind: KeyEventKind::Press,
+                code: KeyCode::Char('g'),
+                modifiers: KeyModifiers::NONE,
+                ..
+            } => self.open_investigate_input(),
+            KeyEvent {
+                kind: KeyEventKind::Press,
+                code: KeyCode::Char('w'),
+                modifiers: KeyModifiers::NONE,
+                ..
+            } if !self.explicit_approval => self.open_approval_note_input(),
+            KeyEvent {
+                kind: KeyEventKind::Press,
+                code: KeyCode::Char('c'),
+                modifiers: KeyModifiers::NONE,
+                ..
+            } if matches!(
+                self.current_request,
+                Some(ApprovalRequest::Exec { .. } | ApprovalRequest::ApplyPatch { .. })
+            ) =>
+            {
+                let Some(text) = self.current_request.as_ref().and_then(copy_text) else {
+                    return false;
+                };
+                self.app_event_tx.send(AppEvent::CopyToClipboard(text));
+                self.copied.show();
+                true
+            }
+            KeyEvent {
+                kind: KeyEventKind::Press,
+                code: KeyCode::Char('f'),
+                modifiers: KeyModifiers::NONE,
+                ..
+            } => {
+                let Some(ApprovalRequest::Exec {
+                    command,
+                    network_approval_context: None,
+                    ..
+                }) = self.current_request.as_ref()
+                else {
+                    return false;
+                };
+                let shown = redact_secrets(&strip_bash_lc_and_escape(&bounded_argv(command)));
+                if !collapses(&shown) {
+                    return false;
+                }
+                self.full_command_shown.set(!self.full_command_shown.get());
+                if let Some(prompt) = &self.prompt {
+                    prompt.invalidate();
+                }
+                true
+            }
+            KeyEvent {
+                kind: KeyEventKind::Press,
+                code: KeyCode::Tab,
+                modifiers: KeyModifiers::NONE,
+                ..
+            } => self.defer_current(None),
+            KeyEvent {
+                kind: KeyEventKind::Press,
+                code: KeyCode::BackTab,
+                ..
+            } => self.open_defer_note_input(),
+            KeyEvent {
+                kind: KeyEventKind::Press,
+                code: KeyCode::Char('R'),
+                ..
+            } => {
+                let Some(ApprovalRequest::Exec {
+                    network_approval_context: None,
+                    ..
+                }) = self.current_request.as_ref()
+                else {
+                    return false;
+                };
+                self.raw_command_shown.set(!self.raw_command_shown.get());
+                if let Some(prompt) = &self.prompt {
+                    prompt.invalidate();
+                }
+                true
+            }
+            KeyEvent {
+                kind: KeyEventKind::Press,
+                code: KeyCode::Char(' '),
+                modifiers: KeyModifiers::NONE,
+                ..
+            } if self.config.compact.unwrap_or(false) => {
+                self.expanded = !self.expanded;
+                true
+            }
+            KeyEvent {
+                kind: KeyEventKind::Press,
+                code: KeyCode::Char('v'),
+                modifiers: KeyModifiers::NONE,
+                ..
+            } => {
+                // The request stays pending; the pager only suspends the TUI.
+                let Some(text) = self.current_request.as_ref().and_then(copy_text) else {
+                    return false;
+                };
+                self.app_event_tx.send(AppEvent::OpenInPager(text));
+                true
+            }
+            KeyEvent {
+                kind: KeyEventKind::Press | KeyEventKind::Repeat,
+                code: KeyCode::PageDown,
+                ..
+            } => self.prompt_scroll.page_down(),
+            KeyEvent {
+                kind: KeyEventKind::Press | KeyEventKind::Repeat,
+                code: KeyCode::PageUp,
+                ..
+            } => self.prompt_scroll.page_up(),
+            KeyEvent {
+                kind: KeyEventKind::Press,
+                code: KeyCode::Char('Y'),
+                ..
+            } if !self.explicit_approval => {
+                self.app_event_tx
+                    .send(AppEvent::ApproveAllPending(ReviewDecision::Approved));
+                true
+            }
+            KeyEvent {
+                kind: KeyEventKind::Press,
+                code: KeyCode::Char('.'),
+                ..
+            } => {
+                if let Some(idx) = self.replay_idx {
+                    self.apply_selection(idx);
+                    true
+                } else {
+                    false
+                }
+            }
+            e => {
+                if let Some(idx) = self
+                    .options
+                    .iter()
+                    .position(|opt| opt.shortcuts().any(|s| s.is_press(*e)))
+                {
+                    self.apply_selection(idx);
+                    true
+                } else {
+                    false
+                }
+            }
+        }
+    }
+
+    /// Whether `key_event` echoes the press just before it, for the keys whose
+    /// second press would move the selection or answer again.
+    pub(crate) fn is_duplicate_press(&mut self, key_event: &KeyEvent) -> bool {
+        if key_event.kind != KeyEventKind::Press {
+            return false;
+        }
+        let duplicate = self
+            .key_debounce
+            .is_duplicate(key_event.code, Instant::now());
+        let moves_or_answers = matches!(
+            key_event.code,
+            KeyCode::Up
+                | KeyCode::Down
+                | KeyCode::Left
+                | KeyCode::Right
+                | KeyCode::PageUp
+                | KeyCode::PageDown
+                | KeyCode::Enter
+                | KeyCode::Char('h' | 'l' | '0'..='9')
+        ) || (key_event.code == KeyCode::Char(' ')
+            && !self.config.compact.unwrap_or(false))
+            || self.options.iter().any(|opt| {
+                opt.shortcuts()
+                    .any(|shortcut| shortcut.is_press(*key_event))
+            });
+        duplicate && moves_or_answers
+    }
+
+    /// Enable the options held back by the warning gate.
+    fn acknowledge_warning(&mut self) {
+        if self.warning_gate.acknowledge() {
+            self.list.set_items_disabled(false);
+            self.list
+                .set_footer_note(request_age_note(self.issued_at.elapsed()));
+        }
+    }
+
+    /// Let Enter confirm from now on, clearing the note an ignored Enter left.
+    pub(crate) fn note_interaction(&mut self) {
+        if self.enter_guard.interact() {
+            self.list
+                .set_footer_note(request_age_note(self.issued_at.elapsed()));
+        }
+    }
+}
+
+/// `key_event` with Shift set for an uppercase letter. Terminals differ on
+/// whether they report it, and the options bind uppercase keys with Shift.
+fn with_shift_for_uppercase(mut key_event: KeyEvent) -> KeyEvent {
+    if let KeyCode::Char(c) = key_event.code
+        && c.is_uppercase()
+    {
+        key_event.modifiers |= KeyModifiers::SHIFT;
+    }
+    key_event
+}
+
+// Dropping the second of two identical presses that arrive together.
+//
+// Some terminals report a single keypress as two `Press` events a moment
+// apart. For a navigation key that steps the selection twice; for an answer
+// it would answer the prompt and then the one queued behind it. No one
+// presses a key twice within [`DUPLICATE_WINDOW`], so the second press is
+// dropped. Held keys arrive as `Repeat` events and are not affected.
+
+/// Presses of the same key closer together than this count as one.
+const DUPLICATE_WINDOW: Duration = Duration::from_millis(10);
+
+#[derive(Default)]
+pub(crate) struct KeyDebounce {
+    last_key: Option<(KeyCode, Instant)>,
+}
+
+impl KeyDebounce {
+    /// Record a press of `code` at `now`; returns whether it repeats the
+    /// previous press too soon to be a second keypress.
+    pub(crate) fn is_duplicate(&mut self, code: KeyCode, now: Instant) -> bool {
+        let duplicate = self.last_key.is_some_and(|(last, at)| {
+            last == code && now.saturating_duration_since(at) < DUPLICATE_WINDOW
+        });
+        self.last_key = Some((code, now));
+        duplicate
+    }
+}
+
+// Holding back Enter on a risky prompt until an option is chosen on purpose.
+//
+// Risky requests already preselect "No", but with
+// `tui.approvals.require_explicit_select` a stray Enter does not even
+// decline: it is ignored, with a note above the footer, until the user has
+// moved the selection or pressed a shortcut.
+
+const SELECT_FIRST: &str = "Select an option first: move with ↑/↓, then press Enter";
+
+#[derive(Default)]
+pub(crate) struct EnterGuard {
+    /// Set for a risky request while the option is on.
+    armed: bool,
+    /// Set once the user moved the selection or pressed a key other than
+    /// Enter.
+    has_interacted: bool,
+    /// Set by an ignored Enter, so the note shows until the next interaction.
+    refused: bool,
+}
+
+impl EnterGuard {
+    pub(crate) fn new(armed: bool) -> Self {
+        Self {
+            armed,
+            ..Self::default()
+        }
+    }
+
+    /// Record an interaction. Returns whether the note was showing.
+    pub(crate) fn interact(&mut self) -> bool {
+        self.has_interacted = true;
+        std::mem::take(&mut self.refused)
+    }
+
+    /// Whether Enter may confirm the selected option now.
+    pub(crate) fn allows_enter(&mut self) -> bool {
+        self.refused = self.armed && !self.has_interacted;
+        !self.refused
+    }
+
+    /// The note after an ignored Enter.
+    pub(crate) fn note(&self, theme: &ApprovalTheme) -> Option<Line<'static>> {
+        self.refused
+            .then(|| Line::from(SELECT_FIRST.fg(theme.warning)))
+    }
+}
+
+// Holding the options back on the most dangerous prompts until the warning
+// has been read.
+//
+// With `tui.approvals.acknowledge_warnings`, a destructive command or a
+// high-risk patch opens with its options dimmed and deaf to their keys.
+// Space acknowledges the warning and enables them; Esc still dismisses the
+// request.
+
+const ACKNOWLEDGE: &str = "Press space to acknowledge the warning";
+
+#[derive(Default)]
+pub(crate) struct WarningGate {
+    /// Set for a high-risk request while the option is on.
+    armed: bool,
+    /// Set once space has been pressed.
+    warning_acknowledged: bool,
+}
+
+impl WarningGate {
+    pub(crate) fn new(armed: bool) -> Self {
+        Self {
+            armed,
+            ..Self::default()
+        }
+    }
+
+    /// Whether the options still ignore their keys.
+    pub(crate) fn holds_options(&self) -> bool {
+        self.armed && !self.warning_acknowledged
+    }
+
+    /// Record the acknowledgement. Returns whether it enabled the options.
+    pub(crate) fn acknowledge(&mut self) -> bool {
+        let held = self.holds_options();
+        self.warning_acknowledged = true;
+        held
+    }
+
+    /// The prompt shown above the footer while the options are held.
+    pub(crate) fn prompt(&self, theme: &ApprovalTheme) -> Option<Line<'static>> {
+        self.holds_options()
+            .then(|| Line::from(ACKNOWLEDGE.fg(theme.warning).bold()))
+    }
+}
+
+// `c` copies the pending command or patch so it can be tried elsewhere.
+//
+// Exec requests copy the command as displayed in the prompt; patch requests
+// copy a unified diff of every change. [`CopiedHint`] keeps a short
+// confirmation next to the prompt title after a copy.
+//
+// With `tui.approvals.copy_on_approve`, approving a command also copies it,
+// as [`shell_quoted`] argv, so it can be run again by hand.
+
+/// How long the confirmation stays next to the title.
+const HINT_DURATION: Duration = Duration::from_secs(2);
+
+#[derive(Default)]
+pub(crate) struct CopiedHint {
+    shown_until: Cell<Option<Instant>>,
+}
+
+impl CopiedHint {
+    pub(crate) fn show(&self) {
+        self.shown_until.set(Some(Instant::now() + HINT_DURATION));
+    }
+
+    /// Time until the hint disappears, or `None` once it is gone.
+    pub(crate) fn remaining(&self) -> Option<Duration> {
+        let remaining = self
+            .shown_until
+            .get()?
+            .saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            self.shown_until.set(None);
+            return None;
+        }
+        Some(remaining)
+    }
+}
+
+/// Text copied for `request`, or `None` for requests without a command or patch.
+pub(crate) fn copy_text(request: &ApprovalRequest) -> Option<String> {
+    match request {
+        ApprovalRequest::Exec { command, .. } => Some(strip_bash_lc_and_escape(command)),
+        ApprovalRequest::ApplyPatch { cwd, changes, .. } => Some(patch_diff(cwd, changes)),
+        ApprovalRequest::Permissions { .. } | ApprovalRequest::McpElicitation { .. } => None,
+    }
+}
+
+/// `command` quoted for a POSIX shell, so pasting it runs the argv codex
+/// runs, `bash -lc` wrapper included. `None` when an argument holds a NUL
+/// byte, which no shell word can carry.
+pub(crate) fn shell_quoted(command: &[String]) -> Option<String> {
+    shlex::try_join(command.iter().map(String::as_str)).ok()
+}
+
+/// Render `changes` as one unified diff, with paths relative to `cwd`.
+pub(crate) fn patch_diff(cwd: &Path, changes: &HashMap<PathBuf, FileChange>) -> String {
+    let mut paths: Vec<&PathBuf> = changes.keys().collect();
+    paths.sort();
+    let display = |path: &Path| path.strip_prefix(cwd).unwrap_or(path).display().to_string();
+    let mut diff = String::new();
+    for path in paths {
+        let name = display(path);
+        match &changes[path] {
+            FileChange::Add { content } => {
+                diff.push_str(&format!("--- /dev/null\n+++ b/{name}\n"));
+                push_hunk(&mut diff, '+', content);
+            }
+            FileChange::Delete { content } => {
+                diff.push_str(&format!("--- a/{name}\n+++ /dev/null\n"));
+                push_hunk(&mut diff, '-', content);
+            }
+            FileChange::Update {
+                unified_diff,
+                move_path,
+            } => {
+                let new_name = move_path.as_deref().map_or_else(|| name.clone(), display);
+                diff.push_str(&format!("--- a/{name}\n+++ b/{new_name}\n"));
+                diff.push_str(unified_diff);
+                if !unified_diff.ends_with('\n') {
+                    diff.push('\n');
+                }
+            }
+        }
+    }
+    diff
+}
+
+/// A hunk that adds or removes every line of `content`.
+fn push_hunk(diff: &mut String, sign: char, content: &str) {
+    let count = content.lines().count();
+    let range = format!("{},{count}", usize::from(count > 0));
+    let header = if sign == '+' {
+        format!("@@ -0,0 +{range} @@\n")
+    } else {
+        format!("@@ -{range} +0,0 @@\n")
+    };
+    diff.push_str(&header);
+    for line in content.lines() {
+        diff.push(sign);
+        diff.push_str(line);
+        diff.push('\n');
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn only_the_same_key_within_the_window_is_a_duplicate() {
+        let mut debounce = KeyDebounce::default();
+        let start = Instant::now();
+        assert!(!debounce.is_duplicate(KeyCode::Right, start));
+        assert!(debounce.is_duplicate(KeyCode::Right, start + Duration::from_millis(2)));
+        assert!(!debounce.is_duplicate(KeyCode::Left, start + Duration::from_millis(3)));
+        assert!(!debounce.is_duplicate(KeyCode::Left, start + Duration::from_millis(50)));
+    }
+
+    #[test]
+    fn enter_waits_for_an_interaction_only_when_armed() {
+        let theme = ApprovalTheme::default();
+        let mut guard = EnterGuard::new(true);
+        assert!(!guard.allows_enter());
+        assert!(guard.note(&theme).is_some());
+        assert!(guard.interact());
+        assert!(guard.note(&theme).is_none());
+        assert!(guard.allows_enter());
+
+        assert!(EnterGuard::new(false).allows_enter());
+    }
+
+    #[test]
+    fn options_are_held_until_acknowledged_only_when_armed() {
+        let theme = ApprovalTheme::default();
+        let mut gate = WarningGate::new(true);
+        assert!(gate.holds_options());
+        assert!(gate.prompt(&theme).is_some());
+        assert!(gate.acknowledge());
+        assert!(!gate.holds_options());
+        assert!(gate.prompt(&theme).is_none());
+        assert!(!gate.acknowledge());
+
+        assert!(!WarningGate::new(false).holds_options());
+    }
+
+    fn argv(args: &[&str]) -> Vec<String> {
+        args.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn arguments_with_spaces_are_quoted() {
+        let command = argv(&["git", "commit", "-m", "fix the build"]);
+        assert_eq!(
+            shell_quoted(&command),
+            Some("git commit -m 'fix the build'".to_string())
+        );
+    }
+
+    #[test]
+    fn quoted_commands_split_back_into_the_same_argv() {
+        for command in [
+            argv(&["echo", "it's done"]),
+            argv(&["echo", r#"say "hi""#, "$HOME", "a\\b"]),
+            argv(&["bash", "-lc", "grep -n 'x' *.rs | head"]),
+            argv(&["printf", "", "line1\nline2"]),
+        ] {
+            let quoted = shell_quoted(&command).expect("quotable");
+            assert_eq!(shlex::split(&quoted), Some(command));
+        }
+    }
+
+    #[test]
+    fn nul_bytes_cannot_be_quoted() {
+        assert_eq!(shell_quoted(&argv(&["echo", "a\0b"])), None);
+    }
+
+    #[test]
+    fn patch_diff_covers_every_change() {
+        let cwd = PathBuf::from("/repo");
+        let changes = HashMap::from([
+            (
+                cwd.join("new.txt"),
+                FileChange::Add {
+                    content: "one\ntwo\n".to_string(),
+                },
+            ),
+            (
+                cwd.join("old.txt"),
+                FileChange::Delete {
+                    content: "gone\n".to_string(),
+                },
+            ),
+            (
+                cwd.join("src/lib.rs"),
+                FileChange::Update {
+                    unified_diff: "@@ -1 +1 @@\n-a\n+b\n".to_string(),
+                    move_path: Some(cwd.join("src/main.rs")),
+                },
+            ),
+        ]);
+
+        assert_eq!(
+            patch_diff(&cwd, &changes),
+            "--- /dev/null\n+++ b/new.txt\n@@ -0,0 +1,2 @@\n+one\n+two\n\
+             --- a/old.txt\n+++ /dev/null\n@@ -1,1 +0,0 @@\n-gone\n\
+             --- a/src/lib.rs\n+++ b/src/main.rs\n@@ -1 +1 @@\n-a\n+b\n"
+        );
+    }
+}
@@ -0,0 +1,1506 @@
+//! The answers the modal offers and how they are labeled.
+//!
+//! Core sends the decisions a request allows; this module turns them into
+//! option rows with labels in the configured language and shortcut keys,
+//! adds the answers the modal itself contributes, and explains each one for
+//! the `?` view.
+
+use super::ApprovalOverlay;
+use super::ApprovalRequest;
+use super::decisions::AutoDeny;
+use super::keys::CopiedHint;
+use super::prompt::ContextPanel;
+use super::render::ApprovalTheme;
+use super::render::CachedHeight;
+use super::render::PromptScroll;
+use super::render::PromptTitle;
+use super::render::QueuePosition;
+use super::render::ScrollablePrompt;
+use crate::bottom_pane::list_selection_view::SelectionItem;
+use crate::bottom_pane::list_selection_view::SelectionViewParams;
+use crate::exec_command::strip_bash_lc_and_escape;
+use crate::key_hint;
+use crate::key_hint::KeyBinding;
+use crate::render::renderable::ColumnRenderable;
+use crate::render::renderable::Renderable;
+use codex_core::config::ApprovalLanguage;
+use codex_core::config::ApprovalShortcut;
+use codex_core::config::TuiApprovals;
+use codex_core::features::Features;
+use codex_protocol::models::MacOsAutomationPermission;
+use codex_protocol::models::MacOsContactsPermission;
+use codex_protocol::models::MacOsPreferencesPermission;
+use codex_protocol::models::PermissionProfile;
+use codex_protocol::protocol::ElicitationAction;
+use codex_protocol::protocol::NetworkApprovalContext;
+use codex_protocol::protocol::NetworkPolicyRuleAction;
+use codex_protocol::protocol::PostRunAssertion;
+use codex_protocol::protocol::ReviewDecision;
+use codex_shell_command::bash::extract_bash_command;
+use codex_shell_command::bash::parse_shell_lc_plain_commands;
+use crossterm::event::KeyCode;
+use ratatui::style::Stylize;
+use ratatui::text::Line;
+use ratatui::text::Span;
+use std::cell::Cell;
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::path::PathBuf;
+use std::rc::Rc;
+use std::sync::Arc;
+use std::time::Duration;
+
+impl ApprovalOverlay {
+    pub(crate) fn build_options(
+        request: &ApprovalRequest,
+        header: Box<dyn Renderable>,
+        _features: &Features,
+        config: &TuiApprovals,
+        scroll: &Rc<PromptScroll>,
+        auto_deny: &Rc<AutoDeny>,
+        copied: &Rc<CopiedHint>,
+        queue_position: &QueuePosition,
+        identical: &Rc<Cell<usize>>,
+        abort_pending: &Rc<Cell<bool>>,
+        recent_context: &[Line<'static>],
+        theme: ApprovalTheme,
+    ) -> (
+        Vec<ApprovalOption>,
+        SelectionViewParams,
+        Arc<CachedHeight<ColumnRenderable<'static>>>,
+    ) {
+        let lang = config.language.unwrap_or_default();
+        let (options, title) = match request {
+            ApprovalRequest::Exec {
+                command,
+                available_decisions,
+                network_approval_context,
+                additional_permissions,
+                ..
+            } => {
+                let scoped;
+                let available_decisions =
+                    if network_approval_context.is_none() && additional_permissions.is_none() {
+                        scoped = if config.extended_decisions.unwrap_or(false) {
+                            with_extended_decisions(&with_directory_approval(available_decisions))
+                        } else {
+                            with_directory_approval(available_decisions)
+                        };
+                        &scoped
+                    } else {
+                        available_decisions
+                    };
+                let mut options = exec_options(
+                    command,
+                    available_decisions,
+                    network_approval_context.as_ref(),
+                    additional_permissions.as_ref(),
+                    config.post_run_checks.unwrap_or(false),
+                    lang,
+                );
+                // Explaining and editing keep the prompt open, so they follow
+                // the decisions.
+                if network_approval_context.is_none() {
+                    if config.session_macros.unwrap_or(false)
+                        && available_decisions.contains(&ReviewDecision::Approved)
+                    {
+                        options.push(macro_option(lang));
+                    }
+                    options.push(explain_option(lang));
+                    options.push(edit_command_option(lang));
+                }
+                let title = network_approval_context.as_ref().map_or_else(
+                    || "Would you like to run the following command?".to_string(),
+                    |network_approval_context| {
+                        format!(
+                            "Do you want to approve network access to \"{}\"?",
+                            network_approval_context.host
+                        )
+                    },
+                );
+                (options, title)
+            }
+            ApprovalRequest::Permissions { .. } => (
+                permissions_options(lang),
+                "Would you like to grant these permissions?".to_string(),
+            ),
+            ApprovalRequest::ApplyPatch { changes, .. } => {
+                let mut options = patch_options(lang);
+                if changes.len() > 1 {
+                    options.push(pick_files_option(lang));
+                }
+                (
+                    options,
+                    "Would you like to make the following edits?".to_string(),
+                )
+            }
+            ApprovalRequest::McpElicitation { server_name, .. } => (
+                elicitation_options(lang),
+                format!("{server_name} needs your approval."),
+            ),
+        };
+        let options = with_configured_keys(options, config.keys.as_ref());
+
+        let prompt = Arc::new(CachedHeight::new(ColumnRenderable::with([
+            ContextPanel::new(recent_context).into(),
+            PromptTitle::new(
+                Line::from(title.bold()),
+                auto_deny.clone(),
+                copied.clone(),
+                queue_position.clone(),
+                identical.clone(),
+                abort_pending.clone(),
+                theme,
+            )
+            .into(),
+            Line::from("").into(),
+            header,
+        ])));
+
+        let items = options
+            .iter()
+            .map(|opt| SelectionItem {
+                name: opt.label.clone(),
+                selected_description: opt.description(lang),
+                display_shortcut: opt
+                    .display_shortcut
+                    .or_else(|| opt.additional_shortcuts.first().copied()),
+                dismiss_on_select: false,
+                ..Default::default()
+            })
+            .collect();
+
+        let params = SelectionViewParams {
+            footer_hint: Some(approval_footer_hint(request, &options)),
+            items,
+            header: Box::new(ScrollablePrompt::new(prompt.clone(), scroll.clone())),
+            ..Default::default()
+        };
+
+        (options, params, prompt)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum ApprovalDecision {
+    Review(ReviewDecision),
+    /// Approve the exec request but run this verbose variant of the command.
+    ApproveVerbose(Vec<String>),
+    /// Approve the exec request and have core check these conditions once it ran.
+    ApproveWithAssertions(Vec<PostRunAssertion>),
+    /// Ask the agent to explain the exec request without resolving it.
+    Explain,
+    /// Open an editor to change the exec command before approving it.
+    EditCommand,
+    /// Ask why the request is declined before denying it.
+    DenyWithFeedback,
+    /// Deny with a request to investigate first, edited before it is sent.
+    DenyToInvestigate,
+    /// Ask how long to approve the exec request for, starting at this
+    /// duration, before approving it.
+    PickDuration(Duration),
+    /// Ask for a name, then approve the exec request and save it as a
+    /// session macro.
+    ApproveAsMacro,
+    /// Ask which of the patch's files to apply before approving those.
+    PickFiles,
+    McpElicitation(ElicitationAction),
+}
+
+impl ApprovalDecision {
+    /// Whether this decision lets the agent's request proceed.
+    pub(crate) fn is_approval(&self) -> bool {
+        match self {
+            ApprovalDecision::Review(
+                ReviewDecision::Approved
+                | ReviewDecision::ApprovedExecpolicyAmendment { .. }
+                | ReviewDecision::ApprovedForSession
+                | ReviewDecision::ApprovedForDirectory
+                | ReviewDecision::ApprovedForDuration { .. }
+                | ReviewDecision::ApprovedAlways
+                | ReviewDecision::ApprovedSandboxed
+                | ReviewDecision::NetworkPolicyAmendment { .. },
+            )
+            | ApprovalDecision::ApproveVerbose(_)
+            | ApprovalDecision::ApproveWithAssertions(_)
+            | ApprovalDecision::ApproveAsMacro
+            | ApprovalDecision::PickFiles => true,
+            ApprovalDecision::Review(
+                ReviewDecision::DryRun
+                | ReviewDecision::Denied
+                | ReviewDecision::DeniedForSession
+                | ReviewDecision::Abort,
+            )
+            | ApprovalDecision::Explain
+            | ApprovalDecision::EditCommand
+            | ApprovalDecision::DenyWithFeedback
+            | ApprovalDecision::DenyToInvestigate
+            | ApprovalDecision::PickDuration(_)
+            | ApprovalDecision::McpElicitation(_) => false,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub(crate) struct ApprovalOption {
+    pub(crate) label: String,
+    pub(crate) decision: ApprovalDecision,
+    pub(crate) display_shortcut: Option<KeyBinding>,
+    pub(crate) additional_shortcuts: Vec<KeyBinding>,
+}
+
+impl ApprovalOption {
+    /// Shown next to the option while it is highlighted.
+    pub(crate) fn description(&self, lang: ApprovalLanguage) -> Option<String> {
+        matches!(
+            self.decision,
+            ApprovalDecision::Review(ReviewDecision::ApprovedSandboxed)
+        )
+        .then(|| text(lang, Label::SandboxedDescription).to_string())
+    }
+
+    pub(crate) fn shortcuts(&self) -> impl Iterator<Item = KeyBinding> + '_ {
+        self.display_shortcut
+            .into_iter()
+            .chain(self.additional_shortcuts.iter().copied())
+    }
+}
+
+fn exec_options(
+    command: &[String],
+    available_decisions: &[ReviewDecision],
+    network_approval_context: Option<&NetworkApprovalContext>,
+    additional_permissions: Option<&PermissionProfile>,
+    post_run_checks: bool,
+    lang: ApprovalLanguage,
+) -> Vec<ApprovalOption> {
+    let mut options: Vec<ApprovalOption> = available_decisions
+        .iter()
+        .filter_map(|decision| match decision {
+            ReviewDecision::Approved => {
+                let label = if network_approval_context.is_some() {
+                    text(lang, Label::JustThisOnce)
+                } else {
+                    text(lang, Label::Proceed)
+                };
+                Some(ApprovalOption {
+                    label: label.to_string(),
+                    decision: ApprovalDecision::Review(ReviewDecision::Approved),
+                    display_shortcut: None,
+                    additional_shortcuts: accelerator(label),
+                })
+            }
+            ReviewDecision::ApprovedExecpolicyAmendment {
+                proposed_execpolicy_amendment,
+            } => {
+                let rendered_prefix =
+                    strip_bash_lc_and_escape(proposed_execpolicy_amendment.command());
+                if rendered_prefix.contains('\n') || rendered_prefix.contains('\r') {
+                    return None;
+                }
+
+                Some(ApprovalOption {
+                    label: format!("{} `{rendered_prefix}`", text(lang, Label::AllowPrefix)),
+                    decision: ApprovalDecision::Review(
+                        ReviewDecision::ApprovedExecpolicyAmendment {
+                            proposed_execpolicy_amendment: proposed_execpolicy_amendment.clone(),
+                        },
+                    ),
+                    display_shortcut: None,
+                    additional_shortcuts: vec![key_hint::plain(KeyCode::Char('p'))],
+                })
+            }
+            ReviewDecision::ApprovedForSession => Some(ApprovalOption {
+                label: if network_approval_context.is_some() {
+                    text(lang, Label::AllowHostForConversation).to_string()
+                } else if additional_permissions.is_some() {
+                    text(lang, Label::AllowPermissionsForSession).to_string()
+                } else {
+                    text(lang, Label::AllowCommandForSession).to_string()
+                },
+                decision: ApprovalDecision::Review(ReviewDecision::ApprovedForSession),
+                display_shortcut: None,
+                additional_shortcuts: vec![key_hint::plain(KeyCode::Char('a'))],
+            }),
+            ReviewDecision::ApprovedForDirectory => Some(ApprovalOption {
+                label: text(lang, Label::AllowInDirectory).to_string(),
+                decision: ApprovalDecision::Review(ReviewDecision::ApprovedForDirectory),
+                display_shortcut: None,
+                additional_shortcuts: vec![key_hint::shift(KeyCode::Char('D'))],
+            }),
+            ReviewDecision::ApprovedForDuration { duration } => Some(ApprovalOption {
+                label: text(lang, Label::AllowForAWhile).to_string(),
+                decision: ApprovalDecision::PickDuration(*duration),
+                display_shortcut: None,
+                additional_shortcuts: vec![key_hint::plain(KeyCode::Char('t'))],
+            }),
+            ReviewDecision::ApprovedAlways => Some(ApprovalOption {
+                label: text(lang, Label::AlwaysAcrossSessions).to_string(),
+                decision: ApprovalDecision::Review(ReviewDecision::ApprovedAlways),
+                display_shortcut: None,
+                additional_shortcuts: vec![key_hint::shift(KeyCode::Char('A'))],
+            }),
+            ReviewDecision::ApprovedSandboxed => Some(ApprovalOption {
+                label: text(lang, Label::RunSandboxed).to_string(),
+                decision: ApprovalDecision::Review(ReviewDecision::ApprovedSandboxed),
+                display_shortcut: None,
+                additional_shortcuts: vec![key_hint::plain(KeyCode::Char('s'))],
+            }),
+            ReviewDecision::DryRun => Some(ApprovalOption {
+                label: text(lang, Label::DryRun).to_string(),
+                decision: ApprovalDecision::Review(ReviewDecision::DryRun),
+                display_shortcut: None,
+                additional_shortcuts: vec![key_hint::plain(KeyCode::Char('r'))],
+            }),
+            ReviewDecision::NetworkPolicyAmendment {
+                network_policy_amendment,
+            } => {
+                let (label, shortcut) = match network_policy_amendment.action {
+                    NetworkPolicyRuleAction::Allow => {
+                        (text(lang, Label::AllowHostInFuture), KeyCode::Char('p'))
+                    }
+                    NetworkPolicyRuleAction::Deny => {
+                        (text(lang, Label::BlockHostInFuture), KeyCode::Char('d'))
+                    }
+                };
+                Some(ApprovalOption {
+                    label: label.to_string(),
+                    decision: ApprovalDecision::Review(ReviewDecision::NetworkPolicyAmendment {
+                        network_policy_amendment: network_policy_amendment.clone(),
+                    }),
+                    display_shortcut: None,
+                    additional_shortcuts: vec![key_hint::plain(shortcut)],
+                })
+            }
+            ReviewDecision::Denied => Some(ApprovalOption {
+                label: text(lang, Label::DenyAndContinue).to_string(),
+                decision: ApprovalDecision::Review(ReviewDecision::Denied),
+                display_shortcut: None,
+                additional_shortcuts: vec![key_hint::plain(KeyCode::Char('d'))],
+            }),
+            ReviewDecision::DeniedForSession => Some(ApprovalOption {
+                label: text(lang, Label::NeverThisSession).to_string(),
+                decision: ApprovalDecision::Review(ReviewDecision::DeniedForSession),
+                display_shortcut: None,
+                additional_shortcuts: vec![key_hint::plain(KeyCode::Char('x'))],
+            }),
+            ReviewDecision::Abort => Some(ApprovalOption {
+                label: text(lang, Label::TellCodex).to_string(),
+                decision: ApprovalDecision::Review(ReviewDecision::Abort),
+                display_shortcut: Some(key_hint::plain(KeyCode::Esc)),
+                additional_shortcuts: accelerator(text(lang, Label::TellCodex)),
+            }),
+        })
+        .collect();
+
+    // Offer a verbose variant right after "Yes, proceed" when the program has a
+    // known verbose flag; the label shows the command that will actually run.
+    if network_approval_context.is_none()
+        && let Some(approved_idx) = options.iter().position(|opt| {
+            matches!(
+                opt.decision,
+                ApprovalDecision::Review(ReviewDecision::Approved)
+            )
+        })
+        && let Some(verbose) = verbose_command(command)
+    {
+        let rendered = strip_bash_lc_and_escape(&verbose);
+        if !rendered.contains('\n') && !rendered.contains('\r') {
+            options.insert(
+                approved_idx + 1,
+                ApprovalOption {
+                    label: format!("{} `{rendered}`", text(lang, Label::ApproveVerbose)),
+                    decision: ApprovalDecision::ApproveVerbose(verbose),
+                    display_shortcut: None,
+                    additional_shortcuts: Vec::new(),
+                },
+            );
+        }
+    }
+
+    // Then one "approve and check afterward" option per assertion template.
+    if post_run_checks
+        && network_approval_context.is_none()
+        && let Some(approved_idx) = options.iter().position(|opt| {
+            matches!(
+                opt.decision,
+                ApprovalDecision::Review(ReviewDecision::Approved)
+            )
+        })
+    {
+        let insert_at = options[approved_idx + 1..]
+            .iter()
+            .take_while(|opt| matches!(opt.decision, ApprovalDecision::ApproveVerbose(_)))
+            .count()
+            + approved_idx
+            + 1;
+        let checked = assertion_templates(command)
+            .into_iter()
+            .map(|assertion| ApprovalOption {
+                label: format!("{} {assertion}", text(lang, Label::ApproveAndCheck)),
+                decision: ApprovalDecision::ApproveWithAssertions(vec![assertion]),
+                display_shortcut: None,
+                additional_shortcuts: Vec::new(),
+            });
+        options.splice(insert_at..insert_at, checked);
+    }
+    options
+}
+
+fn macro_option(lang: ApprovalLanguage) -> ApprovalOption {
+    ApprovalOption {
+        label: text(lang, Label::SaveAsMacro).to_string(),
+        decision: ApprovalDecision::ApproveAsMacro,
+        display_shortcut: None,
+        additional_shortcuts: vec![key_hint::shift(KeyCode::Char('M'))],
+    }
+}
+
+fn pick_files_option(lang: ApprovalLanguage) -> ApprovalOption {
+    ApprovalOption {
+        label: text(lang, Label::PickFiles).to_string(),
+        decision: ApprovalDecision::PickFiles,
+        display_shortcut: None,
+        additional_shortcuts: vec![key_hint::plain(KeyCode::Char('p'))],
+    }
+}
+
+fn explain_option(lang: ApprovalLanguage) -> ApprovalOption {
+    ApprovalOption {
+        label: text(lang, Label::Explain).to_string(),
+        decision: ApprovalDecision::Explain,
+        display_shortcut: None,
+        additional_shortcuts: vec![key_hint::shift(KeyCode::Char('E'))],
+    }
+}
+
+fn edit_command_option(lang: ApprovalLanguage) -> ApprovalOption {
+    ApprovalOption {
+        label: text(lang, Label::EditCommand).to_string(),
+        decision: ApprovalDecision::EditCommand,
+        display_shortcut: None,
+        additional_shortcuts: vec![key_hint::plain(KeyCode::Char('i'))],
+    }
+}
+
+pub(crate) fn format_additional_permissions_rule(
+    additional_permissions: &PermissionProfile,
+) -> Option<String> {
+    let mut parts = Vec::new();
+    if additional_permissions
+        .network
+        .as_ref()
+        .and_then(|network| network.enabled)
+        .unwrap_or(false)
+    {
+        parts.push("network".to_string());
+    }
+    if let Some(file_system) = additional_permissions.file_system.as_ref() {
+        if let Some(read) = file_system.read.as_ref() {
+            let reads = read
+                .iter()
+                .map(|path| format!("`{}`", path.display()))
+                .collect::<Vec<_>>()
+                .join(", ");
+            parts.push(format!("read {reads}"));
+        }
+        if let Some(write) = file_system.write.as_ref() {
+            let writes = write
+                .iter()
+                .map(|path| format!("`{}`", path.display()))
+                .collect::<Vec<_>>()
+                .join(", ");
+            parts.push(format!("write {writes}"));
+        }
+    }
+    if let Some(macos) = additional_permissions.macos.as_ref() {
+        if !matches!(
+            macos.macos_preferences,
+            MacOsPreferencesPermission::ReadOnly
+        ) {
+            let value = match macos.macos_preferences {
+                MacOsPreferencesPermission::ReadOnly => "readonly",
+                MacOsPreferencesPermission::ReadWrite => "readwrite",
+                MacOsPreferencesPermission::None => "none",
+            };
+            parts.push(format!("macOS preferences {value}"));
+        }
+        match &macos.macos_automation {
+            MacOsAutomationPermission::All => {
+                parts.push("macOS automation all".to_string());
+            }
+            MacOsAutomationPermission::BundleIds(bundle_ids) => {
+                if !bundle_ids.is_empty() {
+                    parts.push(format!("macOS automation {}", bundle_ids.join(", ")));
+                }
+            }
+            MacOsAutomationPermission::None => {}
+        }
+        if macos.macos_accessibility {
+            parts.push("macOS accessibility".to_string());
+        }
+        if macos.macos_calendar {
+            parts.push("macOS calendar".to_string());
+        }
+        if macos.macos_reminders {
+            parts.push("macOS reminders".to_string());
+        }
+        if !matches!(macos.macos_contacts, MacOsContactsPermission::None) {
+            let value = match macos.macos_contacts {
+                MacOsContactsPermission::None => "none",
+                MacOsContactsPermission::ReadOnly => "readonly",
+                MacOsContactsPermission::ReadWrite => "readwrite",
+            };
+            parts.push(format!("macOS contacts {value}"));
+        }
+    }
+
+    if parts.is_empty() {
+        None
+    } else {
+        Some(parts.join("; "))
+    }
+}
+
+fn patch_options(lang: ApprovalLanguage) -> Vec<ApprovalOption> {
+    vec![
+        ApprovalOption {
+            label: text(lang, Label::Proceed).to_string(),
+            decision: ApprovalDecision::Review(ReviewDecision::Approved),
+            display_shortcut: None,
+            additional_shortcuts: accelerator(text(lang, Label::Proceed)),
+        },
+        ApprovalOption {
+            label: text(lang, Label::ApplyForSession).to_string(),
+            decision: ApprovalDecision::Review(ReviewDecision::ApprovedForSession),
+            display_shortcut: None,
+            additional_shortcuts: vec![key_hint::plain(KeyCode::Char('a'))],
+        },
+        ApprovalOption {
+            label: text(lang, Label::TellCodex).to_string(),
+            decision: ApprovalDecision::Review(ReviewDecision::Abort),
+            display_shortcut: Some(key_hint::plain(KeyCode::Esc)),
+            additional_shortcuts: accelerator(text(lang, Label::TellCodex)),
+        },
+        ApprovalOption {
+            label: text(lang, Label::DenyWithReason).to_string(),
+            decision: ApprovalDecision::DenyWithFeedback,
+            display_shortcut: None,
+            additional_shortcuts: vec![key_hint::plain(KeyCode::Char('e'))],
+        },
+        ApprovalOption {
+            label: text(lang, Label::DenyToInvestigate).to_string(),
+            decision: ApprovalDecision::DenyToInvestigate,
+            display_shortcut: None,
+            additional_shortcuts: vec![key_hint::plain(KeyCode::Char('g'))],
+        },
+    ]
+}
+
+fn permissions_options(lang: ApprovalLanguage) -> Vec<ApprovalOption> {
+    vec![
+        ApprovalOption {
+            label: text(lang, Label::GrantPermissions).to_string(),
+            decision: ApprovalDecision::Review(ReviewDecision::Approved),
+            display_shortcut: None,
+            additional_shortcuts: accelerator(text(lang, Label::GrantPermissions)),
+        },
+        ApprovalOption {
+            label: text(lang, Label::GrantPermissionsForSession).to_string(),
+            decision: ApprovalDecision::Review(ReviewDecision::ApprovedForSession),
+            display_shortcut: None,
+            additional_shortcuts: vec![key_hint::plain(KeyCode::Char('a'))],
+        },
+        ApprovalOption {
+            label: text(lang, Label::DenyPermissions).to_string(),
+            decision: ApprovalDecision::Review(ReviewDecision::Denied),
+            display_shortcut: None,
+            additional_shortcuts: accelerator(text(lang, Label::DenyPermissions)),
+        },
+    ]
+}
+
+fn elicitation_options(lang: ApprovalLanguage) -> Vec<ApprovalOption> {
+    vec![
+        ApprovalOption {
+            label: text(lang, Label::ProvideInfo).to_string(),
+            decision: ApprovalDecision::McpElicitation(ElicitationAction::Accept),
+            display_shortcut: None,
+            additional_shortcuts: accelerator(text(lang, Label::ProvideInfo)),
+        },
+        ApprovalOption {
+            label: text(lang, Label::DeclineInfo).to_string(),
+            decision: ApprovalDecision::McpElicitation(ElicitationAction::Decline),
+            display_shortcut: None,
+            additional_shortcuts: accelerator(text(lang, Label::DeclineInfo)),
+        },
+        ApprovalOption {
+            label: text(lang, Label::CancelRequest).to_string(),
+            decision: ApprovalDecision::McpElicitation(ElicitationAction::Cancel),
+            display_shortcut: Some(key_hint::plain(KeyCode::Esc)),
+            additional_shortcuts: accelerator(text(lang, Label::CancelRequest)),
+        },
+    ]
+}
+
+/// Keybinding help generated from the options so it lists exactly the
+/// shortcuts the modal accepts.
+fn approval_footer_hint(request: &ApprovalRequest, options: &[ApprovalOption]) -> Line<'static> {
+    let sep = || Span::from(" · ");
+    let mut spans = vec![
+        key_hint::plain(KeyCode::Up).into(),
+        "/".into(),
+        key_hint::plain(KeyCode::Down).into(),
+        " move".into(),
+        sep(),
+        key_hint::plain(KeyCode::Enter).into(),
+        " confirm".into(),
+    ];
+    let select = select_shortcuts(options);
+    if !select.is_empty() {
+        spans.push(sep());
+        for (idx, shortcut) in select.iter().enumerate() {
+            if idx > 0 {
+                spans.push("/".into());
+            }
+            spans.push(shortcut.into());
+        }
+        spans.push(" select".into());
+    }
+    spans.extend([
+        sep(),
+        key_hint::plain(KeyCode::Esc).into(),
+        " cancel".into(),
+    ]);
+    if request.thread_label().is_some() {
+        spans.extend([
+            sep(),
+            key_hint::plain(KeyCode::Char('o')).into(),
+            " open thread".into(),
+        ]);
+    }
+    Line::from(spans)
+}
+
+/// The key shown for each option that answers directly, leaving out Esc,
+/// which the footer lists as cancel.
+pub(crate) fn select_shortcuts(options: &[ApprovalOption]) -> Vec<KeyBinding> {
+    options
+        .iter()
+        .filter_map(|opt| {
+            opt.display_shortcut
+                .or_else(|| opt.additional_shortcuts.first().copied())
+        })
+        .filter(|shortcut| *shortcut != key_hint::plain(KeyCode::Esc))
+        .collect()
+}
+
+// Answers the modal adds to the ones core offers for a command approval.
+//
+// Approving for this directory is a narrower "for this session", so it is
+// offered wherever that is. The rest only come with `extended_decisions`:
+// they lengthen the list a lot, and clients built on the app-server protocol
+// cannot show them, so the agent keeps its short list by default.
+
+/// How long "approve for a while" lasts unless the user picks another
+/// duration.
+const DEFAULT_APPROVAL_DURATION: Duration = Duration::from_secs(30 * 60);
+
+/// `available` with "for this directory" right after "for this session",
+/// when core offers the latter.
+pub(crate) fn with_directory_approval(available: &[ReviewDecision]) -> Vec<ReviewDecision> {
+    let mut decisions = available.to_vec();
+    if !decisions.contains(&ReviewDecision::ApprovedForDirectory)
+        && let Some(at) = decisions
+            .iter()
+            .position(|decision| *decision == ReviewDecision::ApprovedForSession)
+    {
+        decisions.insert(at + 1, ReviewDecision::ApprovedForDirectory);
+    }
+    decisions
+}
+
+/// `available` with the extra answers it lacks, placed before the abort so
+/// "No" stays last.
+pub(crate) fn with_extended_decisions(available: &[ReviewDecision]) -> Vec<ReviewDecision> {
+    let has = |wanted: &ReviewDecision| {
+        available
+            .iter()
+            .any(|decision| std::mem::discriminant(decision) == std::mem::discriminant(wanted))
+    };
+    let extra = [
+        ReviewDecision::ApprovedForDuration {
+            duration: DEFAULT_APPROVAL_DURATION,
+        },
+        ReviewDecision::ApprovedAlways,
+        ReviewDecision::ApprovedSandboxed,
+        ReviewDecision::DryRun,
+        ReviewDecision::DeniedForSession,
+    ]
+    .into_iter()
+    .filter(|decision| !has(decision))
+    .collect::<Vec<_>>();
+    let at = available
+        .iter()
+        .position(|decision| *decision == ReviewDecision::Abort)
+        .unwrap_or(available.len());
+    let mut decisions = available.to_vec();
+    decisions.splice(at..at, extra);
+    decisions
+}
+
+// Shortcut keys for the answers, as configured in `tui.approvals.keys`.
+//
+// Each option starts with its built-in key; [`with_configured_keys`] swaps in
+// the configured ones. A configuration that would leave two options on one
+// key, or give an option a key the modal handles before the options (or a
+// digit, which selects by position), is dropped as a whole: half-applied
+// keys would be harder to predict than the defaults.
+
+/// Keys the modal claims for itself before the options see them.
+const RESERVED: &[char] = &[
+    'c', 'e', 'f', 'g', 'm', 'o', 'u', 'v', 'w', 'R', 'Y', '.', '?', ' ',
+];
+
+/// `options` with the configured keys in place of their defaults, or as they
+/// are when the configuration conflicts.
+pub(crate) fn with_configured_keys(
+    options: Vec<ApprovalOption>,
+    keys: Option<&BTreeMap<ApprovalShortcut, char>>,
+) -> Vec<ApprovalOption> {
+    let Some(keys) = keys.filter(|keys| !keys.is_empty()) else {
+        return options;
+    };
+    if let Some(key) = keys.values().find(|key| is_reserved(**key)) {
+        tracing::warn!("tui.approvals.keys: the modal already uses `{key}`; keeping defaults");
+        return options;
+    }
+    let configured: Vec<ApprovalOption> = options
+        .iter()
+        .cloned()
+        .map(|mut option| {
+            if let Some(key) = shortcut_for(&option.decision).and_then(|which| keys.get(&which)) {
+                option.additional_shortcuts = bindings(*key);
+            }
+            option
+        })
+        .collect();
+    // Only the configured keys are checked, so a clash the defaults already
+    // have for some request does not throw the configuration away.
+    let clash = keys.values().flat_map(|key| bindings(*key)).any(|binding| {
+        configured
+            .iter()
+            .filter(|option| option.shortcuts().any(|shortcut| shortcut == binding))
+            .count()
+            > 1
+    });
+    if clash {
+        tracing::warn!("tui.approvals.keys: two answers share a key; keeping defaults");
+        return options;
+    }
+    configured
+}
+
+/// Whether the modal handles `key` before the options, so no answer can
+/// use it.
+pub(crate) fn is_reserved(key: char) -> bool {
+    RESERVED.contains(&key) || key.is_ascii_digit()
+}
+
+/// Which configurable answer `decision` is, if any.
+fn shortcut_for(decision: &ApprovalDecision) -> Option<ApprovalShortcut> {
+    match decision {
+        ApprovalDecision::Review(decision) => match decision {
+            ReviewDecision::Approved => Some(ApprovalShortcut::Approve),
+            ReviewDecision::ApprovedExecpolicyAmendment { .. } => {
+                Some(ApprovalShortcut::AllowPrefix)
+            }
+            ReviewDecision::ApprovedForSession => Some(ApprovalShortcut::ApproveForSession),
+            ReviewDecision::ApprovedForDirectory => Some(ApprovalShortcut::ApproveForDirectory),
+            ReviewDecision::ApprovedForDuration { .. } => {
+                Some(ApprovalShortcut::ApproveForDuration)
+            }
+            ReviewDecision::ApprovedAlways => Some(ApprovalShortcut::ApproveAlways),
+            ReviewDecision::ApprovedSandboxed => Some(ApprovalShortcut::ApproveSandboxed),
+            ReviewDecision::DryRun => Some(ApprovalShortcut::DryRun),
+            ReviewDecision::Denied => Some(ApprovalShortcut::Deny),
+            ReviewDecision::DeniedForSession => Some(ApprovalShortcut::DenyForSession),
+            ReviewDecision::Abort => Some(ApprovalShortcut::Abort),
+            ReviewDecision::NetworkPolicyAmendment { .. } => None,
+        },
+        ApprovalDecision::McpElicitation(action) => match action {
+            ElicitationAction::Accept => Some(ApprovalShortcut::Approve),
+            ElicitationAction::Decline => Some(ApprovalShortcut::Deny),
+            ElicitationAction::Cancel => Some(ApprovalShortcut::Abort),
+        },
+        ApprovalDecision::PickDuration(_) => Some(ApprovalShortcut::ApproveForDuration),
+        ApprovalDecision::ApproveVerbose(_)
+        | ApprovalDecision::ApproveWithAssertions(_)
+        | ApprovalDecision::Explain
+        | ApprovalDecision::EditCommand
+        | ApprovalDecision::DenyWithFeedback
+        | ApprovalDecision::DenyToInvestigate
+        | ApprovalDecision::ApproveAsMacro
+        | ApprovalDecision::PickFiles => None,
+    }
+}
+
+/// Uppercase letters bind with Shift, like the built-in `A` and `D`; the
+/// modal adds Shift to an uppercase letter from a terminal that leaves it off.
+fn bindings(key: char) -> Vec<KeyBinding> {
+    if key.is_uppercase() {
+        vec![key_hint::shift(KeyCode::Char(key))]
+    } else {
+        vec![key_hint::plain(KeyCode::Char(key))]
+    }
+}
+
+// Option labels in the language chosen by `tui.approvals.language`.
+//
+// Each fixed label is a [`Label`]. A translation may be incomplete: labels
+// it lacks are shown in English. The yes, no and cancel answers take their
+// shortcut from the first letter of the translated label, so the key the
+// user reads is the key that works. A letter the modal already handles
+// itself gives no shortcut, since the answer would never see it.
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Label {
+    Proceed,
+    JustThisOnce,
+    /// Followed by the command prefix.
+    AllowPrefix,
+    AllowHostForConversation,
+    AllowPermissionsForSession,
+    AllowCommandForSession,
+    AllowInDirectory,
+    AllowForAWhile,
+    AlwaysAcrossSessions,
+    RunSandboxed,
+    SandboxedDescription,
+    DryRun,
+    AllowHostInFuture,
+    BlockHostInFuture,
+    DenyAndContinue,
+    NeverThisSession,
+    TellCodex,
+    /// Followed by the verbose command.
+    ApproveVerbose,
+    /// Followed by the condition checked afterward.
+    ApproveAndCheck,
+    SaveAsMacro,
+    Explain,
+    EditCommand,
+    ApplyForSession,
+    PickFiles,
+    DenyWithReason,
+    DenyToInvestigate,
+    GrantPermissions,
+    GrantPermissionsForSession,
+    DenyPermissions,
+    ProvideInfo,
+    DeclineInfo,
+    CancelRequest,
+}
+
+/// `label` in `lang`, or in English when `lang` has no translation for it.
+pub(crate) fn text(lang: ApprovalLanguage, label: Label) -> &'static str {
+    match lang {
+        ApprovalLanguage::En => english(label),
+        ApprovalLanguage::De => german(label).unwrap_or_else(|| english(label)),
+    }
+}
+
+/// Shortcut for an answer whose key is the first letter of its label, unless
+/// the modal claims that letter first.
+pub(crate) fn accelerator(label: &str) -> Vec<KeyBinding> {
+    label
+        .chars()
+        .find(|c| c.is_alphabetic())
+        .and_then(|c| c.to_lowercase().next())
+        .filter(|c| !is_reserved(*c))
+        .map(|c| key_hint::plain(KeyCode::Char(c)))
+        .into_iter()
+        .collect()
+}
+
+fn english(label: Label) -> &'static str {
+    match label {
+        Label::Proceed => "Yes, proceed",
+        Label::JustThisOnce => "Yes, just this once",
+        Label::AllowPrefix => "Yes, and don't ask again for commands that start with",
+        Label::AllowHostForConversation => "Yes, and allow this host for this conversation",
+        Label::AllowPermissionsForSession => "Yes, and allow these permissions for this session",
+        Label::AllowCommandForSession => {
+            "Yes, and don't ask again for this command in this session"
+        }
+        Label::AllowInDirectory => "Yes, approve this command in this directory for the session",
+        Label::AllowForAWhile => "Yes, and don't ask again for a while",
+        Label::AlwaysAcrossSessions => "Always, even across sessions",
+        Label::RunSandboxed => "Run sandboxed",
+        Label::SandboxedDescription => "Runs read-only: network access and file writes are blocked",
+        Label::DryRun => "Dry run it read-only first",
+        Label::AllowHostInFuture => "Yes, and allow this host in the future",
+        Label::BlockHostInFuture => "No, and block this host in the future",
+        Label::DenyAndContinue => "No, continue without running it",
+        Label::NeverThisSession => "Never run this command this session",
+        Label::TellCodex => "No, and tell Codex what to do differently",
+        Label::ApproveVerbose => "Yes, run with verbose output:",
+        Label::ApproveAndCheck => "Yes, and check afterward:",
+        Label::SaveAsMacro => "Yes, and save it as a session macro",
+        Label::Explain => "Explain this command",
+        Label::EditCommand => "Edit the command first",
+        Label::ApplyForSession => "Apply and allow further edits this session",
+        Label::PickFiles => "Yes, but pick which files to apply",
+        Label::DenyWithReason => "No, and explain why",
+        Label::DenyToInvestigate => "No, and have Codex investigate first",
+        Label::GrantPermissions => "Yes, grant these permissions",
+        Label::GrantPermissionsForSession => "Yes, grant these permissions for this session",
+        Label::DenyPermissions => "No, continue without permissions",
+        Label::ProvideInfo => "Yes, provide the requested info",
+        Label::DeclineInfo => "No, but continue without it",
+        Label::CancelRequest => "Cancel this request",
+    }
+}
+
+fn german(label: Label) -> Option<&'static str> {
+    Some(match label {
+        Label::Proceed => "Ja, fortfahren",
+        Label::JustThisOnce => "Ja, nur dieses Mal",
+        Label::AllowCommandForSession => {
+            "Ja, und für diesen Befehl in dieser Sitzung nicht mehr fragen"
+        }
+        Label::AllowForAWhile => "Ja, und eine Weile nicht mehr fragen",
+        Label::AlwaysAcrossSessions => "Immer, auch in künftigen Sitzungen",
+        Label::RunSandboxed => "In der Sandbox ausführen",
+        Label::DenyAndContinue => "Nein, ohne Ausführen fortfahren",
+        Label::NeverThisSession => "Diesen Befehl in dieser Sitzung nie ausführen",
+        Label::TellCodex => "Nein, und Codex sagen, was es anders machen soll",
+        Label::Explain => "Diesen Befehl erklären",
+        Label::EditCommand => "Befehl zuerst bearbeiten",
+        Label::DenyWithReason => "Nein, und begründen",
+        Label::DenyToInvestigate => "Nein, und Codex zuerst nachforschen lassen",
+        Label::GrantPermissions => "Ja, diese Berechtigungen gewähren",
+        Label::DenyPermissions => "Nein, ohne Berechtigungen fortfahren",
+        Label::ProvideInfo => "Ja, die angeforderten Angaben bereitstellen",
+        Label::DeclineInfo => "Nein, aber ohne sie fortfahren",
+        Label::CancelRequest => "Anfrage abbrechen",
+        Label::AllowPrefix
+        | Label::AllowHostForConversation
+        | Label::AllowPermissionsForSession
+        | Label::AllowInDirectory
+        | Label::SandboxedDescription
+        | Label::DryRun
+        | Label::AllowHostInFuture
+        | Label::BlockHostInFuture
+        | Label::ApproveVerbose
+        | Label::ApproveAndCheck
+        | Label::SaveAsMacro
+        | Label::ApplyForSession
+        | Label::PickFiles
+        | Label::GrantPermissionsForSession => return None,
+    })
+}
+
+// Every option with what it does, to compare before choosing.
+//
+// The list only describes the highlighted option. `?` swaps the list for
+// this view, which stacks each option's key and label over a line saying
+// what answering with it does. `?` again, Esc or an option's key goes back.
+
+/// The preview's lines: a heading, then each option over its description.
+pub(crate) fn all_descriptions(
+    options: &[ApprovalOption],
+    lang: ApprovalLanguage,
+) -> Vec<Line<'static>> {
+    let mut lines = vec![
+        Line::from("All options".bold()),
+        Line::from("? or Esc to go back · an option's key answers with it".dim()),
+    ];
+    for (idx, option) in options.iter().enumerate() {
+        let key: Span<'static> = match option.shortcuts().next() {
+            Some(key) => key.into(),
+            None => (idx + 1).to_string().into(),
+        };
+        lines.push(Line::from(""));
+        lines.push(Line::from(vec![
+            key,
+            "  ".into(),
+            option.label.clone().bold(),
+        ]));
+        let description = option
+            .description(lang)
+            .unwrap_or_else(|| what_it_does(&option.decision).to_string());
+        lines.push(Line::from(format!("   {description}").dim()));
+    }
+    lines
+}
+
+fn what_it_does(decision: &ApprovalDecision) -> &'static str {
+    match decision {
+        ApprovalDecision::Review(decision) => match decision {
+            ReviewDecision::Approved => "Allows this request once; the next one asks again.",
+            ReviewDecision::ApprovedExecpolicyAmendment { .. } => {
+                "Allows it and adds a rule so commands with this prefix stop asking."
+            }
+            ReviewDecision::ApprovedForSession => {
+                "Allows it and identical requests until the session ends."
+            }
+            ReviewDecision::ApprovedForDirectory => {
+                "Allows it here and in subdirectories until the session ends."
+            }
+            ReviewDecision::ApprovedAlways => {
+                "Allows it now and in future sessions; saved to your rules."
+            }
+            ReviewDecision::ApprovedForDuration { .. } => {
+                "Allows it and identical requests for a while."
+            }
+            ReviewDecision::ApprovedSandboxed => "Runs it inside the sandbox instead.",
+            ReviewDecision::DryRun => "Runs a dry run that changes nothing, then asks again.",
+            ReviewDecision::NetworkPolicyAmendment { .. } => {
+                "Saves a network rule for this host, so it is not asked about again."
+            }
+            ReviewDecision::Denied => "Declines it; codex continues and tries something else.",
+            ReviewDecision::DeniedForSession => {
+                "Declines it and identical requests until the session ends."
+            }
+            ReviewDecision::Abort => "Declines it and stops codex until you say what to do.",
+        },
+        ApprovalDecision::ApproveVerbose(_) => {
+            "Allows it, running the variant that prints what it does."
+        }
+        ApprovalDecision::ApproveWithAssertions(_) => {
+            "Allows it and has codex check the listed condition afterward."
+        }
+        ApprovalDecision::Explain => "Asks codex why it needs this; the request stays open.",
+        ApprovalDecision::EditCommand => "Opens the command for editing before running it.",
+        ApprovalDecision::DenyWithFeedback => "Declines it with a reason you type for codex.",
+        ApprovalDecision::DenyToInvestigate => {
+            "Declines it and asks codex to investigate before retrying."
+        }
+        ApprovalDecision::PickDuration(_) => "Asks how long to allow it for, then allows it.",
+        ApprovalDecision::PickFiles => "Lists the changed files so you can leave some out.",
+        ApprovalDecision::ApproveAsMacro => {
+            "Allows it and identical commands for the session, under a name you choose."
+        }
+        ApprovalDecision::McpElicitation(action) => match action {
+            ElicitationAction::Accept => "Sends the requested information to the server.",
+            ElicitationAction::Decline => "Tells the server you decline to provide it.",
+            ElicitationAction::Cancel => "Cancels the server's request.",
+        },
+    }
+}
+
+// Post-run assertion templates offered when approving an exec request.
+//
+// Every command gets an "exits with code 0" template. Commands whose output
+// file is easy to read off the argv (copies, moves, downloads, ...) also get
+// a "path exists afterward" template for that file.
+
+/// Programs whose last positional argument is the path they create.
+const LAST_ARG_CREATES: &[&str] = &["cp", "install", "ln", "mkdir", "mv", "touch"];
+
+/// Templates for `command`, most general first.
+pub(crate) fn assertion_templates(command: &[String]) -> Vec<PostRunAssertion> {
+    let mut templates = vec![PostRunAssertion::ExitCode { code: 0 }];
+    let commands = parse_shell_lc_plain_commands(command).unwrap_or_else(|| vec![command.to_vec()]);
+    // Paths are checked against the command's working directory, so skip
+    // scripts that change directory first.
+    let changes_dir = commands
+        .iter()
+        .any(|argv| argv.first().is_some_and(|program| program == "cd"));
+    if !changes_dir && let Some(path) = commands.last().and_then(|argv| created_path(argv)) {
+        templates.push(PostRunAssertion::PathExists { path });
+    }
+    templates
+}
+
+fn created_path(argv: &[String]) -> Option<PathBuf> {
+    let (program, args) = argv.split_first()?;
+    let name = Path::new(program).file_name()?.to_str()?;
+    let path = match name {
+        "curl" | "wget" => output_flag_value(args, name == "wget")?,
+        _ if LAST_ARG_CREATES.contains(&name) => {
+            let positional: Vec<&String> =
+                args.iter().filter(|arg| !arg.starts_with('-')).collect();
+            // A single operand is a destination only for programs that create it.
+            if positional.len() < 2 && !matches!(name, "mkdir" | "touch") {
+                return None;
+            }
+            positional.last()?.as_str()
+        }
+        _ => return None,
+    };
+    Some(PathBuf::from(path))
+}
+
+/// Value of `-o`/`--output` (and `-O` for wget, which names the output file).
+fn output_flag_value(args: &[String], is_wget: bool) -> Option<&str> {
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        if arg == "-o" || arg == "--output" || (is_wget && arg == "-O") {
+            return args.next().map(String::as_str);
+        }
+        if let Some(value) = arg.strip_prefix("--output=") {
+            return Some(value);
+        }
+    }
+    None
+}
+
+// Rewrites an exec approval command so the program runs with verbose output.
+//
+// Only programs whose verbose flag is known (and accepted directly after the
+// program name) are rewritten; everything else is approved as-is.
+
+/// Programs paired with the flag that turns on verbose output. Every flag in
+/// this table must be valid immediately after the program name.
+const VERBOSE_FLAGS: &[(&str, &str)] = &[
+    ("cargo", "--verbose"),
+    ("chmod", "-v"),
+    ("chown", "-v"),
+    ("cp", "-v"),
+    ("curl", "--verbose"),
+    ("ln", "-v"),
+    ("mkdir", "-v"),
+    ("mv", "-v"),
+    ("pip", "--verbose"),
+    ("pip3", "--verbose"),
+    ("pytest", "-v"),
+    ("rm", "-v"),
+    ("rmdir", "-v"),
+    ("rsync", "--verbose"),
+    ("scp", "-v"),
+    ("ssh", "-v"),
+    ("tar", "-v"),
+    ("wget", "--verbose"),
+];
+
+/// Characters that make a `bash -lc` script more than a single simple command.
+const SHELL_OPERATOR_CHARS: &[char] = &['|', '&', ';', '<', '>', '(', ')', '$', '`', '\n'];
+
+/// Returns `command` with the program's verbose flag inserted, or `None` when
+/// the program has no known verbose flag or the flag is already present.
+pub(crate) fn verbose_command(command: &[String]) -> Option<Vec<String>> {
+    if let Some((_, script)) = extract_bash_command(command) {
+        if script.contains(SHELL_OPERATOR_CHARS) {
+            return None;
+        }
+        let words = shlex::split(script)?;
+        let verbose_words = with_verbose_flag(&words)?;
+        let verbose_script = shlex::try_join(verbose_words.iter().map(String::as_str)).ok()?;
+        let mut rewritten = command.to_vec();
+        *rewritten.last_mut()? = verbose_script;
+        return Some(rewritten);
+    }
+    with_verbose_flag(command)
+}
+
+fn with_verbose_flag(argv: &[String]) -> Option<Vec<String>> {
+    let (program, args) = argv.split_first()?;
+    let name = Path::new(program).file_name()?.to_str()?;
+    let (_, flag) = VERBOSE_FLAGS
+        .iter()
+        .find(|(candidate, _)| *candidate == name)?;
+    if args.iter().any(|arg| arg == flag) {
+        return None;
+    }
+    let mut rewritten = Vec::with_capacity(argv.len() + 1);
+    rewritten.push(program.clone());
+    rewritten.push((*flag).to_string());
+    rewritten.extend(args.iter().cloned());
+    Some(rewritten)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_protocol::models::FileSystemPermissions;
+    use codex_protocol::protocol::NetworkApprovalProtocol;
+    use codex_protocol::protocol::NetworkPolicyAmendment;
+    use codex_utils_absolute_path::AbsolutePathBuf;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn network_exec_options_use_expected_labels_and_hide_execpolicy_amendment() {
+        let network_context = NetworkApprovalContext {
+            host: "example.com".to_string(),
+            protocol: NetworkApprovalProtocol::Https,
+        };
+        let options = exec_options(
+            &[],
+            &[
+                ReviewDecision::Approved,
+                ReviewDecision::ApprovedForSession,
+                ReviewDecision::NetworkPolicyAmendment {
+                    network_policy_amendment: NetworkPolicyAmendment {
+                        host: "example.com".to_string(),
+                        action: NetworkPolicyRuleAction::Allow,
+                    },
+                },
+                ReviewDecision::Abort,
+            ],
+            Some(&network_context),
+            None,
+            true,
+            ApprovalLanguage::En,
+        );
+
+        let labels: Vec<String> = options.into_iter().map(|option| option.label).collect();
+        assert_eq!(
+            labels,
+            vec![
+                "Yes, just this once".to_string(),
+                "Yes, and allow this host for this conversation".to_string(),
+                "Yes, and allow this host in the future".to_string(),
+                "No, and tell Codex what to do differently".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn generic_exec_options_can_offer_allow_for_session() {
+        let options = exec_options(
+            &[],
+            &[
+                ReviewDecision::Approved,
+                ReviewDecision::ApprovedForSession,
+                ReviewDecision::Abort,
+            ],
+            None,
+            None,
+            false,
+            ApprovalLanguage::En,
+        );
+
+        let labels: Vec<String> = options.into_iter().map(|option| option.label).collect();
+        assert_eq!(
+            labels,
+            vec![
+                "Yes, proceed".to_string(),
+                "Yes, and don't ask again for this command in this session".to_string(),
+                "No, and tell Codex what to do differently".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn additional_permissions_exec_options_hide_execpolicy_amendment() {
+        let additional_permissions = PermissionProfile {
+            file_system: Some(FileSystemPermissions {
+                read: Some(vec![
+                    AbsolutePathBuf::from_absolute_path("/tmp/readme.txt").expect("absolute path"),
+                ]),
+                write: Some(vec![
+                    AbsolutePathBuf::from_absolute_path("/tmp/out.txt").expect("absolute path"),
+                ]),
+            }),
+            ..Default::default()
+        };
+        let options = exec_options(
+            &[],
+            &[ReviewDecision::Approved, ReviewDecision::Abort],
+            None,
+            Some(&additional_permissions),
+            false,
+            ApprovalLanguage::En,
+        );
+
+        let labels: Vec<String> = options.into_iter().map(|option| option.label).collect();
+        assert_eq!(
+            labels,
+            vec![
+                "Yes, proceed".to_string(),
+                "No, and tell Codex what to do differently".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn permissions_options_use_expected_labels() {
+        let labels: Vec<String> = permissions_options(ApprovalLanguage::En)
+            .into_iter()
+            .map(|option| option.label)
+            .collect();
+        assert_eq!(
+            labels,
+            vec![
+                "Yes, grant these permissions".to_string(),
+                "Yes, grant these permissions for this session".to_string(),
+                "No, continue without permissions".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn directory_approval_follows_session_approval() {
+        assert_eq!(
+            with_directory_approval(&[
+                ReviewDecision::Approved,
+                ReviewDecision::ApprovedForSession,
+                ReviewDecision::Abort,
+            ]),
+            vec![
+                ReviewDecision::Approved,
+                ReviewDecision::ApprovedForSession,
+                ReviewDecision::ApprovedForDirectory,
+                ReviewDecision::Abort,
+            ]
+        );
+        let without_session = [ReviewDecision::Approved, ReviewDecision::Abort];
+        assert_eq!(
+            with_directory_approval(&without_session),
+            without_session.to_vec()
+        );
+    }
+
+    #[test]
+    fn extra_answers_go_before_the_abort_once() {
+        let decisions = with_extended_decisions(&[
+            ReviewDecision::Approved,
+            ReviewDecision::DryRun,
+            ReviewDecision::Abort,
+        ]);
+        assert_eq!(
+            decisions,
+            vec![
+                ReviewDecision::Approved,
+                ReviewDecision::DryRun,
+                ReviewDecision::ApprovedForDuration {
+                    duration: DEFAULT_APPROVAL_DURATION,
+                },
+                ReviewDecision::ApprovedAlways,
+                ReviewDecision::ApprovedSandboxed,
+                ReviewDecision::DeniedForSession,
+                ReviewDecision::Abort,
+            ]
+        );
+    }
+
+    fn shortcuts(options: &[ApprovalOption]) -> Vec<Vec<KeyBinding>> {
+        options
+            .iter()
+            .map(|option| option.shortcuts().collect())
+            .collect()
+    }
+
+    #[test]
+    fn configured_keys_replace_the_defaults() {
+        let options = patch_options(ApprovalLanguage::En);
+        let keys = BTreeMap::from([(ApprovalShortcut::Approve, 'j')]);
+        let configured = with_configured_keys(options.clone(), Some(&keys));
+        let mut expected = shortcuts(&options);
+        expected[0] = vec![key_hint::plain(KeyCode::Char('j'))];
+        assert_eq!(shortcuts(&configured), expected);
+    }
+
+    #[test]
+    fn conflicting_or_reserved_keys_keep_the_defaults() {
+        let options = patch_options(ApprovalLanguage::En);
+        for keys in [
+            // `a` is already "Yes, for this session".
+            BTreeMap::from([(ApprovalShortcut::Approve, 'a')]),
+            BTreeMap::from([(ApprovalShortcut::Approve, 'v')]),
+            BTreeMap::from([(ApprovalShortcut::Abort, '1')]),
+        ] {
+            let configured = with_configured_keys(options.clone(), Some(&keys));
+            assert_eq!(shortcuts(&configured), shortcuts(&options), "{keys:?}");
+        }
+    }
+
+    #[test]
+    fn missing_translations_fall_back_to_english() {
+        assert_eq!(text(ApprovalLanguage::De, Label::Proceed), "Ja, fortfahren");
+        assert_eq!(
+            text(ApprovalLanguage::De, Label::DryRun),
+            text(ApprovalLanguage::En, Label::DryRun)
+        );
+    }
+
+    #[test]
+    fn accelerator_is_the_lowercase_first_letter() {
+        assert_eq!(
+            accelerator(text(ApprovalLanguage::En, Label::TellCodex)),
+            vec![key_hint::plain(KeyCode::Char('n'))]
+        );
+        assert_eq!(
+            accelerator(text(ApprovalLanguage::De, Label::CancelRequest)),
+            vec![key_hint::plain(KeyCode::Char('a'))]
+        );
+        assert_eq!(accelerator("…"), Vec::new());
+        // `e` opens the feedback input before any answer sees it.
+        assert_eq!(accelerator("Erlauben"), Vec::new());
+    }
+
+    fn templates(args: &[&str]) -> Vec<PostRunAssertion> {
+        let command: Vec<String> = args.iter().map(ToString::to_string).collect();
+        assertion_templates(&command)
+    }
+
+    fn exists(path: &str) -> PostRunAssertion {
+        PostRunAssertion::PathExists {
+            path: PathBuf::from(path),
+        }
+    }
+
+    #[test]
+    fn offers_path_exists_for_created_files() {
+        let exit_zero = PostRunAssertion::ExitCode { code: 0 };
+        assert_eq!(
+            templates(&["cp", "-r", "src", "backup/src"]),
+            vec![exit_zero.clone(), exists("backup/src")]
+        );
+        assert_eq!(
+            templates(&["curl", "-L", "-o", "out.tar.gz", "https://example.com"]),
+            vec![exit_zero.clone(), exists("out.tar.gz")]
+        );
+        assert_eq!(
+            templates(&["bash", "-lc", "make && mkdir -p dist"]),
+            vec![exit_zero.clone(), exists("dist")]
+        );
+        assert_eq!(
+            templates(&["bash", "-lc", "cd build && mkdir -p dist"]),
+            vec![exit_zero.clone()]
+        );
+        assert_eq!(templates(&["cargo", "build"]), vec![exit_zero.clone()]);
+        assert_eq!(templates(&["mv", "only-one"]), vec![exit_zero]);
+    }
+
+    fn argv(words: &[&str]) -> Vec<String> {
+        words.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn inserts_flag_after_known_program() {
+        assert_eq!(
+            verbose_command(&argv(&["cargo", "build", "--release"])),
+            Some(argv(&["cargo", "--verbose", "build", "--release"]))
+        );
+        assert_eq!(
+            verbose_command(&argv(&["/bin/rm", "-r", "target"])),
+            Some(argv(&["/bin/rm", "-v", "-r", "target"]))
+        );
+    }
+
+    #[test]
+    fn skips_unknown_programs_and_existing_flags() {
+        assert_eq!(verbose_command(&argv(&["echo", "hi"])), None);
+        assert_eq!(verbose_command(&argv(&["curl", "--verbose", "x"])), None);
+        assert_eq!(verbose_command(&[]), None);
+    }
+
+    #[test]
+    fn rewrites_simple_shell_scripts_only() {
+        assert_eq!(
+            verbose_command(&argv(&["bash", "-lc", "curl 'https://example.com/a b'"])),
+            Some(argv(&[
+                "bash",
+                "-lc",
+                "curl --verbose 'https://example.com/a b'"
+            ]))
+        );
+        assert_eq!(
+            verbose_command(&argv(&["bash", "-lc", "cargo build && rm -r target"])),
+            None
+        );
+    }
+}
@@ -0,0 +1,1299 @@
+//! Drawing the modal.
+//!
+//! Covers the theme and frame, the compact and popup layouts, the prompt
+//! title, scrolling and wrapping, the accessibility passes (high contrast,
+//! colorblind palette, dimming without focus) and rendering without a
+//! terminal.
+
+use super::ApprovalOverlay;
+use super::ApprovalRequest;
+use super::decisions::AutoDeny;
+use super::decisions::display_secs;
+use super::decisions::sent_line;
+use super::keys::CopiedHint;
+use super::options::ApprovalOption;
+use super::options::all_descriptions;
+use super::options::select_shortcuts;
+use super::prompt::bounded_argv;
+use super::prompt::redact_secrets;
+use super::risk::RiskLevel;
+use crate::bottom_pane::BottomPaneView;
+use crate::exec_command::strip_bash_lc_and_escape;
+use crate::key_hint;
+use crate::key_hint::KeyBinding;
+use crate::line_truncation::truncate_line_with_ellipsis_if_overflow;
+use crate::render::line_utils::push_owned_lines;
+use crate::render::renderable::Renderable;
+use crate::wrapping::RtOptions;
+use crate::wrapping::word_wrap_line;
+use crossterm::Command;
+use crossterm::event::KeyCode;
+use crossterm::style::Attribute;
+use crossterm::style::SetAttribute;
+use crossterm::style::SetBackgroundColor;
+use crossterm::style::SetForegroundColor;
+use ratatui::buffer::Buffer;
+use ratatui::buffer::Cell as BufferCell;
+use ratatui::layout::Rect;
+use ratatui::style::Color;
+use ratatui::style::Modifier;
+use ratatui::style::Style;
+use ratatui::style::Stylize;
+use ratatui::text::Line;
+use ratatui::text::Span;
+use ratatui::widgets::Block;
+use ratatui::widgets::BorderType;
+use ratatui::widgets::Borders;
+use ratatui::widgets::Padding;
+use ratatui::widgets::Paragraph;
+use ratatui::widgets::Widget;
+use ratatui::widgets::Wrap;
+use std::cell::Cell;
+use std::fmt;
+use std::rc::Rc;
+use std::sync::Arc;
+use textwrap::WordSplitter;
+use unicode_width::UnicodeWidthStr;
+
+impl ApprovalOverlay {
+    /// The modal drawn into a `width` by `height` area off screen, as the
+    /// text a terminal would show, for docs and bug reports.
+    #[cfg_attr(not(test), allow(dead_code))]
+    pub(crate) fn render_to_string(&self, width: u16, height: u16) -> String {
+        buffer_text(&self.render_offscreen(width, height))
+    }
+
+    /// [`Self::render_to_string`] with colors and attributes kept as ANSI
+    /// escapes.
+    #[cfg_attr(not(test), allow(dead_code))]
+    pub(crate) fn render_to_ansi_string(&self, width: u16, height: u16) -> String {
+        // Writing to a `String` does not fail.
+        buffer_ansi(&self.render_offscreen(width, height)).unwrap_or_default()
+    }
+
+    #[cfg_attr(not(test), allow(dead_code))]
+    fn render_offscreen(&self, width: u16, height: u16) -> Buffer {
+        let area = Rect::new(0, 0, width, height);
+        let mut buf = Buffer::empty(area);
+        self.render(area, &mut buf);
+        buf
+    }
+
+    /// Every option's description, while `?` has them shown.
+    fn descriptions(&self) -> Option<Paragraph<'static>> {
+        self.show_all_descriptions.then(|| {
+            let lang = self.config.language.unwrap_or_default();
+            Paragraph::new(all_descriptions(&self.options, lang)).wrap(Wrap { trim: false })
+        })
+    }
+
+    /// Whether the prompt is drawn as two rows: `compact` is on, the user has
+    /// not expanded it, and no text input is open.
+    pub(crate) fn is_compact(&self) -> bool {
+        self.config.compact.unwrap_or(false) && !self.expanded && self.text_input().is_none()
+    }
+
+    fn compact_lines(&self) -> [Line<'static>; 2] {
+        let summary = self
+            .current_request
+            .as_ref()
+            .map(summary_line)
+            .unwrap_or_default();
+        let selected = self
+            .list
+            .selected_index()
+            .and_then(|idx| self.options.get(idx));
+        [
+            summary,
+            options_line(selected, &select_shortcuts(&self.options), &self.theme),
+        ]
+    }
+
+    /// The note replacing the prompt while the modal lingers after the last
+    /// answer.
+    fn sent_line(&self) -> Option<Line<'static>> {
+        if !self.done || self.linger.remaining().is_none() {
+            return None;
+        }
+        let decision = self.last_decision.borrow();
+        decision
+            .as_ref()
+            .map(|decision| sent_line(decision, &self.theme))
+    }
+
+    /// The part of `area` the modal draws into, border included: a centered
+    /// column no wider than the configured `max_width`.
+    pub(crate) fn frame_area(&self, area: Rect) -> Rect {
+        let width = self
+            .config
+            .max_width
+            .filter(|max| *max > 0)
+            .map_or(area.width, |max| area.width.min(max));
+        Rect {
+            x: area.x + (area.width - width) / 2,
+            width,
+            ..area
+        }
+    }
+
+    /// The area inside the modal's border.
+    pub(crate) fn content_area(&self, area: Rect) -> Rect {
+        let frame = self.frame_area(area);
+        self.border_block()
+            .map_or(frame, |block| block.inner(frame))
+    }
+
+    /// The popup frame when drawn as a popup, otherwise the theme's.
+    fn border_block(&self) -> Option<Block<'static>> {
+        if self.popup {
+            Some(popup_block(&self.theme, self.current_request.as_ref()))
+        } else {
+            self.theme.border_block()
+        }
+    }
+
+    fn border_rows(&self) -> u16 {
+        if self.popup {
+            POPUP_BORDER_ROWS
+        } else {
+            self.theme.border_rows()
+        }
+    }
+}
+
+impl Renderable for ApprovalOverlay {
+    fn desired_height(&self, width: u16) -> u16 {
+        let border_rows = self.border_rows();
+        if self.sent_line().is_some() {
+            return 1 + border_rows;
+        }
+        if self.is_compact() {
+            return COMPACT_HEIGHT + border_rows;
+        }
+        let area = self.content_area(Rect::new(0, 0, width, 0));
+        let content_rows = match (self.text_input(), self.descriptions()) {
+            (Some(input), _) => input.desired_height(area.width),
+            (None, Some(descriptions)) => descriptions.desired_height(area.width),
+            (None, None) => self.list.desired_height(area.width),
+        };
+        // Past `max_height` the list view keeps its option rows and footer
+        // and shrinks the prompt, which then scrolls.
+        let height = content_rows + border_rows;
+        self.config
+            .max_height
+            .filter(|max| *max > 0)
+            .map_or(height, |max| height.min(max))
+    }
+
+    fn render(&self, area: Rect, buf: &mut Buffer) {
+        if let Some(block) = self.border_block() {
+            let frame = self.frame_area(area);
+            block.render(frame, buf);
+            if self.popup || self.theme.borders.contains(Borders::LEFT) {
+                paint_left_edge(
+                    frame,
+                    self.severity.color(&self.theme),
+                    self.severity.edge_symbol(&self.theme),
+                    buf,
+                );
+            }
+        }
+        if let Some(line) = self.sent_line() {
+            line.render(self.content_area(area), buf);
+        } else if self.is_compact() {
+            render_compact(self.compact_lines(), self.content_area(area), buf);
+        } else {
+            match (self.text_input(), self.descriptions()) {
+                (Some(input), _) => input.render(self.content_area(area), buf),
+                (None, Some(descriptions)) => descriptions.render(self.content_area(area), buf),
+                (None, None) => self.list.render(self.content_area(area), buf),
+            }
+        }
+        if self.theme.high_contrast {
+            apply_high_contrast(area, buf);
+        } else if self.theme.colorblind_safe {
+            apply_colorblind_palette(area, buf);
+        }
+        if !self.focused {
+            mute(area, buf);
+        }
+    }
+
+    fn cursor_pos(&self, area: Rect) -> Option<(u16, u16)> {
+        if self.is_compact() {
+            return None;
+        }
+        match self.text_input() {
+            Some(input) => input.cursor_pos(self.content_area(area)),
+            None => self.list.cursor_pos(self.content_area(area)),
+        }
+    }
+}
+
+// Colors and border the approval modal draws with.
+//
+// The defaults follow the TUI's usual palette. Hosts that know the terminal
+// background (or the user's preferred palette) can pass their own so
+// warnings stay readable on light themes and low-contrast setups. The modal
+// has no border by default, like the other bottom-pane popups; a host can
+// frame it on any sides, drawn in the accent color except for a left side,
+// which takes the color of the request's riskiest warning.
+//
+// [`ApprovalTheme::high_contrast`] is the preset `tui.approvals.high_contrast`
+// selects: no colors at all, a thick frame, and a pass after rendering that
+// strips whatever color or dimming the prompt's parts drew with.
+//
+// [`ApprovalTheme::colorblind_safe`] is what `tui.approvals.colorblind_safe`
+// applies on top: blue for success and orange for warnings, so no meaning
+// rests on telling red from green. ANSI has no orange; the terminal's yellow
+// stands in for it, and cautions move to magenta to stay apart from
+// warnings.
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) struct ApprovalTheme {
+    /// Emphasis for neutral status: rule lines, new-command badges, target
+    /// names, command flags and the feedback input gutter.
+    pub(crate) accent: Color,
+    /// Risky details: destructive commands, production targets, test edits,
+    /// network access and failed prior runs.
+    pub(crate) warning: Color,
+    /// Milder warnings: network access and unbalanced quoting, on the
+    /// modal's left edge, and the shell operators in a command.
+    pub(crate) caution: Color,
+    /// Confirmations, successful prior runs and quoted strings in a command.
+    pub(crate) success: Color,
+    /// Sides framed around the modal.
+    pub(crate) borders: Borders,
+    pub(crate) border_type: BorderType,
+    /// Redraw what was rendered in the terminal's own colors, with the
+    /// selected option reversed.
+    pub(crate) high_contrast: bool,
+    /// Recolor red and green in what was rendered, and add shapes to
+    /// color-coded risk cues.
+    pub(crate) colorblind_safe: bool,
+}
+
+impl Default for ApprovalTheme {
+    fn default() -> Self {
+        Self {
+            accent: Color::Cyan,
+            warning: Color::Red,
+            caution: Color::Yellow,
+            success: Color::Green,
+            borders: Borders::NONE,
+            border_type: BorderType::Plain,
+            high_contrast: false,
+            colorblind_safe: false,
+        }
+    }
+}
+
+impl ApprovalTheme {
+    /// The terminal's own foreground for every role, framed thick on all
+    /// sides.
+    pub(crate) fn high_contrast() -> Self {
+        Self {
+            accent: Color::Reset,
+            warning: Color::Reset,
+            caution: Color::Reset,
+            success: Color::Reset,
+            borders: Borders::ALL,
+            border_type: BorderType::Thick,
+            high_contrast: true,
+            colorblind_safe: false,
+        }
+    }
+
+    /// `self` with blue and orange in place of green and red. A high-contrast
+    /// theme keeps the terminal's own colors and only gains the shapes.
+    pub(crate) fn colorblind_safe(self) -> Self {
+        if self.high_contrast {
+            return Self {
+                colorblind_safe: true,
+                ..self
+            };
+        }
+        Self {
+            warning: Color::Yellow,
+            caution: Color::Magenta,
+            success: Color::Blue,
+            colorblind_safe: true,
+            ..self
+        }
+    }
+
+    /// The modal's frame, or `None` when no side is framed.
+    pub(crate) fn border_block(&self) -> Option<Block<'static>> {
+        (!self.borders.is_empty()).then(|| {
+            Block::new()
+                .borders(self.borders)
+                .border_type(self.border_type)
+                .border_style(Style::new().fg(self.accent))
+        })
+    }
+
+    /// Rows the frame takes above and below the content.
+    pub(crate) fn border_rows(&self) -> u16 {
+        u16::from(self.borders.contains(Borders::TOP))
+            + u16::from(self.borders.contains(Borders::BOTTOM))
+    }
+}
+
+// The modal drawn as a boxed popup instead of inline in the bottom pane.
+//
+// Inline, the options sit on the shared menu surface with whatever sides the
+// theme frames. A host that floats the modal over the transcript wants it
+// closed on all four sides, with a column of padding inside the border and
+// the request's kind named in the top edge so it reads on its own.
+
+/// Rows the popup frame takes above and below the content.
+pub(crate) const POPUP_BORDER_ROWS: u16 = 2;
+
+/// The popup's frame: every side in the theme's border type and accent
+/// color, titled with what `request` asks for.
+pub(crate) fn popup_block(
+    theme: &ApprovalTheme,
+    request: Option<&ApprovalRequest>,
+) -> Block<'static> {
+    let block = Block::new()
+        .borders(Borders::ALL)
+        .border_type(theme.border_type)
+        .border_style(Style::new().fg(theme.accent))
+        .padding(Padding::horizontal(1));
+    match request {
+        Some(request) => block.title_top(Line::from(format!(" {} ", popup_title(request)).bold())),
+        None => block,
+    }
+}
+
+/// The kind of request, short enough for the top border.
+fn popup_title(request: &ApprovalRequest) -> String {
+    match request {
+        ApprovalRequest::Exec {
+            network_approval_context: Some(_),
+            ..
+        } => "Network access".to_string(),
+        ApprovalRequest::Exec { .. } => "Run command".to_string(),
+        ApprovalRequest::Permissions { .. } => "Permissions".to_string(),
+        ApprovalRequest::ApplyPatch { .. } => "Edit files".to_string(),
+        ApprovalRequest::McpElicitation { server_name, .. } => server_name.clone(),
+    }
+}
+
+// A two-row prompt for busy screens.
+//
+// With `compact` set, the request is summed up on one line and the
+// highlighted option with the accepted shortcuts on the next. Space shows
+// the full prompt and hides it again; the keys work the same either way.
+
+/// Rows the prompt takes while compact.
+pub(crate) const COMPACT_HEIGHT: u16 = 2;
+
+/// What the request asks for, in one line.
+pub(crate) fn summary_line(request: &ApprovalRequest) -> Line<'static> {
+    let summary = match request {
+        ApprovalRequest::Exec {
+            network_approval_context: Some(network_approval_context),
+            ..
+        } => format!(
+            "codex wants network access to {}",
+            network_approval_context.host
+        ),
+        ApprovalRequest::Exec { command, .. } => {
+            let command = redact_secrets(&strip_bash_lc_and_escape(&bounded_argv(command)));
+            let mut lines = command.lines();
+            let first = lines.next().unwrap_or_default();
+            let more = if lines.next().is_some() { "…" } else { "" };
+            format!("codex wants to run: {first}{more}")
+        }
+        ApprovalRequest::Permissions { .. } => "codex wants additional permissions".to_string(),
+        ApprovalRequest::ApplyPatch { changes, .. } => {
+            let count = changes.len();
+            let files = if count == 1 { "file" } else { "files" };
+            format!("codex wants to edit {count} {files}")
+        }
+        ApprovalRequest::McpElicitation { server_name, .. } => {
+            format!("{server_name} needs your approval")
+        }
+    };
+    Line::from(summary.bold())
+}
+
+/// The highlighted option, the shortcuts that answer directly, and how to
+/// expand.
+pub(crate) fn options_line(
+    selected: Option<&ApprovalOption>,
+    shortcuts: &[KeyBinding],
+    theme: &ApprovalTheme,
+) -> Line<'static> {
+    let sep = || Span::from(" · ").dim();
+    let mut spans = Vec::new();
+    if let Some(option) = selected {
+        spans.push("› ".fg(theme.accent));
+        spans.push(option.label.clone().fg(theme.accent).bold());
+        spans.push(sep());
+    }
+    for (idx, shortcut) in shortcuts.iter().enumerate() {
+        if idx > 0 {
+            spans.push("/".dim());
+        }
+        spans.push(shortcut.into());
+    }
+    if !shortcuts.is_empty() {
+        spans.push(" select".dim());
+        spans.push(sep());
+    }
+    spans.push(key_hint::plain(KeyCode::Char(' ')).into());
+    spans.push(" expand".dim());
+    Line::from(spans)
+}
+
+/// Draw `lines`, each cut to one row of `area`.
+pub(crate) fn render_compact(lines: [Line<'static>; 2], area: Rect, buf: &mut Buffer) {
+    let width = usize::from(area.width);
+    let lines: Vec<Line<'static>> = lines
+        .into_iter()
+        .map(|line| truncate_line_with_ellipsis_if_overflow(line, width))
+        .collect();
+    Paragraph::new(lines).render(area, buf);
+}
+
+// Memoizes the height of the approval prompt for the last queried width.
+//
+// The list view asks the header for its height on every `desired_height`
+// and `render`, and each answer re-wraps the whole prompt. The header only
+// changes when the user expands or collapses a long command, which calls
+// [`CachedHeight::invalidate`]; otherwise only a width change needs a new
+// measurement.
+
+pub(crate) struct CachedHeight<R> {
+    inner: R,
+    /// Last `(width, height)` pair measured.
+    last: Cell<Option<(u16, u16)>>,
+}
+
+impl<R: Renderable> CachedHeight<R> {
+    pub(crate) fn new(inner: R) -> Self {
+        Self {
+            inner,
+            last: Cell::new(None),
+        }
+    }
+
+    /// Forget the last measurement after the inner content changed.
+    pub(crate) fn invalidate(&self) {
+        self.last.set(None);
+    }
+}
+
+impl<R: Renderable> Renderable for CachedHeight<R> {
+    fn render(&self, area: Rect, buf: &mut Buffer) {
+        self.inner.render(area, buf);
+    }
+
+    fn desired_height(&self, width: u16) -> u16 {
+        if let Some((last_width, height)) = self.last.get()
+            && last_width == width
+        {
+            return height;
+        }
+        let height = self.inner.desired_height(width);
+        self.last.set(Some((width, height)));
+        height
+    }
+
+    fn cursor_pos(&self, area: Rect) -> Option<(u16, u16)> {
+        self.inner.cursor_pos(area)
+    }
+}
+
+// Rendering the modal without a terminal.
+//
+// [`ApprovalOverlay::render_to_string`] draws the modal into an off-screen
+// buffer through the same `render` the terminal uses and returns what it
+// shows, for documentation, snapshot tests and modal state attached to bug
+// reports. The plain form has the text only. The ANSI form keeps colors and
+// attributes as SGR escapes. Both trim trailing blanks from each row and
+// print a wide character once rather than once per column it covers.
+//
+// [`ApprovalOverlay::render_to_string`]: super::ApprovalOverlay::render_to_string
+
+/// Attributes carried into the ANSI form.
+const ATTRIBUTES: &[(Modifier, Attribute)] = &[
+    (Modifier::BOLD, Attribute::Bold),
+    (Modifier::DIM, Attribute::Dim),
+    (Modifier::ITALIC, Attribute::Italic),
+    (Modifier::UNDERLINED, Attribute::Underlined),
+    (Modifier::REVERSED, Attribute::Reverse),
+    (Modifier::CROSSED_OUT, Attribute::CrossedOut),
+];
+
+/// The text `buf` shows, one line per row.
+pub(crate) fn buffer_text(buf: &Buffer) -> String {
+    rows(buf)
+        .map(|cells| {
+            cells
+                .iter()
+                .map(|cell| cell.symbol())
+                .collect::<String>()
+                .trim_end()
+                .to_string()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// The text `buf` shows with its styling as SGR escapes, one line per row.
+pub(crate) fn buffer_ansi(buf: &Buffer) -> Result<String, fmt::Error> {
+    let mut out = String::new();
+    for (idx, mut cells) in rows(buf).enumerate() {
+        if idx > 0 {
+            out.push('\n');
+        }
+        while cells
+            .last()
+            .is_some_and(|cell| cell.symbol() == " " && is_unstyled(cell))
+        {
+            cells.pop();
+        }
+        let mut styled = false;
+        let mut current = Style::default();
+        for cell in cells {
+            let style = cell.style();
+            if style != current {
+                if styled {
+                    SetAttribute(Attribute::Reset).write_ansi(&mut out)?;
+                }
+                styled = write_style(cell, &mut out)?;
+                current = style;
+            }
+            out.push_str(cell.symbol());
+        }
+        if styled {
+            SetAttribute(Attribute::Reset).write_ansi(&mut out)?;
+        }
+    }
+    Ok(out)
+}
+
+/// The cells of each row, leaving out the columns a wide character before
+/// them covers.
+fn rows(buf: &Buffer) -> impl Iterator<Item = Vec<&BufferCell>> {
+    let area = buf.area;
+    (area.top()..area.bottom()).map(move |y| {
+        let mut cells = Vec::new();
+        let mut covered = 0;
+        for x in area.left()..area.right() {
+            if covered > 0 {
+                covered -= 1;
+                continue;
+            }
+            let cell = &buf[(x, y)];
+            covered = cell.symbol().width().saturating_sub(1);
+            cells.push(cell);
+        }
+        cells
+    })
+}
+
+fn is_unstyled(cell: &BufferCell) -> bool {
+    cell.fg == Color::Reset && cell.bg == Color::Reset && cell.modifier.is_empty()
+}
+
+/// Write the escapes for `cell`'s style, returning whether there were any.
+fn write_style(cell: &BufferCell, out: &mut String) -> Result<bool, fmt::Error> {
+    for (modifier, attribute) in ATTRIBUTES {
+        if cell.modifier.contains(*modifier) {
+            SetAttribute(*attribute).write_ansi(out)?;
+        }
+    }
+    if cell.fg != Color::Reset {
+        SetForegroundColor(cell.fg.into()).write_ansi(out)?;
+    }
+    if cell.bg != Color::Reset {
+        SetBackgroundColor(cell.bg.into()).write_ansi(out)?;
+    }
+    Ok(!is_unstyled(cell))
+}
+
+// Redrawing the modal at the terminal's highest contrast.
+//
+// The prompt is assembled from many parts, each styling its own spans, so
+// rather than thread a flag through all of them the finished frame is
+// rewritten: every cell takes the terminal's default foreground and
+// background, dimmed text is drawn at full intensity, and the selected
+// option's row, the one marked `›`, becomes bold reversed video.
+
+/// Marks the selected option in the list and in the compact prompt.
+const SELECTION_MARKER: &str = "›";
+
+/// The thick frame's sides, which the selection highlight stops short of.
+const THICK_SIDE: &str = "┃";
+
+pub(crate) fn apply_high_contrast(area: Rect, buf: &mut Buffer) {
+    let area = area.intersection(buf.area);
+    for y in area.top()..area.bottom() {
+        let symbol = |x: u16| buf[(x, y)].symbol().to_string();
+        let selected = (area.left()..area.right())
+            .find(|&x| symbol(x) == SELECTION_MARKER)
+            .and_then(|start| {
+                (start..area.right())
+                    .rev()
+                    .find(|&x| !symbol(x).trim().is_empty() && symbol(x) != THICK_SIDE)
+                    .map(|end| start..=end)
+            });
+        for x in area.left()..area.right() {
+            let cell = &mut buf[(x, y)];
+            cell.set_fg(Color::Reset);
+            cell.set_bg(Color::Reset);
+            cell.modifier.remove(Modifier::DIM);
+            if selected.as_ref().is_some_and(|row| row.contains(&x)) {
+                cell.modifier.insert(Modifier::BOLD | Modifier::REVERSED);
+            }
+        }
+    }
+}
+
+// Redrawing the modal without red-green distinctions.
+//
+// The modal's own parts pick their colors from the theme, but the diffs and
+// highlighted commands it embeds draw with fixed reds and greens. Rather than
+// thread the flag through those renderers, the finished frame is recolored:
+// red becomes the theme's orange (the terminal's yellow) and green becomes
+// blue, in foregrounds and backgrounds alike. Diff lines keep their `+` and
+// `-` signs, so tinted backgrounds the pass leaves alone still read.
+
+pub(crate) fn apply_colorblind_palette(area: Rect, buf: &mut Buffer) {
+    let area = area.intersection(buf.area);
+    for y in area.top()..area.bottom() {
+        for x in area.left()..area.right() {
+            let cell = &mut buf[(x, y)];
+            cell.fg = substitute(cell.fg);
+            cell.bg = substitute(cell.bg);
+        }
+    }
+}
+
+fn substitute(color: Color) -> Color {
+    match color {
+        Color::Red => Color::Yellow,
+        Color::LightRed => Color::LightYellow,
+        Color::Green => Color::Blue,
+        Color::LightGreen => Color::LightBlue,
+        other => other,
+    }
+}
+
+// Dimming the modal while another view has input focus.
+//
+// When a view is pushed on top of the modal, the modal may still show
+// beneath it. It is then drawn without colors or emphasis so it does not
+// look like it is waiting for keys.
+
+/// Strip colors and emphasis from `area`, including the selected option's
+/// highlight, and draw it dimmed.
+pub(crate) fn mute(area: Rect, buf: &mut Buffer) {
+    let area = area.intersection(buf.area);
+    for y in area.top()..area.bottom() {
+        for x in area.left()..area.right() {
+            if let Some(cell) = buf.cell_mut((x, y)) {
+                cell.set_fg(Color::Reset);
+                cell.set_bg(Color::Reset);
+                cell.modifier = Modifier::DIM;
+            }
+        }
+    }
+}
+
+// The modal's left edge, colored by how risky the request is.
+//
+// When the modal is framed on the left, that side is drawn in the accent
+// color for an ordinary request, the caution color for network access or a
+// script with unbalanced quoting, and the warning color for destructive or
+// privileged commands. It runs the full height of the modal, so the cue stays
+// visible while the warnings themselves are scrolled out of view. The
+// severity comes from the same checks that add those warnings.
+//
+// With `colorblind_safe` the edge changes shape too, dashed for caution and a
+// solid bar for danger, so telling the three apart does not take color.
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) enum Severity {
+    #[default]
+    Normal,
+    /// Network access or unbalanced quotes/brackets.
+    Caution,
+    /// Destructive or privileged commands.
+    Danger,
+}
+
+impl Severity {
+    /// The highest severity among a request's warnings.
+    pub(crate) fn of(risk: RiskLevel, network: bool, unbalanced: bool) -> Self {
+        match risk {
+            RiskLevel::Destructive | RiskLevel::Privileged => Severity::Danger,
+            RiskLevel::Benign if network || unbalanced => Severity::Caution,
+            RiskLevel::Benign => Severity::Normal,
+        }
+    }
+
+    pub(crate) fn color(self, theme: &ApprovalTheme) -> Color {
+        match self {
+            Severity::Normal => theme.accent,
+            Severity::Caution => theme.caution,
+            Severity::Danger => theme.warning,
+        }
+    }
+
+    /// What the edge is drawn with in place of the frame's line, when it
+    /// needs a shape as well as a color.
+    pub(crate) fn edge_symbol(self, theme: &ApprovalTheme) -> Option<&'static str> {
+        if !theme.colorblind_safe {
+            return None;
+        }
+        match self {
+            Severity::Normal => None,
+            Severity::Caution => Some("╎"),
+            Severity::Danger => Some("▌"),
+        }
+    }
+}
+
+/// Recolor the left side of the frame drawn around `frame`, redrawing its
+/// line (not the corners) with `symbol` when given.
+pub(crate) fn paint_left_edge(frame: Rect, color: Color, symbol: Option<&str>, buf: &mut Buffer) {
+    if frame.is_empty() {
+        return;
+    }
+    for y in frame.top()..frame.bottom() {
+        let cell = &mut buf[(frame.x, y)];
+        cell.set_fg(color);
+        if let Some(symbol) = symbol
+            && matches!(cell.symbol(), "│" | "┃" | "║")
+        {
+            cell.set_symbol(symbol);
+        }
+    }
+}
+
+// Marking where the command wraps rather than breaks.
+//
+// A command wider than the prompt wraps onto more rows, which reads the same
+// as a script with real newlines. Each row a line wraps onto starts with a
+// dim [`CONTINUATION`] in the gutter, so only the rows that begin a line of
+// the command start at the left edge.
+
+/// Gutter of a row that continues the line above it.
+pub(crate) const CONTINUATION: &str = "↪ ";
+
+/// `lines` wrapped to `width`, each soft-wrapped row led by [`CONTINUATION`].
+pub(crate) fn soft_wrap_lines(lines: &[Line<'static>], width: u16) -> Vec<Line<'static>> {
+    let options = RtOptions::new(usize::from(width.max(1)))
+        .subsequent_indent(Line::from(CONTINUATION.dim()))
+        .word_splitter(WordSplitter::NoHyphenation);
+    let mut wrapped = Vec::new();
+    for line in lines {
+        push_owned_lines(&word_wrap_line(line, options.clone()), &mut wrapped);
+    }
+    wrapped
+}
+
+/// Command lines drawn with [`soft_wrap_lines`].
+pub(crate) struct SoftWrapped {
+    lines: Vec<Line<'static>>,
+}
+
+impl SoftWrapped {
+    pub(crate) fn new(lines: Vec<Line<'static>>) -> Self {
+        Self { lines }
+    }
+}
+
+impl Renderable for SoftWrapped {
+    fn render(&self, area: Rect, buf: &mut Buffer) {
+        Paragraph::new(soft_wrap_lines(&self.lines, area.width)).render(area, buf);
+    }
+
+    fn desired_height(&self, width: u16) -> u16 {
+        u16::try_from(soft_wrap_lines(&self.lines, width).len()).unwrap_or(u16::MAX)
+    }
+}
+
+// PageUp/PageDown scrolling for approval prompts taller than the modal.
+//
+// The list view clips its header to the rows it has left, which hides the
+// tail of long heredocs and patch previews. [`ScrollablePrompt`] renders the
+// whole prompt off-screen and copies the window selected by the shared
+// [`PromptScroll`] state, with indicators for the lines above and below.
+
+/// Scroll position shared between the overlay (keys) and the prompt (render).
+#[derive(Default)]
+pub(crate) struct PromptScroll {
+    offset: Cell<u16>,
+    /// Largest useful offset, as of the last render.
+    max_offset: Cell<u16>,
+    /// Rows the prompt had in the last render.
+    page: Cell<u16>,
+}
+
+impl PromptScroll {
+    /// Scroll down a page. Returns `false` when the prompt fits.
+    pub(crate) fn page_down(&self) -> bool {
+        self.scroll_to(self.offset.get().saturating_add(self.page_step()))
+    }
+
+    /// Scroll up a page. Returns `false` when the prompt fits.
+    pub(crate) fn page_up(&self) -> bool {
+        self.scroll_to(self.offset.get().saturating_sub(self.page_step()))
+    }
+
+    /// Keep the offset inside a prompt that now has `total` rows. The next
+    /// render narrows it to the rows that fit on screen.
+    pub(crate) fn clamp(&self, total: u16) {
+        let last_row = total.saturating_sub(1);
+        self.offset.set(self.offset.get().min(last_row));
+        self.max_offset.set(self.max_offset.get().min(last_row));
+    }
+
+    fn scroll_to(&self, offset: u16) -> bool {
+        let max_offset = self.max_offset.get();
+        self.offset.set(offset.min(max_offset));
+        max_offset > 0
+    }
+
+    /// Overlap consecutive pages by the two indicator rows.
+    fn page_step(&self) -> u16 {
+        self.page.get().saturating_sub(2).max(1)
+    }
+}
+
+pub(crate) struct ScrollablePrompt {
+    inner: Arc<dyn Renderable>,
+    scroll: Rc<PromptScroll>,
+}
+
+impl ScrollablePrompt {
+    pub(crate) fn new(inner: Arc<dyn Renderable>, scroll: Rc<PromptScroll>) -> Self {
+        Self { inner, scroll }
+    }
+}
+
+impl Renderable for ScrollablePrompt {
+    fn desired_height(&self, width: u16) -> u16 {
+        self.inner.desired_height(width)
+    }
+
+    fn render(&self, area: Rect, buf: &mut Buffer) {
+        let total = self.inner.desired_height(area.width);
+        self.scroll.page.set(area.height);
+        if total <= area.height || area.height < 3 {
+            self.scroll.max_offset.set(0);
+            self.scroll.offset.set(0);
+            self.inner.render(area, buf);
+            return;
+        }
+        let max_offset = total - area.height;
+        let offset = self.scroll.offset.get().min(max_offset);
+        self.scroll.max_offset.set(max_offset);
+        self.scroll.offset.set(offset);
+
+        let mut full = Buffer::empty(Rect::new(0, 0, area.width, total));
+        self.inner.render(full.area, &mut full);
+        for row in 0..area.height {
+            for col in 0..area.width {
+                buf[(area.x + col, area.y + row)] = full[(col, offset + row)].clone();
+            }
+        }
+
+        // Indicators replace the first and last visible rows, so those rows
+        // count as hidden too.
+        if offset > 0 {
+            let text = format!("↑ {} more lines · PgUp", offset + 1);
+            render_indicator(text, area.y, area, buf);
+        }
+        if offset < max_offset {
+            let text = format!("↓ {} more lines · PgDn", max_offset - offset + 1);
+            render_indicator(text, area.bottom() - 1, area, buf);
+        }
+    }
+}
+
+fn render_indicator(text: String, y: u16, area: Rect, buf: &mut Buffer) {
+    for x in area.left()..area.right() {
+        buf[(x, y)].reset();
+    }
+    Line::from(text.dim()).render(Rect::new(area.x, y, area.width, 1), buf);
+}
+
+// First line of the approval prompt.
+//
+// Besides the question itself, the title carries transient status: the
+// auto-deny countdown while it runs, a confirmation after `c` copies the
+// request, how many identical requests it answers, and where the request
+// sits among those still waiting. While an
+// Esc is waiting to be confirmed, the title asks for the second Esc instead.
+
+/// `(current, total)` for the request on screen, counting the ones already
+/// resolved in this prompt and the ones queued behind it.
+pub(crate) type QueuePosition = Rc<Cell<Option<(usize, usize)>>>;
+
+pub(crate) struct PromptTitle {
+    title: Line<'static>,
+    auto_deny: Rc<AutoDeny>,
+    copied: Rc<CopiedHint>,
+    queue_position: QueuePosition,
+    /// Requests the decision answers, counting merged duplicates.
+    identical: Rc<Cell<usize>>,
+    abort_pending: Rc<Cell<bool>>,
+    theme: ApprovalTheme,
+}
+
+impl PromptTitle {
+    pub(crate) fn new(
+        title: Line<'static>,
+        auto_deny: Rc<AutoDeny>,
+        copied: Rc<CopiedHint>,
+        queue_position: QueuePosition,
+        identical: Rc<Cell<usize>>,
+        abort_pending: Rc<Cell<bool>>,
+        theme: ApprovalTheme,
+    ) -> Self {
+        Self {
+            title,
+            auto_deny,
+            copied,
+            queue_position,
+            identical,
+            abort_pending,
+            theme,
+        }
+    }
+}
+
+impl Renderable for PromptTitle {
+    fn desired_height(&self, _width: u16) -> u16 {
+        1
+    }
+
+    fn render(&self, area: Rect, buf: &mut Buffer) {
+        if self.abort_pending.get() {
+            Line::from("Press Esc again to abort, any other key to cancel".fg(self.theme.warning))
+                .render(area, buf);
+            return;
+        }
+        let mut title = self.title.clone();
+        let identical = self.identical.get();
+        if identical > 1 {
+            title.push_span(format!(" (applies to {identical} identical requests)").dim());
+        }
+        if let Some((current, total)) = self.queue_position.get() {
+            title.push_span(format!(" ({current} of {total})").dim());
+        }
+        if let Some(remaining) = self.auto_deny.remaining() {
+            title.push_span(format!(" (auto-deny in {}s)", display_secs(remaining)).dim());
+        }
+        if self.copied.remaining().is_some() {
+            title.push_span(" copied!".fg(self.theme.success));
+        }
+        title.render(area, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bottom_pane::approval_overlay::options::ApprovalDecision;
+    use codex_protocol::ThreadId;
+    use codex_protocol::protocol::ReviewDecision;
+    use pretty_assertions::assert_eq;
+    use std::path::PathBuf;
+
+    fn text(line: &Line<'_>) -> String {
+        line.spans
+            .iter()
+            .map(|span| span.content.as_ref())
+            .collect()
+    }
+
+    #[test]
+    fn summary_shows_the_first_line_of_the_command() {
+        let request = ApprovalRequest::Exec {
+            thread_id: ThreadId::new(),
+            thread_label: None,
+            id: "call-1".to_string(),
+            command: vec![
+                "bash".to_string(),
+                "-lc".to_string(),
+                "python - <<'PY'\nprint(1)\nPY".to_string(),
+            ],
+            cwd: PathBuf::from("/repo"),
+            reason: None,
+            available_decisions: Vec::new(),
+            network_approval_context: None,
+            additional_permissions: None,
+            sandbox_policy: None,
+            env: Vec::new(),
+            duplicate_ids: Vec::new(),
+        };
+        assert_eq!(
+            text(&summary_line(&request)),
+            "codex wants to run: python - <<'PY'…"
+        );
+    }
+
+    #[test]
+    fn options_line_lists_selection_and_shortcuts() {
+        let option = ApprovalOption {
+            label: "Yes, proceed".to_string(),
+            decision: ApprovalDecision::Review(ReviewDecision::Approved),
+            display_shortcut: None,
+            additional_shortcuts: vec![key_hint::plain(KeyCode::Char('y'))],
+        };
+        let shortcuts = [
+            key_hint::plain(KeyCode::Char('y')),
+            key_hint::plain(KeyCode::Char('n')),
+        ];
+        let line = options_line(Some(&option), &shortcuts, &ApprovalTheme::default());
+        assert_eq!(text(&line), "› Yes, proceed · y/n select · space expand");
+    }
+
+    /// Counts how often the wrapped paragraph is measured.
+    struct CountingParagraph {
+        paragraph: Paragraph<'static>,
+        measured: Rc<Cell<usize>>,
+    }
+
+    impl Renderable for CountingParagraph {
+        fn render(&self, area: Rect, buf: &mut Buffer) {
+            Renderable::render(&self.paragraph, area, buf);
+        }
+
+        fn desired_height(&self, width: u16) -> u16 {
+            self.measured.set(self.measured.get() + 1);
+            self.paragraph.desired_height(width)
+        }
+    }
+
+    #[test]
+    fn measures_once_per_width() {
+        let measured = Rc::new(Cell::new(0));
+        let prompt = CachedHeight::new(CountingParagraph {
+            paragraph: Paragraph::new(Line::from("a prompt long enough to wrap"))
+                .wrap(Wrap { trim: false }),
+            measured: measured.clone(),
+        });
+
+        let height = prompt.desired_height(10);
+        assert_eq!(prompt.desired_height(10), height);
+        assert_eq!(measured.get(), 1);
+
+        assert_ne!(prompt.desired_height(80), height);
+        assert_eq!(measured.get(), 2);
+    }
+
+    #[test]
+    fn rows_are_trimmed_and_styles_become_escapes() {
+        let mut buf = Buffer::empty(Rect::new(0, 0, 8, 2));
+        buf.set_string(0, 0, "版 ok", Style::default());
+        buf.set_string(0, 1, "no", Style::default().fg(Color::Red));
+        buf.set_string(2, 1, "!", Style::default().add_modifier(Modifier::BOLD));
+
+        assert_eq!(buffer_text(&buf), "版 ok\nno!");
+        assert_eq!(
+            buffer_ansi(&buf),
+            Ok("版 ok\n\u{1b}[38;5;1mno\u{1b}[0m\u{1b}[1m!\u{1b}[0m".to_string())
+        );
+    }
+
+    #[test]
+    fn colors_and_dimming_go_and_the_selected_row_is_reversed() {
+        let area = Rect::new(0, 0, 20, 2);
+        let mut buf = Buffer::empty(area);
+        buf.set_string(
+            0,
+            0,
+            "┃ › 1. Yes        ┃",
+            Style::default().cyan().on_dark_gray(),
+        );
+        buf.set_string(0, 1, "┃   2. No  (n)     ┃", Style::default().dim());
+        apply_high_contrast(area, &mut buf);
+
+        let style_at = |x, y| {
+            let cell: &ratatui::buffer::Cell = &buf[(x, y)];
+            (cell.fg, cell.bg, cell.modifier)
+        };
+        let reversed = Modifier::BOLD | Modifier::REVERSED;
+        assert_eq!(style_at(2, 0), (Color::Reset, Color::Reset, reversed));
+        assert_eq!(style_at(9, 0), (Color::Reset, Color::Reset, reversed));
+        assert_eq!(style_at(10, 0).2, Modifier::empty());
+        assert_eq!(style_at(0, 0).2, Modifier::empty());
+        assert_eq!(
+            style_at(6, 1),
+            (Color::Reset, Color::Reset, Modifier::empty())
+        );
+    }
+
+    #[test]
+    fn red_and_green_become_orange_and_blue() {
+        let area = Rect::new(0, 0, 4, 1);
+        let mut buf = Buffer::empty(area);
+        buf.set_string(0, 0, "-", Style::default().fg(Color::Red));
+        buf.set_string(1, 0, "+", Style::default().fg(Color::Green));
+        buf.set_string(2, 0, "x", Style::default().bg(Color::LightGreen));
+        buf.set_string(3, 0, "y", Style::default().fg(Color::Cyan));
+
+        apply_colorblind_palette(area, &mut buf);
+
+        let colors = (0..4)
+            .map(|x| (buf[(x, 0)].fg, buf[(x, 0)].bg))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            colors,
+            vec![
+                (Color::Yellow, Color::Reset),
+                (Color::Blue, Color::Reset),
+                (Color::Reset, Color::LightBlue),
+                (Color::Cyan, Color::Reset),
+            ]
+        );
+    }
+
+    #[test]
+    fn highlight_is_replaced_by_dim_text() {
+        let area = Rect::new(0, 0, 10, 1);
+        let mut buf = Buffer::empty(area);
+        buf.set_string(0, 0, "› 1. Yes", Style::default().cyan().bold());
+        mute(area, &mut buf);
+        let cell = &buf[(0, 0)];
+        assert_eq!(cell.symbol(), "›");
+        assert_eq!(
+            (cell.fg, cell.bg, cell.modifier),
+            (Color::Reset, Color::Reset, Modifier::DIM)
+        );
+    }
+
+    #[test]
+    fn the_highest_warning_wins() {
+        assert_eq!(
+            Severity::of(RiskLevel::Benign, false, false),
+            Severity::Normal
+        );
+        assert_eq!(
+            Severity::of(RiskLevel::Benign, true, false),
+            Severity::Caution
+        );
+        assert_eq!(
+            Severity::of(RiskLevel::Benign, false, true),
+            Severity::Caution
+        );
+        assert_eq!(
+            Severity::of(RiskLevel::Privileged, true, true),
+            Severity::Danger
+        );
+        assert_eq!(
+            Severity::of(RiskLevel::Destructive, false, false),
+            Severity::Danger
+        );
+
+        let theme = ApprovalTheme::default();
+        assert_eq!(Severity::Normal.color(&theme), Color::Cyan);
+        assert_eq!(Severity::Caution.color(&theme), Color::Yellow);
+        assert_eq!(Severity::Danger.color(&theme), Color::Red);
+        assert_eq!(Severity::Danger.edge_symbol(&theme), None);
+
+        let theme = theme.colorblind_safe();
+        assert_eq!(Severity::Normal.edge_symbol(&theme), None);
+        assert_eq!(Severity::Caution.edge_symbol(&theme), Some("╎"));
+        assert_eq!(Severity::Danger.edge_symbol(&theme), Some("▌"));
+    }
+
+    fn texts(lines: &[Line<'static>]) -> Vec<String> {
+        lines.iter().map(text).collect()
+    }
+
+    #[test]
+    fn only_wrapped_rows_get_the_marker() {
+        let lines = vec![
+            Line::from("$ grep -rn pattern src tests docs"),
+            Line::from("echo done"),
+        ];
+        assert_eq!(
+            texts(&soft_wrap_lines(&lines, 20)),
+            vec!["$ grep -rn pattern", "↪ src tests docs", "echo done",]
+        );
+        assert_eq!(
+            texts(&soft_wrap_lines(&lines, 80)),
+            vec!["$ grep -rn pattern src tests docs", "echo done"]
+        );
+    }
+
+    fn rows(buf: &Buffer) -> Vec<String> {
+        (0..buf.area.height)
+            .map(|row| {
+                (0..buf.area.width)
+                    .map(|col| buf[(col, row)].symbol().to_string())
+                    .collect::<String>()
+                    .trim_end()
+                    .to_string()
+            })
+            .collect()
+    }
+
+    fn render(prompt: &ScrollablePrompt, height: u16) -> Vec<String> {
+        let mut buf = Buffer::empty(Rect::new(0, 0, 30, height));
+        prompt.render(buf.area, &mut buf);
+        rows(&buf)
+    }
+
+    #[test]
+    fn pages_through_a_tall_prompt() {
+        let lines: Vec<Line<'static>> = (1..=12).map(|n| Line::from(format!("line {n}"))).collect();
+        let scroll = Rc::new(PromptScroll::default());
+        let prompt = ScrollablePrompt::new(Arc::new(Paragraph::new(lines)), scroll.clone());
+
+        assert_eq!(
+            render(&prompt, 5),
+            vec![
+                "line 1",
+                "line 2",
+                "line 3",
+                "line 4",
+                "↓ 8 more lines · PgDn"
+            ]
+        );
+
+        assert!(scroll.page_down());
+        assert_eq!(
+            render(&prompt, 5),
+            vec![
+                "↑ 4 more lines · PgUp",
+                "line 5",
+                "line 6",
+                "line 7",
+                "↓ 5 more lines · PgDn",
+            ]
+        );
+
+        for _ in 0..5 {
+            scroll.page_down();
+        }
+        assert_eq!(
+            render(&prompt, 5),
+            vec![
+                "↑ 8 more lines · PgUp",
+                "line 9",
+                "line 10",
+                "line 11",
+                "line 12"
+            ]
+        );
+    }
+
+    #[test]
+    fn short_prompts_do_not_scroll() {
+        let scroll = Rc::new(PromptScroll::default());
+        let prompt = ScrollablePrompt::new(Arc::new(Line::from("only line")), scroll.clone());
+        assert_eq!(render(&prompt, 3), vec!["only line", "", ""]);
+        assert!(!scroll.page_down());
+    }
+}
@@ -1,8 +1,13 @@
 use crate::bottom_pane::ApprovalRequest;
 use crate::bottom_pane::McpServerElicitationFormRequest;
+use crate::key_hint::KeyBinding;
 use crate::render::renderable::Renderable;
+use codex_protocol::ThreadId;
+use codex_protocol::protocol::ReviewDecision;
 use codex_protocol::request_user_input::RequestUserInputEvent;
 use crossterm::event::KeyEvent;
+use crossterm::event::MouseEvent;
+use std::time::Duration;
 
 use super::CancellationEvent;
 
@@ -17,6 +22,31 @@ pub(crate) trait BottomPaneView: Renderable {
         false
     }
 
+    /// Tell the view whether it receives input. The bottom pane clears this
+    /// while another view is stacked on top of it.
+    fn set_focused(&mut self, _focused: bool) {}
+
+    /// Called when the terminal is resized, before the next draw, so the view
+    /// can drop measurements taken at the old size.
+    fn on_resize(&mut self, _width: u16, _height: u16) {}
+
+    /// How an approval view was resolved once it is complete: the decision
+    /// for the last request it answered, or `None` while still pending.
+    fn outcome(&self) -> Option<ReviewDecision> {
+        None
+    }
+
+    /// Drop the approval requests `thread_id` raised, without answering
+    /// them, once core has stopped waiting for them. An approval view left
+    /// with nothing to ask completes.
+    fn dismiss_approvals(&mut self, _thread_id: ThreadId) {}
+
+    /// Keys that act on the view right now, each with what it does, for
+    /// hints drawn outside the view. Empty once the view is complete.
+    fn active_hotkeys(&self) -> Vec<(KeyBinding, String)> {
+        Vec::new()
+    }
+
     /// Stable identifier for views that need external refreshes while open.
     fn view_id(&self) -> Option<&'static str> {
         None
@@ -45,6 +75,24 @@ pub(crate) trait BottomPaneView: Renderable {
         false
     }
 
+    /// Optional mouse handler. Only called while [`Self::wants_mouse_capture`]
+    /// is true. Return true if the view needs a redraw.
+    fn handle_mouse_event(&mut self, _mouse_event: MouseEvent) -> bool {
+        false
+    }
+
+    /// Whether the terminal should report mouse events while this view is
+    /// active. Capturing the mouse disables the terminal's own text selection.
+    fn wants_mouse_capture(&self) -> bool {
+        false
+    }
+
+    /// Width the view needs when placed in a popup at most `max` columns
+    /// wide. Views that fill the bottom pane take all of it.
+    fn desired_width(&self, max: u16) -> u16 {
+        max
+    }
+
     /// Flush any pending paste-burst state. Return true if state changed.
     ///
     /// This lets a modal that reuses `ChatComposer` participate in the same
@@ -61,6 +109,13 @@ pub(crate) trait BottomPaneView: Renderable {
         false
     }
 
+    /// Process time-based transitions before the next draw.
+    ///
+    /// Return how long until the view needs another tick, if it does.
+    fn pre_draw_tick(&mut self) -> Option<Duration> {
+        None
+    }
+
     /// Try to handle approval request; return the original value if not
     /// consumed.
     fn try_consume_approval_request(
@@ -70,6 +125,12 @@ pub(crate) trait BottomPaneView: Renderable {
         Some(request)
     }
 
+    /// Resolve every pending approval with `decision`, one at a time. Return
+    /// `true` if any were resolved.
+    fn approve_all_pending(&mut self, _decision: ReviewDecision) -> bool {
+        false
+    }
+
     /// Try to handle request_user_input; return the original value if not
     /// consumed.
     fn try_consume_user_input_request(
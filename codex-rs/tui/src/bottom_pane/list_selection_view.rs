@@ -1,10 +1,14 @@
 use crossterm::event::KeyCode;
 use crossterm::event::KeyEvent;
 use crossterm::event::KeyModifiers;
+use crossterm::event::MouseButton;
+use crossterm::event::MouseEvent;
+use crossterm::event::MouseEventKind;
 use itertools::Itertools as _;
 use ratatui::buffer::Buffer;
 use ratatui::layout::Constraint;
 use ratatui::layout::Layout;
+use ratatui::layout::Position;
 use ratatui::layout::Rect;
 use ratatui::style::Stylize;
 use ratatui::text::Line;
@@ -31,6 +35,8 @@ use super::selection_popup_common::measure_rows_height_with_col_width_mode;
 use super::selection_popup_common::render_rows;
 use super::selection_popup_common::render_rows_stable_col_widths;
 use super::selection_popup_common::render_rows_with_col_width_mode;
+use super::selection_popup_common::row_index_at;
+use std::cell::Cell;
 use unicode_width::UnicodeWidthStr;
 
 /// Minimum list width (in content columns) required before the side-by-side
@@ -227,6 +233,9 @@ pub(crate) struct ListSelectionView {
     stacked_side_content: Option<Box<dyn Renderable>>,
     preserve_side_content_bg: bool,
 
+    /// Where the rows were drawn in the last render, for mouse hit-testing.
+    rows_area: Cell<Option<Rect>>,
+
     /// Called when the highlighted item changes (navigation, filter, number-key).
     on_selection_changed: OnSelectionChangedCallback,
 
@@ -278,6 +287,7 @@ impl ListSelectionView {
             side_content_min_width: params.side_content_min_width,
             stacked_side_content: params.stacked_side_content,
             preserve_side_content_bg: params.preserve_side_content_bg,
+            rows_area: Cell::new(None),
             on_selection_changed: params.on_selection_changed,
             on_cancel: params.on_cancel,
         };
@@ -429,6 +439,34 @@ impl ListSelectionView {
         }
     }
 
+    fn move_to_first(&mut self) {
+        let before = self.selected_actual_idx();
+        let len = self.visible_len();
+        if len == 0 {
+            return;
+        }
+        self.state.selected_idx = Some(0);
+        self.skip_disabled_down();
+        self.state.ensure_visible(len, Self::max_visible_rows(len));
+        if self.selected_actual_idx() != before {
+            self.fire_selection_changed();
+        }
+    }
+
+    fn move_to_last(&mut self) {
+        let before = self.selected_actual_idx();
+        let len = self.visible_len();
+        if len == 0 {
+            return;
+        }
+        self.state.selected_idx = Some(len - 1);
+        self.skip_disabled_up();
+        self.state.ensure_visible(len, Self::max_visible_rows(len));
+        if self.selected_actual_idx() != before {
+            self.fire_selection_changed();
+        }
+    }
+
     fn fire_selection_changed(&self) {
         if let Some(cb) = &self.on_selection_changed
             && let Some(actual) = self.selected_actual_idx()
@@ -472,6 +510,18 @@ impl ListSelectionView {
         self.apply_filter();
     }
 
+    /// Replace the note shown above the footer hint.
+    pub(crate) fn set_footer_note(&mut self, note: Option<Line<'static>>) {
+        self.footer_note = note;
+    }
+
+    /// Disable or enable every item at once.
+    pub(crate) fn set_items_disabled(&mut self, disabled: bool) {
+        for item in &mut self.items {
+            item.is_disabled = disabled;
+        }
+    }
+
     pub(crate) fn take_last_selected_index(&mut self) -> Option<usize> {
         self.last_selected_actual_idx.take()
     }
@@ -615,6 +665,13 @@ impl BottomPaneView for ListSelectionView {
                 modifiers: KeyModifiers::NONE,
                 ..
             } if !self.is_searchable => self.move_down(),
+            KeyEvent {
+                code: KeyCode::Home,
+                ..
+            } => self.move_to_first(),
+            KeyEvent {
+                code: KeyCode::End, ..
+            } => self.move_to_last(),
             KeyEvent {
                 code: KeyCode::Backspace,
                 ..
@@ -669,6 +726,44 @@ impl BottomPaneView for ListSelectionView {
         }
     }
 
+    /// Hovering a row highlights it and a left click selects it, matching the
+    /// arrow keys and Enter.
+    fn handle_mouse_event(&mut self, mouse_event: MouseEvent) -> bool {
+        let Some(area) = self.rows_area.get() else {
+            return false;
+        };
+        if !area.contains(Position::new(mouse_event.column, mouse_event.row)) {
+            return false;
+        }
+        let rows = self.build_rows();
+        let Some(idx) = row_index_at(
+            area,
+            &rows,
+            &self.state,
+            area.height as usize,
+            self.col_width_mode,
+            mouse_event.row,
+        ) else {
+            return false;
+        };
+        if rows[idx].is_disabled {
+            return false;
+        }
+        match mouse_event.kind {
+            MouseEventKind::Moved if self.state.selected_idx != Some(idx) => {
+                self.state.selected_idx = Some(idx);
+                self.fire_selection_changed();
+                true
+            }
+            MouseEventKind::Down(MouseButton::Left) => {
+                self.state.selected_idx = Some(idx);
+                self.accept();
+                true
+            }
+            _ => false,
+        }
+    }
+
     fn is_complete(&self) -> bool {
         self.complete
     }
@@ -856,6 +951,7 @@ impl Renderable for ListSelectionView {
         }
 
         // -- List rows --
+        self.rows_area.set(None);
         if list_area.height > 0 {
             let render_area = Rect {
                 x: list_area.x.saturating_sub(2),
@@ -863,6 +959,7 @@ impl Renderable for ListSelectionView {
                 width: effective_rows_width.max(1),
                 height: list_area.height,
             };
+            self.rows_area.set(Some(render_area));
             match self.col_width_mode {
                 ColumnWidthMode::AutoVisible => render_rows(
                     render_area,
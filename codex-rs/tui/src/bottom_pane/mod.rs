@@ -26,16 +26,23 @@ use crate::render::renderable::FlexRenderable;
 use crate::render::renderable::Renderable;
 use crate::render::renderable::RenderableItem;
 use crate::tui::FrameRequester;
+use approval_overlay::blocked_commands_hook;
+use approval_overlay::resolve_by_macro;
+use approval_overlay::resolve_by_policy;
 use bottom_pane_view::BottomPaneView;
+use codex_core::config::TuiApprovals;
 use codex_core::features::Features;
 use codex_core::plugins::PluginCapabilitySummary;
 use codex_core::skills::model::SkillMetadata;
 use codex_file_search::FileMatch;
+use codex_protocol::ThreadId;
+use codex_protocol::protocol::ReviewDecision;
 use codex_protocol::request_user_input::RequestUserInputEvent;
 use codex_protocol::user_input::TextElement;
 use crossterm::event::KeyCode;
 use crossterm::event::KeyEvent;
 use crossterm::event::KeyEventKind;
+use crossterm::event::MouseEvent;
 use ratatui::buffer::Buffer;
 use ratatui::layout::Rect;
 use ratatui::text::Line;
@@ -51,7 +58,14 @@ pub(crate) use app_link_view::AppLinkView;
 pub(crate) use app_link_view::AppLinkViewParams;
 pub(crate) use approval_overlay::ApprovalOverlay;
 pub(crate) use approval_overlay::ApprovalRequest;
+pub(crate) use approval_overlay::ApprovalSession;
+pub(crate) use approval_overlay::CommandOutcome;
+pub(crate) use approval_overlay::CommandSignature;
+pub(crate) use approval_overlay::RecentContext;
+pub(crate) use approval_overlay::env_overrides;
 pub(crate) use approval_overlay::format_additional_permissions_rule;
+pub(crate) use approval_overlay::format_approval_duration;
+pub(crate) use approval_overlay::redact_secrets;
 pub(crate) use mcp_server_elicitation::McpServerElicitationFormRequest;
 pub(crate) use mcp_server_elicitation::McpServerElicitationOverlay;
 pub(crate) use request_user_input::RequestUserInputOverlay;
@@ -182,6 +196,10 @@ pub(crate) struct BottomPane {
     pending_input_preview: PendingInputPreview,
     /// Inactive threads with pending approval requests.
     pending_thread_approvals: PendingThreadApprovals,
+    /// Approval state shared across approval modals.
+    approval_session: ApprovalSession,
+    /// The transcript tail approval prompts can show above the request.
+    recent_context: RecentContext,
     context_window_percent: Option<i64>,
     context_window_used_tokens: Option<i64>,
 }
@@ -231,6 +249,8 @@ impl BottomPane {
             unified_exec_footer: UnifiedExecFooter::new(),
             pending_input_preview: PendingInputPreview::new(),
             pending_thread_approvals: PendingThreadApprovals::new(),
+            approval_session: ApprovalSession::default(),
+            recent_context: RecentContext::default(),
             esc_backtrack_hint: false,
             animations_enabled,
             context_window_percent: None,
@@ -359,10 +379,47 @@ impl BottomPane {
     }
 
     fn push_view(&mut self, view: Box<dyn BottomPaneView>) {
+        if let Some(covered) = self.view_stack.last_mut() {
+            covered.set_focused(false);
+        }
         self.view_stack.push(view);
         self.request_redraw();
     }
 
+    /// Remove the active view and hand input back to the one beneath it.
+    fn pop_view(&mut self) {
+        self.view_stack.pop();
+        if let Some(view) = self.view_stack.last_mut() {
+            view.set_focused(true);
+        }
+    }
+
+    /// Drop approval requests from `thread_id` that core no longer waits on,
+    /// closing any approval view left with nothing to ask.
+    pub(crate) fn dismiss_approvals(&mut self, thread_id: ThreadId) {
+        for view in &mut self.view_stack {
+            view.dismiss_approvals(thread_id);
+        }
+        let views = self.view_stack.len();
+        self.view_stack.retain(|view| !view.is_complete());
+        if self.view_stack.len() == views {
+            return;
+        }
+        match self.view_stack.last_mut() {
+            Some(view) => view.set_focused(true),
+            None => self.on_active_view_complete(),
+        }
+        self.request_redraw();
+    }
+
+    /// Tell every stacked view, covered ones included, about a new terminal
+    /// size.
+    pub(crate) fn on_resize(&mut self, width: u16, height: u16) {
+        for view in &mut self.view_stack {
+            view.on_resize(width, height);
+        }
+    }
+
     /// Forward a key event to the active view or the composer.
     pub fn handle_key_event(&mut self, key_event: KeyEvent) -> InputResult {
         // Do not globally intercept space; only composer handles hold-to-talk.
@@ -403,7 +460,7 @@ impl BottomPane {
             };
 
             if ctrl_c_completed {
-                self.view_stack.pop();
+                self.pop_view();
                 self.on_active_view_complete();
                 if let Some(next_view) = self.view_stack.last()
                     && next_view.is_in_paste_burst()
@@ -465,7 +522,7 @@ impl BottomPane {
             let event = view.on_ctrl_c();
             if matches!(event, CancellationEvent::Handled) {
                 if view.is_complete() {
-                    self.view_stack.pop();
+                    self.pop_view();
                     self.on_active_view_complete();
                 }
                 self.show_quit_shortcut_hint(key_hint::ctrl(KeyCode::Char('c')));
@@ -501,6 +558,28 @@ impl BottomPane {
         }
     }
 
+    /// Forward a mouse event to the active view. The composer ignores the mouse.
+    pub(crate) fn handle_mouse_event(&mut self, mouse_event: MouseEvent) {
+        let Some(view) = self.view_stack.last_mut() else {
+            return;
+        };
+        let needs_redraw = view.handle_mouse_event(mouse_event);
+        if view.is_complete() {
+            self.view_stack.clear();
+            self.on_active_view_complete();
+            self.request_redraw();
+        } else if needs_redraw {
+            self.request_redraw();
+        }
+    }
+
+    /// Whether the active view wants the terminal to report mouse events.
+    pub(crate) fn wants_mouse_capture(&self) -> bool {
+        self.view_stack
+            .last()
+            .is_some_and(|view| view.wants_mouse_capture())
+    }
+
     pub(crate) fn insert_str(&mut self, text: &str) {
         self.composer.insert_str(text);
         self.composer.sync_popups();
@@ -513,6 +592,17 @@ impl BottomPane {
         #[cfg(not(target_os = "linux"))]
         self.composer.process_space_hold_trigger();
         self.composer.sync_popups();
+        let Some(view) = self.view_stack.last_mut() else {
+            return;
+        };
+        let next_tick = view.pre_draw_tick();
+        if view.is_complete() {
+            self.view_stack.clear();
+            self.on_active_view_complete();
+            self.request_redraw();
+        } else if let Some(delay) = next_tick {
+            self.request_redraw_in(delay);
+        }
     }
 
     /// Replace the composer text with `text`.
@@ -797,7 +887,7 @@ impl BottomPane {
             return false;
         }
 
-        self.view_stack.pop();
+        self.pop_view();
         let view = list_selection_view::ListSelectionView::new(params, self.app_event_tx.clone());
         self.push_view(Box::new(view));
         true
@@ -898,8 +988,54 @@ impl BottomPane {
         self.push_view(view);
     }
 
+    /// Remember how a command finished so later approvals of the same command
+    /// can show what happened last time.
+    pub(crate) fn record_command_outcome(
+        &self,
+        command: Vec<String>,
+        cwd: PathBuf,
+        outcome: CommandOutcome,
+    ) {
+        self.approval_session
+            .record_command_outcome(command, cwd, outcome);
+    }
+
+    /// Remember lines just committed to the transcript, for approval prompts
+    /// that show what came right before them.
+    pub(crate) fn record_history_lines(&mut self, lines: Vec<Line<'static>>) {
+        self.recent_context.record(lines);
+    }
+
     /// Called when the agent requests user approval.
-    pub fn push_approval_request(&mut self, request: ApprovalRequest, features: &Features) {
+    pub fn push_approval_request(
+        &mut self,
+        request: ApprovalRequest,
+        features: &Features,
+        approvals: &TuiApprovals,
+    ) {
+        // A configured policy program may answer before the modal is shown.
+        // It runs in the background and sends the request back if it abstains.
+        let Some(request) = resolve_by_policy(request, approvals, &self.app_event_tx) else {
+            self.request_redraw();
+            return;
+        };
+        self.show_approval_request(request, features, approvals);
+    }
+
+    /// Show `request` in the approval modal without asking the policy
+    /// program, e.g. once it has abstained.
+    pub(crate) fn show_approval_request(
+        &mut self,
+        request: ApprovalRequest,
+        features: &Features,
+        approvals: &TuiApprovals,
+    ) {
+        // A command the user saved as a session macro may answer first.
+        let Some(request) = resolve_by_macro(request, &self.approval_session, &self.app_event_tx)
+        else {
+            self.request_redraw();
+            return;
+        };
         let request = if let Some(view) = self.view_stack.last_mut() {
             match view.try_consume_approval_request(request) {
                 Some(request) => request,
@@ -913,7 +1049,18 @@ impl BottomPane {
         };
 
         // Otherwise create a new approval modal overlay.
-        let modal = ApprovalOverlay::new(request, self.app_event_tx.clone(), features.clone());
+        tracing::debug!(request = %request.to_json(), "showing approval request");
+        let context_lines = usize::from(approvals.context_lines.unwrap_or(0));
+        let mut modal = ApprovalOverlay::builder(request, self.app_event_tx.clone())
+            .features(features.clone())
+            .config(approvals.clone())
+            .session(self.approval_session.clone())
+            .recent_context(self.recent_context.tail(context_lines))
+            .build();
+        if let Some(hook) = blocked_commands_hook(approvals) {
+            modal.set_pre_decision_hook(hook);
+        }
+        modal.announce();
         self.pause_status_timer_for_modal();
         self.push_view(Box::new(modal));
     }
@@ -978,6 +1125,27 @@ impl BottomPane {
         self.push_view(Box::new(modal));
     }
 
+    /// Approve commands matching `signature` without asking for the rest of
+    /// the session.
+    pub(crate) fn register_auto_approve(&mut self, name: String, signature: CommandSignature) {
+        self.approval_session.register_macro(name, signature);
+    }
+
+    /// Resolve every approval queued in the active view with `decision`.
+    pub(crate) fn approve_all_pending(&mut self, decision: ReviewDecision) {
+        let Some(view) = self.view_stack.last_mut() else {
+            return;
+        };
+        if !view.approve_all_pending(decision) {
+            return;
+        }
+        if view.is_complete() {
+            self.pop_view();
+            self.on_active_view_complete();
+        }
+        self.request_redraw();
+    }
+
     fn on_active_view_complete(&mut self) {
         self.resume_status_timer_after_modal();
         self.set_composer_input_enabled(true, None);
@@ -1222,6 +1390,7 @@ mod tests {
             thread_label: None,
             id: "1".to_string(),
             command: vec!["echo".into(), "ok".into()],
+            cwd: PathBuf::from("/tmp"),
             reason: None,
             available_decisions: vec![
                 codex_protocol::protocol::ReviewDecision::Approved,
@@ -1229,6 +1398,9 @@ mod tests {
             ],
             network_approval_context: None,
             additional_permissions: None,
+            sandbox_policy: None,
+            env: Vec::new(),
+            duplicate_ids: Vec::new(),
         }
     }
 
@@ -1247,12 +1419,90 @@ mod tests {
             animations_enabled: true,
             skills: Some(Vec::new()),
         });
-        pane.push_approval_request(exec_request(), &features);
+        pane.push_approval_request(exec_request(), &features, &TuiApprovals::default());
         assert_eq!(CancellationEvent::Handled, pane.on_ctrl_c());
         assert!(!pane.quit_shortcut_hint_visible());
         assert_eq!(CancellationEvent::NotHandled, pane.on_ctrl_c());
     }
 
+    #[cfg(unix)]
+    #[test]
+    fn policy_program_runs_in_the_background_and_returns_abstentions() {
+        let (tx_raw, mut rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx_raw);
+        let features = Features::with_defaults();
+        let mut pane = BottomPane::new(BottomPaneParams {
+            app_event_tx: tx,
+            frame_requester: FrameRequester::test_dummy(),
+            has_input_focus: true,
+            enhanced_keys_supported: false,
+            placeholder_text: "Ask Codex to do anything".to_string(),
+            disable_paste_burst: false,
+            animations_enabled: true,
+            skills: Some(Vec::new()),
+        });
+        let approvals = TuiApprovals {
+            policy_command: Some(vec![
+                "sh".to_string(),
+                "-c".to_string(),
+                "sleep 1; exit 2".to_string(),
+            ]),
+            ..TuiApprovals::default()
+        };
+
+        let started = std::time::Instant::now();
+        pane.push_approval_request(exec_request(), &features, &approvals);
+        assert!(started.elapsed() < std::time::Duration::from_secs(1));
+        assert!(!pane.has_active_view());
+
+        let request = loop {
+            match rx.blocking_recv() {
+                Some(AppEvent::ApprovalPolicyAbstained(request)) => break request,
+                Some(_) => {}
+                None => panic!("channel closed before the policy answered"),
+            }
+        };
+        pane.show_approval_request(request, &features, &approvals);
+        assert!(pane.has_active_view());
+    }
+
+    #[test]
+    fn blocked_commands_cannot_be_approved_from_the_modal() {
+        let (tx_raw, mut rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx_raw);
+        let features = Features::with_defaults();
+        let mut pane = BottomPane::new(BottomPaneParams {
+            app_event_tx: tx,
+            frame_requester: FrameRequester::test_dummy(),
+            has_input_focus: true,
+            enhanced_keys_supported: false,
+            placeholder_text: "Ask Codex to do anything".to_string(),
+            disable_paste_burst: false,
+            animations_enabled: true,
+            skills: Some(Vec::new()),
+        });
+        let approvals = TuiApprovals {
+            blocked_commands: Some(vec!["echo *".to_string()]),
+            ..TuiApprovals::default()
+        };
+        pane.push_approval_request(exec_request(), &features, &approvals);
+
+        pane.handle_key_event(KeyEvent::new(KeyCode::Char('y'), KeyModifiers::NONE));
+        assert!(pane.has_active_view());
+        let approvals_sent = std::iter::from_fn(|| rx.try_recv().ok())
+            .filter(|ev| {
+                matches!(
+                    ev,
+                    AppEvent::SubmitThreadOp {
+                        op: Op::ApprovalResponse { .. },
+                        ..
+                    }
+                )
+            })
+            .count();
+        assert_eq!(approvals_sent, 0);
+    }
+
     // live ring removed; related tests deleted.
 
     #[test]
@@ -1272,7 +1522,7 @@ mod tests {
         });
 
         // Create an approval modal (active view).
-        pane.push_approval_request(exec_request(), &features);
+        pane.push_approval_request(exec_request(), &features, &TuiApprovals::default());
 
         // Render and verify the top row does not include an overlay.
         let area = Rect::new(0, 0, 60, 6);
@@ -1309,7 +1559,7 @@ mod tests {
         pane.set_task_running(true);
 
         // Push an approval modal (e.g., command approval) which should hide the status view.
-        pane.push_approval_request(exec_request(), &features);
+        pane.push_approval_request(exec_request(), &features, &TuiApprovals::default());
 
         // Simulate pressing 'n' (No) on the modal.
         use crossterm::event::KeyCode;
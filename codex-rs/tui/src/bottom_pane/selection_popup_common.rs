@@ -661,6 +661,52 @@ pub(crate) fn render_rows_with_col_width_mode(
     )
 }
 
+/// Index into `rows_all` of the row drawn at terminal row `y`.
+///
+/// Mirrors the layout of [`render_rows_with_col_width_mode`] for the same
+/// arguments, so wrapped rows claim every line they occupy. Returns `None`
+/// outside `area` and below the last rendered row.
+pub(crate) fn row_index_at(
+    area: Rect,
+    rows_all: &[GenericDisplayRow],
+    state: &ScrollState,
+    max_results: usize,
+    col_width_mode: ColumnWidthMode,
+    y: u16,
+) -> Option<usize> {
+    let max_items = max_results.min(rows_all.len());
+    if max_items == 0 || y < area.y || y >= area.bottom() {
+        return None;
+    }
+    let desc_measure_items = max_items.min(area.height.max(1) as usize);
+    let start_idx = adjust_start_for_wrapped_selection_visibility(
+        rows_all,
+        state,
+        max_items,
+        desc_measure_items,
+        area.width,
+        area.height,
+        col_width_mode,
+    );
+    let desc_col = compute_desc_col(
+        rows_all,
+        start_idx,
+        desc_measure_items,
+        area.width,
+        col_width_mode,
+    );
+
+    let mut cur_y = area.y;
+    for (i, row) in rows_all.iter().enumerate().skip(start_idx).take(max_items) {
+        let height = wrap_row_lines(row, desc_col, area.width).len() as u16;
+        cur_y = cur_y.saturating_add(height);
+        if y < cur_y {
+            return Some(i);
+        }
+    }
+    None
+}
+
 /// Render rows as a single line each (no wrapping), truncating overflow with an ellipsis.
 ///
 /// This path always uses viewport-local width alignment and is best for dense
@@ -866,4 +912,24 @@ mod tests {
         let two_col = wrap_two_column_row(&row, 0, 1);
         assert_eq!(two_col.len(), 0);
     }
+
+    #[test]
+    fn row_index_at_maps_lines_to_rows() {
+        let rows: Vec<GenericDisplayRow> = ["1. Yes", "2. No"]
+            .into_iter()
+            .map(|name| GenericDisplayRow {
+                name: name.to_string(),
+                ..Default::default()
+            })
+            .collect();
+        let mut state = ScrollState::new();
+        state.selected_idx = Some(0);
+        let area = Rect::new(0, 3, 20, 4);
+        let at = |y| row_index_at(area, &rows, &state, 4, ColumnWidthMode::AutoVisible, y);
+
+        assert_eq!(
+            (2..8).map(at).collect::<Vec<_>>(),
+            vec![None, Some(0), Some(1), None, None, None]
+        );
+    }
 }
@@ -126,6 +126,7 @@ use codex_protocol::protocol::McpToolCallEndEvent;
 use codex_protocol::protocol::Op;
 use codex_protocol::protocol::PatchApplyBeginEvent;
 use codex_protocol::protocol::RateLimitSnapshot;
+use codex_protocol::protocol::ReviewDecision;
 use codex_protocol::protocol::ReviewRequest;
 use codex_protocol::protocol::ReviewTarget;
 use codex_protocol::protocol::SkillMetadata as ProtocolSkillMetadata;
@@ -152,6 +153,7 @@ use crossterm::event::KeyCode;
 use crossterm::event::KeyEvent;
 use crossterm::event::KeyEventKind;
 use crossterm::event::KeyModifiers;
+use crossterm::event::MouseEvent;
 use rand::Rng;
 use ratatui::buffer::Buffer;
 use ratatui::layout::Rect;
@@ -221,6 +223,8 @@ use crate::bottom_pane::BottomPaneParams;
 use crate::bottom_pane::CancellationEvent;
 use crate::bottom_pane::CollaborationModeIndicator;
 use crate::bottom_pane::ColumnWidthMode;
+use crate::bottom_pane::CommandOutcome;
+use crate::bottom_pane::CommandSignature;
 use crate::bottom_pane::DOUBLE_PRESS_QUIT_SHORTCUT_ENABLED;
 use crate::bottom_pane::ExperimentalFeatureItem;
 use crate::bottom_pane::ExperimentalFeaturesView;
@@ -234,6 +238,7 @@ use crate::bottom_pane::SelectionAction;
 use crate::bottom_pane::SelectionItem;
 use crate::bottom_pane::SelectionViewParams;
 use crate::bottom_pane::custom_prompt_view::CustomPromptView;
+use crate::bottom_pane::env_overrides;
 use crate::bottom_pane::popup_consts::standard_popup_hint_line;
 use crate::clipboard_paste::paste_image_to_temp_png;
 use crate::clipboard_text;
@@ -1156,6 +1161,11 @@ impl ChatWidget {
         self.set_status_line(line);
     }
 
+    /// Passes a terminal resize down to the bottom pane.
+    pub(crate) fn on_resize(&mut self, width: u16, height: u16) {
+        self.bottom_pane.on_resize(width, height);
+    }
+
     /// Records that status-line setup was canceled.
     ///
     /// Cancellation is intentionally side-effect free for config state; the existing configuration
@@ -1976,6 +1986,11 @@ impl ChatWidget {
         self.request_redraw();
     }
 
+    fn on_approval_explanation(&mut self, explanation: String) {
+        self.add_to_history(history_cell::new_approval_explanation(explanation));
+        self.request_redraw();
+    }
+
     fn on_mcp_startup_update(&mut self, ev: McpStartupUpdateEvent) {
         let mut status = self.mcp_startup_status.take().unwrap_or_default();
         if let McpStartupStatus::Failed { error } = &ev.status {
@@ -2050,6 +2065,10 @@ impl ChatWidget {
     fn on_interrupted_turn(&mut self, reason: TurnAbortReason) {
         // Finalize, log a gentle prompt, and clear running state.
         self.finalize_turn();
+        // Core drops the aborted turn's pending approvals, so answering them
+        // now would send decisions for requests that no longer exist.
+        self.bottom_pane
+            .dismiss_approvals(self.thread_id.unwrap_or_default());
         if reason == TurnAbortReason::Interrupted {
             self.clear_unified_exec_processes();
         }
@@ -2404,6 +2423,13 @@ impl ChatWidget {
     }
 
     fn on_exec_command_end(&mut self, ev: ExecCommandEndEvent) {
+        if ev.source != ExecCommandSource::UnifiedExecInteraction {
+            self.bottom_pane.record_command_outcome(
+                ev.command.clone(),
+                ev.cwd.clone(),
+                CommandOutcome::new(ev.exit_code, ev.duration, &ev.aggregated_output),
+            );
+        }
         if is_unified_exec_source(ev.source) {
             if let Some(process_id) = ev.process_id.as_deref()
                 && self
@@ -2953,6 +2979,9 @@ impl ChatWidget {
 
     pub(crate) fn handle_exec_approval_now(&mut self, ev: ExecApprovalRequestEvent) {
         self.flush_answer_stream_with_separator();
+        self.app_event_tx.send(AppEvent::MarkApprovalSource {
+            id: ev.effective_approval_id(),
+        });
         let command = shlex::try_join(ev.command.iter().map(String::as_str))
             .unwrap_or_else(|_| ev.command.join(" "));
         self.notify(Notification::ExecApprovalRequested { command });
@@ -2963,18 +2992,28 @@ impl ChatWidget {
             thread_label: None,
             id: ev.effective_approval_id(),
             command: ev.command,
+            cwd: ev.cwd,
             reason: ev.reason,
             available_decisions,
             network_approval_context: ev.network_approval_context,
             additional_permissions: ev.additional_permissions,
+            sandbox_policy: Some(self.config.permissions.sandbox_policy.get().clone()),
+            env: env_overrides(ev.env.as_ref()),
+            duplicate_ids: Vec::new(),
         };
-        self.bottom_pane
-            .push_approval_request(request, &self.config.features);
+        self.bottom_pane.push_approval_request(
+            request,
+            &self.config.features,
+            &self.config.tui_approvals,
+        );
         self.request_redraw();
     }
 
     pub(crate) fn handle_apply_patch_approval_now(&mut self, ev: ApplyPatchApprovalRequestEvent) {
         self.flush_answer_stream_with_separator();
+        self.app_event_tx.send(AppEvent::MarkApprovalSource {
+            id: ev.call_id.clone(),
+        });
 
         let request = ApprovalRequest::ApplyPatch {
             thread_id: self.thread_id.unwrap_or_default(),
@@ -2983,9 +3022,13 @@ impl ChatWidget {
             reason: ev.reason,
             changes: ev.changes.clone(),
             cwd: self.config.cwd.clone(),
+            grant_root: ev.grant_root,
         };
-        self.bottom_pane
-            .push_approval_request(request, &self.config.features);
+        self.bottom_pane.push_approval_request(
+            request,
+            &self.config.features,
+            &self.config.tui_approvals,
+        );
         self.request_redraw();
         self.notify(Notification::EditApprovalRequested {
             cwd: self.config.cwd.clone(),
@@ -3012,18 +3055,42 @@ impl ChatWidget {
                 request_id: ev.id,
                 message: ev.request.message().to_string(),
             };
-            self.bottom_pane
-                .push_approval_request(request, &self.config.features);
+            self.bottom_pane.push_approval_request(
+                request,
+                &self.config.features,
+                &self.config.tui_approvals,
+            );
         }
         self.request_redraw();
     }
 
     pub(crate) fn push_approval_request(&mut self, request: ApprovalRequest) {
-        self.bottom_pane
-            .push_approval_request(request, &self.config.features);
+        self.bottom_pane.push_approval_request(
+            request,
+            &self.config.features,
+            &self.config.tui_approvals,
+        );
         self.request_redraw();
     }
 
+    /// Show a request the approval policy program left to the user.
+    pub(crate) fn show_approval_request(&mut self, request: ApprovalRequest) {
+        self.bottom_pane.show_approval_request(
+            request,
+            &self.config.features,
+            &self.config.tui_approvals,
+        );
+        self.request_redraw();
+    }
+
+    pub(crate) fn approve_all_pending(&mut self, decision: ReviewDecision) {
+        self.bottom_pane.approve_all_pending(decision);
+    }
+
+    pub(crate) fn register_auto_approve(&mut self, name: String, signature: CommandSignature) {
+        self.bottom_pane.register_auto_approve(name, signature);
+    }
+
     pub(crate) fn push_mcp_server_elicitation_request(
         &mut self,
         request: McpServerElicitationFormRequest,
@@ -3052,8 +3119,11 @@ impl ChatWidget {
             reason: ev.reason,
             permissions: ev.permissions,
         };
-        self.bottom_pane
-            .push_approval_request(request, &self.config.features);
+        self.bottom_pane.push_approval_request(
+            request,
+            &self.config.features,
+            &self.config.tui_approvals,
+        );
         self.request_redraw();
     }
 
@@ -4439,6 +4509,15 @@ impl ChatWidget {
         self.bottom_pane.handle_paste(text);
     }
 
+    pub(crate) fn handle_mouse_event(&mut self, mouse_event: MouseEvent) {
+        self.bottom_pane.handle_mouse_event(mouse_event);
+    }
+
+    /// Whether the terminal should report mouse events for the current view.
+    pub(crate) fn wants_mouse_capture(&self) -> bool {
+        self.bottom_pane.wants_mouse_capture()
+    }
+
     // Returns true if caller should skip rendering this frame (a future frame is scheduled).
     pub(crate) fn handle_paste_burst_tick(&mut self, frame_requester: FrameRequester) -> bool {
         if self.bottom_pane.flush_paste_burst_if_due() {
@@ -4460,10 +4539,26 @@ impl ChatWidget {
     fn flush_active_cell(&mut self) {
         if let Some(active) = self.active_cell.take() {
             self.needs_final_message_separator = true;
+            self.record_approval_context(active.as_ref());
             self.app_event_tx.send(AppEvent::InsertHistoryCell(active));
         }
     }
 
+    /// Keep what `cell` shows for the transcript context an approval prompt
+    /// opens with, when `tui.approvals.context_lines` asks for it.
+    fn record_approval_context(&mut self, cell: &dyn HistoryCell) {
+        if self.config.tui_approvals.context_lines.unwrap_or(0) == 0 {
+            return;
+        }
+        let width = self
+            .last_rendered_width
+            .get()
+            .and_then(|width| u16::try_from(width).ok())
+            .unwrap_or(80);
+        self.bottom_pane
+            .record_history_lines(cell.display_lines(width));
+    }
+
     pub(crate) fn add_to_history(&mut self, cell: impl HistoryCell + 'static) {
         self.add_boxed_history(Box::new(cell));
     }
@@ -4482,6 +4577,7 @@ impl ChatWidget {
             self.flush_active_cell();
             self.needs_final_message_separator = true;
         }
+        self.record_approval_context(cell.as_ref());
         self.app_event_tx.send(AppEvent::InsertHistoryCell(cell));
     }
 
@@ -4957,6 +5053,7 @@ impl ChatWidget {
                 // For replayed events, synthesize an empty id (these should not occur).
                 self.on_exec_approval_request(id.unwrap_or_default(), ev)
             }
+            EventMsg::ApprovalExplanation(ev) => self.on_approval_explanation(ev.explanation),
             EventMsg::ApplyPatchApprovalRequest(ev) => {
                 self.on_apply_patch_approval_request(id.unwrap_or_default(), ev)
             }
@@ -3240,6 +3240,7 @@ async fn exec_approval_emits_proposed_command_and_decision_history() {
         turn_id: "turn-short".into(),
         command: vec!["bash".into(), "-lc".into(), "echo hello world".into()],
         cwd: std::env::current_dir().unwrap_or_else(|_| PathBuf::from(".")),
+        env: None,
         reason: Some(
             "this is a test reason such as one that would be produced by the model".into(),
         ),
@@ -3279,6 +3280,40 @@ async fn exec_approval_emits_proposed_command_and_decision_history() {
     );
 }
 
+#[tokio::test]
+async fn exec_approval_shows_the_env_overrides_of_the_request() {
+    let (mut chat, _rx, _op_rx) = make_chatwidget_manual(None).await;
+
+    chat.handle_codex_event(Event {
+        id: "sub-env".into(),
+        msg: EventMsg::ExecApprovalRequest(ExecApprovalRequestEvent {
+            call_id: "call-env".into(),
+            approval_id: None,
+            turn_id: "turn-env".into(),
+            command: vec!["cargo".into(), "test".into()],
+            cwd: std::env::current_dir().unwrap_or_else(|_| PathBuf::from(".")),
+            env: Some(HashMap::from([
+                ("RUST_LOG".to_string(), "debug".to_string()),
+                ("CARGO_TERM_COLOR".to_string(), "never".to_string()),
+            ])),
+            reason: None,
+            network_approval_context: None,
+            proposed_execpolicy_amendment: None,
+            proposed_network_policy_amendments: None,
+            additional_permissions: None,
+            skill_metadata: None,
+            available_decisions: None,
+            parsed_cmd: vec![],
+        }),
+    });
+
+    let popup = render_bottom_popup(&chat, 80);
+    assert!(
+        popup.contains("env: CARGO_TERM_COLOR=never RUST_LOG=debug"),
+        "expected the env overrides under the command, got {popup:?}"
+    );
+}
+
 #[tokio::test]
 async fn exec_approval_uses_approval_id_when_present() {
     let (mut chat, mut rx, _op_rx) = make_chatwidget_manual(None).await;
@@ -3291,6 +3326,7 @@ async fn exec_approval_uses_approval_id_when_present() {
             turn_id: "turn-short".into(),
             command: vec!["bash".into(), "-lc".into(), "echo hello world".into()],
             cwd: std::env::current_dir().unwrap_or_else(|_| PathBuf::from(".")),
+            env: None,
             reason: Some(
                 "this is a test reason such as one that would be produced by the model".into(),
             ),
@@ -3309,17 +3345,20 @@ async fn exec_approval_uses_approval_id_when_present() {
     let mut found = false;
     while let Ok(app_ev) = rx.try_recv() {
         if let AppEvent::SubmitThreadOp {
-            op: Op::ExecApproval { id, decision, .. },
+            op: Op::ApprovalResponse { id, response, .. },
             ..
         } = app_ev
         {
             assert_eq!(id, "approval-subcommand");
-            assert_matches!(decision, codex_protocol::protocol::ReviewDecision::Approved);
+            assert_matches!(
+                response.decision,
+                codex_protocol::protocol::ReviewDecision::Approved
+            );
             found = true;
             break;
         }
     }
-    assert!(found, "expected ExecApproval op to be sent");
+    assert!(found, "expected ApprovalResponse op to be sent");
 }
 
 #[tokio::test]
@@ -3333,6 +3372,7 @@ async fn exec_approval_decision_truncates_multiline_and_long_commands() {
         turn_id: "turn-multi".into(),
         command: vec!["bash".into(), "-lc".into(), "echo line1\necho line2".into()],
         cwd: std::env::current_dir().unwrap_or_else(|_| PathBuf::from(".")),
+        env: None,
         reason: Some(
             "this is a test reason such as one that would be produced by the model".into(),
         ),
@@ -3391,6 +3431,7 @@ async fn exec_approval_decision_truncates_multiline_and_long_commands() {
         turn_id: "turn-long".into(),
         command: vec!["bash".into(), "-lc".into(), long],
         cwd: std::env::current_dir().unwrap_or_else(|_| PathBuf::from(".")),
+        env: None,
         reason: None,
         network_approval_context: None,
         proposed_execpolicy_amendment: None,
@@ -8360,6 +8401,7 @@ async fn approval_modal_exec_snapshot() -> anyhow::Result<()> {
         turn_id: "turn-approve-cmd".into(),
         command: vec!["bash".into(), "-lc".into(), "echo hello world".into()],
         cwd: std::env::current_dir().unwrap_or_else(|_| PathBuf::from(".")),
+        env: None,
         reason: Some(
             "this is a test reason such as one that would be produced by the model".into(),
         ),
@@ -8424,6 +8466,7 @@ async fn approval_modal_exec_without_reason_snapshot() -> anyhow::Result<()> {
         turn_id: "turn-approve-cmd-noreason".into(),
         command: vec!["bash".into(), "-lc".into(), "echo hello world".into()],
         cwd: std::env::current_dir().unwrap_or_else(|_| PathBuf::from(".")),
+        env: None,
         reason: None,
         network_approval_context: None,
         proposed_execpolicy_amendment: Some(ExecPolicyAmendment::new(vec![
@@ -8477,6 +8520,7 @@ async fn approval_modal_exec_multiline_prefix_hides_execpolicy_option_snapshot()
         turn_id: "turn-approve-cmd-multiline-trunc".into(),
         command: command.clone(),
         cwd: std::env::current_dir().unwrap_or_else(|_| PathBuf::from(".")),
+        env: None,
         reason: None,
         network_approval_context: None,
         proposed_execpolicy_amendment: Some(ExecPolicyAmendment::new(command)),
@@ -8840,6 +8884,7 @@ async fn status_widget_and_approval_modal_snapshot() {
         turn_id: "turn-approve-exec".into(),
         command: vec!["echo".into(), "hello world".into()],
         cwd: PathBuf::from("/tmp"),
+        env: None,
         reason: Some(
             "this is a test reason such as one that would be produced by the model".into(),
         ),
@@ -9199,17 +9244,20 @@ async fn apply_patch_approval_sends_op_with_call_id() {
     let mut found = false;
     while let Ok(app_ev) = rx.try_recv() {
         if let AppEvent::SubmitThreadOp {
-            op: Op::PatchApproval { id, decision },
+            op: Op::ApprovalResponse { id, response, .. },
             ..
         } = app_ev
         {
             assert_eq!(id, "call-999");
-            assert_matches!(decision, codex_protocol::protocol::ReviewDecision::Approved);
+            assert_matches!(
+                response.decision,
+                codex_protocol::protocol::ReviewDecision::Approved
+            );
             found = true;
             break;
         }
     }
-    assert!(found, "expected PatchApproval op to be sent");
+    assert!(found, "expected ApprovalResponse op to be sent");
 }
 
 #[tokio::test]
@@ -9250,9 +9298,12 @@ async fn apply_patch_full_flow_integration_like() {
         .try_recv()
         .expect("expected op forwarded to codex channel");
     match forwarded {
-        Op::PatchApproval { id, decision } => {
+        Op::ApprovalResponse { id, response, .. } => {
             assert_eq!(id, "call-1");
-            assert_matches!(decision, codex_protocol::protocol::ReviewDecision::Approved);
+            assert_matches!(
+                response.decision,
+                codex_protocol::protocol::ReviewDecision::Approved
+            );
         }
         other => panic!("unexpected op forwarded: {other:?}"),
     }
@@ -97,6 +97,8 @@ use codex_core::terminal::TerminalName;
 use codex_core::terminal::terminal_info;
 use codex_protocol::protocol::FileChange;
 
+mod word_diff;
+
 /// Classifies a diff line for gutter sign rendering and style selection.
 ///
 /// `Insert` renders with a `+` sign and green text, `Delete` with `-` and red
@@ -295,24 +297,53 @@ fn quantize_rgb_to_ansi256(target: (u8, u8, u8)) -> Color {
 pub struct DiffSummary {
     changes: HashMap<PathBuf, FileChange>,
     cwd: PathBuf,
+    word_diff: bool,
 }
 
 impl DiffSummary {
     pub fn new(changes: HashMap<PathBuf, FileChange>, cwd: PathBuf) -> Self {
-        Self { changes, cwd }
+        Self {
+            changes,
+            cwd,
+            word_diff: false,
+        }
+    }
+
+    /// Highlight the changed words within edited lines. Off by default since
+    /// it compares every edited line token by token.
+    pub(crate) fn word_diff(mut self, enabled: bool) -> Self {
+        self.word_diff = enabled;
+        self
     }
 }
 
 impl Renderable for FileChange {
     fn render(&self, area: Rect, buf: &mut Buffer) {
         let mut lines = vec![];
-        render_change(self, &mut lines, area.width as usize, None);
+        render_change(self, &mut lines, area.width as usize, None, false);
+        Paragraph::new(lines).render(area, buf);
+    }
+
+    fn desired_height(&self, width: u16) -> u16 {
+        let mut lines = vec![];
+        render_change(self, &mut lines, width as usize, None, false);
+        lines.len() as u16
+    }
+}
+
+/// A change rendered with word-level highlighting in its edited lines.
+struct WordDiffChange(FileChange);
+
+impl Renderable for WordDiffChange {
+    fn render(&self, area: Rect, buf: &mut Buffer) {
+        let mut lines = vec![];
+        render_change(&self.0, &mut lines, area.width as usize, None, true);
         Paragraph::new(lines).render(area, buf);
     }
 
     fn desired_height(&self, width: u16) -> u16 {
         let mut lines = vec![];
-        render_change(self, &mut lines, width as usize, None);
+        render_change(&self.0, &mut lines, width as usize, None, true);
         lines.len() as u16
     }
 }
@@ -330,8 +361,13 @@ impl From<DiffSummary> for Box<dyn Renderable> {
             path.extend(render_line_count_summary(row.added, row.removed));
             rows.push(Box::new(path));
             rows.push(Box::new(RtLine::from("")));
+            let change: Box<dyn Renderable> = if val.word_diff {
+                Box::new(WordDiffChange(row.change))
+            } else {
+                Box::new(row.change)
+            };
             rows.push(Box::new(InsetRenderable::new(
-                Box::new(row.change) as Box<dyn Renderable>,
+                change,
                 Insets::tlbr(0, 2, 0, 0),
             )));
         }
@@ -454,7 +490,7 @@ fn render_changes_block(rows: Vec<Row>, wrap_cols: usize, cwd: &Path) -> Vec<RtL
         let lang_path = r.move_path.as_deref().unwrap_or(&r.path);
         let lang = detect_lang_for_path(lang_path);
         let mut lines = vec![];
-        render_change(&r.change, &mut lines, wrap_cols - 4, lang.as_deref());
+        render_change(&r.change, &mut lines, wrap_cols - 4, lang.as_deref(), false);
         out.extend(prefix_lines(lines, "    ".into(), "    ".into()));
     }
 
@@ -474,6 +510,7 @@ fn render_change(
     out: &mut Vec<RtLine<'static>>,
     width: usize,
     lang: Option<&str>,
+    word_diff: bool,
 ) {
     let style_context = current_diff_render_style_context();
     match change {
@@ -617,6 +654,11 @@ fn render_change(
                         let syntax_lines = highlight_code_to_styled_spans(&hunk_text, language)?;
                         (syntax_lines.len() == h.lines().len()).then_some(syntax_lines)
                     });
+                    let hunk_word_lines = if word_diff {
+                        word_diff::paired_word_diffs(h.lines())
+                    } else {
+                        Vec::new()
+                    };
 
                     let mut old_ln = h.old_range().start();
                     let mut new_ln = h.new_range().start();
@@ -624,10 +666,20 @@ fn render_change(
                         let syntax_spans = hunk_syntax_lines
                             .as_ref()
                             .and_then(|syntax_lines| syntax_lines.get(line_idx));
+                        let word_line = hunk_word_lines.get(line_idx).and_then(Option::as_ref);
                         match l {
                             diffy::Line::Insert(text) => {
                                 let s = text.trim_end_matches('\n');
-                                if let Some(syn) = syntax_spans {
+                                if let Some(words) = word_line {
+                                    out.extend(word_diff::push_wrapped_word_diff_line(
+                                        new_ln,
+                                        DiffLineType::Insert,
+                                        words,
+                                        width,
+                                        line_number_width,
+                                        style_context,
+                                    ));
+                                } else if let Some(syn) = syntax_spans {
                                     out.extend(
                                         push_wrapped_diff_line_inner_with_theme_and_color_level(
                                             new_ln,
@@ -660,7 +712,16 @@ fn render_change(
                             }
                             diffy::Line::Delete(text) => {
                                 let s = text.trim_end_matches('\n');
-                                if let Some(syn) = syntax_spans {
+                                if let Some(words) = word_line {
+                                    out.extend(word_diff::push_wrapped_word_diff_line(
+                                        old_ln,
+                                        DiffLineType::Delete,
+                                        words,
+                                        width,
+                                        line_number_width,
+                                        style_context,
+                                    ));
+                                } else if let Some(syn) = syntax_spans {
                                     out.extend(
                                         push_wrapped_diff_line_inner_with_theme_and_color_level(
                                             old_ln,
@@ -2421,4 +2482,26 @@ mod tests {
 
         assert_eq!(actual_style, expected_style);
     }
+
+    #[test]
+    fn word_diff_emphasizes_only_the_edited_token() {
+        let patch = diffy::create_patch("let n = 1;\n", "let n = 2;\n").to_string();
+        let change = FileChange::Update {
+            unified_diff: patch,
+            move_path: None,
+        };
+        let bold_spans = |word_diff| {
+            let mut lines = vec![];
+            render_change(&change, &mut lines, 80, None, word_diff);
+            lines
+                .iter()
+                .flat_map(|line| &line.spans)
+                .filter(|span| span.style.add_modifier.contains(Modifier::BOLD))
+                .map(|span| span.content.to_string())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(bold_spans(false), Vec::<String>::new());
+        assert_eq!(bold_spans(true), vec!["1;".to_string(), "2;".to_string()]);
+    }
 }
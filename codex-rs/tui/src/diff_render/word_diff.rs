@@ -0,0 +1,279 @@
+//! Word-level highlighting within changed lines.
+//!
+//! A hunk that deletes some lines and inserts the same number right after is
+//! usually an edit of those lines. Each deleted line is paired with the
+//! inserted line at the same position, and the two are compared token by
+//! token (runs of whitespace and of everything else) with a longest common
+//! subsequence. Tokens only one side has are drawn bold in the line's color;
+//! the tokens both share are dimmed, so a one-word edit in a long line stands
+//! out.
+//!
+//! The comparison is quadratic in the tokens per line, so lines longer than
+//! [`MAX_TOKENS`] are left to the plain line-level coloring.
+
+use ratatui::style::Modifier;
+use ratatui::style::Style;
+use ratatui::text::Line as RtLine;
+use ratatui::text::Span as RtSpan;
+
+use super::DiffLineType;
+use super::DiffRenderStyleContext;
+use super::style_add;
+use super::style_del;
+use super::style_gutter_for;
+use super::style_line_bg_for;
+use super::style_sign_add;
+use super::style_sign_del;
+use super::wrap_styled_spans;
+
+/// Tokens per line beyond which a pair is not compared word by word.
+const MAX_TOKENS: usize = 512;
+
+/// `old` and `new` with the tokens they share dimmed and the rest bold.
+///
+/// Either line past [`MAX_TOKENS`] comes back unstyled.
+pub(crate) fn word_diff(old: &str, new: &str) -> (RtLine<'static>, RtLine<'static>) {
+    let old_tokens = tokens(old);
+    let new_tokens = tokens(new);
+    if old_tokens.len() > MAX_TOKENS || new_tokens.len() > MAX_TOKENS {
+        return (RtLine::from(old.to_string()), RtLine::from(new.to_string()));
+    }
+    let (old_kept, new_kept) = common_tokens(&old_tokens, &new_tokens);
+    (
+        styled_line(&old_tokens, &old_kept),
+        styled_line(&new_tokens, &new_kept),
+    )
+}
+
+/// The word-diffed line for each line of `lines` that pairs with another,
+/// indexed like `lines`.
+pub(super) fn paired_word_diffs(lines: &[diffy::Line<'_, str>]) -> Vec<Option<RtLine<'static>>> {
+    let mut out = vec![None; lines.len()];
+    let mut idx = 0;
+    while idx < lines.len() {
+        let deletes_start = idx;
+        while matches!(lines.get(idx), Some(diffy::Line::Delete(_))) {
+            idx += 1;
+        }
+        let inserts_start = idx;
+        while matches!(lines.get(idx), Some(diffy::Line::Insert(_))) {
+            idx += 1;
+        }
+        if deletes_start == idx {
+            idx += 1;
+            continue;
+        }
+        let pairs = (inserts_start - deletes_start).min(idx - inserts_start);
+        for offset in 0..pairs {
+            let old_idx = deletes_start + offset;
+            let new_idx = inserts_start + offset;
+            let (old, new) = word_diff(line_text(&lines[old_idx]), line_text(&lines[new_idx]));
+            out[old_idx] = Some(old);
+            out[new_idx] = Some(new);
+        }
+    }
+    out
+}
+
+/// Render a word-diffed line like a plain diff line: gutter, sign, then the
+/// content wrapped to `width`, with each token's emphasis laid over the
+/// line's color.
+pub(super) fn push_wrapped_word_diff_line(
+    line_number: usize,
+    kind: DiffLineType,
+    words: &RtLine<'static>,
+    width: usize,
+    line_number_width: usize,
+    style_context: DiffRenderStyleContext,
+) -> Vec<RtLine<'static>> {
+    let DiffRenderStyleContext {
+        theme,
+        color_level,
+        diff_backgrounds,
+    } = style_context;
+    let (sign_char, sign_style, content_style) = match kind {
+        DiffLineType::Insert => (
+            '+',
+            style_sign_add(theme, color_level, diff_backgrounds),
+            style_add(theme, color_level, diff_backgrounds),
+        ),
+        DiffLineType::Delete => (
+            '-',
+            style_sign_del(theme, color_level, diff_backgrounds),
+            style_del(theme, color_level, diff_backgrounds),
+        ),
+        DiffLineType::Context => (' ', Style::default(), Style::default()),
+    };
+    let line_bg = style_line_bg_for(kind, diff_backgrounds);
+    let gutter_style = style_gutter_for(kind, theme, color_level);
+    let gutter_width = line_number_width.max(1);
+
+    let styled: Vec<RtSpan<'static>> = words
+        .spans
+        .iter()
+        .map(|span| RtSpan::styled(span.content.clone(), content_style.patch(span.style)))
+        .collect();
+    let available_content_cols = width.saturating_sub(gutter_width + 2).max(1);
+    wrap_styled_spans(&styled, available_content_cols)
+        .into_iter()
+        .enumerate()
+        .map(|(i, chunk)| {
+            let mut row_spans = if i == 0 {
+                vec![
+                    RtSpan::styled(format!("{line_number:>gutter_width$} "), gutter_style),
+                    RtSpan::styled(sign_char.to_string(), sign_style),
+                ]
+            } else {
+                vec![RtSpan::styled(
+                    format!("{:gutter_width$}  ", ""),
+                    gutter_style,
+                )]
+            };
+            row_spans.extend(chunk);
+            RtLine::from(row_spans).style(line_bg)
+        })
+        .collect()
+}
+
+fn line_text<'a>(line: &diffy::Line<'a, str>) -> &'a str {
+    match line {
+        diffy::Line::Insert(text) | diffy::Line::Delete(text) | diffy::Line::Context(text) => {
+            text.trim_end_matches('\n')
+        }
+    }
+}
+
+/// `text` split into alternating runs of whitespace and non-whitespace.
+fn tokens(text: &str) -> Vec<&str> {
+    let mut tokens = Vec::new();
+    let mut start = 0;
+    let mut in_space = None;
+    for (idx, ch) in text.char_indices() {
+        let space = ch.is_whitespace();
+        if in_space.is_some_and(|prev| prev != space) {
+            tokens.push(&text[start..idx]);
+            start = idx;
+        }
+        in_space = Some(space);
+    }
+    if start < text.len() {
+        tokens.push(&text[start..]);
+    }
+    tokens
+}
+
+/// For each token of `old` and of `new`, whether it is part of their longest
+/// common subsequence.
+fn common_tokens(old: &[&str], new: &[&str]) -> (Vec<bool>, Vec<bool>) {
+    let cols = new.len() + 1;
+    // lengths[i * cols + j] is the LCS length of old[i..] and new[j..].
+    let mut lengths = vec![0u32; (old.len() + 1) * cols];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lengths[i * cols + j] = if old[i] == new[j] {
+                lengths[(i + 1) * cols + j + 1] + 1
+            } else {
+                lengths[(i + 1) * cols + j].max(lengths[i * cols + j + 1])
+            };
+        }
+    }
+
+    let mut old_kept = vec![false; old.len()];
+    let mut new_kept = vec![false; new.len()];
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() && j < new.len() {
+        if old[i] == new[j] {
+            old_kept[i] = true;
+            new_kept[j] = true;
+            i += 1;
+            j += 1;
+        } else if lengths[(i + 1) * cols + j] >= lengths[i * cols + j + 1] {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+    (old_kept, new_kept)
+}
+
+/// One span per run of tokens that are all kept or all changed.
+fn styled_line(tokens: &[&str], kept: &[bool]) -> RtLine<'static> {
+    let mut spans: Vec<RtSpan<'static>> = Vec::new();
+    let mut run = String::new();
+    for (idx, token) in tokens.iter().enumerate() {
+        run.push_str(token);
+        if kept.get(idx + 1) != Some(&kept[idx]) {
+            let modifier = if kept[idx] {
+                Modifier::DIM
+            } else {
+                Modifier::BOLD
+            };
+            spans.push(RtSpan::styled(
+                std::mem::take(&mut run),
+                Style::default().add_modifier(modifier),
+            ));
+        }
+    }
+    RtLine::from(spans)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn runs(line: &RtLine<'_>) -> Vec<(String, bool)> {
+        line.spans
+            .iter()
+            .map(|span| {
+                let changed = span.style.add_modifier.contains(Modifier::BOLD);
+                (span.content.to_string(), changed)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn only_the_edited_word_is_emphasized() {
+        let (old, new) = word_diff("let total = count + 1;", "let total = count + 2;");
+        assert_eq!(
+            runs(&old),
+            vec![
+                ("let total = count + ".to_string(), false),
+                ("1;".to_string(), true),
+            ]
+        );
+        assert_eq!(
+            runs(&new),
+            vec![
+                ("let total = count + ".to_string(), false),
+                ("2;".to_string(), true),
+            ]
+        );
+    }
+
+    #[test]
+    fn inserted_words_only_show_on_the_new_side() {
+        let (old, new) = word_diff("fn run()", "pub fn run()");
+        assert_eq!(runs(&old), vec![("fn run()".to_string(), false)]);
+        assert_eq!(
+            runs(&new),
+            vec![("pub ".to_string(), true), ("fn run()".to_string(), false),]
+        );
+    }
+
+    #[test]
+    fn deletes_pair_with_the_inserts_that_follow() {
+        let lines = [
+            diffy::Line::Context("fn main() {\n"),
+            diffy::Line::Delete("    old();\n"),
+            diffy::Line::Insert("    new();\n"),
+            diffy::Line::Insert("    extra();\n"),
+            diffy::Line::Context("}\n"),
+        ];
+        let paired: Vec<bool> = paired_word_diffs(&lines)
+            .iter()
+            .map(Option::is_some)
+            .collect();
+        assert_eq!(paired, vec![false, true, true, false, false]);
+    }
+}
@@ -34,6 +34,15 @@ where
     Some(rel.to_path_buf())
 }
 
+/// Display `path` with the home directory abbreviated to `~`.
+pub(crate) fn display_path(path: &Path) -> String {
+    match relativize_to_home(path) {
+        Some(rel) if rel.as_os_str().is_empty() => "~".to_string(),
+        Some(rel) => format!("~{}{}", std::path::MAIN_SEPARATOR, rel.display()),
+        None => path.display().to_string(),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -67,4 +76,14 @@ mod tests {
         let cmdline = strip_bash_lc_and_escape(&args);
         assert_eq!(cmdline, "echo hello");
     }
+
+    #[test]
+    fn display_path_abbreviates_home() {
+        let Some(home) = home_dir() else {
+            return;
+        };
+        let expected = format!("~{}project", std::path::MAIN_SEPARATOR);
+        assert_eq!(display_path(&home.join("project")), expected);
+        assert_eq!(display_path(&home), "~");
+    }
 }
@@ -0,0 +1,110 @@
+use std::env;
+use std::fs;
+use std::process::Stdio;
+
+use color_eyre::eyre::Report;
+use color_eyre::eyre::Result;
+use tempfile::Builder;
+use tokio::process::Command;
+
+/// Pager used when `PAGER` is unset or empty.
+#[cfg(not(windows))]
+const DEFAULT_PAGER: &[&str] = &["less", "-R"];
+#[cfg(windows)]
+const DEFAULT_PAGER: &[&str] = &["more"];
+
+/// Resolve the pager command from `PAGER`, falling back to `less -R`.
+pub(crate) fn resolve_pager_command() -> Vec<String> {
+    let from_env = env::var("PAGER").ok().and_then(|raw| {
+        #[cfg(windows)]
+        {
+            Some(winsplit::split(&raw))
+        }
+        #[cfg(not(windows))]
+        {
+            shlex::split(&raw)
+        }
+    });
+    match from_env {
+        Some(parts) if !parts.is_empty() => parts,
+        Some(_) | None => DEFAULT_PAGER.iter().map(|part| part.to_string()).collect(),
+    }
+}
+
+/// Write `text` to a temp file and open it in the pager command.
+pub(crate) async fn run_pager(text: &str, pager_cmd: &[String]) -> Result<()> {
+    let Some((program, args)) = pager_cmd.split_first() else {
+        return Err(Report::msg("pager command is empty"));
+    };
+
+    // Convert to TempPath immediately so no file handle stays open on Windows.
+    let temp_path = Builder::new().suffix(".txt").tempfile()?.into_temp_path();
+    fs::write(&temp_path, text)?;
+
+    let status = Command::new(program)
+        .args(args)
+        .arg(&temp_path)
+        .stdin(Stdio::inherit())
+        .stdout(Stdio::inherit())
+        .stderr(Stdio::inherit())
+        .status()
+        .await?;
+    if !status.success() {
+        return Err(Report::msg(format!("pager exited with status {status}")));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use serial_test::serial;
+
+    struct PagerGuard(Option<String>);
+
+    impl Drop for PagerGuard {
+        fn drop(&mut self) {
+            match self.0.take() {
+                Some(val) => unsafe { env::set_var("PAGER", val) },
+                None => unsafe { env::remove_var("PAGER") },
+            }
+        }
+    }
+
+    #[test]
+    #[serial]
+    fn resolve_pager_uses_env_then_default() {
+        let _guard = PagerGuard(env::var("PAGER").ok());
+        unsafe { env::set_var("PAGER", "most -s") };
+        assert_eq!(
+            resolve_pager_command(),
+            vec!["most".to_string(), "-s".to_string()]
+        );
+        unsafe { env::remove_var("PAGER") };
+        assert_eq!(
+            resolve_pager_command(),
+            DEFAULT_PAGER
+                .iter()
+                .map(|part| part.to_string())
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[tokio::test]
+    #[cfg(unix)]
+    async fn run_pager_opens_the_text() {
+        let dir = tempfile::tempdir().unwrap();
+        let out = dir.path().join("out.txt");
+        let cmd = vec![
+            "sh".to_string(),
+            "-c".to_string(),
+            format!("cat \"$0\" > '{}'", out.display()),
+        ];
+        run_pager("line one\nline two\n", &cmd).await.unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&out).unwrap(),
+            "line one\nline two\n"
+        );
+    }
+}
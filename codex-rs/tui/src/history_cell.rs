@@ -10,6 +10,8 @@
 //! bumps the active-cell revision tracked by `ChatWidget`, so the cache key changes whenever the
 //! rendered transcript output can change.
 
+use crate::bottom_pane::format_approval_duration;
+use crate::bottom_pane::redact_secrets;
 use crate::diff_render::create_diff_summary;
 use crate::diff_render::display_path_for;
 use crate::exec_cell::CommandOutput;
@@ -17,7 +19,7 @@ use crate::exec_cell::OutputLinesParams;
 use crate::exec_cell::TOOL_CALL_MAX_LINES;
 use crate::exec_cell::output_lines;
 use crate::exec_cell::spinner;
-use crate::exec_command::relativize_to_home;
+use crate::exec_command::display_path;
 use crate::exec_command::strip_bash_lc_and_escape;
 use crate::live_wrap::take_prefix_by_width;
 use crate::markdown::append_markdown;
@@ -787,20 +789,87 @@ fn truncate_exec_snippet(full_cmd: &str) -> String {
 }
 
 fn exec_snippet(command: &[String]) -> String {
-    let full_cmd = strip_bash_lc_and_escape(command);
+    // Scrollback outlives the prompt, so credentials never reach it.
+    let full_cmd = redact_secrets(&strip_bash_lc_and_escape(command));
     truncate_exec_snippet(&full_cmd)
 }
 
 pub fn new_approval_decision_cell(
     command: Vec<String>,
     decision: codex_protocol::protocol::ReviewDecision,
+    feedback: Option<String>,
 ) -> Box<dyn HistoryCell> {
+    Box::new(ApprovalDecisionCell {
+        command,
+        decision,
+        feedback: feedback.filter(|feedback| !feedback.trim().is_empty()),
+        compact: false,
+    })
+}
+
+/// Like [`new_approval_decision_cell`], but drawn as a single
+/// `✔ approved: <command>` line without the feedback.
+pub(crate) fn new_compact_approval_decision_cell(
+    command: Vec<String>,
+    decision: codex_protocol::protocol::ReviewDecision,
+    feedback: Option<String>,
+) -> Box<dyn HistoryCell> {
+    Box::new(ApprovalDecisionCell {
+        command,
+        decision,
+        feedback: feedback.filter(|feedback| !feedback.trim().is_empty()),
+        compact: true,
+    })
+}
+
+/// The user's answer to an exec or network approval prompt.
+#[derive(Debug)]
+pub(crate) struct ApprovalDecisionCell {
+    pub command: Vec<String>,
+    pub decision: codex_protocol::protocol::ReviewDecision,
+    /// What the user told codex along with the decision, if anything.
+    pub feedback: Option<String>,
+    /// Drawn as one line naming the outcome and the command.
+    pub compact: bool,
+}
+
+impl HistoryCell for ApprovalDecisionCell {
+    fn display_lines(&self, width: u16) -> Vec<Line<'static>> {
+        let (symbol, summary) = approval_decision_summary(&self.command, self.decision.clone());
+        if self.compact {
+            return vec![compact_decision_line(
+                symbol,
+                &self.command,
+                &self.decision,
+                width,
+            )];
+        }
+        let mut lines =
+            PrefixedWrappedHistoryCell::new(Line::from(summary), symbol, "  ").display_lines(width);
+        if let Some(feedback) = &self.feedback {
+            lines.extend(
+                PrefixedWrappedHistoryCell::new(
+                    Line::from(feedback.clone().italic()),
+                    "  ↳ ".dim(),
+                    "    ",
+                )
+                .display_lines(width),
+            );
+        }
+        lines
+    }
+}
+
+fn approval_decision_summary(
+    command: &[String],
+    decision: codex_protocol::protocol::ReviewDecision,
+) -> (Span<'static>, Vec<Span<'static>>) {
     use codex_protocol::protocol::NetworkPolicyRuleAction;
     use codex_protocol::protocol::ReviewDecision::*;
 
-    let (symbol, summary): (Span<'static>, Vec<Span<'static>>) = match decision {
+    match decision {
         Approved => {
-            let snippet = Span::from(exec_snippet(&command)).dim();
+            let snippet = Span::from(exec_snippet(command)).dim();
             (
                 "✔ ".green(),
                 vec![
@@ -827,7 +896,7 @@ pub fn new_approval_decision_cell(
             )
         }
         ApprovedForSession => {
-            let snippet = Span::from(exec_snippet(&command)).dim();
+            let snippet = Span::from(exec_snippet(command)).dim();
             (
                 "✔ ".green(),
                 vec![
@@ -839,6 +908,76 @@ pub fn new_approval_decision_cell(
                 ],
             )
         }
+        ApprovedForDirectory => {
+            let snippet = Span::from(exec_snippet(command)).dim();
+            (
+                "✔ ".green(),
+                vec![
+                    "You ".into(),
+                    "approved".bold(),
+                    " codex to run ".into(),
+                    snippet,
+                    " in this directory every time this session".bold(),
+                ],
+            )
+        }
+        ApprovedForDuration { duration } => {
+            let snippet = Span::from(exec_snippet(command)).dim();
+            (
+                "✔ ".green(),
+                vec![
+                    "You ".into(),
+                    "approved".bold(),
+                    " codex to run ".into(),
+                    snippet,
+                    format!(
+                        " every time for the next {}",
+                        format_approval_duration(duration)
+                    )
+                    .bold(),
+                ],
+            )
+        }
+        ApprovedAlways => {
+            let snippet = Span::from(exec_snippet(command)).dim();
+            (
+                "✔ ".green(),
+                vec![
+                    "You ".into(),
+                    "approved".bold(),
+                    " codex to always run ".into(),
+                    snippet,
+                    " in this directory".bold(),
+                ],
+            )
+        }
+        ApprovedSandboxed => {
+            let snippet = Span::from(exec_snippet(command)).dim();
+            (
+                "✔ ".green(),
+                vec![
+                    "You ".into(),
+                    "approved".bold(),
+                    " codex to run ".into(),
+                    snippet,
+                    " sandboxed".bold(),
+                    " (read-only, no network)".dim(),
+                ],
+            )
+        }
+        DryRun => {
+            let snippet = Span::from(exec_snippet(command)).dim();
+            (
+                "» ".cyan(),
+                vec![
+                    "You asked codex for a ".into(),
+                    "dry run".bold(),
+                    " of ".into(),
+                    snippet,
+                    " (read-only)".dim(),
+                ],
+            )
+        }
         NetworkPolicyAmendment {
             network_policy_amendment,
         } => match network_policy_amendment.action {
@@ -863,7 +1002,7 @@ pub fn new_approval_decision_cell(
             ),
         },
         Denied => {
-            let snippet = Span::from(exec_snippet(&command)).dim();
+            let snippet = Span::from(exec_snippet(command)).dim();
             (
                 "✗ ".red(),
                 vec![
@@ -871,28 +1010,75 @@ pub fn new_approval_decision_cell(
                     "did not approve".bold(),
                     " codex to run ".into(),
                     snippet,
+                    " (codex continues)".dim(),
                 ],
             )
         }
-        Abort => {
-            let snippet = Span::from(exec_snippet(&command)).dim();
+        DeniedForSession => {
+            let snippet = Span::from(exec_snippet(command)).dim();
             (
                 "✗ ".red(),
+                vec![
+                    "You ".into(),
+                    "did not approve".bold(),
+                    " codex to run ".into(),
+                    snippet,
+                    " for the rest of this session".dim(),
+                ],
+            )
+        }
+        Abort => {
+            let snippet = Span::from(exec_snippet(command)).dim();
+            (
+                "⊘ ".red().bold(),
                 vec![
                     "You ".into(),
                     "canceled".bold(),
                     " the request to run ".into(),
                     snippet,
+                    " (turn stopped)".dim(),
                 ],
             )
         }
-    };
+    }
+}
 
-    Box::new(PrefixedWrappedHistoryCell::new(
-        Line::from(summary),
-        symbol,
-        "  ",
-    ))
+/// `symbol outcome: command`, with the command cut to fit in `width`.
+fn compact_decision_line(
+    symbol: Span<'static>,
+    command: &[String],
+    decision: &codex_protocol::protocol::ReviewDecision,
+    width: u16,
+) -> Line<'static> {
+    use codex_protocol::protocol::NetworkPolicyRuleAction;
+    use codex_protocol::protocol::ReviewDecision::*;
+
+    let (outcome, subject) = match decision {
+        Approved
+        | ApprovedExecpolicyAmendment { .. }
+        | ApprovedForSession
+        | ApprovedForDirectory
+        | ApprovedForDuration { .. }
+        | ApprovedAlways => ("approved", exec_snippet(command)),
+        ApprovedSandboxed => ("approved sandboxed", exec_snippet(command)),
+        DryRun => ("dry run", exec_snippet(command)),
+        NetworkPolicyAmendment {
+            network_policy_amendment,
+        } => match network_policy_amendment.action {
+            NetworkPolicyRuleAction::Allow => {
+                ("allowed network", network_policy_amendment.host.clone())
+            }
+            NetworkPolicyRuleAction::Deny => {
+                ("blocked network", network_policy_amendment.host.clone())
+            }
+        },
+        Denied | DeniedForSession => ("denied", exec_snippet(command)),
+        Abort => ("canceled", exec_snippet(command)),
+    };
+    let mut line = Line::from(vec![symbol, outcome.bold(), ": ".into()]);
+    let room = usize::from(width).saturating_sub(line.width());
+    line.push_span(Span::from(truncate_text(&subject, room)).dim());
+    line
 }
 
 /// Cyan history cell line showing the current review status.
@@ -1201,15 +1387,7 @@ impl SessionHeaderHistoryCell {
     }
 
     fn format_directory_inner(directory: &Path, max_width: Option<usize>) -> String {
-        let formatted = if let Some(rel) = relativize_to_home(directory) {
-            if rel.as_os_str().is_empty() {
-                "~".to_string()
-            } else {
-                format!("~{}{}", std::path::MAIN_SEPARATOR, rel.display())
-            }
-        } else {
-            directory.display().to_string()
-        };
+        let formatted = display_path(directory);
 
         if let Some(max_width) = max_width {
             if max_width == 0 {
@@ -1674,6 +1852,15 @@ pub(crate) fn new_warning_event(message: String) -> PrefixedWrappedHistoryCell {
     PrefixedWrappedHistoryCell::new(message.yellow(), "⚠ ".yellow(), "  ")
 }
 
+/// The model's explanation of a command that is still waiting for approval.
+pub(crate) fn new_approval_explanation(explanation: String) -> PrefixedWrappedHistoryCell {
+    PrefixedWrappedHistoryCell::new(
+        Text::from(explanation),
+        vec!["• ".dim(), "Explanation: ".bold()],
+        "  ",
+    )
+}
+
 #[derive(Debug)]
 pub(crate) struct DeprecationNoticeCell {
     summary: String,
@@ -2479,6 +2666,7 @@ mod tests {
     use codex_protocol::parse_command::ParsedCommand;
     use codex_protocol::protocol::AskForApproval;
     use codex_protocol::protocol::McpAuthStatus;
+    use codex_protocol::protocol::ReviewDecision;
     use codex_protocol::protocol::SandboxPolicy;
     use codex_protocol::protocol::SessionConfiguredEvent;
     use dirs::home_dir;
@@ -4177,4 +4365,67 @@ mod tests {
             ]
         );
     }
+
+    #[test]
+    fn denied_and_aborted_decisions_state_their_consequence() {
+        let command = vec!["ls".to_string()];
+        let rendered = |decision| {
+            render_lines(
+                &new_approval_decision_cell(command.clone(), decision, None).display_lines(80),
+            )
+        };
+        assert_eq!(
+            rendered(ReviewDecision::Denied),
+            vec!["✗ You did not approve codex to run ls (codex continues)".to_string()]
+        );
+        assert_eq!(
+            rendered(ReviewDecision::Abort),
+            vec!["⊘ You canceled the request to run ls (turn stopped)".to_string()]
+        );
+    }
+
+    #[test]
+    fn decision_feedback_follows_the_summary() {
+        let cell = new_approval_decision_cell(
+            vec!["rm".to_string(), "-rf".to_string(), "target".to_string()],
+            ReviewDecision::Denied,
+            Some("use cargo clean instead".to_string()),
+        );
+        assert_eq!(
+            render_lines(&cell.display_lines(80)),
+            vec![
+                "✗ You did not approve codex to run rm -rf target (codex continues)".to_string(),
+                "  ↳ use cargo clean instead".to_string(),
+            ]
+        );
+        let cell = cell
+            .as_any()
+            .downcast_ref::<ApprovalDecisionCell>()
+            .expect("approval decision cell");
+        assert_eq!(cell.decision, ReviewDecision::Denied);
+        assert_eq!(cell.feedback.as_deref(), Some("use cargo clean instead"));
+    }
+
+    #[test]
+    fn compact_decisions_fit_on_one_line() {
+        let cell = new_compact_approval_decision_cell(
+            vec!["cargo".to_string(), "test".to_string()],
+            ReviewDecision::Approved,
+            Some("thanks".to_string()),
+        );
+        assert_eq!(
+            render_lines(&cell.display_lines(80)),
+            vec!["✔ approved: cargo test".to_string()]
+        );
+
+        let cell = new_compact_approval_decision_cell(
+            vec!["echo".to_string(), "a".repeat(40)],
+            ReviewDecision::Denied,
+            None,
+        );
+        assert_eq!(
+            render_lines(&cell.display_lines(30)),
+            vec![format!("✗ denied: echo {}...", "a".repeat(12))]
+        );
+    }
 }
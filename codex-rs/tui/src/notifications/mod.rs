@@ -44,6 +44,11 @@ impl DesktopNotificationBackend {
     }
 }
 
+/// Ring the terminal bell, whichever notification method is configured.
+pub fn ring_bell() -> io::Result<()> {
+    BelBackend.notify("")
+}
+
 pub fn detect_backend(method: NotificationMethod) -> DesktopNotificationBackend {
     DesktopNotificationBackend::for_method(method)
 }
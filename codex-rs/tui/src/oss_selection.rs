@@ -22,6 +22,7 @@ use ratatui::buffer::Buffer;
 use ratatui::layout::Alignment;
 use ratatui::layout::Constraint;
 use ratatui::layout::Direction;
+use ratatui::layout::Flex;
 use ratatui::layout::Layout;
 use ratatui::layout::Margin;
 use ratatui::layout::Rect;
@@ -251,23 +252,33 @@ impl WidgetRef for &OssSelectionWidget<'_> {
             })
             .collect();
 
+        let response_chunk = response_chunk.inner(Margin::new(1, 0));
+        let widths: Vec<u16> = lines.iter().map(|l| l.width() as u16 + 2).collect();
+        let spacing = button_spacing(&widths, response_chunk.width);
+        let button_rows = if spacing.is_some() {
+            1
+        } else {
+            lines.len() as u16
+        };
         let [title_area, button_area, description_area] = Layout::vertical([
             Constraint::Length(1),
-            Constraint::Length(1),
+            Constraint::Length(button_rows),
             Constraint::Min(0),
         ])
-        .areas(response_chunk.inner(Margin::new(1, 0)));
+        .areas(response_chunk);
 
         Line::from("Select provider?").render(title_area, buf);
 
         self.confirmation_prompt.clone().render(prompt_chunk, buf);
-        let areas = Layout::horizontal(
-            lines
-                .iter()
-                .map(|l| Constraint::Length(l.width() as u16 + 2)),
-        )
-        .spacing(1)
-        .split(button_area);
+        let areas = match spacing {
+            Some(spacing) => Layout::horizontal(widths.iter().map(|w| Constraint::Length(*w)))
+                .spacing(spacing)
+                .flex(Flex::Center)
+                .split(button_area),
+            None => {
+                Layout::vertical(widths.iter().map(|_| Constraint::Length(1))).split(button_area)
+            }
+        };
         for (idx, area) in areas.iter().enumerate() {
             let line = &lines[idx];
             line.render(*area, buf);
@@ -279,6 +290,22 @@ impl WidgetRef for &OssSelectionWidget<'_> {
     }
 }
 
+/// Gap between buttons laid out side by side in `available` columns: one
+/// column when they fit with it, none when they only fit without it. `None`
+/// means they do not fit on one row and are stacked instead, so that ratatui
+/// does not drop the ones past the edge.
+fn button_spacing(widths: &[u16], available: u16) -> Option<u16> {
+    let total: u16 = widths.iter().sum();
+    let gaps = widths.len().saturating_sub(1) as u16;
+    if total.saturating_add(gaps) <= available {
+        Some(1)
+    } else if total <= available {
+        Some(0)
+    } else {
+        None
+    }
+}
+
 fn get_status_symbol_and_color(status: &ProviderStatus) -> (&'static str, Color) {
     match status {
         ProviderStatus::Running => ("●", Color::Green),
@@ -371,3 +398,57 @@ async fn check_port_status(port: u16) -> io::Result<bool> {
         Err(_) => Ok(false), // Connection failed = not running
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn spacing_shrinks_before_buttons_stack() {
+        assert_eq!(button_spacing(&[10, 10], 21), Some(1));
+        assert_eq!(button_spacing(&[10, 10], 20), Some(0));
+        assert_eq!(button_spacing(&[10, 10], 19), None);
+    }
+
+    #[test]
+    fn buttons_that_do_not_fit_are_stacked_instead_of_dropped() {
+        let labels = [
+            "LM Studio on this machine",
+            "Ollama with the Responses API",
+            "Ollama with the Chat API",
+            "Another local server",
+        ];
+        let options: Vec<SelectOption> = labels
+            .iter()
+            .map(|label| SelectOption {
+                label: Line::from(*label),
+                description: "",
+                key: KeyCode::Null,
+                provider_id: "",
+            })
+            .collect();
+        let mut widget = OssSelectionWidget::new(ProviderStatus::Running, ProviderStatus::Unknown)
+            .expect("widget");
+        widget.select_options = &options;
+
+        let width = 60;
+        let area = Rect::new(0, 0, width, widget.desired_height(width) + 2);
+        let mut buf = Buffer::empty(area);
+        (&widget).render_ref(area, &mut buf);
+        let rendered: String = (0..area.height)
+            .map(|y| {
+                (0..area.width)
+                    .map(|x| buf[(x, y)].symbol())
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        for label in labels {
+            assert!(
+                rendered.contains(label),
+                "{label} missing from:\n{rendered}"
+            );
+        }
+    }
+}
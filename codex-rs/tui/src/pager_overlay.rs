@@ -988,9 +988,12 @@ mod tests {
         let apply_begin_cell: Arc<dyn HistoryCell> = Arc::new(new_patch_event(apply_changes, &cwd));
         cells.push(apply_begin_cell);
 
-        let apply_end_cell: Arc<dyn HistoryCell> =
-            history_cell::new_approval_decision_cell(vec!["ls".into()], ReviewDecision::Approved)
-                .into();
+        let apply_end_cell: Arc<dyn HistoryCell> = history_cell::new_approval_decision_cell(
+            vec!["ls".into()],
+            ReviewDecision::Approved,
+            None,
+        )
+        .into();
         cells.push(apply_end_cell);
 
         let mut exec_cell = crate::exec_cell::new_active_exec_command(
@@ -1,4 +1,4 @@
-use crate::exec_command::relativize_to_home;
+use crate::exec_command::display_path;
 use crate::text_formatting;
 use chrono::DateTime;
 use chrono::Local;
@@ -144,15 +144,7 @@ pub(crate) fn format_tokens_compact(value: i64) -> String {
 }
 
 pub(crate) fn format_directory_display(directory: &Path, max_width: Option<usize>) -> String {
-    let formatted = if let Some(rel) = relativize_to_home(directory) {
-        if rel.as_os_str().is_empty() {
-            "~".to_string()
-        } else {
-            format!("~{}{}", std::path::MAIN_SEPARATOR, rel.display())
-        }
-    } else {
-        directory.display().to_string()
-    };
+    let formatted = display_path(directory);
 
     if let Some(max_width) = max_width {
         if max_width == 0 {
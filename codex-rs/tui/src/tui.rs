@@ -16,10 +16,13 @@ use crossterm::Command;
 use crossterm::SynchronizedUpdate;
 use crossterm::event::DisableBracketedPaste;
 use crossterm::event::DisableFocusChange;
+use crossterm::event::DisableMouseCapture;
 use crossterm::event::EnableBracketedPaste;
 use crossterm::event::EnableFocusChange;
+use crossterm::event::EnableMouseCapture;
 use crossterm::event::KeyEvent;
 use crossterm::event::KeyboardEnhancementFlags;
+use crossterm::event::MouseEvent;
 use crossterm::event::PopKeyboardEnhancementFlags;
 use crossterm::event::PushKeyboardEnhancementFlags;
 use crossterm::terminal::EnterAlternateScreen;
@@ -129,6 +132,7 @@ fn restore_common(should_disable_raw_mode: bool) -> Result<()> {
     let _ = execute!(stdout(), PopKeyboardEnhancementFlags);
     execute!(stdout(), DisableBracketedPaste)?;
     let _ = execute!(stdout(), DisableFocusChange);
+    let _ = execute!(stdout(), DisableMouseCapture);
     if should_disable_raw_mode {
         disable_raw_mode()?;
     }
@@ -235,6 +239,7 @@ fn set_panic_hook() {
 pub enum TuiEvent {
     Key(KeyEvent),
     Paste(String),
+    Mouse(MouseEvent),
     Draw,
 }
 
@@ -255,6 +260,8 @@ pub struct Tui {
     notification_backend: Option<DesktopNotificationBackend>,
     // When false, enter_alt_screen() becomes a no-op (for Zellij scrollback support)
     alt_screen_enabled: bool,
+    // True while the terminal reports mouse events (see `set_mouse_capture`)
+    mouse_capture: bool,
 }
 
 impl Tui {
@@ -283,6 +290,7 @@ impl Tui {
             enhanced_keys_supported,
             notification_backend: Some(detect_backend(NotificationMethod::default())),
             alt_screen_enabled: true,
+            mouse_capture: false,
         }
     }
 
@@ -291,6 +299,23 @@ impl Tui {
         self.alt_screen_enabled = enabled;
     }
 
+    /// Start or stop mouse reporting. Capture is only enabled while a view asks
+    /// for it, because it disables the terminal's native text selection.
+    pub fn set_mouse_capture(&mut self, enabled: bool) {
+        if self.mouse_capture == enabled {
+            return;
+        }
+        let result = if enabled {
+            execute!(stdout(), EnableMouseCapture)
+        } else {
+            execute!(stdout(), DisableMouseCapture)
+        };
+        match result {
+            Ok(()) => self.mouse_capture = enabled,
+            Err(err) => tracing::warn!("failed to toggle mouse capture: {err}"),
+        }
+    }
+
     pub fn set_notification_method(&mut self, method: NotificationMethod) {
         self.notification_backend = Some(detect_backend(method));
     }
@@ -346,6 +371,8 @@ impl Tui {
         if let Err(err) = set_modes() {
             tracing::warn!("failed to re-enable terminal modes after external program: {err}");
         }
+        // Restoring disabled mouse capture; the next draw re-enables it if needed.
+        self.mouse_capture = false;
         // After the external program `f` finishes, reset terminal state and flush any buffered keypresses.
         flush_terminal_input_buffer();
 